@@ -0,0 +1,38 @@
+//! Set operations on bounding boxes must agree with each other for every pair of valid boxes.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use volume::prelude::*;
+
+type Corners = ([i64; 3], [i64; 3]);
+
+fuzz_target!(|input: (Corners, Corners, [i64; 3])| {
+    let ((a1, a2), (b1, b2), pos) = input;
+    let (Some(a), Some(b)) = (
+        BoundingBox::checked_new(a1, a2),
+        BoundingBox::checked_new(b1, b2),
+    ) else {
+        return;
+    };
+
+    let intersection = a.intersection(&b);
+    assert_eq!(a.overlaps(&b), intersection.is_some());
+    assert_eq!(
+        a.contains(pos) && b.contains(pos),
+        intersection.is_some_and(|i| i.contains(pos))
+    );
+
+    let pieces = a.difference(&b);
+    // The capacity saturates for huge boxes, in which case the pieces can't be counted exactly.
+    if a.capacity() < i128::MAX {
+        let removed = intersection.map_or(0, |i| i.capacity());
+        let total = pieces.iter().map(|piece| piece.capacity()).sum::<i128>();
+        assert_eq!(a.capacity() - removed, total);
+    }
+    assert!(pieces.iter().all(|piece| !piece.overlaps(&b)));
+    assert_eq!(
+        a.contains(pos) && !b.contains(pos),
+        pieces.iter().any(|piece| piece.contains(pos))
+    );
+});
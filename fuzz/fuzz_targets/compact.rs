@@ -0,0 +1,37 @@
+//! Every compact representation must decode to exactly the items it was encoded from.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use volume::compact::{PaletteVolume, RleVolume};
+use volume::prelude::*;
+
+fuzz_target!(|input: ([u8; 3], [i8; 3], u8, Vec<u8>)| {
+    let (dims, min, max_palette, items) = input;
+    let dims = dims.map(|d| (d % 9) as i64);
+    let min = min.map(i64::from);
+    let bounds = BoundingBox::new(min, [0, 1, 2].map(|axis| min[axis] + dims[axis]));
+
+    let mut vol = HeapVolume::new(0u8, bounds);
+    for (idx, &item) in bounds.into_iter().zip(items.iter().cycle()) {
+        vol[idx] = item;
+    }
+
+    let rle = RleVolume::encode(&vol);
+    assert_eq!(Ok(()), rle.validate());
+    assert!(bounds.into_iter().all(|idx| rle.get(idx) == vol.get(idx)));
+
+    if let Some(palette) = PaletteVolume::encode(&vol, max_palette as usize) {
+        assert_eq!(Ok(()), palette.validate());
+        assert!(bounds
+            .into_iter()
+            .all(|idx| palette.get(idx) == vol.get(idx)));
+    }
+
+    let compact = vol.clone().compress();
+    assert_eq!(Ok(()), compact.validate());
+    assert!(bounds
+        .into_iter()
+        .all(|idx| compact.get(idx) == vol.get(idx)));
+    assert_eq!(vol, compact.decompress());
+});
@@ -0,0 +1,24 @@
+//! Parsing untrusted strings into indices and bounding boxes must never panic, and must round-trip through `Display`.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use volume::prelude::*;
+
+fuzz_target!(|data: &str| {
+    if let Ok(idx) = data.parse::<VIdx>() {
+        assert_eq!(Ok(idx), idx.to_string().parse());
+    }
+
+    if let Ok(bounds) = data.parse::<BoundingBox>() {
+        assert_eq!(Ok(bounds), bounds.to_string().parse());
+        assert!(bounds.capacity() >= 0);
+        assert!(bounds.is_empty() || bounds.contains(bounds.center()));
+
+        let mut positions = bounds.into_iter();
+        if let Ok(len) = usize::try_from(bounds.capacity()) {
+            assert_eq!(len, positions.len());
+        }
+        assert!(positions.by_ref().take(8).all(|pos| bounds.contains(pos)));
+    }
+});
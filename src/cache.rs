@@ -0,0 +1,327 @@
+//! Capacity limited caches of volumes, for keeping the most recently used chunks of a streamed world resident.
+
+use crate::prelude::*;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+
+/// How much a [`VolumeCache`] may hold before it starts evicting volumes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CacheCapacity {
+    /// At most this many volumes.
+    Count(usize),
+    /// At most this many bytes, as reported by [`VolumeCache::weight`].
+    Bytes(usize),
+}
+
+// The hook is only ever called through `&mut self`, so the mutex is never contended (see `Mutex::get_mut`).
+// It's only there to make the cache `Sync` without requiring the hook to be `Sync`.
+type EvictionHook<K, V> = Mutex<Box<dyn FnMut(K, V) + Send>>;
+
+struct Entry<V> {
+    volume: V,
+    last_used: u64,
+    bytes: usize,
+    /// Unique for every inserted volume, so that handles to a replaced volume don't resolve to its replacement.
+    generation: u64,
+    /// Shared with every [`VolumePin`] of this entry.
+    pins: Arc<()>,
+}
+
+impl<V> Entry<V> {
+    #[inline]
+    fn is_pinned(&self) -> bool {
+        Arc::strong_count(&self.pins) > 1
+    }
+}
+
+/// A handle to a volume in a [`VolumeCache`] which doesn't keep the volume resident. Resolving the handle with
+/// [`VolumeCache::upgrade`] returns [`None`] once the volume was evicted, removed, or replaced, even if another volume
+/// has since been inserted with the same key.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WeakVolumeHandle<K> {
+    key: K,
+    generation: u64,
+}
+
+impl<K> WeakVolumeHandle<K> {
+    /// The key of the volume this handle refers to.
+    #[inline]
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+}
+
+/// A guard preventing a volume from being evicted from a [`VolumeCache`] while it's alive. The volume can still be
+/// removed or replaced explicitly. Doesn't borrow the cache, so it can be held by long-running tasks on other threads.
+#[derive(Debug, Clone)]
+pub struct VolumePin<K> {
+    handle: WeakVolumeHandle<K>,
+    _pin: Arc<()>,
+}
+
+impl<K> VolumePin<K> {
+    /// A handle to the pinned volume.
+    #[inline]
+    pub fn handle(&self) -> &WeakVolumeHandle<K> {
+        &self.handle
+    }
+}
+
+/// A cache of volumes with a maximum capacity which evicts the least recently used volumes when it's full.
+///
+/// An eviction hook can be registered with [`VolumeCache::on_evict`] to be given every evicted volume, for example to save it to disk.
+/// Volumes removed explicitly with [`VolumeCache::remove`] or replaced with [`VolumeCache::insert`] are returned instead and never given to the hook.
+///
+/// Systems that need to refer to a volume over a longer time (e.g., pathfinding or meshing tasks) can hold a [`WeakVolumeHandle`],
+/// which resolves to [`None`] after the volume is evicted, or a [`VolumePin`] which prevents the volume from being evicted at all.
+pub struct VolumeCache<K, V: Volume> {
+    entries: HashMap<K, Entry<V>>,
+    /// The keys of all entries ordered by when they were last used.
+    recency: BTreeMap<u64, K>,
+    tick: u64,
+    capacity: CacheCapacity,
+    bytes: usize,
+    on_evict: Option<EvictionHook<K, V>>,
+}
+
+impl<K: Hash + Eq + Clone, V: Volume> VolumeCache<K, V> {
+    #[inline]
+    pub fn new(capacity: CacheCapacity) -> Self {
+        Self {
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            tick: 0,
+            capacity,
+            bytes: 0,
+            on_evict: None,
+        }
+    }
+
+    /// Set the hook which is called with every volume evicted from this cache, replacing the previous hook.
+    #[inline]
+    pub fn on_evict<F>(&mut self, hook: F)
+    where
+        F: FnMut(K, V) + Send + 'static,
+    {
+        self.on_evict = Some(Mutex::new(Box::new(hook)));
+    }
+
+    /// How many bytes a volume counts as when the capacity is given in bytes: its own size plus its
+    /// [heap size](Volume::heap_size), so sparse and compressed volumes only count the memory they actually use.
+    #[inline]
+    pub fn weight(volume: &V) -> usize {
+        std::mem::size_of::<V>().saturating_add(volume.heap_size())
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> CacheCapacity {
+        self.capacity
+    }
+
+    /// Change the capacity of this cache, evicting volumes if it's now over capacity.
+    #[inline]
+    pub fn set_capacity(&mut self, capacity: CacheCapacity) {
+        self.capacity = capacity;
+        self.evict_to_capacity(None);
+    }
+
+    /// The amount of volumes in this cache.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The total [weight](VolumeCache::weight) of every volume in this cache, i.e. the memory used by the cached volumes.
+    #[inline]
+    pub fn resident_bytes(&self) -> usize {
+        self.bytes
+    }
+
+    #[inline]
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    fn touch(&mut self, key: &K) -> Option<&mut Entry<V>> {
+        let entry = self.entries.get_mut(key)?;
+
+        self.recency.remove(&entry.last_used);
+        self.tick += 1;
+        entry.last_used = self.tick;
+        self.recency.insert(self.tick, key.clone());
+
+        Some(entry)
+    }
+
+    /// Get the volume with the given key, marking it as the most recently used.
+    #[inline]
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        self.touch(key).map(|entry| &entry.volume)
+    }
+
+    /// Get the volume with the given key mutably, marking it as the most recently used.
+    ///
+    /// The volume's weight is computed when it's inserted, so if the volume's memory use is changed through the returned
+    /// reference (e.g., by changing its bounds or allocating chunks of a sparse volume) the cache's byte count becomes
+    /// inaccurate. Reinsert the volume in that case.
+    #[inline]
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.touch(key).map(|entry| &mut entry.volume)
+    }
+
+    /// Get the volume with the given key without marking it as used.
+    #[inline]
+    pub fn peek(&self, key: &K) -> Option<&V> {
+        self.entries.get(key).map(|entry| &entry.volume)
+    }
+
+    /// Insert a volume as the most recently used one, evicting the least recently used volumes if the cache is over capacity.
+    /// If the cache already had a volume with this key, the old volume is returned.
+    ///
+    /// The inserted volume itself is never evicted by this, even if it alone exceeds the capacity.
+    #[inline]
+    pub fn insert(&mut self, key: K, volume: V) -> Option<V> {
+        let old = self.remove(&key);
+
+        let bytes = Self::weight(&volume);
+        self.tick += 1;
+        self.bytes += bytes;
+        self.recency.insert(self.tick, key.clone());
+        self.entries.insert(
+            key.clone(),
+            Entry {
+                volume,
+                last_used: self.tick,
+                bytes,
+                generation: self.tick,
+                pins: Arc::new(()),
+            },
+        );
+
+        self.evict_to_capacity(Some(&key));
+        old
+    }
+
+    /// Create a weak handle to the volume with the given key, or return [`None`] if there is no such volume.
+    #[inline]
+    pub fn downgrade(&self, key: &K) -> Option<WeakVolumeHandle<K>> {
+        let entry = self.entries.get(key)?;
+
+        Some(WeakVolumeHandle {
+            key: key.clone(),
+            generation: entry.generation,
+        })
+    }
+
+    /// Get the volume a weak handle refers to, marking it as the most recently used.
+    /// Returns [`None`] if the volume was evicted, removed, or replaced since the handle was created.
+    #[inline]
+    pub fn upgrade(&mut self, handle: &WeakVolumeHandle<K>) -> Option<&V> {
+        self.upgrade_mut(handle).map(|volume| &*volume)
+    }
+
+    /// Same as [`VolumeCache::upgrade`], but returns the volume mutably.
+    #[inline]
+    pub fn upgrade_mut(&mut self, handle: &WeakVolumeHandle<K>) -> Option<&mut V> {
+        if self.entries.get(&handle.key)?.generation != handle.generation {
+            return None;
+        }
+
+        self.get_mut(&handle.key)
+    }
+
+    /// Pin the volume with the given key, preventing it from being evicted until the returned guard (and all its clones) are dropped.
+    /// Returns [`None`] if there is no such volume.
+    ///
+    /// While pinned volumes can't be evicted, they still count towards the capacity, so the cache may stay over capacity.
+    #[inline]
+    pub fn pin(&self, key: &K) -> Option<VolumePin<K>> {
+        let entry = self.entries.get(key)?;
+
+        Some(VolumePin {
+            handle: self.downgrade(key)?,
+            _pin: Arc::clone(&entry.pins),
+        })
+    }
+
+    /// Check if the volume with the given key is pinned.
+    #[inline]
+    pub fn is_pinned(&self, key: &K) -> bool {
+        self.entries.get(key).is_some_and(|entry| entry.is_pinned())
+    }
+
+    /// Get the volume with the given key, or insert the volume returned by `f` if there is none.
+    /// The volume is marked as the most recently used either way.
+    #[inline]
+    pub fn get_or_insert_with<F>(&mut self, key: K, f: F) -> &mut V
+    where
+        F: FnOnce() -> V,
+    {
+        if !self.entries.contains_key(&key) {
+            self.insert(key.clone(), f());
+        }
+
+        self.get_mut(&key).unwrap()
+    }
+
+    /// Remove the volume with the given key and return it. The eviction hook is not called.
+    #[inline]
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let entry = self.entries.remove(key)?;
+        self.recency.remove(&entry.last_used);
+        self.bytes -= entry.bytes;
+
+        Some(entry.volume)
+    }
+
+    /// Remove every volume in this cache without calling the eviction hook.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+        self.bytes = 0;
+    }
+
+    /// Evict every unpinned volume in this cache (from least to most recently used), passing them to the eviction hook.
+    #[inline]
+    pub fn flush(&mut self) {
+        while self.evict_lru(None) {}
+    }
+
+    fn over_capacity(&self) -> bool {
+        match self.capacity {
+            CacheCapacity::Count(count) => self.entries.len() > count,
+            CacheCapacity::Bytes(bytes) => self.bytes > bytes,
+        }
+    }
+
+    fn evict_to_capacity(&mut self, keep: Option<&K>) {
+        while self.over_capacity() && self.evict_lru(keep) {}
+    }
+
+    /// Evict the least recently used unpinned volume (other than `keep`). Returns `false` if there was nothing to evict.
+    fn evict_lru(&mut self, keep: Option<&K>) -> bool {
+        let key = match self
+            .recency
+            .values()
+            .find(|&key| Some(key) != keep && !self.entries[key].is_pinned())
+            .cloned()
+        {
+            Some(key) => key,
+            None => return false,
+        };
+
+        let volume = self.remove(&key).unwrap();
+        if let Some(hook) = self.on_evict.as_mut() {
+            (hook.get_mut().unwrap_or_else(|e| e.into_inner()))(key, volume);
+        }
+
+        true
+    }
+}
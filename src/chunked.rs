@@ -0,0 +1,451 @@
+//! Sparse volumes made up of equally sized chunks, with the shape of the chunks chosen by a [`ChunkLayout`].
+
+use crate::prelude::*;
+use crate::types::{BoundingBoxIterator, StaticBounds, ValidationError};
+use std::collections::BTreeMap;
+
+/// The shape of the chunks in a [`ChunkedVolume`]. Chunk `[cx, cy, cz]` covers the positions from
+/// `[cx, cy, cz] * dimensions` (inclusive) to `([cx, cy, cz] + 1) * dimensions` (exclusive).
+///
+/// Implemented by [`StaticBounds`] for chunk sizes known at compile time (see [`Cubic16`], [`Cubic32`], and [`Slab16x256`]),
+/// and by [`DynamicLayout`] for chunk sizes only known at runtime.
+pub trait ChunkLayout: Copy {
+    /// The dimensions of every chunk. Must be at least 1 along every axis.
+    fn chunk_dimensions(&self) -> [i64; 3];
+
+    /// The coordinates of the chunk containing the given worldspace position.
+    #[inline]
+    fn chunk_of(&self, pos: [i64; 3]) -> [i64; 3] {
+        let dims = self.chunk_dimensions();
+        [0, 1, 2].map(|axis| pos[axis].div_euclid(dims[axis]))
+    }
+
+    /// The worldspace bounds of the chunk with the given coordinates.
+    ///
+    /// # Panics
+    /// Panics if the bounds overflow [`i64`]. See [`ChunkLayout::checked_chunk_bounds`] for a non-panicking version.
+    #[inline]
+    fn chunk_bounds(&self, chunk: [i64; 3]) -> BoundingBox {
+        self.checked_chunk_bounds(chunk)
+            .expect("chunk bounds overflow i64")
+    }
+
+    /// The worldspace bounds of the chunk with the given coordinates, or [`None`] if they overflow [`i64`].
+    #[inline]
+    fn checked_chunk_bounds(&self, chunk: [i64; 3]) -> Option<BoundingBox> {
+        let dims = self.chunk_dimensions();
+        let min = [
+            chunk[0].checked_mul(dims[0])?,
+            chunk[1].checked_mul(dims[1])?,
+            chunk[2].checked_mul(dims[2])?,
+        ];
+
+        Some(BoundingBox::new(min, min.checked_add(dims)?))
+    }
+
+    /// The coordinates of every chunk overlapping `region`, in X-major order.
+    #[inline]
+    fn chunks_overlapping(&self, region: BoundingBox) -> BoundingBoxIterator {
+        if region.is_empty() {
+            return BoundingBox::new_origin([0, 0, 0]).into_iter();
+        }
+
+        let last = self.chunk_of(crate::util::sub_ivec3(region.max(), [1, 1, 1]));
+        BoundingBox::new(
+            self.chunk_of(region.min()),
+            crate::util::sum_ivec3(last, [1, 1, 1]),
+        )
+        .into_iter()
+    }
+}
+
+impl<const X: usize, const Y: usize, const Z: usize> ChunkLayout for StaticBounds<X, Y, Z> {
+    #[inline(always)]
+    fn chunk_dimensions(&self) -> [i64; 3] {
+        Self::DIMENSIONS
+    }
+}
+
+/// 16×16×16 chunks.
+pub type Cubic16 = StaticBounds<16, 16, 16>;
+/// 32×32×32 chunks.
+pub type Cubic32 = StaticBounds<32, 32, 32>;
+/// Vertical slabs of 16×256×16, spanning the full height of the world in many block games.
+pub type Slab16x256 = StaticBounds<16, 256, 16>;
+
+/// A chunk layout with dimensions chosen at runtime.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct DynamicLayout {
+    dimensions: [i64; 3],
+}
+
+impl DynamicLayout {
+    /// # Panics
+    /// Panics if any of the dimensions is less than 1.
+    #[inline]
+    pub fn new(dimensions: [i64; 3]) -> Self {
+        assert!(
+            dimensions.iter().all(|&d| d > 0),
+            "chunk dimensions must be at least 1"
+        );
+
+        Self { dimensions }
+    }
+}
+
+impl ChunkLayout for DynamicLayout {
+    #[inline(always)]
+    fn chunk_dimensions(&self) -> [i64; 3] {
+        self.dimensions
+    }
+}
+
+/// A sparse volume made up of chunks shaped by the layout `L`. Chunks are allocated (filled with the volume's default item) when
+/// an item in them is first written to with [`ChunkedVolume::set`] or [`ChunkedVolume::chunk_or_insert`].
+///
+/// The [bounding box](Volume::bounding_box) is the smallest box containing every allocated chunk. Positions inside of it
+/// whose chunk isn't allocated read as the default item, and get their chunk allocated when accessed mutably.
+pub struct ChunkedVolume<T, L = Cubic16> {
+    /// Keyed by the chunk coordinates in reverse (`[z, y, x]`), so that iterating over the map visits the chunks in X-major order.
+    chunks: BTreeMap<[i64; 3], HeapVolume<T>>,
+    default: T,
+    layout: L,
+    bounds: Option<BoundingBox>,
+}
+
+impl_indexing!([T, L], ChunkedVolume<T, L>);
+
+/// The smallest box containing both boxes.
+#[inline]
+fn enclose(a: BoundingBox, b: BoundingBox) -> BoundingBox {
+    BoundingBox::new(
+        crate::math::min_ivec3(a.min(), b.min()),
+        crate::math::max_ivec3(a.max(), b.max()),
+    )
+}
+
+#[inline(always)]
+fn key([x, y, z]: [i64; 3]) -> [i64; 3] {
+    [z, y, x]
+}
+
+impl<T, L: ChunkLayout> ChunkedVolume<T, L> {
+    /// Create an empty volume where every unallocated position reads as `default`.
+    #[inline]
+    pub fn new(default: T, layout: L) -> Self {
+        Self {
+            chunks: BTreeMap::new(),
+            default,
+            layout,
+            bounds: None,
+        }
+    }
+
+    #[inline]
+    pub fn layout(&self) -> L {
+        self.layout
+    }
+
+    /// The item unallocated positions read as.
+    #[inline]
+    pub fn default_item(&self) -> &T {
+        &self.default
+    }
+
+    /// The amount of allocated chunks.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.chunks.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.chunks.is_empty()
+    }
+
+    /// Get the chunk with the given chunk coordinates, if it's allocated.
+    #[inline]
+    pub fn chunk(&self, chunk: [i64; 3]) -> Option<&HeapVolume<T>> {
+        self.chunks.get(&key(chunk))
+    }
+
+    /// Get the chunk with the given chunk coordinates mutably, if it's allocated.
+    #[inline]
+    pub fn chunk_mut(&mut self, chunk: [i64; 3]) -> Option<&mut HeapVolume<T>> {
+        self.chunks.get_mut(&key(chunk))
+    }
+
+    /// Remove the chunk with the given chunk coordinates, returning it if it was allocated.
+    #[inline]
+    pub fn remove_chunk(&mut self, chunk: [i64; 3]) -> Option<HeapVolume<T>> {
+        let removed = self.chunks.remove(&key(chunk))?;
+        self.recompute_bounds();
+
+        Some(removed)
+    }
+
+    /// Iterate over the coordinates and contents of every allocated chunk, in X-major order of the chunk coordinates.
+    #[inline]
+    pub fn iter_chunks(&self) -> impl Iterator<Item = ([i64; 3], &HeapVolume<T>)> {
+        self.chunks.iter().map(|(&k, chunk)| (key(k), chunk))
+    }
+
+    /// Iterate mutably over the coordinates and contents of every allocated chunk, in X-major order of the chunk coordinates.
+    #[inline]
+    pub fn iter_chunks_mut(&mut self) -> impl Iterator<Item = ([i64; 3], &mut HeapVolume<T>)> {
+        self.chunks.iter_mut().map(|(&k, chunk)| (key(k), chunk))
+    }
+
+    /// Shift the origin of this volume's worldspace to `new_origin`, so the item at `new_origin` ends up at `[0, 0, 0]`.
+    /// Every chunk is moved without touching its items. See [`HeapVolume::rebase`].
+    ///
+    /// # Panics
+    /// Panics if `new_origin` isn't on a chunk boundary (a multiple of the chunk dimensions along every axis), since the
+    /// chunks couldn't stay aligned to the layout otherwise. Also panics if the new bounds overflow [`i64`].
+    /// See [`ChunkedVolume::try_rebase`] for a non-panicking version.
+    #[inline]
+    pub fn rebase(&mut self, new_origin: [i64; 3]) {
+        let dims = self.layout.chunk_dimensions();
+        assert!(
+            (0..3).all(|axis| new_origin[axis] % dims[axis] == 0),
+            "new origin {new_origin:?} isn't on a chunk boundary"
+        );
+        assert!(self.try_rebase(new_origin), "rebased bounds overflow i64");
+    }
+
+    /// Same as [`ChunkedVolume::rebase`], but returns `false` (and changes nothing) instead of panicking if `new_origin`
+    /// isn't on a chunk boundary or the new bounds overflow [`i64`].
+    #[inline]
+    pub fn try_rebase(&mut self, new_origin: [i64; 3]) -> bool {
+        let dims = self.layout.chunk_dimensions();
+        if (0..3).any(|axis| new_origin[axis] % dims[axis] != 0) {
+            return false;
+        }
+        // Every chunk is inside of the bounds, so none of them can overflow if the bounds don't.
+        let Some(bounds) = self.bounds.map(|bounds| bounds.checked_rebased(new_origin)) else {
+            return true;
+        };
+        if bounds.is_none() {
+            return false;
+        }
+
+        let offset = [0, 1, 2].map(|axis| new_origin[axis] / dims[axis]);
+        self.chunks = std::mem::take(&mut self.chunks)
+            .into_iter()
+            .map(|(k, mut chunk)| {
+                chunk.rebase(new_origin);
+                (key(crate::util::sub_ivec3(key(k), offset)), chunk)
+            })
+            .collect();
+        self.bounds = bounds;
+        true
+    }
+
+    fn recompute_bounds(&mut self) {
+        let layout = self.layout;
+        self.bounds = self
+            .chunks
+            .keys()
+            .map(|&k| layout.chunk_bounds(key(k)))
+            .reduce(enclose);
+    }
+}
+
+impl<T: Clone, L: ChunkLayout> ChunkedVolume<T, L> {
+    /// Get the chunk with the given chunk coordinates, allocating it if needed.
+    ///
+    /// # Panics
+    /// Panics if the chunk's bounds (or the bounds of the whole volume, once it's included) overflow [`i64`].
+    /// See [`ChunkedVolume::try_chunk_or_insert`] for a non-panicking version.
+    #[inline]
+    pub fn chunk_or_insert(&mut self, chunk: [i64; 3]) -> &mut HeapVolume<T> {
+        self.try_chunk_or_insert(chunk)
+            .expect("chunk bounds overflow i64")
+    }
+
+    /// Same as [`ChunkedVolume::chunk_or_insert`], but returns [`None`] (and allocates nothing) instead of panicking if the
+    /// chunk's bounds (or the bounds of the whole volume, once it's included) overflow [`i64`].
+    #[inline]
+    pub fn try_chunk_or_insert(&mut self, chunk: [i64; 3]) -> Option<&mut HeapVolume<T>> {
+        if !self.chunks.contains_key(&key(chunk)) {
+            let bounds = self.layout.checked_chunk_bounds(chunk)?;
+            let enclosing = match self.bounds {
+                Some(acc) => {
+                    let acc = enclose(acc, bounds);
+                    BoundingBox::checked_new(acc.min(), acc.max())?
+                }
+                None => bounds,
+            };
+
+            self.chunks
+                .insert(key(chunk), HeapVolume::new(self.default.clone(), bounds));
+            self.bounds = Some(enclosing);
+        }
+
+        self.chunks.get_mut(&key(chunk))
+    }
+
+    /// Set the item at any worldspace position, allocating its chunk if needed. Returns the previous item.
+    ///
+    /// # Panics
+    /// Panics if `idx` cannot be converted to `[i64; 3]`, or if its chunk can't be allocated (see
+    /// [`ChunkedVolume::chunk_or_insert`]). See [`ChunkedVolume::try_set`] for a non-panicking version.
+    #[inline]
+    pub fn set<Idx: VolumeIdx>(&mut self, idx: Idx, item: T) -> T {
+        self.try_set(idx, item)
+            .expect("cannot cast index to [i64; 3] or allocate its chunk")
+    }
+
+    /// Same as [`ChunkedVolume::set`], but returns [`None`] (and changes nothing) instead of panicking if `idx` cannot be
+    /// converted to `[i64; 3]` or its chunk can't be allocated.
+    #[inline]
+    pub fn try_set<Idx: VolumeIdx>(&mut self, idx: Idx, item: T) -> Option<T> {
+        let pos = idx.array::<i64>()?;
+        let chunk = self.try_chunk_or_insert(self.layout.chunk_of(pos))?;
+
+        Some(std::mem::replace(chunk.get_mut(pos)?, item))
+    }
+}
+
+impl<T: Clone + PartialEq, L: ChunkLayout> ChunkedVolume<T, L> {
+    /// Reset every position in `region` to the default item. Chunks that end up entirely default (including every chunk
+    /// `region` covers completely) are deallocated. Returns the amount of bytes freed, as counted by [`Volume::heap_size`].
+    #[inline]
+    pub fn clear_region(&mut self, region: BoundingBox) -> usize {
+        let layout = self.layout;
+        let mut emptied = Vec::new();
+
+        for chunk in layout.chunks_overlapping(region) {
+            let Some(vol) = self.chunks.get_mut(&key(chunk)) else {
+                continue;
+            };
+
+            let bounds = layout.chunk_bounds(chunk);
+            if region.intersection(&bounds) != Some(bounds) {
+                vol.fill_range(region, self.default.clone());
+                if vol.is_uniform() != Some(&self.default) {
+                    continue;
+                }
+            }
+            emptied.push(chunk);
+        }
+
+        self.deallocate(emptied)
+    }
+
+    /// Deallocate every chunk in which `is_empty` returns `true` for every item. Returns the amount of bytes freed,
+    /// as counted by [`Volume::heap_size`].
+    ///
+    /// Positions in deallocated chunks read as the default item afterwards, so `is_empty` should only accept items that are
+    /// interchangeable with it (e.g., air after a block was placed and removed again).
+    #[inline]
+    pub fn prune<F>(&mut self, mut is_empty: F) -> usize
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let emptied = self
+            .iter_chunks()
+            .filter(|(_, vol)| vol.iter().all(&mut is_empty))
+            .map(|(chunk, _)| chunk)
+            .collect();
+
+        self.deallocate(emptied)
+    }
+
+    fn deallocate(&mut self, chunks: Vec<[i64; 3]>) -> usize {
+        let freed = chunks
+            .iter()
+            .filter_map(|&chunk| self.chunks.remove(&key(chunk)))
+            .map(|vol| std::mem::size_of::<HeapVolume<T>>() + vol.heap_size())
+            .sum();
+
+        if freed > 0 {
+            self.recompute_bounds();
+        }
+
+        freed
+    }
+}
+
+impl<T, L: ChunkLayout> Volume for ChunkedVolume<T, L> {
+    type Item = T;
+
+    #[inline]
+    fn ls_get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        let min = self.bounds?.min();
+        self.get(idx.array::<i64>()?.checked_add(min)?)
+    }
+
+    #[inline]
+    fn bounding_box(&self) -> BoundingBox {
+        self.bounds.unwrap_or(BoundingBox::new_origin([0, 0, 0]))
+    }
+
+    #[inline]
+    fn get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        let pos = idx.array::<i64>()?;
+        if !self.bounds?.contains(pos) {
+            return None;
+        }
+
+        match self.chunk(self.layout.chunk_of(pos)) {
+            Some(chunk) => chunk.get(pos),
+            None => Some(&self.default),
+        }
+    }
+
+    #[inline]
+    fn heap_size(&self) -> usize {
+        self.chunks
+            .values()
+            .map(|chunk| std::mem::size_of::<HeapVolume<T>>() + chunk.heap_size())
+            .sum()
+    }
+
+    #[inline]
+    fn validate(&self) -> Result<(), ValidationError> {
+        self.chunks.values().try_for_each(|chunk| chunk.validate())
+    }
+}
+
+impl<T: Clone, L: ChunkLayout> VolumeMut for ChunkedVolume<T, L> {
+    #[inline]
+    fn ls_get_mut<Idx: VolumeIdx>(&mut self, idx: Idx) -> Option<&mut Self::Item> {
+        let min = self.bounds?.min();
+        self.get_mut(idx.array::<i64>()?.checked_add(min)?)
+    }
+
+    /// Get a mutable reference to the item at the given worldspace position, allocating its chunk if needed.
+    /// Returns [`None`] if the position is outside of the [bounding box](Volume::bounding_box), use [`ChunkedVolume::set`]
+    /// to write anywhere.
+    #[inline]
+    fn get_mut<Idx: VolumeIdx>(&mut self, idx: Idx) -> Option<&mut Self::Item> {
+        let pos = idx.array::<i64>()?;
+        if !self.bounds?.contains(pos) {
+            return None;
+        }
+
+        self.chunk_or_insert(self.layout.chunk_of(pos)).get_mut(pos)
+    }
+}
+
+impl<T: Clone, L: ChunkLayout> Clone for ChunkedVolume<T, L> {
+    fn clone(&self) -> Self {
+        Self {
+            chunks: self.chunks.clone(),
+            default: self.default.clone(),
+            layout: self.layout,
+            bounds: self.bounds,
+        }
+    }
+}
+
+impl<T, L: ChunkLayout + std::fmt::Debug> std::fmt::Debug for ChunkedVolume<T, L> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChunkedVolume")
+            .field("layout", &self.layout)
+            .field("chunks", &self.chunks.len())
+            .field("bounds", &self.bounds)
+            .finish()
+    }
+}
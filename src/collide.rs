@@ -0,0 +1,155 @@
+//! Collision queries of moving boxes against the solid cells of a volume.
+//!
+//! Like in [`geometry`](crate::geometry), the cell at index `[x, y, z]` is the unit cube from `(x, y, z)` to `(x + 1, y + 1, z + 1)`.
+
+use crate::prelude::*;
+
+/// An axis aligned box with fractional coordinates, e.g. the collision box of an entity.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Aabb {
+    pub min: [f64; 3],
+    pub max: [f64; 3],
+}
+
+impl Aabb {
+    #[inline]
+    pub fn new(min: [f64; 3], max: [f64; 3]) -> Self {
+        Self { min, max }
+    }
+
+    /// This box moved by `offset`.
+    #[inline]
+    pub fn translated(&self, offset: [f64; 3]) -> Self {
+        Self {
+            min: [0, 1, 2].map(|a| self.min[a] + offset[a]),
+            max: [0, 1, 2].map(|a| self.max[a] + offset[a]),
+        }
+    }
+
+    /// The cells this box overlaps (cells it merely touches are not included).
+    #[inline]
+    pub fn overlapping_cells(&self) -> BoundingBox {
+        BoundingBox::new(
+            self.min.map(|c| c.floor() as i64),
+            self.max.map(|c| c.ceil() as i64),
+        )
+    }
+}
+
+/// The result of [`sweep_aabb`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SweepResult {
+    /// How far along the velocity the box can move before hitting a solid cell, from 0.0 to 1.0. 1.0 if nothing was hit.
+    pub time: f64,
+    /// The normal of the face that was hit (pointing out of the solid cell), or all zeros if nothing was hit.
+    pub normal: [f64; 3],
+    /// The solid cell that was hit, if any.
+    pub cell: Option<[i64; 3]>,
+}
+
+impl SweepResult {
+    /// Check if the box hit a solid cell.
+    #[inline]
+    pub fn hit(&self) -> bool {
+        self.cell.is_some()
+    }
+}
+
+/// Move `aabb` by `velocity` and find the first solid cell (according to `is_solid`) it hits along the way.
+/// Cells outside of the volume are never solid.
+///
+/// Touching a solid cell only counts as a hit when moving into it, so a box resting on the ground can slide along it.
+/// Cells the box already overlaps when the sweep starts are ignored, which lets boxes that ended up inside of a solid escape.
+pub fn sweep_aabb<V, F>(vol: &V, aabb: Aabb, velocity: [f64; 3], is_solid: F) -> SweepResult
+where
+    V: Volume,
+    F: Fn(&V::Item) -> bool,
+{
+    let mut result = SweepResult {
+        time: 1.0,
+        normal: [0.0; 3],
+        cell: None,
+    };
+
+    if !velocity
+        .iter()
+        .chain(&aabb.min)
+        .chain(&aabb.max)
+        .all(|c| c.is_finite())
+    {
+        return result;
+    }
+
+    // Every cell the box could touch during the sweep.
+    let end = aabb.translated(velocity);
+    let swept = Aabb::new(
+        [0, 1, 2].map(|a| aabb.min[a].min(end.min[a])),
+        [0, 1, 2].map(|a| aabb.max[a].max(end.max[a])),
+    );
+    let candidates = match swept.overlapping_cells().intersection(&vol.bounding_box()) {
+        Some(candidates) => candidates,
+        None => return result,
+    };
+
+    for cell in candidates {
+        if let Some((time, axis)) = sweep_cell(&aabb, velocity, cell) {
+            if time < result.time && vol.get(cell).is_some_and(&is_solid) {
+                let mut normal = [0.0; 3];
+                normal[axis] = -velocity[axis].signum();
+
+                result = SweepResult {
+                    time,
+                    normal,
+                    cell: Some(cell),
+                };
+            }
+        }
+    }
+
+    result
+}
+
+/// The time (from 0.0 to 1.0) and axis at which the moving box starts overlapping the cell, if it does.
+fn sweep_cell(aabb: &Aabb, velocity: [f64; 3], cell: [i64; 3]) -> Option<(f64, usize)> {
+    let mut entry = f64::NEG_INFINITY;
+    let mut exit = f64::INFINITY;
+    let mut entry_axis = 0;
+
+    for axis in 0..3 {
+        let (lo, hi) = (cell[axis] as f64, cell[axis] as f64 + 1.0);
+
+        if velocity[axis] == 0.0 {
+            // Not moving along this axis, so the box has to overlap the cell on it the whole time.
+            if aabb.max[axis] <= lo || aabb.min[axis] >= hi {
+                return None;
+            }
+            continue;
+        }
+
+        let (axis_entry, axis_exit) = if velocity[axis] > 0.0 {
+            (
+                (lo - aabb.max[axis]) / velocity[axis],
+                (hi - aabb.min[axis]) / velocity[axis],
+            )
+        } else {
+            (
+                (hi - aabb.min[axis]) / velocity[axis],
+                (lo - aabb.max[axis]) / velocity[axis],
+            )
+        };
+
+        if axis_entry > entry {
+            entry = axis_entry;
+            entry_axis = axis;
+        }
+        exit = exit.min(axis_exit);
+    }
+
+    // Entering after exiting means the box passes the cell without overlapping it. A negative entry time means
+    // the box already overlaps the cell (or it's behind the box), which isn't counted as a hit.
+    if entry >= exit || !(0.0..=1.0).contains(&entry) {
+        return None;
+    }
+
+    Some((entry, entry_axis))
+}
@@ -0,0 +1,368 @@
+//! Compact (read-only) volume representations, and automatically picking the most compact one for a volume's contents.
+
+use crate::prelude::*;
+use crate::types::ValidationError;
+
+/// The largest palette a [`PaletteVolume`] is built with by [`HeapVolume::compress`].
+const MAX_PALETTE: usize = 256;
+
+/// The X-major index (the order of [`BoundingBox`] iteration) of a localspace position.
+#[inline(always)]
+fn linear<Idx: VolumeIdx>(dims: [usize; 3], idx: Idx) -> Option<usize> {
+    let [x, y, z] = idx.array::<usize>()?;
+    if x >= dims[0] || y >= dims[1] || z >= dims[2] {
+        return None;
+    }
+
+    Some(x + dims[0] * (y + dims[1] * z))
+}
+
+#[inline(always)]
+fn usize_dims(bounds: BoundingBox) -> [usize; 3] {
+    crate::util::cast_ivec3(bounds.dimensions()).unwrap()
+}
+
+/// Run-length encoded volume. Stores runs of equal items in X-major order, so it's compact for volumes with long
+/// stretches of the same item along the X axis (e.g., layered terrain).
+#[derive(Debug, Clone, PartialEq)]
+pub struct RleVolume<T> {
+    /// The (exclusive) X-major index each run ends at, and the item of the run.
+    runs: Vec<(usize, T)>,
+    bounds: BoundingBox,
+    dims: [usize; 3],
+}
+
+impl_indexing!(T, RleVolume<T>);
+
+impl<T: Clone + PartialEq> RleVolume<T> {
+    /// Encode the items of a volume.
+    #[inline]
+    pub fn encode<V: Volume<Item = T>>(vol: &V) -> Self {
+        let mut runs: Vec<(usize, T)> = Vec::new();
+        for (i, item) in vol.iter().enumerate() {
+            match runs.last_mut() {
+                Some((end, last)) if last == item => *end = i + 1,
+                _ => runs.push((i + 1, item.clone())),
+            }
+        }
+
+        Self {
+            runs,
+            bounds: vol.bounding_box(),
+            dims: usize_dims(vol.bounding_box()),
+        }
+    }
+}
+
+impl<T> RleVolume<T> {
+    /// The amount of runs.
+    #[inline]
+    pub fn runs(&self) -> usize {
+        self.runs.len()
+    }
+}
+
+impl<T> Volume for RleVolume<T> {
+    type Item = T;
+
+    #[inline]
+    fn ls_get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        let i = linear(self.dims, idx)?;
+        let run = self.runs.partition_point(|&(end, _)| end <= i);
+
+        self.runs.get(run).map(|(_, item)| item)
+    }
+
+    #[inline]
+    fn bounding_box(&self) -> BoundingBox {
+        self.bounds
+    }
+
+    #[inline]
+    fn heap_size(&self) -> usize {
+        self.runs.capacity() * std::mem::size_of::<(usize, T)>()
+    }
+
+    #[inline]
+    fn validate(&self) -> Result<(), ValidationError> {
+        let expected = self.bounds.capacity() as usize;
+        let found = self.runs.last().map_or(0, |&(end, _)| end);
+        let increasing = self.runs.windows(2).all(|w| w[0].0 < w[1].0);
+
+        if found != expected || !increasing {
+            return Err(ValidationError::RunLengthMismatch { expected, found });
+        }
+
+        Ok(())
+    }
+}
+
+/// Palette compressed volume. Stores every distinct item once, and the items of the volume as bit-packed indices into
+/// the palette, using as few bits per item as the size of the palette allows.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaletteVolume<T> {
+    palette: Vec<T>,
+    /// Indices into the palette, `bits` bits each. Indices never straddle two words.
+    words: Vec<u64>,
+    bits: u32,
+    bounds: BoundingBox,
+    dims: [usize; 3],
+}
+
+impl_indexing!(T, PaletteVolume<T>);
+
+impl<T: Clone + PartialEq> PaletteVolume<T> {
+    /// Encode the items of a volume. Returns [`None`] if it has more than `max_palette` distinct items.
+    #[inline]
+    pub fn encode<V: Volume<Item = T>>(vol: &V, max_palette: usize) -> Option<Self> {
+        let mut palette: Vec<T> = Vec::new();
+        let mut indices = Vec::with_capacity(vol.bounding_box().capacity() as usize);
+
+        for item in vol.iter() {
+            let index = match palette.iter().position(|p| p == item) {
+                Some(index) => index,
+                None if palette.len() < max_palette => {
+                    palette.push(item.clone());
+                    palette.len() - 1
+                }
+                None => return None,
+            };
+            indices.push(index as u64);
+        }
+
+        let bits = Self::bits_for(palette.len());
+        let per_word = (64 / bits) as usize;
+        let mut words = vec![0u64; indices.len().div_ceil(per_word)];
+        for (i, index) in indices.into_iter().enumerate() {
+            words[i / per_word] |= index << ((i % per_word) as u32 * bits);
+        }
+
+        Some(Self {
+            palette,
+            words,
+            bits,
+            bounds: vol.bounding_box(),
+            dims: usize_dims(vol.bounding_box()),
+        })
+    }
+
+    /// The amount of bits used to store an index into a palette of the given length.
+    #[inline]
+    fn bits_for(len: usize) -> u32 {
+        (usize::BITS - len.saturating_sub(1).leading_zeros()).max(1)
+    }
+}
+
+impl<T> PaletteVolume<T> {
+    /// Every distinct item in the volume.
+    #[inline]
+    pub fn palette(&self) -> &[T] {
+        &self.palette
+    }
+
+    #[inline(always)]
+    fn index(&self, i: usize) -> usize {
+        let per_word = (64 / self.bits) as usize;
+        let word = self.words[i / per_word];
+        let mask = (1u64 << self.bits) - 1;
+
+        ((word >> ((i % per_word) as u32 * self.bits)) & mask) as usize
+    }
+}
+
+impl<T> Volume for PaletteVolume<T> {
+    type Item = T;
+
+    #[inline]
+    fn ls_get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        let i = linear(self.dims, idx)?;
+        self.palette.get(self.index(i))
+    }
+
+    #[inline]
+    fn bounding_box(&self) -> BoundingBox {
+        self.bounds
+    }
+
+    #[inline]
+    fn heap_size(&self) -> usize {
+        self.palette.capacity() * std::mem::size_of::<T>()
+            + self.words.capacity() * std::mem::size_of::<u64>()
+    }
+
+    #[inline]
+    fn validate(&self) -> Result<(), ValidationError> {
+        let per_word = (64 / self.bits) as usize;
+        let expected = (self.bounds.capacity() as usize).div_ceil(per_word);
+
+        if self.words.len() != expected {
+            return Err(ValidationError::StorageMismatch {
+                axis: 0,
+                expected,
+                found: self.words.len(),
+            });
+        }
+
+        let len = self.bounds.capacity() as usize;
+        match (0..len).find(|&i| self.index(i) >= self.palette.len()) {
+            Some(i) => Err(ValidationError::PaletteIndexOutOfRange {
+                index: self.index(i),
+                len: self.palette.len(),
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A volume in whichever representation is the most compact for its contents. Created with [`HeapVolume::compress`].
+///
+/// Implements [`Volume`] by forwarding to the representation, so it can be read like any other volume.
+/// To modify it, convert it back to a [`HeapVolume`] with [`CompactVolume::decompress`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum CompactVolume<T> {
+    /// Contents with no useful structure to exploit.
+    Dense(HeapVolume<T>),
+    /// Contents with long runs of equal items.
+    Rle(RleVolume<T>),
+    /// Contents with few distinct items.
+    Palette(PaletteVolume<T>),
+    /// Contents where every item is the same.
+    Uniform(UniformVolume<T>),
+}
+
+impl_indexing!(T, CompactVolume<T>);
+
+impl<T: Clone + PartialEq> HeapVolume<T> {
+    /// Analyze the contents of this volume (distinct items and runs of equal items) and convert it to the representation
+    /// that takes up the least memory. The volume is returned unchanged (as [`CompactVolume::Dense`]) if no other
+    /// representation is smaller.
+    #[inline]
+    pub fn compress(self) -> CompactVolume<T> {
+        use std::mem::size_of;
+
+        let bounds = self.bounding_box();
+        let len = bounds.capacity() as usize;
+        debug_span!("compress", %bounds);
+
+        let mut distinct: Vec<&T> = Vec::new();
+        let mut runs = 0;
+        let mut last = None;
+        for item in self.iter() {
+            if last != Some(item) {
+                runs += 1;
+                last = Some(item);
+            }
+            if distinct.len() <= MAX_PALETTE && !distinct.contains(&item) {
+                distinct.push(item);
+            }
+        }
+
+        if distinct.len() == 1 {
+            return CompactVolume::Uniform(UniformVolume::new(distinct[0].clone(), bounds));
+        }
+
+        let dense = self.heap_size();
+        let rle = runs * size_of::<(usize, T)>();
+        let palette = (distinct.len() <= MAX_PALETTE).then(|| {
+            let bits = PaletteVolume::<T>::bits_for(distinct.len());
+            distinct.len() * size_of::<T>() + len.div_ceil((64 / bits) as usize) * size_of::<u64>()
+        });
+
+        if palette.is_some_and(|palette| palette < dense && palette <= rle) {
+            CompactVolume::Palette(PaletteVolume::encode(&self, MAX_PALETTE).unwrap())
+        } else if rle < dense {
+            CompactVolume::Rle(RleVolume::encode(&self))
+        } else {
+            CompactVolume::Dense(self)
+        }
+    }
+}
+
+impl<T: Clone> CompactVolume<T> {
+    /// Convert this volume back to a [`HeapVolume`] so it can be modified.
+    #[inline]
+    pub fn decompress(self) -> HeapVolume<T> {
+        match self {
+            Self::Dense(vol) => vol,
+            Self::Uniform(vol) => vol.into_heap(),
+            Self::Rle(vol) => Self::copy(&vol),
+            Self::Palette(vol) => Self::copy(&vol),
+        }
+    }
+
+    fn copy<V: Volume<Item = T>>(vol: &V) -> HeapVolume<T> {
+        let bounds = vol.bounding_box();
+        let (min, max) = (bounds.min(), bounds.max());
+
+        let planes = (min[0]..max[0])
+            .map(|x| {
+                (min[1]..max[1])
+                    .map(|y| {
+                        (min[2]..max[2])
+                            .map(|z| vol.get([x, y, z]).unwrap().clone())
+                            .collect()
+                    })
+                    .collect()
+            })
+            .collect();
+
+        HeapVolume::from_planes(bounds, planes)
+    }
+}
+
+impl<T> Volume for CompactVolume<T> {
+    type Item = T;
+
+    #[inline]
+    fn ls_get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        match self {
+            Self::Dense(vol) => vol.ls_get(idx),
+            Self::Rle(vol) => vol.ls_get(idx),
+            Self::Palette(vol) => vol.ls_get(idx),
+            Self::Uniform(vol) => vol.ls_get(idx),
+        }
+    }
+
+    #[inline]
+    fn bounding_box(&self) -> BoundingBox {
+        match self {
+            Self::Dense(vol) => vol.bounding_box(),
+            Self::Rle(vol) => vol.bounding_box(),
+            Self::Palette(vol) => vol.bounding_box(),
+            Self::Uniform(vol) => vol.bounding_box(),
+        }
+    }
+
+    #[inline]
+    fn is_uniform(&self) -> Option<&Self::Item>
+    where
+        Self::Item: PartialEq,
+    {
+        match self {
+            Self::Dense(vol) => vol.is_uniform(),
+            Self::Rle(vol) => vol.is_uniform(),
+            Self::Palette(vol) => vol.is_uniform(),
+            Self::Uniform(vol) => Volume::is_uniform(vol),
+        }
+    }
+
+    #[inline]
+    fn heap_size(&self) -> usize {
+        match self {
+            Self::Dense(vol) => vol.heap_size(),
+            Self::Rle(vol) => vol.heap_size(),
+            Self::Palette(vol) => vol.heap_size(),
+            Self::Uniform(vol) => vol.heap_size(),
+        }
+    }
+
+    #[inline]
+    fn validate(&self) -> Result<(), ValidationError> {
+        match self {
+            Self::Dense(vol) => vol.validate(),
+            Self::Rle(vol) => vol.validate(),
+            Self::Palette(vol) => vol.validate(),
+            Self::Uniform(vol) => vol.validate(),
+        }
+    }
+}
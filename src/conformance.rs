@@ -0,0 +1,284 @@
+//! Behavioral checks every volume is expected to pass, for authors of custom volume types.
+//!
+//! Each check builds volumes with the given constructor and panics (like a failing test) if the volume doesn't behave the way
+//! the rest of this crate assumes. Use [`conformance_tests!`](crate::conformance_tests) to run all of them as unit tests,
+//! or call the individual checks from your own tests.
+//!
+//! Constructors are given the bounds of the volume to create, and may fill it with any items. The checks write their own
+//! items converted from [`u8`], so the item type only needs to implement `From<u8>` (plus [`Clone`], [`PartialEq`], and
+//! [`Debug`] for the assertions).
+
+use crate::prelude::*;
+use std::fmt::Debug;
+
+/// The bounds the checks create volumes with, including ones with a negative minimum and ones only a single item thick.
+fn test_bounds() -> [BoundingBox; 4] {
+    [
+        BoundingBox::new_origin([4, 3, 2]),
+        BoundingBox::new([-5, -1, 3], [-1, 2, 5]),
+        BoundingBox::new_origin([1, 1, 1]),
+        BoundingBox::new([-2, 7, -2], [2, 8, 2]),
+    ]
+}
+
+/// A distinct-ish item for every position, so misplaced items are noticed.
+fn item_for<T: From<u8>>(bounds: BoundingBox, pos: [i64; 3]) -> T {
+    let [x, y, z] = crate::util::sub_ivec3(pos, bounds.min());
+    T::from(((x * 31 + y * 7 + z) % 250 + 1) as u8)
+}
+
+/// Fill `vol` with [`item_for`] through [`VolumeMut::get_mut`].
+fn fill_distinct<V>(vol: &mut V)
+where
+    V: VolumeMut,
+    V::Item: From<u8>,
+{
+    let bounds = vol.bounding_box();
+    for pos in bounds {
+        *vol.get_mut(pos).unwrap() = item_for(bounds, pos);
+    }
+}
+
+/// Check that volumes have the bounds they were created with, that every position within them is accessible, and that no
+/// position outside of them is (in both worldspace and localspace).
+#[track_caller]
+pub fn check_bounds<V, F>(new: F)
+where
+    V: VolumeMut,
+    F: Fn(BoundingBox) -> V,
+{
+    for bounds in test_bounds() {
+        let mut vol = new(bounds);
+        assert_eq!(bounds, vol.bounding_box(), "volume has the wrong bounds");
+
+        for pos in bounds {
+            let ls = crate::util::sub_ivec3(pos, bounds.min());
+            assert!(
+                vol.get(pos).is_some(),
+                "{pos:?} is in {bounds} but get returned None"
+            );
+            assert!(
+                vol.get_mut(pos).is_some(),
+                "{pos:?} is in {bounds} but get_mut returned None"
+            );
+            assert!(
+                vol.ls_get(ls).is_some(),
+                "localspace {ls:?} is in bounds but ls_get returned None"
+            );
+            assert!(vol.contains(pos));
+        }
+
+        let (min, max) = (bounds.min(), bounds.max());
+        for axis in 0..3 {
+            let mut below = min;
+            below[axis] -= 1;
+            let mut above = min;
+            above[axis] = max[axis];
+
+            for pos in [below, above] {
+                assert!(
+                    vol.get(pos).is_none(),
+                    "{pos:?} is outside of {bounds} but get returned Some"
+                );
+                assert!(
+                    vol.get_mut(pos).is_none(),
+                    "{pos:?} is outside of {bounds} but get_mut returned Some"
+                );
+                assert!(!vol.contains(pos));
+            }
+
+            let ls = crate::util::sub_ivec3(above, min);
+            assert!(
+                vol.ls_get(ls).is_none(),
+                "localspace {ls:?} is out of bounds but ls_get returned Some"
+            );
+        }
+    }
+}
+
+/// Check that writes are visible to later reads of the same position and of no other position, and that
+/// [`VolumeMut::swap`] returns the previous item.
+#[track_caller]
+pub fn check_get_set<V, F>(new: F)
+where
+    V: VolumeMut,
+    V::Item: Clone + PartialEq + Debug + From<u8>,
+    F: Fn(BoundingBox) -> V,
+{
+    for bounds in test_bounds() {
+        let mut vol = new(bounds);
+        fill_distinct(&mut vol);
+
+        for pos in bounds {
+            let expected = item_for::<V::Item>(bounds, pos);
+            assert_eq!(
+                Some(&expected),
+                vol.get(pos),
+                "item at {pos:?} wasn't what was written"
+            );
+
+            let ls = crate::util::sub_ivec3(pos, bounds.min());
+            assert_eq!(
+                vol.get(pos),
+                vol.ls_get(ls),
+                "get and ls_get disagree at {pos:?}"
+            );
+        }
+
+        let pos = bounds.max().map(|c| c - 1);
+        let previous = vol.swap(pos, V::Item::from(0));
+        assert_eq!(
+            Some(item_for(bounds, pos)),
+            previous,
+            "swap didn't return the previous item"
+        );
+        assert_eq!(Some(V::Item::from(0)), vol.swap(pos, V::Item::from(255)));
+        assert_eq!(Some(&V::Item::from(255)), vol.get(pos));
+        assert_eq!(
+            None,
+            vol.swap(bounds.max(), V::Item::from(0)),
+            "swap out of bounds returned Some"
+        );
+
+        for other in bounds.into_iter().filter(|&other| other != pos) {
+            assert_eq!(
+                Some(&item_for(bounds, other)),
+                vol.get(other),
+                "writing to {pos:?} changed the item at {other:?}"
+            );
+        }
+    }
+}
+
+/// Check that [`Volume::iter`] and [`Volume::iter_indexed`] visit every position exactly once, in the same order as
+/// iterating over the volume's bounding box.
+#[track_caller]
+pub fn check_iteration_order<V, F>(new: F)
+where
+    V: VolumeMut,
+    V::Item: Clone + PartialEq + Debug + From<u8>,
+    F: Fn(BoundingBox) -> V,
+{
+    for bounds in test_bounds() {
+        let mut vol = new(bounds);
+        fill_distinct(&mut vol);
+
+        let expected = bounds
+            .into_iter()
+            .map(|pos| item_for::<V::Item>(bounds, pos))
+            .collect::<Vec<_>>();
+        let found = vol.iter().cloned().collect::<Vec<_>>();
+        assert_eq!(
+            expected, found,
+            "iter didn't follow the bounding box's order"
+        );
+
+        let indices = vol.iter_indexed().map(|(pos, _)| pos).collect::<Vec<_>>();
+        assert_eq!(
+            bounds.into_iter().collect::<Vec<_>>(),
+            indices,
+            "iter_indexed didn't follow the bounding box's order"
+        );
+        assert!(vol
+            .iter_indexed()
+            .all(|(pos, item)| Some(item) == vol.get(pos)));
+    }
+}
+
+/// Check that [`VolumeMut::insert`] copies a volume into the right place, and fails without changing anything when it
+/// doesn't fit.
+#[track_caller]
+pub fn check_insert<V, F>(new: F)
+where
+    V: VolumeMut,
+    V::Item: Clone + PartialEq + Debug + From<u8>,
+    F: Fn(BoundingBox) -> V,
+{
+    let bounds = BoundingBox::new([-3, -3, -3], [3, 3, 3]);
+    let mut vol = new(bounds);
+    fill_distinct(&mut vol);
+
+    let src_bounds = BoundingBox::new([0, 0, 0], [2, 3, 1]);
+    let mut src = HeapVolume::new(0u8, src_bounds);
+    for pos in src_bounds {
+        src[pos] = 200 + pos[1] as u8 * 2 + pos[0] as u8;
+    }
+
+    let at = [-1, -3, 2];
+    let report = vol
+        .insert(at, &src)
+        .expect("inserting a volume that fits failed");
+    assert_eq!(src_bounds.capacity() as u64, report.written as u64);
+
+    for pos in bounds {
+        let expected = match src.get(crate::util::sub_ivec3(pos, at)) {
+            Some(&item) => V::Item::from(item),
+            None => item_for(bounds, pos),
+        };
+        assert_eq!(
+            Some(&expected),
+            vol.get(pos),
+            "wrong item at {pos:?} after inserting"
+        );
+    }
+
+    let before = vol.iter().cloned().collect::<Vec<_>>();
+    assert!(
+        vol.insert([2, 0, 0], &src).is_err(),
+        "inserting a volume that doesn't fit succeeded"
+    );
+    assert_eq!(
+        before,
+        vol.iter().cloned().collect::<Vec<_>>(),
+        "failed insert changed the volume"
+    );
+}
+
+/// Generate unit tests running every check in [`crate::conformance`] against a volume type, given a constructor taking
+/// the volume's [`BoundingBox`].
+///
+/// The tests are put in a module named `conformance`, or the module name given as the first argument.
+///
+/// ```
+/// use volume::prelude::*;
+///
+/// volume::conformance_tests!(HeapVolume<u16>, |bounds| HeapVolume::new(0, bounds));
+/// volume::conformance_tests!(wide, HeapVolume<u64>, |bounds| HeapVolume::new(0, bounds));
+/// # fn main() {}
+/// ```
+#[macro_export]
+macro_rules! conformance_tests {
+    ($module:ident, $backend:ty, $constructor:expr) => {
+        #[allow(unused_imports)]
+        mod $module {
+            use super::*;
+
+            fn new(bounds: $crate::prelude::BoundingBox) -> $backend {
+                ($constructor)(bounds)
+            }
+
+            #[test]
+            fn bounds() {
+                $crate::conformance::check_bounds(new);
+            }
+
+            #[test]
+            fn get_set() {
+                $crate::conformance::check_get_set(new);
+            }
+
+            #[test]
+            fn iteration_order() {
+                $crate::conformance::check_iteration_order(new);
+            }
+
+            #[test]
+            fn insert() {
+                $crate::conformance::check_insert(new);
+            }
+        }
+    };
+    ($backend:ty, $constructor:expr) => {
+        $crate::conformance_tests!(conformance, $backend, $constructor);
+    };
+}
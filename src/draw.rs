@@ -0,0 +1,294 @@
+//! Rasterizing simple shapes and text into volumes, see [`VolumeMut::draw_line`], [`VolumeMut::draw_box_outline`],
+//! [`VolumeMut::draw_box_filled`], and [`VolumeMut::draw_text`].
+
+use crate::prelude::*;
+use std::collections::BTreeMap;
+
+/// Iterator over the positions of a rasterized line, from the start to the end (both inclusive). Returned by [`line()`].
+#[derive(Debug, Clone)]
+pub struct LineIterator {
+    pos: [i64; 3],
+    deltas: [i128; 3],
+    steps: [i64; 3],
+    /// The axis the line moves along the most, which it takes a step along for every position.
+    major: usize,
+    errors: [i128; 3],
+    remaining: u64,
+}
+
+/// Rasterize the line from `a` to `b` using 3D Bresenham. Every position differs from the previous one by exactly one step along
+/// the axis the line moves along the most, and by at most one step along the other axes.
+#[inline]
+pub fn line(a: [i64; 3], b: [i64; 3]) -> LineIterator {
+    let deltas = [0, 1, 2].map(|axis| (b[axis] as i128 - a[axis] as i128).abs());
+    let steps = [0, 1, 2].map(|axis| (b[axis] - a[axis]).signum());
+    let major = (0..3).max_by_key(|&axis| (deltas[axis], 2 - axis)).unwrap();
+
+    LineIterator {
+        pos: a,
+        deltas,
+        steps,
+        major,
+        errors: deltas.map(|d| 2 * d - deltas[major]),
+        remaining: deltas[major] as u64 + 1,
+    }
+}
+
+impl Iterator for LineIterator {
+    type Item = [i64; 3];
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let out = self.pos;
+        self.remaining -= 1;
+        if self.remaining > 0 {
+            for axis in (0..3).filter(|&axis| axis != self.major) {
+                if self.errors[axis] >= 0 {
+                    self.pos[axis] += self.steps[axis];
+                    self.errors[axis] -= 2 * self.deltas[self.major];
+                }
+                self.errors[axis] += 2 * self.deltas[axis];
+            }
+            self.pos[self.major] += self.steps[self.major];
+        }
+
+        Some(out)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = usize::try_from(self.remaining).ok();
+        (remaining.unwrap_or(usize::MAX), remaining)
+    }
+}
+
+/// Every position on the 12 edges of `bounds`, each exactly once. Empty if `bounds` is empty.
+#[inline]
+pub fn box_outline(bounds: BoundingBox) -> Vec<[i64; 3]> {
+    if bounds.is_empty() {
+        return Vec::new();
+    }
+
+    let (min, max) = (bounds.min(), bounds.max());
+    let mut positions = Vec::new();
+
+    for axis in 0..3 {
+        let [a, b] = [(axis + 1) % 3, (axis + 2) % 3];
+        // Edges along X include the corners, the edges along the other axes start and end just inside of them.
+        let range = match axis {
+            0 => min[axis]..max[axis],
+            _ => min[axis] + 1..max[axis] - 1,
+        };
+
+        let mut corners = Vec::with_capacity(4);
+        for ca in [min[a], max[a] - 1] {
+            for cb in [min[b], max[b] - 1] {
+                if !corners.contains(&(ca, cb)) {
+                    corners.push((ca, cb));
+                }
+            }
+        }
+
+        for (ca, cb) in corners {
+            for c in range.clone() {
+                let mut pos = [0; 3];
+                pos[axis] = c;
+                pos[a] = ca;
+                pos[b] = cb;
+                positions.push(pos);
+            }
+        }
+    }
+
+    positions
+}
+
+/// The glyphs of [`VoxelFont::tiny`], as rows from top to bottom with the leftmost column in the highest bit.
+#[rustfmt::skip]
+const TINY: &[(char, [u32; 5])] = &[
+    ('0', [0b111, 0b101, 0b101, 0b101, 0b111]),
+    ('1', [0b010, 0b110, 0b010, 0b010, 0b111]),
+    ('2', [0b111, 0b001, 0b111, 0b100, 0b111]),
+    ('3', [0b111, 0b001, 0b111, 0b001, 0b111]),
+    ('4', [0b101, 0b101, 0b111, 0b001, 0b001]),
+    ('5', [0b111, 0b100, 0b111, 0b001, 0b111]),
+    ('6', [0b111, 0b100, 0b111, 0b101, 0b111]),
+    ('7', [0b111, 0b001, 0b001, 0b001, 0b001]),
+    ('8', [0b111, 0b101, 0b111, 0b101, 0b111]),
+    ('9', [0b111, 0b101, 0b111, 0b001, 0b111]),
+    ('A', [0b010, 0b101, 0b111, 0b101, 0b101]),
+    ('B', [0b110, 0b101, 0b110, 0b101, 0b110]),
+    ('C', [0b011, 0b100, 0b100, 0b100, 0b011]),
+    ('D', [0b110, 0b101, 0b101, 0b101, 0b110]),
+    ('E', [0b111, 0b100, 0b110, 0b100, 0b111]),
+    ('F', [0b111, 0b100, 0b110, 0b100, 0b100]),
+    ('G', [0b011, 0b100, 0b101, 0b101, 0b011]),
+    ('H', [0b101, 0b101, 0b111, 0b101, 0b101]),
+    ('I', [0b111, 0b010, 0b010, 0b010, 0b111]),
+    ('J', [0b001, 0b001, 0b001, 0b101, 0b010]),
+    ('K', [0b101, 0b101, 0b110, 0b101, 0b101]),
+    ('L', [0b100, 0b100, 0b100, 0b100, 0b111]),
+    ('M', [0b101, 0b111, 0b111, 0b101, 0b101]),
+    ('N', [0b110, 0b101, 0b101, 0b101, 0b101]),
+    ('O', [0b010, 0b101, 0b101, 0b101, 0b010]),
+    ('P', [0b110, 0b101, 0b110, 0b100, 0b100]),
+    ('Q', [0b010, 0b101, 0b101, 0b110, 0b011]),
+    ('R', [0b110, 0b101, 0b110, 0b101, 0b101]),
+    ('S', [0b011, 0b100, 0b010, 0b001, 0b110]),
+    ('T', [0b111, 0b010, 0b010, 0b010, 0b010]),
+    ('U', [0b101, 0b101, 0b101, 0b101, 0b111]),
+    ('V', [0b101, 0b101, 0b101, 0b101, 0b010]),
+    ('W', [0b101, 0b101, 0b111, 0b111, 0b101]),
+    ('X', [0b101, 0b101, 0b010, 0b101, 0b101]),
+    ('Y', [0b101, 0b101, 0b010, 0b010, 0b010]),
+    ('Z', [0b111, 0b001, 0b010, 0b100, 0b111]),
+    ('-', [0b000, 0b000, 0b111, 0b000, 0b000]),
+    (':', [0b000, 0b010, 0b000, 0b010, 0b000]),
+    ('.', [0b000, 0b000, 0b000, 0b000, 0b010]),
+    (',', [0b000, 0b000, 0b000, 0b010, 0b100]),
+    ('!', [0b010, 0b010, 0b010, 0b000, 0b010]),
+    ('?', [0b110, 0b001, 0b010, 0b000, 0b010]),
+    ('/', [0b001, 0b001, 0b010, 0b100, 0b100]),
+    ('(', [0b001, 0b010, 0b010, 0b010, 0b001]),
+    (')', [0b100, 0b010, 0b010, 0b010, 0b100]),
+    ('+', [0b000, 0b010, 0b111, 0b010, 0b000]),
+    ('=', [0b000, 0b111, 0b000, 0b111, 0b000]),
+    ('_', [0b000, 0b000, 0b000, 0b000, 0b111]),
+];
+
+/// The plane text is drawn in by [`VolumeMut::draw_text`], named after the axis text runs along followed by the axis that points up
+/// in the glyphs.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum AxisPlane {
+    /// Text runs along +X, glyphs point up along +Y.
+    #[default]
+    Xy,
+    /// Text runs along +X, glyphs point up along +Z.
+    Xz,
+    /// Text runs along +Z, glyphs point up along +Y.
+    Zy,
+}
+
+impl AxisPlane {
+    /// The axis text runs along and the axis that points up in the glyphs (0 = X, 1 = Y, 2 = Z).
+    #[inline]
+    pub fn axes(self) -> [usize; 2] {
+        match self {
+            Self::Xy => [0, 1],
+            Self::Xz => [0, 2],
+            Self::Zy => [2, 1],
+        }
+    }
+}
+
+/// A monospaced bitmap font for drawing text into volumes with [`VolumeMut::draw_text`].
+///
+/// Glyphs are stored as rows from top to bottom, with the leftmost column of each row in its highest bit
+/// (i.e., bit `width - 1`). Characters without a glyph are drawn as blank space.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VoxelFont {
+    width: u32,
+    height: u32,
+    glyphs: BTreeMap<char, Box<[u32]>>,
+}
+
+impl VoxelFont {
+    /// Create a font without any glyphs.
+    ///
+    /// # Panics
+    /// Panics if `width` isn't between 1 and 32, or `height` is 0.
+    #[inline]
+    pub fn new(width: u32, height: u32) -> Self {
+        assert!(
+            (1..=32).contains(&width),
+            "glyph width must be between 1 and 32"
+        );
+        assert!(height > 0, "glyph height must be at least 1");
+
+        Self {
+            width,
+            height,
+            glyphs: BTreeMap::new(),
+        }
+    }
+
+    /// A tiny built-in 3×5 font with digits, uppercase letters (also used for lowercase letters), and some punctuation.
+    #[inline]
+    pub fn tiny() -> Self {
+        let mut font = Self::new(3, 5);
+        for (ch, rows) in TINY {
+            font.insert(*ch, rows);
+        }
+
+        font
+    }
+
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Add a glyph to this font (replacing any existing glyph for the character), given as rows from top to bottom.
+    ///
+    /// # Panics
+    /// Panics if the amount of rows isn't the font's height.
+    #[inline]
+    pub fn insert(&mut self, ch: char, rows: &[u32]) {
+        assert_eq!(
+            rows.len(),
+            self.height as usize,
+            "glyph must have one row for every unit of height"
+        );
+
+        self.glyphs.insert(ch, rows.into());
+    }
+
+    /// The glyph for a character. Falls back to the glyph of the uppercase character if there's none for the character itself.
+    #[inline]
+    pub fn glyph(&self, ch: char) -> Option<&[u32]> {
+        self.glyphs
+            .get(&ch)
+            .or_else(|| self.glyphs.get(&ch.to_ascii_uppercase()))
+            .map(|rows| &rows[..])
+    }
+
+    /// Every position covered by `text` drawn in `plane`, with the bottom left corner of the first glyph at `origin`.
+    /// Glyphs are separated by one column of space, and every `\n` starts a new line below the previous one (again
+    /// separated by one row of space).
+    #[inline]
+    pub fn rasterize(&self, origin: [i64; 3], plane: AxisPlane, text: &str) -> Vec<[i64; 3]> {
+        let [right, up] = plane.axes();
+        let mut positions = Vec::new();
+
+        for (line, chars) in text.split('\n').enumerate() {
+            let baseline = -(line as i64) * (self.height as i64 + 1);
+
+            for (column, ch) in chars.chars().enumerate() {
+                let Some(rows) = self.glyph(ch) else {
+                    continue;
+                };
+                let left = column as i64 * (self.width as i64 + 1);
+
+                for (row, &bits) in rows.iter().enumerate() {
+                    for x in (0..self.width).filter(|&x| bits & (1 << (self.width - 1 - x)) != 0) {
+                        let mut pos = origin;
+                        pos[right] += left + x as i64;
+                        pos[up] += baseline + (self.height as usize - 1 - row) as i64;
+                        positions.push(pos);
+                    }
+                }
+            }
+        }
+
+        positions
+    }
+}
@@ -0,0 +1,50 @@
+//! Every public item of this crate in one place, for when importing from the individual modules gets tedious.
+//! Typical use only needs [`crate::prelude`].
+
+pub use crate::cache::*;
+pub use crate::chunked::*;
+pub use crate::collide::*;
+pub use crate::compact::*;
+pub use crate::draw::*;
+pub use crate::flags::*;
+pub use crate::generate::*;
+pub use crate::geometry::*;
+pub use crate::grow::*;
+pub use crate::layered::*;
+pub use crate::math::*;
+pub use crate::meshed::*;
+pub use crate::migrate::*;
+pub use crate::morph::*;
+pub use crate::multi::*;
+pub use crate::observe::*;
+pub use crate::occupancy::*;
+pub use crate::pool::*;
+pub use crate::prelude::*;
+pub use crate::promote::*;
+pub use crate::registry::*;
+pub use crate::scrolling::*;
+pub use crate::selection::*;
+pub use crate::sequence::*;
+pub use crate::snapshot::*;
+pub use crate::spaces::*;
+pub use crate::sparse::*;
+pub use crate::stencil::*;
+pub use crate::tile::*;
+pub use crate::topology::*;
+pub use crate::traits::*;
+pub use crate::types::*;
+pub use crate::view::*;
+pub use crate::visibility::*;
+
+#[cfg(feature = "bevy")]
+pub use crate::bevy_support::*;
+#[cfg(feature = "wgpu")]
+pub use crate::gpu::*;
+#[cfg(feature = "testing")]
+pub use crate::mock::*;
+#[cfg(feature = "rand")]
+pub use crate::random::*;
+#[cfg(feature = "shared")]
+pub use crate::shared::*;
+#[cfg(feature = "sim")]
+pub use crate::sim::*;
@@ -0,0 +1,181 @@
+//! Composable world generation. A [`VolumeGenerator`] produces the items for any requested region,
+//! and generators can be combined into pipelines with the combinators on the trait.
+
+use crate::cache::{CacheCapacity, VolumeCache};
+use crate::prelude::*;
+use std::sync::Mutex;
+
+/// Produces volumes for arbitrary regions of a world.
+///
+/// Generators must be deterministic: generating the same bounds twice must give the same items,
+/// and the item at a position must not depend on which bounds it was generated as part of.
+/// This is what allows a world to be generated chunk by chunk (and the output to be cached) without seams.
+pub trait VolumeGenerator {
+    type Item;
+
+    /// Generate the items within `bounds`.
+    fn generate(&self, bounds: BoundingBox) -> HeapVolume<Self::Item>;
+
+    /// Run `stage` on every volume generated by this generator, e.g. to carve caves into terrain or place decorations.
+    #[inline]
+    fn then<F>(self, stage: F) -> Then<Self, F>
+    where
+        Self: Sized,
+        F: Fn(&mut HeapVolume<Self::Item>),
+    {
+        Then { base: self, stage }
+    }
+
+    /// Place the items generated by `top` over the items generated by this generator, wherever `top` generates [`Some`].
+    #[inline]
+    fn overlay<G>(self, top: G) -> Overlay<Self, G>
+    where
+        Self: Sized,
+        G: VolumeGenerator<Item = Option<Self::Item>>,
+    {
+        Overlay { base: self, top }
+    }
+
+    /// Remember the volumes generated by this generator, so that generating the same bounds again is just a copy.
+    /// At most `capacity` worth of volumes are remembered, the least recently generated ones are forgotten first.
+    #[inline]
+    fn cached(self, capacity: CacheCapacity) -> Cached<Self>
+    where
+        Self: Sized,
+        Self::Item: Clone,
+    {
+        Cached {
+            base: self,
+            cache: Mutex::new(VolumeCache::new(capacity)),
+        }
+    }
+}
+
+impl<T, F> VolumeGenerator for F
+where
+    F: Fn(BoundingBox) -> HeapVolume<T>,
+{
+    type Item = T;
+
+    #[inline]
+    fn generate(&self, bounds: BoundingBox) -> HeapVolume<T> {
+        self(bounds)
+    }
+}
+
+/// Generator computing every item independently from its worldspace position. Created with [`per_position`].
+#[derive(Debug, Copy, Clone)]
+pub struct PerPosition<F>(F);
+
+/// Create a generator which computes the item at every position with `f`.
+#[inline]
+pub fn per_position<T, F>(f: F) -> PerPosition<F>
+where
+    T: Clone + Default,
+    F: Fn([i64; 3]) -> T,
+{
+    PerPosition(f)
+}
+
+impl<T, F> VolumeGenerator for PerPosition<F>
+where
+    T: Clone + Default,
+    F: Fn([i64; 3]) -> T,
+{
+    type Item = T;
+
+    #[inline]
+    fn generate(&self, bounds: BoundingBox) -> HeapVolume<T> {
+        let mut vol = HeapVolume::new(T::default(), bounds);
+        for idx in bounds {
+            vol[idx] = (self.0)(idx);
+        }
+
+        vol
+    }
+}
+
+/// Created with [`VolumeGenerator::then`].
+#[derive(Debug, Clone)]
+pub struct Then<G, F> {
+    base: G,
+    stage: F,
+}
+
+impl<G, F> VolumeGenerator for Then<G, F>
+where
+    G: VolumeGenerator,
+    F: Fn(&mut HeapVolume<G::Item>),
+{
+    type Item = G::Item;
+
+    #[inline]
+    fn generate(&self, bounds: BoundingBox) -> HeapVolume<Self::Item> {
+        let mut vol = self.base.generate(bounds);
+        (self.stage)(&mut vol);
+        vol
+    }
+}
+
+/// Created with [`VolumeGenerator::overlay`].
+#[derive(Debug, Clone)]
+pub struct Overlay<G, T> {
+    base: G,
+    top: T,
+}
+
+impl<G, T> VolumeGenerator for Overlay<G, T>
+where
+    G: VolumeGenerator,
+    T: VolumeGenerator<Item = Option<G::Item>>,
+{
+    type Item = G::Item;
+
+    #[inline]
+    fn generate(&self, bounds: BoundingBox) -> HeapVolume<Self::Item> {
+        let mut vol = self.base.generate(bounds);
+        let mut top = self.top.generate(bounds);
+
+        for idx in bounds {
+            if let Some(item) = top[idx].take() {
+                vol[idx] = item;
+            }
+        }
+
+        vol
+    }
+}
+
+/// Created with [`VolumeGenerator::cached`].
+pub struct Cached<G: VolumeGenerator> {
+    base: G,
+    cache: Mutex<VolumeCache<BoundingBox, HeapVolume<G::Item>>>,
+}
+
+impl<G: VolumeGenerator> Cached<G> {
+    /// Forget every remembered volume.
+    #[inline]
+    pub fn clear(&self) {
+        self.cache.lock().unwrap().clear();
+    }
+}
+
+impl<G> VolumeGenerator for Cached<G>
+where
+    G: VolumeGenerator,
+    G::Item: Clone,
+{
+    type Item = G::Item;
+
+    #[inline]
+    fn generate(&self, bounds: BoundingBox) -> HeapVolume<Self::Item> {
+        if let Some(vol) = self.cache.lock().unwrap().get(&bounds) {
+            return vol.clone();
+        }
+
+        // The lock isn't held while generating, so other threads can use the cache in the meantime.
+        let vol = self.base.generate(bounds);
+        self.cache.lock().unwrap().insert(bounds, vol.clone());
+        vol
+    }
+}
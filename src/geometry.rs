@@ -0,0 +1,209 @@
+//! Geometric queries on bounding boxes, such as plane and frustum culling and sphere iteration.
+//!
+//! For these queries a [`BoundingBox`] is treated as the continuous region of space covered by its cells,
+//! i.e. the box spanning from `min` to `max` (where `max` is the far corner of the last cell).
+//! The cell at index `[x, y, z]` covers the unit cube from `(x, y, z)` to `(x + 1, y + 1, z + 1)` and has its center at `(x + 0.5, y + 0.5, z + 0.5)`.
+
+use crate::prelude::*;
+use crate::types::BoundingBoxIterator;
+use std::ops::Range;
+
+/// A plane described by the equation `normal · p + d = 0`. Points where `normal · p + d > 0` are on the plane's positive side.
+///
+/// The normal doesn't have to be normalized, but [`Plane::distance`] only returns the true distance if it is.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Plane {
+    pub normal: [f64; 3],
+    pub d: f64,
+}
+
+impl Plane {
+    #[inline]
+    pub const fn new(normal: [f64; 3], d: f64) -> Self {
+        Self { normal, d }
+    }
+
+    /// Signed distance from the plane to the point. Positive if the point is on the positive side of the plane.
+    #[inline]
+    pub fn distance(&self, point: [f64; 3]) -> f64 {
+        let [nx, ny, nz] = self.normal;
+        nx * point[0] + ny * point[1] + nz * point[2] + self.d
+    }
+}
+
+impl BoundingBox {
+    /// The corner of this box furthest along `normal` (the "positive vertex") and the corner furthest against it (the "negative vertex").
+    #[inline]
+    fn extreme_corners(&self, normal: [f64; 3]) -> ([f64; 3], [f64; 3]) {
+        let min = self.min().map(|c| c as f64);
+        let max = self.max().map(|c| c as f64);
+
+        let mut positive = min;
+        let mut negative = max;
+        for axis in 0..3 {
+            if normal[axis] >= 0.0 {
+                positive[axis] = max[axis];
+                negative[axis] = min[axis];
+            }
+        }
+
+        (positive, negative)
+    }
+
+    /// Check if the plane `normal · p + d = 0` passes through this box (including touching its surface).
+    #[inline]
+    pub fn intersects_plane(&self, normal: [f64; 3], d: f64) -> bool {
+        let plane = Plane::new(normal, d);
+        let (positive, negative) = self.extreme_corners(normal);
+
+        plane.distance(positive) >= 0.0 && plane.distance(negative) <= 0.0
+    }
+
+    /// Check if any part of this box is inside the frustum described by `planes`.
+    /// The planes' normals must point into the frustum, so that the inside of the frustum is on the positive side of every plane.
+    ///
+    /// This is the usual conservative culling test: a box is only rejected if it's entirely on the negative side of at least one plane,
+    /// so a few boxes near the frustum's corners may be reported as visible even though they're outside of it.
+    #[inline]
+    pub fn intersects_frustum(&self, planes: &[Plane; 6]) -> bool {
+        planes.iter().all(|plane| {
+            let (positive, _) = self.extreme_corners(plane.normal);
+            plane.distance(positive) >= 0.0
+        })
+    }
+
+    /// Iterate over the positions in this box whose cell centers are within `radius` of `center` (inclusive).
+    ///
+    /// Only the part of this box enclosing the sphere is scanned, and every row along the X axis is clipped to the sphere
+    /// directly instead of testing each position, so the cost is proportional to the sphere's volume rather than the box's.
+    /// Positions are yielded in X-major order. A negative or non-finite radius or center yields nothing.
+    #[inline]
+    pub fn iter_within_sphere(&self, center: [f64; 3], radius: f64) -> SphereIterator {
+        SphereIterator::new(*self, center, radius)
+    }
+}
+
+/// Iterator over the positions in a [`BoundingBox`] whose cell centers are within a sphere.
+/// Returned by [`BoundingBox::iter_within_sphere`].
+#[derive(Debug, Clone)]
+pub struct SphereIterator {
+    center: [f64; 3],
+    radius_squared: f64,
+    /// The range of X coordinates of the enclosing box, rows are clipped to this.
+    x_bounds: Range<i64>,
+    /// One position for each row along the X axis in the enclosing box (with X fixed to the box's minimum).
+    rows: BoundingBoxIterator,
+    row: [i64; 2],
+    current: Range<i64>,
+}
+
+impl SphereIterator {
+    fn new(bounds: BoundingBox, center: [f64; 3], radius: f64) -> Self {
+        let valid = radius >= 0.0 && radius.is_finite() && center.iter().all(|c| c.is_finite());
+
+        // The smallest box containing every cell whose center may be in the sphere.
+        let enclosing = valid
+            .then(|| {
+                let min = center.map(|c| (c - radius - 0.5).ceil() as i64);
+                let max = center.map(|c| (c + radius - 0.5).floor() as i64 + 1);
+                BoundingBox::new(min, max).intersection(&bounds)
+            })
+            .flatten()
+            .filter(|bb| !bb.is_empty());
+
+        let (x_bounds, rows) = match enclosing {
+            Some(bb) => {
+                let (min, max) = (bb.min(), bb.max());
+                (
+                    min[0]..max[0],
+                    BoundingBox::new(min, [min[0] + 1, max[1], max[2]]).into_iter(),
+                )
+            }
+            None => (0..0, BoundingBox::new_origin([0, 0, 0]).into_iter()),
+        };
+
+        Self {
+            center,
+            radius_squared: radius * radius,
+            x_bounds,
+            rows,
+            row: [0, 0],
+            current: 0..0,
+        }
+    }
+
+    #[inline]
+    fn inside(&self, x: i64, [y, z]: [i64; 2]) -> bool {
+        let d = [
+            x as f64 + 0.5 - self.center[0],
+            y as f64 + 0.5 - self.center[1],
+            z as f64 + 0.5 - self.center[2],
+        ];
+
+        d[0] * d[0] + d[1] * d[1] + d[2] * d[2] <= self.radius_squared
+    }
+
+    /// The range of X coordinates in the given row whose cell centers are inside the sphere.
+    fn row_span(&self, row: [i64; 2]) -> Range<i64> {
+        let dy = row[0] as f64 + 0.5 - self.center[1];
+        let dz = row[1] as f64 + 0.5 - self.center[2];
+        let rem = self.radius_squared - dy * dy - dz * dz;
+        if rem < 0.0 {
+            return 0..0;
+        }
+
+        let half = rem.sqrt();
+        let mut start = (self.center[0] - half - 0.5).ceil() as i64;
+        let mut end = (self.center[0] + half - 0.5).floor() as i64 + 1;
+
+        // The square root may be off by a rounding error, so fix up the ends of the span with the exact test.
+        if self.inside(start - 1, row) {
+            start -= 1;
+        } else if start < end && !self.inside(start, row) {
+            start += 1;
+        }
+        if self.inside(end, row) {
+            end += 1;
+        } else if start < end && !self.inside(end - 1, row) {
+            end -= 1;
+        }
+
+        start.max(self.x_bounds.start)..end.min(self.x_bounds.end)
+    }
+}
+
+impl Iterator for SphereIterator {
+    type Item = [i64; 3];
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(x) = self.current.next() {
+                return Some([x, self.row[0], self.row[1]]);
+            }
+
+            let [_, y, z] = self.rows.next()?;
+            self.row = [y, z];
+            self.current = self.row_span(self.row);
+        }
+    }
+}
+
+impl std::iter::FusedIterator for SphereIterator {}
+
+/// Iterator over the positions and items of a volume whose cell centers are within a sphere.
+/// Returned by [`Volume::iter_sphere`].
+pub struct VolumeSphereIterator<'a, Vol: Volume> {
+    pub(crate) volume: &'a Vol,
+    pub(crate) indices: SphereIterator,
+}
+
+impl<'a, Vol: Volume> Iterator for VolumeSphereIterator<'a, Vol> {
+    type Item = ([i64; 3], &'a <Vol as Volume>::Item);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let idx = self.indices.next()?;
+        Some((idx, self.volume.get(idx)?))
+    }
+}
@@ -0,0 +1,137 @@
+//! Uploading volumes to the GPU with wgpu. Requires the `wgpu` feature.
+//!
+//! Items are uploaded in X-major order (the order in which [`BoundingBox`] iterates), which is also the order of texels in a 3D texture:
+//! the item at localspace index `[x, y, z]` becomes the texel at `(x, y, z)`.
+
+use crate::prelude::*;
+use crate::types::TextureError;
+use bytemuck::Pod;
+
+/// The size of the texture matching the bounds.
+#[inline]
+pub fn texture_extent(bounds: BoundingBox) -> Result<wgpu::Extent3d, TextureError> {
+    let [x, y, z] =
+        crate::util::cast_ivec3::<u32, _>(bounds.dimensions()).ok_or(TextureError::TooLarge)?;
+
+    Ok(wgpu::Extent3d {
+        width: x,
+        height: y,
+        depth_or_array_layers: z,
+    })
+}
+
+/// Get the items of a volume as bytes laid out like the texels of a 3D texture, along with the layout describing them.
+///
+/// If `aligned` is true, every row of texels is padded to a multiple of [`wgpu::COPY_BYTES_PER_ROW_ALIGNMENT`] bytes,
+/// which is required when copying from a buffer to a texture. [`wgpu::Queue::write_texture`] doesn't need the padding.
+#[inline]
+pub fn texture_data<V>(
+    vol: &V,
+    aligned: bool,
+) -> Result<(Vec<u8>, wgpu::ImageDataLayout), TextureError>
+where
+    V: Volume,
+    V::Item: Pod,
+{
+    debug_span!("texture_data", bounds = %vol.bounding_box(), aligned);
+
+    let extent = texture_extent(vol.bounding_box())?;
+    let row = (extent.width as usize)
+        .checked_mul(std::mem::size_of::<V::Item>())
+        .ok_or(TextureError::TooLarge)?;
+    let padded_row = if aligned {
+        let alignment = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as usize;
+        row.div_ceil(alignment) * alignment
+    } else {
+        row
+    };
+    let len = padded_row
+        .checked_mul(extent.height as usize)
+        .and_then(|n| n.checked_mul(extent.depth_or_array_layers as usize))
+        .ok_or(TextureError::TooLarge)?;
+    debug_event!(bytes = len as u64, "packing texture data");
+
+    let mut data = Vec::with_capacity(len);
+    for z in 0..extent.depth_or_array_layers as u64 {
+        for y in 0..extent.height as u64 {
+            for x in 0..extent.width as u64 {
+                data.extend_from_slice(bytemuck::bytes_of(vol.ls_get([x, y, z]).unwrap()));
+            }
+            data.resize(data.len() + padded_row - row, 0);
+        }
+    }
+
+    let layout = wgpu::ImageDataLayout {
+        offset: 0,
+        bytes_per_row: Some(u32::try_from(padded_row).map_err(|_| TextureError::TooLarge)?),
+        rows_per_image: Some(extent.height),
+    };
+
+    Ok((data, layout))
+}
+
+#[inline]
+pub(crate) fn upload_to_buffer<V>(
+    vol: &V,
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    usage: wgpu::BufferUsages,
+    aligned: bool,
+) -> Result<(wgpu::Buffer, wgpu::ImageDataLayout), TextureError>
+where
+    V: Volume,
+    V::Item: Pod,
+{
+    let (mut data, layout) = texture_data(vol, aligned)?;
+
+    // Buffer writes must be a multiple of 4 bytes long.
+    let alignment = wgpu::COPY_BUFFER_ALIGNMENT as usize;
+    data.resize(data.len().div_ceil(alignment) * alignment, 0);
+
+    let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: data.len() as u64,
+        usage: usage | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    queue.write_buffer(&buffer, 0, &data);
+
+    Ok((buffer, layout))
+}
+
+#[inline]
+pub(crate) fn write_texture_3d<V>(
+    vol: &V,
+    queue: &wgpu::Queue,
+    texture: &wgpu::Texture,
+) -> Result<(), TextureError>
+where
+    V: Volume,
+    V::Item: Pod,
+{
+    if texture.dimension() != wgpu::TextureDimension::D3 {
+        return Err(TextureError::NotThreeDimensional);
+    }
+
+    let texel = texture.format().block_copy_size(None);
+    if texel != Some(std::mem::size_of::<V::Item>() as u32) {
+        return Err(TextureError::TexelSizeMismatch {
+            item: std::mem::size_of::<V::Item>(),
+            texel,
+        });
+    }
+
+    let extent = texture_extent(vol.bounding_box())?;
+    let size = texture.size();
+    if extent != size {
+        return Err(TextureError::SizeMismatch {
+            volume: [extent.width, extent.height, extent.depth_or_array_layers],
+            texture: [size.width, size.height, size.depth_or_array_layers],
+        });
+    }
+
+    let (data, layout) = texture_data(vol, false)?;
+    queue.write_texture(texture.as_image_copy(), &data, layout, extent);
+
+    Ok(())
+}
@@ -0,0 +1,65 @@
+//! Growing regions of connected cells from seed points, with a limit on how much work a single call may do.
+
+use crate::prelude::*;
+use std::collections::{HashSet, VecDeque};
+
+/// The result of [`region_grow`].
+#[derive(Debug, Clone)]
+pub struct RegionGrowth {
+    /// Every cell that was reached.
+    pub region: Selection,
+    /// `true` if the growth was stopped by the cell budget, i.e. there were more cells left that the region could have grown into.
+    pub truncated: bool,
+}
+
+/// Grow a region from `seeds` into every face-adjacent cell for which `pred` returns `true`, stopping once the region has
+/// `max_cells` cells. Seeds outside of the volume or not matching `pred` are skipped.
+///
+/// Cells are reached in breadth-first order from the seeds (in the order they're given), so a truncated region always
+/// contains the cells closest to the seeds, and growing with the same inputs always gives the same region.
+/// Growth can be continued in a later call by seeding it with the region's cells and a larger budget.
+pub fn region_grow<V, I, P>(vol: &V, seeds: I, mut pred: P, max_cells: usize) -> RegionGrowth
+where
+    V: Volume,
+    I: IntoIterator<Item = [i64; 3]>,
+    P: FnMut(&V::Item) -> bool,
+{
+    debug_span!("region_grow", bounds = %vol.bounding_box(), max_cells);
+
+    let mut visited = HashSet::new();
+    let mut queue = VecDeque::new();
+    let mut truncated = false;
+
+    let mut visit = |pos: [i64; 3], visited: &mut HashSet<[i64; 3]>, queue: &mut VecDeque<_>| {
+        if visited.contains(&pos) || !vol.get(pos).is_some_and(&mut pred) {
+            return true;
+        }
+        if visited.len() >= max_cells {
+            return false;
+        }
+
+        visited.insert(pos);
+        queue.push_back(pos);
+        true
+    };
+
+    let mut seeds = seeds.into_iter();
+    if !seeds.all(|seed| visit(seed, &mut visited, &mut queue)) {
+        queue.clear();
+        truncated = true;
+    }
+
+    'grow: while let Some(pos) = queue.pop_front() {
+        for neighbor in pos.face_neighbors() {
+            if !visit(neighbor, &mut visited, &mut queue) {
+                truncated = true;
+                break 'grow;
+            }
+        }
+    }
+
+    RegionGrowth {
+        region: Selection::from_voxels(visited),
+        truncated,
+    }
+}
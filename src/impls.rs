@@ -1,5 +1,14 @@
 use crate::prelude::*;
+use crate::types::{
+    AllocationError, BoundsMismatchError, LengthMismatchError, Order, ValidationError,
+};
 use crate::util;
+use std::ops::ControlFlow;
+
+impl_as_f64!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, f32, f64);
+impl_schema_name!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64, bool, char
+);
 
 pub(crate) mod heap_volume {
     use super::*;
@@ -7,13 +16,17 @@ pub(crate) mod heap_volume {
     type HeapVolumeStorage<T> = Box<[Box<[Box<[T]>]>]>;
 
     /// Heap allocated volume. Slower to allocate/create than [`StackVolume`] but is more flexible and can have more exotic bounds.
+    ///
+    /// Keeps track of a [version](Versioned) which is bumped every time the volume's items are mutably accessed.
     pub struct HeapVolume<T> {
         inner: HeapVolumeStorage<T>,
         bounds: BoundingBox,
+        version: u64,
     }
 
     impl_indexing!(T, HeapVolume<T>);
     impl_debug!(T, HeapVolume<T>);
+    impl_arithmetic!([T], HeapVolume<T>, T);
 
     impl<T: Clone> HeapVolume<T> {
         #[inline]
@@ -21,12 +34,300 @@ pub(crate) mod heap_volume {
             use util::boxed_slice;
 
             let bounds: BoundingBox = bounds.into();
+            debug_event!(
+                %bounds,
+                capacity = bounds.capacity() as u64,
+                bytes = bounds.capacity() as u64 * std::mem::size_of::<T>() as u64,
+                "allocating heap volume",
+            );
 
             let [x, y, z] = util::cast_ivec3(bounds.dimensions()).unwrap();
 
             Self {
                 inner: boxed_slice(boxed_slice(boxed_slice(item, z), y), x),
                 bounds,
+                version: 0,
+            }
+        }
+
+        /// Fallible version of [`HeapVolume::new`], returning an error instead of panicking or aborting if the volume is too
+        /// large to be allocated (e.g., when the bounds come from untrusted input).
+        #[inline]
+        pub fn try_new(item: T, bounds: impl Into<BoundingBox>) -> Result<Self, AllocationError> {
+            use util::try_boxed_slice;
+
+            let bounds: BoundingBox = bounds.into();
+            let error = AllocationError { bounds };
+
+            let [x, y, z] = util::cast_ivec3::<usize, _>(bounds.dimensions()).ok_or(error)?;
+            // Not strictly necessary since every allocation is checked, but this fails fast instead of after allocating
+            // as much as possible of a volume that can't ever fit in memory.
+            let bytes =
+                (bounds.capacity() as u128).saturating_mul(std::mem::size_of::<T>() as u128);
+            if bytes > isize::MAX as u128 {
+                return Err(error);
+            }
+
+            let inner = try_boxed_slice(x, || {
+                try_boxed_slice(y, || try_boxed_slice(z, || Some(item.clone())))
+            })
+            .ok_or(error)?;
+
+            Ok(Self {
+                inner,
+                bounds,
+                version: 0,
+            })
+        }
+    }
+
+    impl<T> HeapVolume<T> {
+        /// Build a volume from its YZ planes, each of which is indexed as `plane[y][z]`. Used by operations that compute
+        /// their output one plane at a time (possibly in parallel).
+        #[inline]
+        pub(crate) fn from_planes(bounds: BoundingBox, planes: Vec<Box<[Box<[T]>]>>) -> Self {
+            Self {
+                inner: planes.into_boxed_slice(),
+                bounds,
+                version: 0,
+            }
+        }
+
+        /// Convert every item with `f` (in storage order), keeping the bounds.
+        #[inline]
+        pub(crate) fn map_items<U>(self, mut f: impl FnMut(T) -> U) -> HeapVolume<U> {
+            let planes = self
+                .inner
+                .into_vec()
+                .into_iter()
+                .map(|plane| {
+                    plane
+                        .into_vec()
+                        .into_iter()
+                        .map(|row| row.into_vec().into_iter().map(&mut f).collect())
+                        .collect()
+                })
+                .collect();
+
+            HeapVolume::from_planes(self.bounds, planes)
+        }
+
+        /// Build a volume from a flat buffer holding its items in the given order, e.g., [`Order::Zyx`] for a C-ordered
+        /// (row-major) numpy array of shape `(x, y, z)`, or [`Order::Xyz`] for a GPU texture. The inverse of [`Volume::to_vec_in_order`].
+        ///
+        /// Returns an error if the buffer doesn't hold exactly as many items as there are positions in `bounds`.
+        #[inline]
+        pub fn from_vec_in_order(
+            bounds: impl Into<BoundingBox>,
+            order: Order,
+            items: Vec<T>,
+        ) -> Result<Self, LengthMismatchError> {
+            let bounds: BoundingBox = bounds.into();
+            let expected = usize::try_from(bounds.capacity()).unwrap_or(usize::MAX);
+            if items.len() != expected {
+                return Err(LengthMismatchError {
+                    expected,
+                    found: items.len(),
+                });
+            }
+
+            let [dx, dy, dz] = util::cast_ivec3::<usize, _>(bounds.dimensions()).unwrap();
+            let min = bounds.min();
+
+            let mut storage = std::iter::repeat_with(|| None)
+                .take(expected)
+                .collect::<Vec<Option<T>>>();
+            for (idx, item) in bounds.iter_order(order).zip(items) {
+                let [x, y, z] = util::cast_ivec3::<usize, _>(util::sub_ivec3(idx, min)).unwrap();
+                storage[(x * dy + y) * dz + z] = Some(item);
+            }
+
+            let mut items = storage.into_iter().map(Option::unwrap);
+            let planes = std::iter::repeat_with(|| {
+                std::iter::repeat_with(|| items.by_ref().take(dz).collect())
+                    .take(dy)
+                    .collect()
+            })
+            .take(dx)
+            .collect();
+
+            Ok(Self::from_planes(bounds, planes))
+        }
+
+        /// Shift the origin of this volume's worldspace to `new_origin`, so the item at `new_origin` ends up at `[0, 0, 0]`.
+        /// Only the bounds change, the items aren't touched. Bumps the version, since every index now refers to a different item.
+        ///
+        /// Useful for engines that periodically move the origin of the world to keep `f32` coordinates precise. Use a
+        /// [`RebasedView`](crate::view::RebasedView) instead to rebase a volume without changing it.
+        ///
+        /// # Panics
+        /// Panics if the new bounds overflow [`i64`]. See [`HeapVolume::try_rebase`] for a non-panicking version.
+        #[inline]
+        pub fn rebase(&mut self, new_origin: [i64; 3]) {
+            assert!(
+                self.try_rebase(new_origin),
+                "rebased bounding box overflows i64"
+            );
+        }
+
+        /// Same as [`HeapVolume::rebase`], but returns `false` (and changes nothing) instead of panicking if the new bounds
+        /// overflow [`i64`].
+        #[inline]
+        pub fn try_rebase(&mut self, new_origin: [i64; 3]) -> bool {
+            let Some(bounds) = self.bounds.checked_rebased(new_origin) else {
+                return false;
+            };
+
+            self.bounds = bounds;
+            self.version += 1;
+            true
+        }
+
+        /// The YZ planes of this volume, each indexed as `plane[y][z]`. Bumps the version like any other mutable access.
+        #[inline]
+        pub(crate) fn planes_mut(&mut self) -> &mut [Box<[Box<[T]>]>] {
+            self.version += 1;
+            &mut self.inner
+        }
+
+        /// Iterate over the items in storage order.
+        #[inline]
+        pub(crate) fn items(&self) -> impl Iterator<Item = &T> {
+            self.inner
+                .iter()
+                .flat_map(|x| x.iter())
+                .flat_map(|y| y.iter())
+        }
+
+        /// Iterate mutably over the items in storage order.
+        #[inline]
+        pub(crate) fn items_mut(&mut self) -> impl Iterator<Item = &mut T> {
+            self.version += 1;
+            self.inner
+                .iter_mut()
+                .flat_map(|x| x.iter_mut())
+                .flat_map(|y| y.iter_mut())
+        }
+
+        #[inline]
+        fn checked_op(
+            &self,
+            rhs: &Self,
+            op: impl Fn(&mut T, T),
+        ) -> Result<Self, BoundsMismatchError>
+        where
+            T: Copy,
+        {
+            if self.bounds != rhs.bounds {
+                return Err(BoundsMismatchError {
+                    lhs: self.bounds,
+                    rhs: rhs.bounds,
+                });
+            }
+
+            let mut out = self.clone();
+            for (lhs, &rhs) in out.items_mut().zip(rhs.items()) {
+                op(lhs, rhs);
+            }
+
+            Ok(out)
+        }
+
+        /// Element-wise addition of two volumes. Returns an error instead of panicking (like the `+` operator does) if the bounds aren't equal.
+        #[inline]
+        pub fn checked_add(&self, rhs: &Self) -> Result<Self, BoundsMismatchError>
+        where
+            T: Copy + std::ops::AddAssign,
+        {
+            self.checked_op(rhs, |lhs, rhs| *lhs += rhs)
+        }
+
+        /// Element-wise subtraction of two volumes. Returns an error instead of panicking (like the `-` operator does) if the bounds aren't equal.
+        #[inline]
+        pub fn checked_sub(&self, rhs: &Self) -> Result<Self, BoundsMismatchError>
+        where
+            T: Copy + std::ops::SubAssign,
+        {
+            self.checked_op(rhs, |lhs, rhs| *lhs -= rhs)
+        }
+
+        /// Element-wise multiplication of two volumes. Returns an error instead of panicking (like the `*` operator does) if the bounds aren't equal.
+        #[inline]
+        pub fn checked_mul(&self, rhs: &Self) -> Result<Self, BoundsMismatchError>
+        where
+            T: Copy + std::ops::MulAssign,
+        {
+            self.checked_op(rhs, |lhs, rhs| *lhs *= rhs)
+        }
+
+        /// Same as [`VolumeMut::scan_columns`], but scans the columns in parallel using rayon. Requires the `rayon` feature.
+        #[cfg(feature = "rayon")]
+        #[inline]
+        pub fn par_scan_columns<A, F>(&mut self, axis: Axis, descending: bool, init: A, f: F)
+        where
+            T: Send + Sync,
+            A: Clone + Send + Sync,
+            F: Fn(A, &T) -> (A, T) + Send + Sync,
+        {
+            use rayon::prelude::*;
+
+            fn scan<'a, T: 'a, A>(
+                acc: A,
+                column: impl DoubleEndedIterator<Item = &'a mut T>,
+                descending: bool,
+                f: &impl Fn(A, &T) -> (A, T),
+            ) {
+                let mut acc = Some(acc);
+                let mut step = |item: &mut T| {
+                    let (next, replacement) = f(acc.take().unwrap(), item);
+                    *item = replacement;
+                    acc = Some(next);
+                };
+                match descending {
+                    true => column.rev().for_each(&mut step),
+                    false => column.for_each(&mut step),
+                }
+            }
+
+            debug_span!("par_scan_columns", bounds = %self.bounding_box(), ?axis, descending);
+
+            let [dx, dy, dz] = util::cast_ivec3::<usize, _>(self.bounds.dimensions()).unwrap();
+            match axis {
+                // Every column runs across all the planes, so the planes are scanned one after the other and the
+                // rows of each plane in parallel, carrying one accumulator per column from plane to plane.
+                Axis::X => {
+                    let mut accs = vec![Some(init); dy * dz];
+                    let planes = self.planes_mut();
+
+                    for i in 0..dx {
+                        let x = if descending { dx - 1 - i } else { i };
+                        planes[x]
+                            .par_iter_mut()
+                            .zip(accs.par_chunks_mut(dz.max(1)))
+                            .for_each(|(row, accs)| {
+                                for (item, acc) in row.iter_mut().zip(accs) {
+                                    let (next, replacement) = f(acc.take().unwrap(), item);
+                                    *item = replacement;
+                                    *acc = Some(next);
+                                }
+                            });
+                    }
+                }
+                Axis::Y => self.planes_mut().par_iter_mut().for_each(|plane| {
+                    for z in 0..dz {
+                        scan(
+                            init.clone(),
+                            plane.iter_mut().map(|row| &mut row[z]),
+                            descending,
+                            &f,
+                        );
+                    }
+                }),
+                Axis::Z => self.planes_mut().par_iter_mut().for_each(|plane| {
+                    for row in plane.iter_mut() {
+                        scan(init.clone(), row.iter_mut(), descending, &f);
+                    }
+                }),
             }
         }
     }
@@ -44,6 +345,7 @@ pub(crate) mod heap_volume {
             Self {
                 inner: self.inner.clone(),
                 bounds: self.bounds,
+                version: self.version,
             }
         }
     }
@@ -58,16 +360,98 @@ pub(crate) mod heap_volume {
             self.inner.get(x)?.get(y)?.get(z)
         }
 
+        #[inline]
+        fn bounding_box(&self) -> BoundingBox {
+            self.bounds
+        }
+
+        #[inline]
+        fn try_fold_region<B, C, F>(&self, region: BoundingBox, init: B, f: F) -> ControlFlow<C, B>
+        where
+            F: FnMut(B, &Self::Item) -> ControlFlow<C, B>,
+        {
+            util::try_fold_rows(&self.inner, self.bounding_box(), region, init, f)
+        }
+
+        /// Compares whole rows of items at once, which is considerably faster than comparing every item to the first.
+        #[inline]
+        fn is_uniform(&self) -> Option<&Self::Item>
+        where
+            Self::Item: PartialEq,
+        {
+            let first_row = self.inner.first()?.first()?;
+            let first = first_row.first()?;
+
+            let uniform = first_row.iter().all(|item| item == first)
+                && self
+                    .inner
+                    .iter()
+                    .flat_map(|plane| plane.iter())
+                    .all(|row| row == first_row);
+
+            uniform.then_some(first)
+        }
+
+        #[inline]
+        fn heap_size(&self) -> usize {
+            use std::mem::size_of;
+
+            // One allocation of boxed slices for the X axis, one for each Y row, and one of items for each Z row.
+            let rows = self.inner.iter().map(|plane| plane.len()).sum::<usize>();
+            let items = self
+                .inner
+                .iter()
+                .flat_map(|plane| plane.iter())
+                .map(|row| row.len())
+                .sum::<usize>();
+
+            self.inner.len() * size_of::<Box<[Box<[T]>]>>()
+                + rows * size_of::<Box<[T]>>()
+                + items * size_of::<T>()
+        }
+
+        #[inline]
+        fn validate(&self) -> Result<(), ValidationError> {
+            let [x, y, z] = util::cast_ivec3::<usize, _>(self.bounds.dimensions()).unwrap();
+            let check = |axis, expected, found| {
+                if expected == found {
+                    Ok(())
+                } else {
+                    Err(ValidationError::StorageMismatch {
+                        axis,
+                        expected,
+                        found,
+                    })
+                }
+            };
+
+            check(0, x, self.inner.len())?;
+            for plane in self.inner.iter() {
+                check(1, y, plane.len())?;
+                for row in plane.iter() {
+                    check(2, z, row.len())?;
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    impl<T> VolumeMut for HeapVolume<T> {
         #[inline]
         fn ls_get_mut<Idx: VolumeIdx>(&mut self, idx: Idx) -> Option<&mut Self::Item> {
             let [x, y, z] = idx.array::<usize>()?;
 
-            self.inner.get_mut(x)?.get_mut(y)?.get_mut(z)
+            let item = self.inner.get_mut(x)?.get_mut(y)?.get_mut(z)?;
+            self.version += 1;
+            Some(item)
         }
+    }
 
+    impl<T> Versioned for HeapVolume<T> {
         #[inline]
-        fn bounding_box(&self) -> BoundingBox {
-            self.bounds
+        fn version(&self) -> u64 {
+            self.version
         }
     }
 
@@ -82,7 +466,7 @@ pub(crate) mod heap_volume {
                             .into_iter()
                             .map(|array3| {
                                 let mut v = Vec::with_capacity(Z);
-                                v.extend(array3.into_iter());
+                                v.extend(array3);
                                 v.into_boxed_slice()
                             })
                             .collect::<Vec<_>>()
@@ -95,6 +479,7 @@ pub(crate) mod heap_volume {
             Self {
                 inner: data,
                 bounds: BoundingBox::new([0, 0, 0], [X, Y, Z]),
+                version: 0,
             }
         }
     }
@@ -108,6 +493,7 @@ pub(crate) mod stack_volume {
     type StackVolumeStorage<const X: usize, const Y: usize, const Z: usize, T> = [[[T; Z]; Y]; X];
 
     /// Stack allocated volume with size known at compile time. Faster to allocate/create than [`HeapVolume`] but not as flexible.
+    #[derive(Clone)]
     pub struct StackVolume<const X: usize, const Y: usize, const Z: usize, T> {
         inner: StackVolumeStorage<X, Y, Z, T>,
     }
@@ -136,14 +522,59 @@ pub(crate) mod stack_volume {
     }
 
     impl<const X: usize, const Y: usize, const Z: usize, T: Copy> StackVolume<X, Y, Z, T> {
+        /// Create a volume where every item is `item`. This is a `const fn`, so it can be used to build volumes in constants and statics.
         #[inline]
-        pub fn filled(item: T) -> Self {
+        pub const fn filled(item: T) -> Self {
             Self {
                 inner: [[[item; Z]; Y]; X],
             }
         }
     }
 
+    impl_arithmetic!(
+        [const X: usize, const Y: usize, const Z: usize, T],
+        StackVolume<X, Y, Z, T>,
+        T
+    );
+
+    impl<const X: usize, const Y: usize, const Z: usize, T> StackVolume<X, Y, Z, T> {
+        /// Iterate over the items in storage order.
+        #[inline]
+        pub(crate) fn items(&self) -> impl Iterator<Item = &T> {
+            self.inner.iter().flatten().flatten()
+        }
+
+        /// Iterate mutably over the items in storage order.
+        #[inline]
+        pub(crate) fn items_mut(&mut self) -> impl Iterator<Item = &mut T> {
+            self.inner.iter_mut().flatten().flatten()
+        }
+
+        /// Create a volume from an array indexed as `array[x][y][z]`. Same as the [`From`] implementation, but usable in const contexts.
+        #[inline]
+        pub const fn from_array(array: StackVolumeStorage<X, Y, Z, T>) -> Self {
+            Self { inner: array }
+        }
+
+        /// Const version of [`Volume::bounding_box`] for stack volumes, whose bounds are always known at compile time.
+        #[inline]
+        pub const fn bounds() -> BoundingBox {
+            StaticBounds::<X, Y, Z>::bounding_box()
+        }
+
+        /// The bounds of this volume as [`StaticBounds`], whose capacity and strides are constants.
+        #[inline]
+        pub const fn static_bounds() -> StaticBounds<X, Y, Z> {
+            StaticBounds::new()
+        }
+
+        /// The item at the given index into the flattened storage, see [`StaticBounds::linear_index`].
+        #[inline(always)]
+        fn linear<Idx: VolumeIdx>(idx: Idx) -> Option<usize> {
+            StaticBounds::<X, Y, Z>::linear_index(idx.array()?)
+        }
+    }
+
     impl<const X: usize, const Y: usize, const Z: usize, T: Copy + Default> Default
         for StackVolume<X, Y, Z, T>
     {
@@ -186,8 +617,12 @@ pub(crate) mod stack_volume {
         type Output = <Self as Volume>::Item;
 
         #[inline]
+        #[track_caller]
         fn index(&self, idx: Idx) -> &Self::Output {
-            self.get(idx).unwrap()
+            match self.get(idx) {
+                Some(item) => item,
+                None => crate::util::index_out_of_bounds(idx, self.bounding_box()),
+            }
         }
     }
 
@@ -195,8 +630,13 @@ pub(crate) mod stack_volume {
         for StackVolume<X, Y, Z, T>
     {
         #[inline]
+        #[track_caller]
         fn index_mut(&mut self, idx: Idx) -> &mut Self::Output {
-            self.get_mut(idx).unwrap()
+            let bounds = self.bounding_box();
+            match self.get_mut(idx) {
+                Some(item) => item,
+                None => crate::util::index_out_of_bounds(idx, bounds),
+            }
         }
     }
 
@@ -205,21 +645,427 @@ pub(crate) mod stack_volume {
 
         #[inline]
         fn ls_get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
-            let [x, y, z] = idx.array::<usize>()?;
+            self.inner
+                .as_flattened()
+                .as_flattened()
+                .get(Self::linear(idx)?)
+        }
 
-            self.inner.get(x)?.get(y)?.get(z)
+        #[inline]
+        fn bounding_box(&self) -> BoundingBox {
+            Self::bounds()
+        }
+
+        #[inline]
+        fn try_fold_region<B, C, F>(&self, region: BoundingBox, init: B, f: F) -> ControlFlow<C, B>
+        where
+            F: FnMut(B, &Self::Item) -> ControlFlow<C, B>,
+        {
+            util::try_fold_rows(&self.inner, self.bounding_box(), region, init, f)
         }
+    }
 
+    impl<const X: usize, const Y: usize, const Z: usize, T> VolumeMut for StackVolume<X, Y, Z, T> {
         #[inline]
         fn ls_get_mut<Idx: VolumeIdx>(&mut self, idx: Idx) -> Option<&mut Self::Item> {
-            let [x, y, z] = idx.array::<usize>()?;
+            self.inner
+                .as_flattened_mut()
+                .as_flattened_mut()
+                .get_mut(Self::linear(idx)?)
+        }
+    }
+}
+
+pub(crate) mod slice_volume {
+    use super::*;
+    use crate::types::SliceVolumeError;
+
+    /// Default strides for a buffer in X-major order (the order in which [`BoundingBox`] iterates), i.e. `x + y * X + z * X * Y`.
+    #[inline]
+    fn x_major_strides(dims: [usize; 3]) -> [usize; 3] {
+        [1, dims[0], dims[0] * dims[1]]
+    }
 
-            self.inner.get_mut(x)?.get_mut(y)?.get_mut(z)
+    /// Check that every index in the bounds resolves to an offset within a buffer of length `len`.
+    #[inline]
+    fn validate(
+        len: usize,
+        bounds: BoundingBox,
+        strides: [usize; 3],
+    ) -> Result<[usize; 3], SliceVolumeError> {
+        let dims = util::cast_ivec3::<usize, _>(bounds.dimensions())
+            .ok_or(SliceVolumeError::BoundsTooLarge)?;
+
+        if dims.contains(&0) {
+            return Ok(dims);
+        }
+
+        let mut required = 1usize;
+        for axis in 0..3 {
+            required = (dims[axis] - 1)
+                .checked_mul(strides[axis])
+                .and_then(|n| n.checked_add(required))
+                .ok_or(SliceVolumeError::BoundsTooLarge)?;
+        }
+
+        if required > len {
+            return Err(SliceVolumeError::BufferTooSmall { required, len });
+        }
+
+        Ok(dims)
+    }
+
+    #[inline(always)]
+    fn offset<Idx: VolumeIdx>(dims: [usize; 3], strides: [usize; 3], idx: Idx) -> Option<usize> {
+        let [x, y, z] = idx.array::<usize>()?;
+        if x >= dims[0] || y >= dims[1] || z >= dims[2] {
+            return None;
+        }
+
+        Some(x * strides[0] + y * strides[1] + z * strides[2])
+    }
+
+    /// Volume borrowing its items from a slice, for example a buffer received from another library.
+    ///
+    /// The item at localspace index `[x, y, z]` is found at `x * strides[0] + y * strides[1] + z * strides[2]` in the slice.
+    /// By default the strides describe an X-major layout, but any layout (including padded rows or a Z-major layout)
+    /// can be described with [`SliceVolume::with_strides`].
+    #[derive(Copy, Clone)]
+    pub struct SliceVolume<'a, T> {
+        data: &'a [T],
+        bounds: BoundingBox,
+        dims: [usize; 3],
+        strides: [usize; 3],
+    }
+
+    impl_indexing!(['a, T], SliceVolume<'a, T>);
+    impl_debug!(['a, T], SliceVolume<'a, T>);
+
+    impl<'a, T> SliceVolume<'a, T> {
+        /// Create a volume over `data` which is laid out in X-major order.
+        /// Returns an error if `data` is too small to hold every item in `bounds`.
+        #[inline]
+        pub fn new(
+            data: &'a [T],
+            bounds: impl Into<BoundingBox>,
+        ) -> Result<Self, SliceVolumeError> {
+            let bounds = bounds.into();
+            let dims = util::cast_ivec3::<usize, _>(bounds.dimensions())
+                .ok_or(SliceVolumeError::BoundsTooLarge)?;
+
+            Self::with_strides(data, bounds, x_major_strides(dims))
+        }
+
+        /// Create a volume over `data` where the item at localspace index `[x, y, z]` is at offset
+        /// `x * strides[0] + y * strides[1] + z * strides[2]`.
+        /// Returns an error if `data` is too small to hold every item in `bounds`.
+        #[inline]
+        pub fn with_strides(
+            data: &'a [T],
+            bounds: impl Into<BoundingBox>,
+            strides: [usize; 3],
+        ) -> Result<Self, SliceVolumeError> {
+            let bounds = bounds.into();
+            let dims = validate(data.len(), bounds, strides)?;
+
+            Ok(Self {
+                data,
+                bounds,
+                dims,
+                strides,
+            })
+        }
+
+        #[inline]
+        pub fn strides(&self) -> [usize; 3] {
+            self.strides
+        }
+    }
+
+    impl<'a, T> Volume for SliceVolume<'a, T> {
+        type Item = T;
+
+        #[inline]
+        fn ls_get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+            self.data.get(offset(self.dims, self.strides, idx)?)
+        }
+
+        #[inline]
+        fn bounding_box(&self) -> BoundingBox {
+            self.bounds
+        }
+
+        #[inline]
+        fn validate(&self) -> Result<(), ValidationError> {
+            validate(self.data.len(), self.bounds, self.strides)?;
+            Ok(())
+        }
+    }
+
+    /// Mutable version of [`SliceVolume`].
+    pub struct SliceVolumeMut<'a, T> {
+        data: &'a mut [T],
+        bounds: BoundingBox,
+        dims: [usize; 3],
+        strides: [usize; 3],
+    }
+
+    impl_indexing!(['a, T], SliceVolumeMut<'a, T>);
+    impl_debug!(['a, T], SliceVolumeMut<'a, T>);
+
+    impl<'a, T> SliceVolumeMut<'a, T> {
+        /// Create a volume over `data` which is laid out in X-major order.
+        /// Returns an error if `data` is too small to hold every item in `bounds`.
+        #[inline]
+        pub fn new(
+            data: &'a mut [T],
+            bounds: impl Into<BoundingBox>,
+        ) -> Result<Self, SliceVolumeError> {
+            let bounds = bounds.into();
+            let dims = util::cast_ivec3::<usize, _>(bounds.dimensions())
+                .ok_or(SliceVolumeError::BoundsTooLarge)?;
+
+            Self::with_strides(data, bounds, x_major_strides(dims))
+        }
+
+        /// Create a volume over `data` where the item at localspace index `[x, y, z]` is at offset
+        /// `x * strides[0] + y * strides[1] + z * strides[2]`.
+        /// Returns an error if `data` is too small to hold every item in `bounds`.
+        ///
+        /// Note that strides may cause several indices to refer to the same item.
+        #[inline]
+        pub fn with_strides(
+            data: &'a mut [T],
+            bounds: impl Into<BoundingBox>,
+            strides: [usize; 3],
+        ) -> Result<Self, SliceVolumeError> {
+            let bounds = bounds.into();
+            let dims = validate(data.len(), bounds, strides)?;
+
+            Ok(Self {
+                data,
+                bounds,
+                dims,
+                strides,
+            })
+        }
+
+        #[inline]
+        pub fn strides(&self) -> [usize; 3] {
+            self.strides
+        }
+
+        /// Reborrow this volume immutably.
+        #[inline]
+        pub fn as_slice_volume(&self) -> SliceVolume<'_, T> {
+            SliceVolume {
+                data: self.data,
+                bounds: self.bounds,
+                dims: self.dims,
+                strides: self.strides,
+            }
+        }
+    }
+
+    impl<'a, T> Volume for SliceVolumeMut<'a, T> {
+        type Item = T;
+
+        #[inline]
+        fn ls_get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+            self.data.get(offset(self.dims, self.strides, idx)?)
         }
 
         #[inline]
         fn bounding_box(&self) -> BoundingBox {
-            BoundingBox::new([0; 3], [X, Y, Z])
+            self.bounds
+        }
+
+        #[inline]
+        fn validate(&self) -> Result<(), ValidationError> {
+            validate(self.data.len(), self.bounds, self.strides)?;
+            Ok(())
+        }
+    }
+
+    impl<'a, T> VolumeMut for SliceVolumeMut<'a, T> {
+        #[inline]
+        fn ls_get_mut<Idx: VolumeIdx>(&mut self, idx: Idx) -> Option<&mut Self::Item> {
+            self.data.get_mut(offset(self.dims, self.strides, idx)?)
+        }
+    }
+}
+
+pub(crate) mod uniform_volume {
+    use super::*;
+
+    enum Repr<T> {
+        Uniform(T),
+        Heap(HeapVolume<T>),
+    }
+
+    /// Volume where every item is the same, using no storage besides the one item. Useful for the many chunks of a world
+    /// which are entirely air or stone.
+    ///
+    /// The volume is upgraded to a [`HeapVolume`] the first time it's written to with a different item (or when it's accessed
+    /// mutably in any other way), or explicitly with [`UniformVolume::materialize`].
+    pub struct UniformVolume<T> {
+        repr: Repr<T>,
+        bounds: BoundingBox,
+    }
+
+    impl_indexing!(T, UniformVolume<T>);
+    impl_debug!(T, UniformVolume<T>);
+
+    impl<T> UniformVolume<T> {
+        #[inline]
+        pub fn new(item: T, bounds: impl Into<BoundingBox>) -> Self {
+            Self {
+                repr: Repr::Uniform(item),
+                bounds: bounds.into(),
+            }
+        }
+
+        /// Check if this volume is still stored as a single item. Use [`Volume::is_uniform`] to check if every item is equal
+        /// regardless of how they're stored.
+        #[inline]
+        pub fn is_stored_uniform(&self) -> bool {
+            matches!(self.repr, Repr::Uniform(_))
+        }
+
+        /// The item filling this volume, or [`None`] if it has been upgraded to a [`HeapVolume`].
+        #[inline]
+        pub fn uniform_item(&self) -> Option<&T> {
+            match &self.repr {
+                Repr::Uniform(item) => Some(item),
+                Repr::Heap(_) => None,
+            }
+        }
+    }
+
+    impl<T: Clone> UniformVolume<T> {
+        /// Upgrade this volume to a [`HeapVolume`] (if it isn't one already), returning it.
+        #[inline]
+        pub fn materialize(&mut self) -> &mut HeapVolume<T> {
+            if let Repr::Uniform(item) = &self.repr {
+                self.repr = Repr::Heap(HeapVolume::new(item.clone(), self.bounds));
+            }
+
+            match &mut self.repr {
+                Repr::Heap(vol) => vol,
+                Repr::Uniform(_) => unreachable!(),
+            }
+        }
+
+        /// Convert this volume to a [`HeapVolume`].
+        #[inline]
+        pub fn into_heap(self) -> HeapVolume<T> {
+            match self.repr {
+                Repr::Uniform(item) => HeapVolume::new(item, self.bounds),
+                Repr::Heap(vol) => vol,
+            }
+        }
+    }
+
+    impl<T: Clone + PartialEq> UniformVolume<T> {
+        /// Set the item at the given worldspace index, returning the previous item. Only upgrades the volume to a [`HeapVolume`]
+        /// if `item` differs from the item filling it. Returns [`None`] if the index is out of bounds.
+        #[inline]
+        pub fn set<Idx: VolumeIdx>(&mut self, idx: Idx, item: T) -> Option<T> {
+            if !self.bounds.contains(idx) {
+                return None;
+            }
+
+            match &self.repr {
+                Repr::Uniform(current) if *current == item => Some(item),
+                _ => self.materialize().swap(idx, item),
+            }
+        }
+    }
+
+    impl<T: Clone> Clone for UniformVolume<T> {
+        fn clone(&self) -> Self {
+            Self {
+                repr: match &self.repr {
+                    Repr::Uniform(item) => Repr::Uniform(item.clone()),
+                    Repr::Heap(vol) => Repr::Heap(vol.clone()),
+                },
+                bounds: self.bounds,
+            }
+        }
+    }
+
+    impl<T: PartialEq> PartialEq for UniformVolume<T> {
+        #[inline]
+        fn eq(&self, other: &Self) -> bool {
+            self.bounds == other.bounds && self.iter().eq(other.iter())
+        }
+    }
+
+    impl<T> From<UniformVolume<T>> for HeapVolume<T>
+    where
+        T: Clone,
+    {
+        #[inline]
+        fn from(vol: UniformVolume<T>) -> Self {
+            vol.into_heap()
+        }
+    }
+
+    impl<T> Volume for UniformVolume<T> {
+        type Item = T;
+
+        #[inline]
+        fn ls_get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+            match &self.repr {
+                Repr::Uniform(item) => {
+                    let [x, y, z] = idx.array::<i64>()?;
+                    let [dx, dy, dz] = self.bounds.dimensions();
+                    ((0..dx).contains(&x) && (0..dy).contains(&y) && (0..dz).contains(&z))
+                        .then_some(item)
+                }
+                Repr::Heap(vol) => vol.ls_get(idx),
+            }
+        }
+
+        #[inline]
+        fn bounding_box(&self) -> BoundingBox {
+            self.bounds
+        }
+
+        #[inline]
+        fn is_uniform(&self) -> Option<&Self::Item>
+        where
+            Self::Item: PartialEq,
+        {
+            match &self.repr {
+                Repr::Uniform(item) => (!self.bounds.is_empty()).then_some(item),
+                Repr::Heap(vol) => vol.is_uniform(),
+            }
+        }
+
+        #[inline]
+        fn heap_size(&self) -> usize {
+            match &self.repr {
+                Repr::Uniform(_) => 0,
+                Repr::Heap(vol) => vol.heap_size(),
+            }
+        }
+
+        #[inline]
+        fn validate(&self) -> Result<(), ValidationError> {
+            match &self.repr {
+                Repr::Uniform(_) => Ok(()),
+                Repr::Heap(vol) => vol.validate(),
+            }
+        }
+    }
+
+    impl<T: Clone> VolumeMut for UniformVolume<T> {
+        /// Upgrades the volume to a [`HeapVolume`], since the returned reference could be used to write a different item.
+        #[inline]
+        fn ls_get_mut<Idx: VolumeIdx>(&mut self, idx: Idx) -> Option<&mut Self::Item> {
+            self.ls_get(idx)?;
+            self.materialize().ls_get_mut(idx)
         }
     }
 }
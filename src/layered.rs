@@ -0,0 +1,190 @@
+//! Volumes resolving reads through a stack of edit layers over a base volume, for non-destructive editing.
+
+use crate::prelude::*;
+use crate::types::ValidationError;
+
+/// A base volume with a stack of edit layers on top of it, covering the same bounds.
+///
+/// Every layer records which positions it sets. Reads return the item of the topmost layer that sets the position, falling
+/// back to the base volume, and writes always go to the top layer, so the base and the lower layers are never changed by
+/// writing to this volume. That makes it easy to e.g. preview a brush stroke and throw it away with [`LayeredVolume::pop_layer`],
+/// or keep player edits apart from a procedurally generated world.
+///
+/// Mutable access to a position the top layer doesn't set yet first copies the resolved item into the top layer, which is
+/// why [`VolumeMut`] is only implemented for cloneable items.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayeredVolume<V: Volume> {
+    base: V,
+    /// The edit layers from the bottom to the top.
+    layers: Vec<HeapVolume<Option<V::Item>>>,
+}
+
+impl_indexing!([V: Volume], LayeredVolume<V>);
+
+impl<V: Volume> LayeredVolume<V>
+where
+    V::Item: Clone,
+{
+    /// Wrap a base volume with a single empty edit layer on top of it.
+    #[inline]
+    pub fn new(base: V) -> Self {
+        let mut vol = Self {
+            base,
+            layers: Vec::new(),
+        };
+        vol.push_layer();
+        vol
+    }
+
+    /// Push a new empty layer on top of the stack. Writes go to this layer from now on.
+    #[inline]
+    pub fn push_layer(&mut self) {
+        self.layers
+            .push(HeapVolume::new(None, self.base.bounding_box()));
+    }
+
+    /// Flatten the base and every layer into a single volume holding the resolved items.
+    #[inline]
+    pub fn flatten(&self) -> HeapVolume<V::Item> {
+        HeapVolume::from_vec_in_order(
+            self.bounding_box(),
+            Order::Xyz,
+            self.iter().cloned().collect(),
+        )
+        .unwrap()
+    }
+}
+
+impl<V: Volume> LayeredVolume<V> {
+    #[inline]
+    pub fn base(&self) -> &V {
+        &self.base
+    }
+
+    /// Get mutable access to the base volume. Changes to it show through wherever no layer sets the position.
+    #[inline]
+    pub fn base_mut(&mut self) -> &mut V {
+        &mut self.base
+    }
+
+    #[inline]
+    pub fn into_base(self) -> V {
+        self.base
+    }
+
+    /// The amount of edit layers, not counting the base.
+    #[inline]
+    pub fn layers(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// The edit layer at the given height (0 being the bottom layer), with [`None`] at the positions it doesn't set.
+    #[inline]
+    pub fn layer(&self, i: usize) -> Option<&HeapVolume<Option<V::Item>>> {
+        self.layers.get(i)
+    }
+
+    /// Remove the top layer, discarding its edits. Returns [`None`] if there are no layers left.
+    ///
+    /// Writing to a volume without layers pushes a new empty layer first, so removing the last layer is fine.
+    #[inline]
+    pub fn pop_layer(&mut self) -> Option<HeapVolume<Option<V::Item>>> {
+        self.layers.pop()
+    }
+
+    /// Apply the edits of the top layer to the layer below it (or to the base if it's the only layer) and remove it.
+    /// Returns the amount of items that were applied, or [`None`] if there are no layers.
+    #[inline]
+    pub fn merge_top(&mut self) -> Option<usize>
+    where
+        V: VolumeMut,
+    {
+        let mut top = self.layers.pop()?;
+        let mut count = 0;
+        for idx in top.bounding_box() {
+            let Some(item) = top[idx].take() else {
+                continue;
+            };
+
+            match self.layers.last_mut() {
+                Some(below) => below[idx] = Some(item),
+                None => *self.base.get_mut(idx).unwrap() = item,
+            }
+            count += 1;
+        }
+
+        Some(count)
+    }
+}
+
+impl<V: Volume> Volume for LayeredVolume<V> {
+    type Item = V::Item;
+
+    #[inline]
+    fn ls_get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        self.get(idx.array::<i64>()?.checked_add(self.bounding_box().min())?)
+    }
+
+    #[inline(always)]
+    fn bounding_box(&self) -> BoundingBox {
+        self.base.bounding_box()
+    }
+
+    #[inline]
+    fn get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        let pos = idx.array::<i64>()?;
+        for layer in self.layers.iter().rev() {
+            if let Some(item) = layer.get(pos)? {
+                return Some(item);
+            }
+        }
+
+        self.base.get(pos)
+    }
+
+    #[inline]
+    fn heap_size(&self) -> usize {
+        self.base.heap_size() + self.layers.iter().map(Volume::heap_size).sum::<usize>()
+    }
+
+    #[inline]
+    fn validate(&self) -> Result<(), ValidationError> {
+        self.layers.iter().try_for_each(Volume::validate)?;
+        self.base.validate()
+    }
+}
+
+impl<V: Volume> VolumeMut for LayeredVolume<V>
+where
+    V::Item: Clone,
+{
+    #[inline]
+    fn ls_get_mut<Idx: VolumeIdx>(&mut self, idx: Idx) -> Option<&mut Self::Item> {
+        let min = self.bounding_box().min();
+        self.get_mut(idx.array::<i64>()?.checked_add(min)?)
+    }
+
+    /// Get a mutable reference to the item in the top layer, copying the resolved item into it first if the top layer
+    /// doesn't set the position yet. Pushes a new empty layer first if every layer was removed.
+    #[inline]
+    fn get_mut<Idx: VolumeIdx>(&mut self, idx: Idx) -> Option<&mut Self::Item> {
+        let pos = idx.array::<i64>()?;
+        if !self.bounding_box().contains(pos) {
+            return None;
+        }
+        if self.layers.is_empty() {
+            self.push_layer();
+        }
+
+        let resolved = match self.layers.last().unwrap().get(pos)? {
+            Some(_) => None,
+            None => Some(self.get(pos)?.clone()),
+        };
+
+        let slot = self.layers.last_mut().unwrap().get_mut(pos)?;
+        if let Some(item) = resolved {
+            *slot = Some(item);
+        }
+        slot.as_mut()
+    }
+}
@@ -6,14 +6,55 @@ mod macros;
 #[cfg(test)]
 mod tests;
 
+pub mod cache;
+pub mod chunked;
+pub mod collide;
+pub mod compact;
+pub mod conformance;
+pub mod draw;
+pub mod flags;
+pub mod full;
+pub mod generate;
+pub mod geometry;
+#[cfg(feature = "wgpu")]
+pub mod gpu;
+pub mod grow;
 mod impls;
+pub mod layered;
+pub mod math;
+pub mod meshed;
+pub mod migrate;
+#[cfg(feature = "testing")]
+pub mod mock;
+pub mod morph;
+pub mod multi;
+pub mod observe;
+pub mod occupancy;
+pub mod pool;
 pub mod prelude;
+pub mod promote;
+#[cfg(feature = "rand")]
+pub mod random;
+pub mod registry;
+pub mod scrolling;
+pub mod selection;
+pub mod sequence;
+#[cfg(feature = "shared")]
+pub mod shared;
+#[cfg(feature = "sim")]
+pub mod sim;
+pub mod snapshot;
+mod soa;
+pub mod spaces;
+pub mod sparse;
+pub mod stencil;
+pub mod tile;
+pub mod topology;
 pub mod traits;
 pub mod types;
 mod util;
-
-#[cfg(feature = "nalgebra")]
-pub use nalgebra_support::*;
+pub mod view;
+pub mod visibility;
 
 #[cfg(feature = "nalgebra")]
 mod nalgebra_support {
@@ -40,6 +81,15 @@ mod nalgebra_support {
                 <N as NumCast>::from(z).unwrap(),
             )
         }
+
+        #[inline]
+        fn try_from_xyz<T: PrimInt>(x: T, y: T, z: T) -> Option<Self> {
+            Some(Self::new(
+                <N as NumCast>::from(x)?,
+                <N as NumCast>::from(y)?,
+                <N as NumCast>::from(z)?,
+            ))
+        }
     }
 
     impl_boundingbox_from_na_vec_range!(i8, u8, i16, u16, i32, u32, i64);
@@ -52,9 +102,6 @@ mod nalgebra_support {
     }
 }
 
-#[cfg(feature = "glam")]
-pub use glam_support::*;
-
 #[cfg(feature = "glam")]
 mod glam_support {
     extern crate glam;
@@ -80,6 +127,15 @@ mod glam_support {
                 <i32 as NumCast>::from(z).unwrap(),
             )
         }
+
+        #[inline]
+        fn try_from_xyz<N: PrimInt>(x: N, y: N, z: N) -> Option<Self> {
+            Some(Self::new(
+                <i32 as NumCast>::from(x)?,
+                <i32 as NumCast>::from(y)?,
+                <i32 as NumCast>::from(z)?,
+            ))
+        }
     }
 
     impl VolumeIdx for glam::UVec3 {
@@ -100,7 +156,165 @@ mod glam_support {
                 <u32 as NumCast>::from(z).unwrap(),
             )
         }
+
+        #[inline]
+        fn try_from_xyz<T: PrimInt>(x: T, y: T, z: T) -> Option<Self> {
+            Some(Self::new(
+                <u32 as NumCast>::from(x)?,
+                <u32 as NumCast>::from(y)?,
+                <u32 as NumCast>::from(z)?,
+            ))
+        }
     }
 
     impl_boundingbox_from_glam_vec_range!(glam::IVec3, glam::UVec3);
 }
+
+#[cfg(feature = "bevy")]
+pub mod bevy_support {
+    //! Integration with Bevy. Requires the `bevy` feature.
+    //!
+    //! Bevy's `IVec3` and `UVec3` can be used as volume indices, [`BoundingBox`] implements `Reflect`,
+    //! and volumes can be stored as assets or components by wrapping them in [`VolumeAsset`] or [`VolumeComponent`].
+
+    use crate::prelude::*;
+    use bevy_asset::{Asset, UntypedAssetId, VisitAssetDependencies};
+    use bevy_ecs::component::{Component, StorageType};
+    use bevy_math::{IVec3, UVec3};
+    use bevy_reflect::utility::GenericTypePathCell;
+    use bevy_reflect::TypePath;
+    use num_traits::{NumCast, PrimInt};
+
+    impl VolumeIdx for IVec3 {
+        #[inline]
+        fn array<T: NumCast>(self) -> Option<[T; 3]> {
+            Some([
+                <T as NumCast>::from(self.x)?,
+                <T as NumCast>::from(self.y)?,
+                <T as NumCast>::from(self.z)?,
+            ])
+        }
+
+        #[inline]
+        fn from_xyz<N: PrimInt>(x: N, y: N, z: N) -> Self {
+            Self::new(
+                <i32 as NumCast>::from(x).unwrap(),
+                <i32 as NumCast>::from(y).unwrap(),
+                <i32 as NumCast>::from(z).unwrap(),
+            )
+        }
+
+        #[inline]
+        fn try_from_xyz<N: PrimInt>(x: N, y: N, z: N) -> Option<Self> {
+            Some(Self::new(
+                <i32 as NumCast>::from(x)?,
+                <i32 as NumCast>::from(y)?,
+                <i32 as NumCast>::from(z)?,
+            ))
+        }
+    }
+
+    impl VolumeIdx for UVec3 {
+        #[inline]
+        fn array<T: NumCast>(self) -> Option<[T; 3]> {
+            Some([
+                <T as NumCast>::from(self.x)?,
+                <T as NumCast>::from(self.y)?,
+                <T as NumCast>::from(self.z)?,
+            ])
+        }
+
+        #[inline]
+        fn from_xyz<N: PrimInt>(x: N, y: N, z: N) -> Self {
+            Self::new(
+                <u32 as NumCast>::from(x).unwrap(),
+                <u32 as NumCast>::from(y).unwrap(),
+                <u32 as NumCast>::from(z).unwrap(),
+            )
+        }
+
+        #[inline]
+        fn try_from_xyz<N: PrimInt>(x: N, y: N, z: N) -> Option<Self> {
+            Some(Self::new(
+                <u32 as NumCast>::from(x)?,
+                <u32 as NumCast>::from(y)?,
+                <u32 as NumCast>::from(z)?,
+            ))
+        }
+    }
+
+    impl_boundingbox_from_volumeidx_range!(IVec3);
+    impl_boundingbox_from_volumeidx_range!(UVec3);
+
+    macro_rules! impl_volume_wrapper {
+        ($wrapper:ident) => {
+            impl<V: Send + Sync + 'static> TypePath for $wrapper<V> {
+                fn type_path() -> &'static str {
+                    static CELL: GenericTypePathCell = GenericTypePathCell::new();
+                    CELL.get_or_insert::<Self, _>(|| {
+                        format!(
+                            concat!(module_path!(), "::", stringify!($wrapper), "<{}>"),
+                            std::any::type_name::<V>()
+                        )
+                    })
+                }
+
+                fn short_type_path() -> &'static str {
+                    static CELL: GenericTypePathCell = GenericTypePathCell::new();
+                    CELL.get_or_insert::<Self, _>(|| {
+                        format!(
+                            concat!(stringify!($wrapper), "<{}>"),
+                            std::any::type_name::<V>()
+                        )
+                    })
+                }
+            }
+
+            impl<V> std::ops::Deref for $wrapper<V> {
+                type Target = V;
+
+                #[inline]
+                fn deref(&self) -> &V {
+                    &self.0
+                }
+            }
+
+            impl<V> std::ops::DerefMut for $wrapper<V> {
+                #[inline]
+                fn deref_mut(&mut self) -> &mut V {
+                    &mut self.0
+                }
+            }
+
+            impl<V> From<V> for $wrapper<V> {
+                #[inline]
+                fn from(volume: V) -> Self {
+                    Self(volume)
+                }
+            }
+        };
+    }
+
+    /// Wrapper allowing a volume to be stored as a Bevy asset. Dereferences to the wrapped volume.
+    #[derive(Debug, Clone)]
+    pub struct VolumeAsset<V>(pub V);
+
+    impl_volume_wrapper!(VolumeAsset);
+
+    impl<V> VisitAssetDependencies for VolumeAsset<V> {
+        #[inline]
+        fn visit_dependencies(&self, _visit: &mut impl FnMut(UntypedAssetId)) {}
+    }
+
+    impl<V: Send + Sync + 'static> Asset for VolumeAsset<V> {}
+
+    /// Wrapper allowing a volume to be stored as a Bevy component. Dereferences to the wrapped volume.
+    #[derive(Debug, Clone)]
+    pub struct VolumeComponent<V>(pub V);
+
+    impl_volume_wrapper!(VolumeComponent);
+
+    impl<V: Send + Sync + 'static> Component for VolumeComponent<V> {
+        const STORAGE_TYPE: StorageType = StorageType::Table;
+    }
+}
@@ -1,32 +1,46 @@
 macro_rules! impl_indexing {
-    ($param:tt, $tgt:ty) => {
-        impl<$param, Idx: crate::traits::VolumeIdx> std::ops::Index<Idx> for $tgt
+    ([$($param:tt)+], $tgt:ty) => {
+        impl<$($param)+, Idx: crate::traits::VolumeIdx> std::ops::Index<Idx> for $tgt
         where
             $tgt: crate::traits::Volume,
         {
             type Output = <$tgt as crate::traits::Volume>::Item;
 
             #[inline(always)]
+            #[track_caller]
             fn index(&self, idx: Idx) -> &Self::Output {
-                self.get(idx).unwrap()
+                match self.get(idx) {
+                    Some(item) => item,
+                    None => crate::util::index_out_of_bounds(idx, self.bounding_box()),
+                }
             }
         }
 
-        impl<$param, Idx: crate::traits::VolumeIdx> std::ops::IndexMut<Idx> for $tgt
+        impl<$($param)+, Idx: crate::traits::VolumeIdx> std::ops::IndexMut<Idx> for $tgt
         where
-            $tgt: crate::traits::Volume,
+            $tgt: crate::traits::VolumeMut,
         {
             #[inline(always)]
+            #[track_caller]
             fn index_mut(&mut self, idx: Idx) -> &mut Self::Output {
-                self.get_mut(idx).unwrap()
+                // Fetched up front since `self` stays borrowed by `get_mut` in both arms of a match.
+                let bounds = self.bounding_box();
+                match self.get_mut(idx) {
+                    Some(item) => item,
+                    None => crate::util::index_out_of_bounds(idx, bounds),
+                }
             }
         }
     };
+
+    ($param:tt, $tgt:ty) => {
+        impl_indexing!([$param], $tgt);
+    };
 }
 
 macro_rules! impl_debug {
-    ($param:tt, $tgt:ty) => {
-        impl<$param> std::fmt::Debug for $tgt
+    ([$($param:tt)+], $tgt:ty) => {
+        impl<$($param)+> std::fmt::Debug for $tgt
         where
             $tgt: crate::traits::Volume,
         {
@@ -44,9 +58,108 @@ macro_rules! impl_debug {
             }
         }
     };
+
+    ($param:tt, $tgt:ty) => {
+        impl_debug!([$param], $tgt);
+    };
+}
+
+/// Implement element-wise arithmetic between two volumes of the same type (and same bounds), and between a volume and a scalar.
+/// The target type must have `items` and `items_mut` methods iterating over its items in storage order.
+macro_rules! impl_arithmetic {
+    ([$($param:tt)*], $tgt:ty, $item:ident) => {
+        impl_arithmetic!(@op [$($param)*], $tgt, $item, Add, add, AddAssign, add_assign);
+        impl_arithmetic!(@op [$($param)*], $tgt, $item, Sub, sub, SubAssign, sub_assign);
+        impl_arithmetic!(@op [$($param)*], $tgt, $item, Mul, mul, MulAssign, mul_assign);
+    };
+
+    (@op [$($param:tt)*], $tgt:ty, $item:ident, $op:ident, $method:ident, $op_assign:ident, $method_assign:ident) => {
+        impl<$($param)*> std::ops::$op_assign<&$tgt> for $tgt
+        where
+            $item: Copy + std::ops::$op_assign,
+        {
+            /// # Panics
+            /// Panics if the bounds of the two volumes are not equal.
+            #[inline]
+            fn $method_assign(&mut self, rhs: &$tgt) {
+                assert_eq!(
+                    crate::traits::Volume::bounding_box(self),
+                    crate::traits::Volume::bounding_box(rhs),
+                    "element-wise arithmetic requires volumes with equal bounds"
+                );
+
+                for (lhs, &rhs) in self.items_mut().zip(rhs.items()) {
+                    std::ops::$op_assign::$method_assign(lhs, rhs);
+                }
+            }
+        }
+
+        impl<$($param)*> std::ops::$op<&$tgt> for $tgt
+        where
+            $item: Copy + std::ops::$op_assign,
+        {
+            type Output = $tgt;
+
+            /// # Panics
+            /// Panics if the bounds of the two volumes are not equal.
+            #[inline]
+            fn $method(mut self, rhs: &$tgt) -> Self::Output {
+                std::ops::$op_assign::$method_assign(&mut self, rhs);
+                self
+            }
+        }
+
+        impl<$($param)*> std::ops::$op_assign<$item> for $tgt
+        where
+            $item: Copy + std::ops::$op_assign,
+        {
+            #[inline]
+            fn $method_assign(&mut self, rhs: $item) {
+                for lhs in self.items_mut() {
+                    std::ops::$op_assign::$method_assign(lhs, rhs);
+                }
+            }
+        }
+
+        impl<$($param)*> std::ops::$op<$item> for $tgt
+        where
+            $item: Copy + std::ops::$op_assign,
+        {
+            type Output = $tgt;
+
+            #[inline]
+            fn $method(mut self, rhs: $item) -> Self::Output {
+                std::ops::$op_assign::$method_assign(&mut self, rhs);
+                self
+            }
+        }
+    };
 }
 
-#[cfg(any(feature = "nalgebra", feature = "glam"))]
+macro_rules! impl_as_f64 {
+    ($($t:ty),+) => {
+        $(
+            impl crate::traits::AsF64 for $t {
+                #[inline(always)]
+                fn as_f64(self) -> f64 {
+                    self as f64
+                }
+            }
+        )+
+    };
+}
+
+macro_rules! impl_schema_name {
+    ($($t:ty),+) => {
+        $(
+            impl crate::traits::SchemaName for $t {
+                const NAME: &'static str = stringify!($t);
+            }
+        )+
+    };
+}
+
+#[cfg(any(feature = "nalgebra", feature = "glam", feature = "bevy"))]
 macro_rules! impl_boundingbox_from_volumeidx_range {
     ($t:ty) => {
         impl From<std::ops::Range<$t>> for crate::prelude::BoundingBox
@@ -88,3 +201,20 @@ macro_rules! impl_boundingbox_from_glam_vec_range {
         impl_boundingbox_from_glam_vec_range!($($ts),+);
     };
 }
+
+/// Enter a `tracing` debug span which stays entered until the end of the current scope.
+/// Expands to nothing if the `tracing` feature is disabled.
+macro_rules! debug_span {
+    ($($args:tt)+) => {
+        #[cfg(feature = "tracing")]
+        let _span = ::tracing::debug_span!($($args)+).entered();
+    };
+}
+
+/// Emit a `tracing` debug event. Expands to nothing if the `tracing` feature is disabled.
+macro_rules! debug_event {
+    ($($args:tt)+) => {
+        #[cfg(feature = "tracing")]
+        ::tracing::debug!($($args)+);
+    };
+}
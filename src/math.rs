@@ -0,0 +1,66 @@
+//! Component-wise operations on `[N; 3]` vectors, the representation of positions and dimensions used throughout this crate.
+
+use crate::types::BoundingBox;
+use num_traits::{NumCast, PrimInt};
+
+/// Cast every component of a vector to another integer type. Returns [`None`] if any component doesn't fit in `T`.
+#[inline(always)]
+pub fn cast_ivec3<T: NumCast, N: PrimInt>(arr: [N; 3]) -> Option<[T; 3]> {
+    let [x, y, z] = arr;
+
+    Some([
+        <T as NumCast>::from(x)?,
+        <T as NumCast>::from(y)?,
+        <T as NumCast>::from(z)?,
+    ])
+}
+
+/// Cast every component of a vector to another integer type, clamping components that don't fit in `T` to its minimum or maximum.
+#[inline(always)]
+pub fn saturating_cast_ivec3<T: PrimInt, N: PrimInt>(arr: [N; 3]) -> [T; 3] {
+    arr.map(|c| match <T as NumCast>::from(c) {
+        Some(c) => c,
+        None if c < N::zero() => T::min_value(),
+        None => T::max_value(),
+    })
+}
+
+#[inline(always)]
+pub fn sum_ivec3<N: std::ops::Add<Output = N> + Copy>(lhs: [N; 3], rhs: [N; 3]) -> [N; 3] {
+    [lhs[0] + rhs[0], lhs[1] + rhs[1], lhs[2] + rhs[2]]
+}
+
+#[inline(always)]
+pub fn sub_ivec3<N: std::ops::Sub<Output = N> + Copy>(lhs: [N; 3], rhs: [N; 3]) -> [N; 3] {
+    [lhs[0] - rhs[0], lhs[1] - rhs[1], lhs[2] - rhs[2]]
+}
+
+/// Multiply every component of a vector by `scalar`.
+#[inline(always)]
+pub fn scale_ivec3<N: std::ops::Mul<Output = N> + Copy>(arr: [N; 3], scalar: N) -> [N; 3] {
+    [arr[0] * scalar, arr[1] * scalar, arr[2] * scalar]
+}
+
+/// The component-wise minimum of two vectors.
+#[inline(always)]
+pub fn min_ivec3<N: Ord + Copy>(lhs: [N; 3], rhs: [N; 3]) -> [N; 3] {
+    [lhs[0].min(rhs[0]), lhs[1].min(rhs[1]), lhs[2].min(rhs[2])]
+}
+
+/// The component-wise maximum of two vectors.
+#[inline(always)]
+pub fn max_ivec3<N: Ord + Copy>(lhs: [N; 3], rhs: [N; 3]) -> [N; 3] {
+    [lhs[0].max(rhs[0]), lhs[1].max(rhs[1]), lhs[2].max(rhs[2])]
+}
+
+/// The position in `bounds` closest to `idx`, found by clamping every component between the minimum (inclusive) and
+/// maximum (exclusive) corner. Returns [`None`] if the bounds are empty, since then there's no position to clamp to.
+#[inline]
+pub fn clamp_ivec3(idx: [i64; 3], bounds: BoundingBox) -> Option<[i64; 3]> {
+    if bounds.is_empty() {
+        return None;
+    }
+
+    let (min, max) = (bounds.min(), bounds.max());
+    Some([0, 1, 2].map(|axis| idx[axis].clamp(min[axis], max[axis] - 1)))
+}
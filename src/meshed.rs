@@ -0,0 +1,219 @@
+//! Caching derived data (like meshes) per tile of a volume, and only recomputing it for the tiles that changed.
+
+use crate::prelude::*;
+use crate::types::ValidationError;
+
+/// Wrapper around a volume that caches a mesh (or any other data derived from the items) per tile, and keeps track of
+/// which tiles changed since they were last meshed.
+///
+/// Writes through this wrapper mark the tiles they touch as dirty, along with the neighboring tiles when the written position
+/// is on a tile's edge (since the faces a mesher emits on a tile's edge depend on the items right next to it).
+/// [`MeshedVolume::mesh`] then only re-meshes the dirty tiles, so a single edit costs one small tile instead of the whole volume.
+///
+/// Tiles are aligned to the minimum of the inner volume's bounding box. Tiles on the upper edges are smaller if the
+/// dimensions of the volume aren't multiples of the tile dimensions.
+#[derive(Debug, Clone)]
+pub struct MeshedVolume<V, M> {
+    inner: V,
+    tile_dims: [i64; 3],
+    /// The amount of tiles along each axis.
+    counts: [usize; 3],
+    /// The cached mesh of every tile in X-major order, [`None`] if the tile was never meshed.
+    meshes: Vec<Option<M>>,
+    dirty: Vec<bool>,
+}
+
+impl_indexing!([V, M], MeshedVolume<V, M>);
+
+impl<V: Volume, M> MeshedVolume<V, M> {
+    /// Wrap a volume, splitting it into tiles of (up to) `tile_dims` items. Every tile starts out dirty.
+    ///
+    /// # Panics
+    /// Panics if any of the tile dimensions is less than 1.
+    #[inline]
+    pub fn new(inner: V, tile_dims: [i64; 3]) -> Self {
+        assert!(
+            tile_dims.iter().all(|&d| d > 0),
+            "tile dimensions must be at least 1"
+        );
+
+        let dims = inner.bounding_box().dimensions();
+        let counts = [0, 1, 2].map(|axis| (dims[axis] as usize).div_ceil(tile_dims[axis] as usize));
+        let tiles = counts[0] * counts[1] * counts[2];
+
+        Self {
+            inner,
+            tile_dims,
+            counts,
+            meshes: std::iter::repeat_with(|| None).take(tiles).collect(),
+            dirty: vec![true; tiles],
+        }
+    }
+
+    #[inline]
+    pub fn inner(&self) -> &V {
+        &self.inner
+    }
+
+    /// Get mutable access to the inner volume. Changes made through it aren't tracked, so mark the regions they touch
+    /// with [`MeshedVolume::mark_dirty`].
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut V {
+        &mut self.inner
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> V {
+        self.inner
+    }
+
+    #[inline]
+    pub fn tile_dims(&self) -> [i64; 3] {
+        self.tile_dims
+    }
+
+    /// The amount of tiles that need to be re-meshed.
+    #[inline]
+    pub fn dirty_tiles(&self) -> usize {
+        self.dirty.iter().filter(|&&dirty| dirty).count()
+    }
+
+    /// Mark every tile whose mesh could depend on the items in `region` as dirty. That is every tile intersecting the region,
+    /// and the neighbors of those tiles if the region touches their shared edge.
+    #[inline]
+    pub fn mark_dirty(&mut self, region: BoundingBox) {
+        if region.is_empty() {
+            return;
+        }
+
+        let (min, max) = (self.inner.bounding_box().min(), region.max());
+        let [xs, ys, zs] = [0, 1, 2].map(|axis| {
+            // Grown by one position on every side, to reach the tiles sharing an edge with the region.
+            let tile = |pos: i64| (pos - min[axis]).div_euclid(self.tile_dims[axis]);
+            let start = tile(region.min()[axis] - 1).max(0) as usize;
+            let end = (tile(max[axis]) + 1).clamp(0, self.counts[axis] as i64) as usize;
+
+            start..end
+        });
+
+        for z in zs {
+            for y in ys.clone() {
+                for x in xs.clone() {
+                    self.dirty[x + self.counts[0] * (y + self.counts[1] * z)] = true;
+                }
+            }
+        }
+    }
+
+    /// Mark every tile as dirty, e.g. after changing how meshes are built.
+    #[inline]
+    pub fn mark_all_dirty(&mut self) {
+        self.dirty.fill(true);
+    }
+
+    /// Re-mesh every dirty tile by calling `mesher` with the inner volume and the bounds of the tile. Returns how many tiles
+    /// were re-meshed.
+    ///
+    /// The mesher may read outside of the tile's bounds (e.g., to cull faces against neighboring items), but only positions
+    /// directly next to the tile are tracked.
+    #[inline]
+    pub fn mesh<F>(&mut self, mut mesher: F) -> usize
+    where
+        F: FnMut(&V, BoundingBox) -> M,
+    {
+        let mut count = 0;
+        for i in 0..self.dirty.len() {
+            if std::mem::take(&mut self.dirty[i]) {
+                self.meshes[i] = Some(mesher(&self.inner, self.tile_bounds(i)));
+                count += 1;
+            }
+        }
+
+        debug_event!(remeshed = count, "meshed volume");
+        count
+    }
+
+    /// Iterate over the bounds and cached meshes of the tiles that have been meshed, in X-major order.
+    /// The meshes of dirty tiles are the ones from before they became dirty.
+    #[inline]
+    pub fn meshes(&self) -> impl Iterator<Item = (BoundingBox, &M)> {
+        self.meshes
+            .iter()
+            .enumerate()
+            .filter_map(|(i, mesh)| Some((self.tile_bounds(i), mesh.as_ref()?)))
+    }
+
+    /// The bounds of the tile with the given X-major index.
+    #[inline]
+    fn tile_bounds(&self, i: usize) -> BoundingBox {
+        let tile = [
+            i % self.counts[0],
+            i / self.counts[0] % self.counts[1],
+            i / (self.counts[0] * self.counts[1]),
+        ];
+
+        let bounds = self.inner.bounding_box();
+        let [min, max] = [0, 1].map(|corner| {
+            [0, 1, 2].map(|axis| {
+                let pos = bounds.min()[axis] + (tile[axis] + corner) as i64 * self.tile_dims[axis];
+                pos.min(bounds.max()[axis])
+            })
+        });
+
+        BoundingBox::new(min, max)
+    }
+}
+
+impl<V: Volume, M> Volume for MeshedVolume<V, M> {
+    type Item = V::Item;
+
+    #[inline(always)]
+    fn ls_get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        self.inner.ls_get(idx)
+    }
+
+    #[inline(always)]
+    fn bounding_box(&self) -> BoundingBox {
+        self.inner.bounding_box()
+    }
+
+    #[inline(always)]
+    fn get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        self.inner.get(idx)
+    }
+
+    #[inline]
+    fn heap_size(&self) -> usize {
+        self.inner.heap_size()
+            + self.meshes.capacity() * std::mem::size_of::<Option<M>>()
+            + self.dirty.capacity()
+    }
+
+    #[inline]
+    fn validate(&self) -> Result<(), ValidationError> {
+        self.inner.validate()
+    }
+}
+
+impl<V: VolumeMut, M> VolumeMut for MeshedVolume<V, M> {
+    #[inline]
+    fn ls_get_mut<Idx: VolumeIdx>(&mut self, idx: Idx) -> Option<&mut Self::Item> {
+        let min = self.bounding_box().min();
+        self.get_mut(idx.array::<i64>()?.checked_add(min)?)
+    }
+
+    /// Get a mutable reference to an item, marking the tiles whose meshes depend on it as dirty.
+    #[inline]
+    fn get_mut<Idx: VolumeIdx>(&mut self, idx: Idx) -> Option<&mut Self::Item> {
+        let pos = idx.array::<i64>()?;
+        if !self.bounding_box().contains(pos) {
+            return None;
+        }
+
+        self.mark_dirty(BoundingBox::new(
+            pos,
+            crate::util::sum_ivec3(pos, [1, 1, 1]),
+        ));
+        self.inner.get_mut(pos)
+    }
+}
@@ -0,0 +1,220 @@
+//! Item-level migrations for upgrading volumes saved by older versions of a game.
+//!
+//! This crate doesn't read or write volumes itself, so it doesn't know which version some data was saved with. Store a
+//! version number next to your saved volumes (e.g., in your own format or alongside a [`VolumeSchema`](crate::types::VolumeSchema)),
+//! load their items as the item type of that version, and pass them to [`Migrations::migrate`] to bring them up to date.
+
+use crate::prelude::*;
+use crate::types::MigrationError;
+use std::any::{type_name, Any, TypeId};
+use std::marker::PhantomData;
+
+/// A converter from the items of one version to the items of the next, with the item types erased so converters between
+/// different types can be stored in one chain.
+trait Step: Send + Sync {
+    /// The [`TypeId`] and name of the item type this converter takes.
+    fn input(&self) -> (TypeId, &'static str);
+
+    /// Convert a boxed item of the input type.
+    fn item(&self, item: Box<dyn Any>) -> Box<dyn Any>;
+
+    /// Convert a boxed [`HeapVolume`] of the input type.
+    fn volume(&self, vol: Box<dyn Any>) -> Box<dyn Any>;
+}
+
+struct TypedStep<A, B, F> {
+    convert: F,
+    _types: PhantomData<fn(A) -> B>,
+}
+
+// The input types are checked before the first converter is applied, and every other converter takes the output of the one
+// before it, so the downcasts can't fail.
+impl<A: 'static, B: 'static, F: Fn(A) -> B + Send + Sync> Step for TypedStep<A, B, F> {
+    #[inline]
+    fn input(&self) -> (TypeId, &'static str) {
+        (TypeId::of::<A>(), type_name::<A>())
+    }
+
+    #[inline]
+    fn item(&self, item: Box<dyn Any>) -> Box<dyn Any> {
+        Box::new((self.convert)(*item.downcast::<A>().unwrap()))
+    }
+
+    #[inline]
+    fn volume(&self, vol: Box<dyn Any>) -> Box<dyn Any> {
+        Box::new(
+            vol.downcast::<HeapVolume<A>>()
+                .unwrap()
+                .map_items(&self.convert),
+        )
+    }
+}
+
+/// A chain of item converters, each upgrading items from one version to the next, with `T` being the item type of the
+/// current version.
+///
+/// Versions are numbered consecutively: the first converter upgrades items from the first version to the one after it, and
+/// so on. Loading data of any older version applies every converter from that version up to the
+/// [current version](Migrations::current_version) in order, so each release only has to provide a converter from the
+/// version before it.
+///
+/// The item type can change between versions, e.g. when a save format moves from raw block IDs to a block struct:
+///
+/// ```
+/// # use volume::migrate::Migrations;
+/// #[derive(Debug, PartialEq)]
+/// struct Block { id: u16, lit: bool }
+///
+/// // Version 1 stored raw IDs, version 2 renumbered them, and version 3 added the light flag.
+/// let migrations = Migrations::<u8>::new(1)
+///     .then(|id| id as u16 * 10)
+///     .then(|id| Block { id, lit: false });
+///
+/// assert_eq!(3, migrations.current_version());
+/// assert_eq!(Ok(Block { id: 40, lit: false }), migrations.migrate_item(4u8, 1));
+/// assert_eq!(Ok(Block { id: 4, lit: false }), migrations.migrate_item(4u16, 2));
+/// ```
+pub struct Migrations<T> {
+    first: u32,
+    steps: Vec<Box<dyn Step>>,
+    _item: PhantomData<fn() -> T>,
+}
+
+impl<T> std::fmt::Debug for Migrations<T> {
+    #[inline]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Migrations")
+            .field("first", &self.first)
+            .field("current", &self.current_version())
+            .finish()
+    }
+}
+
+impl<T> Migrations<T> {
+    /// Create a chain without any converters, where `first_version` is the oldest version that can be migrated
+    /// (and, until a converter is added, the current one), whose items are of type `T`.
+    #[inline]
+    pub fn new(first_version: u32) -> Self {
+        Self {
+            first: first_version,
+            steps: Vec::new(),
+            _item: PhantomData,
+        }
+    }
+
+    /// The oldest version that can be migrated.
+    #[inline]
+    pub fn first_version(&self) -> u32 {
+        self.first
+    }
+
+    /// The version items are migrated to.
+    #[inline]
+    pub fn current_version(&self) -> u32 {
+        self.first + self.steps.len() as u32
+    }
+}
+
+impl<T: 'static> Migrations<T> {
+    /// Add a converter from the current version to the next one, whose items are of type `U`. The next version becomes the
+    /// new current version.
+    ///
+    /// # Panics
+    /// Panics if the version overflows [`u32`].
+    #[inline]
+    pub fn then<U: 'static, F>(self, convert: F) -> Migrations<U>
+    where
+        F: Fn(T) -> U + Send + Sync + 'static,
+    {
+        assert!(
+            self.current_version() < u32::MAX,
+            "migration version overflows u32"
+        );
+
+        let mut steps = self.steps;
+        steps.push(Box::new(TypedStep {
+            convert,
+            _types: PhantomData,
+        }));
+
+        Migrations {
+            first: self.first,
+            steps,
+            _item: PhantomData,
+        }
+    }
+
+    /// Register a converter from the current version to the next one, for versions that keep the same item type. The next
+    /// version becomes the new current version, which is returned.
+    ///
+    /// # Panics
+    /// Panics if the version overflows [`u32`].
+    #[inline]
+    pub fn register<F>(&mut self, convert: F) -> u32
+    where
+        F: Fn(T) -> T + Send + Sync + 'static,
+    {
+        *self = std::mem::replace(self, Self::new(self.first)).then(convert);
+        self.current_version()
+    }
+
+    /// The converters needed to upgrade items of type `S` from the given version.
+    #[inline]
+    fn steps_from<S: 'static>(&self, version: u32) -> Result<&[Box<dyn Step>], MigrationError> {
+        if version < self.first || version > self.current_version() {
+            return Err(MigrationError::UnsupportedVersion {
+                found: version,
+                first: self.first,
+                current: self.current_version(),
+            });
+        }
+
+        let steps = &self.steps[(version - self.first) as usize..];
+        let (expected, name) = match steps.first() {
+            Some(step) => step.input(),
+            None => (TypeId::of::<T>(), type_name::<T>()),
+        };
+        if TypeId::of::<S>() != expected {
+            return Err(MigrationError::ItemType {
+                version,
+                expected: name,
+                found: type_name::<S>(),
+            });
+        }
+
+        Ok(steps)
+    }
+
+    /// Upgrade a single item of the given version (whose type must be that version's item type) to the current version.
+    #[inline]
+    pub fn migrate_item<S: 'static>(&self, item: S, version: u32) -> Result<T, MigrationError> {
+        let item = self
+            .steps_from::<S>(version)?
+            .iter()
+            .fold(Box::new(item) as Box<dyn Any>, |item, step| step.item(item));
+
+        Ok(*item.downcast::<T>().unwrap())
+    }
+
+    /// Upgrade every item of `vol` from the given version (whose items must be of that version's item type) to the current
+    /// version. Returns an error without converting anything if the version is older than the first version or newer than
+    /// the current one, or if the items are of the wrong type.
+    ///
+    /// Every converter is applied to the whole volume before the next one, so the item types are only checked once per
+    /// converter rather than once per item.
+    #[inline]
+    pub fn migrate<S: 'static>(
+        &self,
+        vol: HeapVolume<S>,
+        version: u32,
+    ) -> Result<HeapVolume<T>, MigrationError> {
+        let steps = self.steps_from::<S>(version)?;
+        debug_span!("migrate", from = version, to = self.current_version());
+
+        let vol = steps
+            .iter()
+            .fold(Box::new(vol) as Box<dyn Any>, |vol, step| step.volume(vol));
+
+        Ok(*vol.downcast::<HeapVolume<T>>().unwrap())
+    }
+}
@@ -0,0 +1,183 @@
+//! Test doubles for unit testing code that's generic over [`Volume`] and [`VolumeMut`].
+
+use crate::prelude::*;
+use crate::types::ValidationError;
+use std::sync::Mutex;
+
+/// A single access made to a [`MockVolume`], with the worldspace index that was accessed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Access {
+    /// The item was accessed immutably, through [`Volume::get`] or [`Volume::ls_get`].
+    Get([i64; 3]),
+    /// The item was accessed mutably, through [`VolumeMut::get_mut`] or [`VolumeMut::ls_get_mut`].
+    GetMut([i64; 3]),
+}
+
+/// A volume that records every access made to it, for testing the access patterns of algorithms taking an
+/// `impl Volume` instead of just their final result.
+///
+/// Reads return canned responses set with [`MockVolume::respond`] (or the default item the volume was created with), and
+/// writes go to the same storage, so a mock volume otherwise behaves just like a [`HeapVolume`]. Accesses can be inspected
+/// with [`MockVolume::accesses`], or checked against a script of expected accesses set up with [`MockVolume::expect`].
+///
+/// Accesses are recorded even if they're out of bounds, but every access is only recorded once, even if it goes through
+/// several trait methods (e.g., [`Volume::get`] calling [`Volume::ls_get`]). Accesses with an index that can't be converted to
+/// `[i64; 3]` aren't recorded.
+#[derive(Debug)]
+pub struct MockVolume<T> {
+    inner: HeapVolume<T>,
+    accesses: Mutex<Vec<Access>>,
+    expected: Vec<Access>,
+}
+
+impl_indexing!(T, MockVolume<T>);
+
+impl<T: Clone> MockVolume<T> {
+    /// Create a mock volume with the given bounds, where every read returns `item` until told otherwise.
+    #[inline]
+    pub fn new(item: T, bounds: BoundingBox) -> Self {
+        Self::from_volume(HeapVolume::new(item, bounds))
+    }
+}
+
+impl<T> MockVolume<T> {
+    /// Create a mock volume responding with the items of the given volume.
+    #[inline]
+    pub fn from_volume(inner: HeapVolume<T>) -> Self {
+        Self {
+            inner,
+            accesses: Mutex::new(Vec::new()),
+            expected: Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> HeapVolume<T> {
+        self.inner
+    }
+
+    /// Set the item returned when reading the given worldspace index, without recording an access.
+    ///
+    /// # Panics
+    /// Panics if the index is out of bounds.
+    #[inline]
+    pub fn respond<Idx: VolumeIdx>(&mut self, idx: Idx, item: T) -> &mut Self {
+        *self
+            .inner
+            .get_mut(idx)
+            .expect("canned response is out of bounds") = item;
+        self
+    }
+
+    /// Add an access to the end of the script of expected accesses, checked by [`MockVolume::verify`].
+    #[inline]
+    pub fn expect(&mut self, access: Access) -> &mut Self {
+        self.expected.push(access);
+        self
+    }
+
+    /// Every access recorded so far, in the order they were made.
+    #[inline]
+    pub fn accesses(&self) -> Vec<Access> {
+        self.log().clone()
+    }
+
+    /// Forget every recorded access (but not the expected ones).
+    #[inline]
+    pub fn clear_accesses(&mut self) {
+        self.log().clear();
+    }
+
+    /// Check that the recorded accesses are exactly the expected ones, in the same order.
+    ///
+    /// # Panics
+    /// Panics with the first access that differs from the script, or if there were too many or too few accesses.
+    #[track_caller]
+    pub fn verify(&self) {
+        let accesses = self.log();
+
+        for (i, (found, expected)) in accesses.iter().zip(&self.expected).enumerate() {
+            assert_eq!(
+                expected, found,
+                "access #{i} doesn't match the expected access"
+            );
+        }
+
+        assert_eq!(
+            self.expected.len(),
+            accesses.len(),
+            "expected {} accesses but {} were made, the first unmatched one was {:?}",
+            self.expected.len(),
+            accesses.len(),
+            accesses
+                .get(self.expected.len())
+                .or(self.expected.get(accesses.len())),
+        );
+    }
+
+    #[inline]
+    fn log(&self) -> std::sync::MutexGuard<'_, Vec<Access>> {
+        // A panic while holding the lock can't leave the log in an inconsistent state.
+        self.accesses.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    #[inline]
+    fn record(&self, access: fn([i64; 3]) -> Access, pos: Option<[i64; 3]>) {
+        if let Some(pos) = pos {
+            self.log().push(access(pos));
+        }
+    }
+}
+
+impl<T> Volume for MockVolume<T> {
+    type Item = T;
+
+    #[inline]
+    fn ls_get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        let min = self.bounding_box().min();
+        self.record(
+            Access::Get,
+            idx.array().and_then(|ls: [i64; 3]| ls.checked_add(min)),
+        );
+        self.inner.ls_get(idx)
+    }
+
+    #[inline]
+    fn get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        self.record(Access::Get, idx.array());
+        self.inner.get(idx)
+    }
+
+    #[inline]
+    fn bounding_box(&self) -> BoundingBox {
+        self.inner.bounding_box()
+    }
+
+    #[inline]
+    fn heap_size(&self) -> usize {
+        self.inner.heap_size()
+    }
+
+    #[inline]
+    fn validate(&self) -> Result<(), ValidationError> {
+        self.inner.validate()
+    }
+}
+
+impl<T> VolumeMut for MockVolume<T> {
+    #[inline]
+    fn ls_get_mut<Idx: VolumeIdx>(&mut self, idx: Idx) -> Option<&mut Self::Item> {
+        let min = self.bounding_box().min();
+        self.record(
+            Access::GetMut,
+            idx.array().and_then(|ls: [i64; 3]| ls.checked_add(min)),
+        );
+        self.inner.ls_get_mut(idx)
+    }
+
+    #[inline]
+    fn get_mut<Idx: VolumeIdx>(&mut self, idx: Idx) -> Option<&mut Self::Item> {
+        self.record(Access::GetMut, idx.array());
+        self.inner.get_mut(idx)
+    }
+}
@@ -0,0 +1,275 @@
+//! Morphological operations (dilation, erosion, opening, closing) on boolean/occupancy volumes.
+//!
+//! All operations treat out of bounds cells as if they didn't exist, meaning that cells outside of a volume's bounds never
+//! cause a cell to be dilated or eroded.
+//!
+//! Every operation has a `_with_progress` variant taking a callback which is periodically given the progress
+//! of the operation (from 0.0 to 1.0), and can cancel it by returning [`ControlFlow::Break`].
+
+use crate::prelude::*;
+use crate::types::Cancelled;
+use crate::util::{self, ProgressTracker};
+use std::ops::ControlFlow;
+
+/// Which neighbors of a cell are considered adjacent to it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Connectivity {
+    /// Cells sharing a face are adjacent. Repeated `radius` times this produces an octahedron shaped neighborhood.
+    Six,
+    /// Cells sharing a face, edge, or corner are adjacent. Repeated `radius` times this produces a cube shaped neighborhood.
+    TwentySix,
+}
+
+/// The shape of the neighborhood used by the morphological operations.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct StructuringElement {
+    pub connectivity: Connectivity,
+    pub radius: usize,
+}
+
+impl StructuringElement {
+    #[inline]
+    pub fn new(connectivity: Connectivity, radius: usize) -> Self {
+        Self {
+            connectivity,
+            radius,
+        }
+    }
+}
+
+/// Dense working buffer in X-major order (the same order as [`BoundingBox`] iteration).
+struct Grid {
+    cells: Vec<bool>,
+    dims: [usize; 3],
+}
+
+impl Grid {
+    fn from_volume<V: Volume<Item = bool>>(vol: &V) -> Self {
+        let bounds = vol.bounding_box();
+        let [x, y, z] = bounds.dimensions();
+
+        Self {
+            cells: vol
+                .iter_indices()
+                .map(|idx| *vol.get(idx).unwrap())
+                .collect(),
+            dims: [x as usize, y as usize, z as usize],
+        }
+    }
+
+    fn into_volume(self, bounds: BoundingBox) -> HeapVolume<bool> {
+        let mut vol = HeapVolume::new(false, bounds);
+        for (idx, cell) in bounds.into_iter().zip(self.cells) {
+            vol[idx] = cell;
+        }
+
+        vol
+    }
+
+    fn stride(&self, axis: usize) -> usize {
+        self.dims[..axis].iter().product()
+    }
+
+    fn invert(&mut self) {
+        self.cells.iter_mut().for_each(|c| *c = !*c);
+    }
+
+    /// How many cells the operations will process when dilating with this element. Used for progress reporting.
+    fn dilate_cost(&self, element: StructuringElement) -> u64 {
+        let passes = match element.connectivity {
+            Connectivity::TwentySix => 3,
+            Connectivity::Six => 3 * element.radius,
+        };
+
+        (passes * self.cells.len()) as u64
+    }
+
+    /// Dilate along a single axis with a 1D window of the given radius.
+    fn dilate_axis<F: FnMut(f32) -> ControlFlow<()>>(
+        &self,
+        axis: usize,
+        radius: usize,
+        progress: &mut ProgressTracker<F>,
+    ) -> Result<Vec<bool>, Cancelled> {
+        let len = self.dims[axis];
+        let stride = self.stride(axis);
+        let mut out = vec![false; self.cells.len()];
+
+        // Every cell whose coordinate along `axis` is 0 starts a line along that axis.
+        for start in 0..self.cells.len() {
+            if !(start / stride).is_multiple_of(len) {
+                continue;
+            }
+
+            // Running count of set cells in the window [i - radius, i + radius], clamped to the line.
+            let line = |i: usize| self.cells[start + i * stride];
+            let mut count = (0..len.min(radius + 1)).filter(|&i| line(i)).count();
+
+            for i in 0..len {
+                out[start + i * stride] = count > 0;
+
+                if i + radius + 1 < len && line(i + radius + 1) {
+                    count += 1;
+                }
+                if i >= radius && line(i - radius) {
+                    count -= 1;
+                }
+            }
+
+            progress.advance(len as u64)?;
+        }
+
+        Ok(out)
+    }
+
+    fn dilate<F: FnMut(f32) -> ControlFlow<()>>(
+        &mut self,
+        element: StructuringElement,
+        progress: &mut ProgressTracker<F>,
+    ) -> Result<(), Cancelled> {
+        if self.cells.is_empty() {
+            return Ok(());
+        }
+
+        match element.connectivity {
+            // A cube is separable, so 3 passes (one per axis) with the full radius are enough.
+            Connectivity::TwentySix => {
+                for axis in 0..3 {
+                    self.cells = self.dilate_axis(axis, element.radius, progress)?;
+                }
+            }
+            // The octahedron isn't separable, but it's equivalent to dilating by the 6-neighborhood cross `radius` times,
+            // and the cross is the union of one radius 1 pass along each axis.
+            Connectivity::Six => {
+                for _ in 0..element.radius {
+                    let x = self.dilate_axis(0, 1, progress)?;
+                    let y = self.dilate_axis(1, 1, progress)?;
+                    let z = self.dilate_axis(2, 1, progress)?;
+                    for (i, cell) in self.cells.iter_mut().enumerate() {
+                        *cell = x[i] || y[i] || z[i];
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn erode<F: FnMut(f32) -> ControlFlow<()>>(
+        &mut self,
+        element: StructuringElement,
+        progress: &mut ProgressTracker<F>,
+    ) -> Result<(), Cancelled> {
+        self.invert();
+        self.dilate(element, progress)?;
+        self.invert();
+
+        Ok(())
+    }
+}
+
+/// Dilate the set cells in `vol` by the structuring element, returning the result as a new volume with the same bounds.
+#[inline]
+pub fn dilate<V: Volume<Item = bool>>(vol: &V, element: StructuringElement) -> HeapVolume<bool> {
+    dilate_with_progress(vol, element, util::no_progress).unwrap()
+}
+
+/// Same as [`dilate`], but reports progress to the given callback which may cancel the operation.
+#[inline]
+pub fn dilate_with_progress<V, F>(
+    vol: &V,
+    element: StructuringElement,
+    progress: F,
+) -> Result<HeapVolume<bool>, Cancelled>
+where
+    V: Volume<Item = bool>,
+    F: FnMut(f32) -> ControlFlow<()>,
+{
+    debug_span!("dilate", bounds = %vol.bounding_box(), ?element);
+
+    let mut grid = Grid::from_volume(vol);
+    let mut progress = ProgressTracker::new(grid.dilate_cost(element), progress);
+
+    grid.dilate(element, &mut progress)?;
+    Ok(grid.into_volume(vol.bounding_box()))
+}
+
+/// Erode the set cells in `vol` by the structuring element, returning the result as a new volume with the same bounds.
+#[inline]
+pub fn erode<V: Volume<Item = bool>>(vol: &V, element: StructuringElement) -> HeapVolume<bool> {
+    erode_with_progress(vol, element, util::no_progress).unwrap()
+}
+
+/// Same as [`erode`], but reports progress to the given callback which may cancel the operation.
+#[inline]
+pub fn erode_with_progress<V, F>(
+    vol: &V,
+    element: StructuringElement,
+    progress: F,
+) -> Result<HeapVolume<bool>, Cancelled>
+where
+    V: Volume<Item = bool>,
+    F: FnMut(f32) -> ControlFlow<()>,
+{
+    debug_span!("erode", bounds = %vol.bounding_box(), ?element);
+
+    let mut grid = Grid::from_volume(vol);
+    let mut progress = ProgressTracker::new(grid.dilate_cost(element), progress);
+
+    grid.erode(element, &mut progress)?;
+    Ok(grid.into_volume(vol.bounding_box()))
+}
+
+/// Morphological opening (erosion followed by dilation). Removes small protrusions and isolated cells.
+#[inline]
+pub fn open<V: Volume<Item = bool>>(vol: &V, element: StructuringElement) -> HeapVolume<bool> {
+    open_with_progress(vol, element, util::no_progress).unwrap()
+}
+
+/// Same as [`open`], but reports progress to the given callback which may cancel the operation.
+#[inline]
+pub fn open_with_progress<V, F>(
+    vol: &V,
+    element: StructuringElement,
+    progress: F,
+) -> Result<HeapVolume<bool>, Cancelled>
+where
+    V: Volume<Item = bool>,
+    F: FnMut(f32) -> ControlFlow<()>,
+{
+    debug_span!("open", bounds = %vol.bounding_box(), ?element);
+
+    let mut grid = Grid::from_volume(vol);
+    let mut progress = ProgressTracker::new(2 * grid.dilate_cost(element), progress);
+
+    grid.erode(element, &mut progress)?;
+    grid.dilate(element, &mut progress)?;
+    Ok(grid.into_volume(vol.bounding_box()))
+}
+
+/// Morphological closing (dilation followed by erosion). Fills small holes and gaps.
+#[inline]
+pub fn close<V: Volume<Item = bool>>(vol: &V, element: StructuringElement) -> HeapVolume<bool> {
+    close_with_progress(vol, element, util::no_progress).unwrap()
+}
+
+/// Same as [`close`], but reports progress to the given callback which may cancel the operation.
+#[inline]
+pub fn close_with_progress<V, F>(
+    vol: &V,
+    element: StructuringElement,
+    progress: F,
+) -> Result<HeapVolume<bool>, Cancelled>
+where
+    V: Volume<Item = bool>,
+    F: FnMut(f32) -> ControlFlow<()>,
+{
+    debug_span!("close", bounds = %vol.bounding_box(), ?element);
+
+    let mut grid = Grid::from_volume(vol);
+    let mut progress = ProgressTracker::new(2 * grid.dilate_cost(element), progress);
+
+    grid.dilate(element, &mut progress)?;
+    grid.erode(element, &mut progress)?;
+    Ok(grid.into_volume(vol.bounding_box()))
+}
@@ -0,0 +1,264 @@
+//! Volumes made up of several parallel channels (e.g., block ids, light levels, and biomes) sharing the same bounds.
+
+use crate::prelude::*;
+use crate::types::{BoundsMismatchError, ValidationError};
+
+/// A tuple of item types which can be stored as the channels of a [`MultiVolume`]. Implemented for tuples of up to 8 types.
+pub trait Channels: Sized {
+    /// The volumes storing each channel, a tuple of [`HeapVolume`]s.
+    type Volumes;
+    /// References to the items of every channel at one position.
+    type Refs<'a>
+    where
+        Self: 'a;
+    /// Mutable references to the items of every channel at one position.
+    type Muts<'a>
+    where
+        Self: 'a;
+
+    #[doc(hidden)]
+    fn filled(items: Self, bounds: BoundingBox) -> Self::Volumes;
+    #[doc(hidden)]
+    fn common_bounds(volumes: &Self::Volumes) -> Result<BoundingBox, BoundsMismatchError>;
+    #[doc(hidden)]
+    fn get(volumes: &Self::Volumes, idx: [i64; 3]) -> Option<Self::Refs<'_>>;
+    #[doc(hidden)]
+    fn get_mut(volumes: &mut Self::Volumes, idx: [i64; 3]) -> Option<Self::Muts<'_>>;
+    #[doc(hidden)]
+    fn set(volumes: &mut Self::Volumes, idx: [i64; 3], items: Self);
+    #[doc(hidden)]
+    fn heap_size(volumes: &Self::Volumes) -> usize;
+    #[doc(hidden)]
+    fn validate(volumes: &Self::Volumes) -> Result<(), ValidationError>;
+}
+
+/// Access to channel number `N` of a [`MultiVolume`]. The index is the position of the channel's item type in the [`Channels`] tuple.
+pub trait ChannelAt<const N: usize>: Channels {
+    type Item;
+
+    #[doc(hidden)]
+    fn channel(volumes: &Self::Volumes) -> &HeapVolume<Self::Item>;
+    #[doc(hidden)]
+    fn channel_mut(volumes: &mut Self::Volumes) -> &mut HeapVolume<Self::Item>;
+}
+
+macro_rules! impl_channels {
+    ($($item:ident $i:tt),+) => {
+        impl<$($item: Clone),+> Channels for ($($item,)+) {
+            type Volumes = ($(HeapVolume<$item>,)+);
+            type Refs<'a> = ($(&'a $item,)+) where Self: 'a;
+            type Muts<'a> = ($(&'a mut $item,)+) where Self: 'a;
+
+            #[inline]
+            fn filled(items: Self, bounds: BoundingBox) -> Self::Volumes {
+                ($(HeapVolume::new(items.$i, bounds),)+)
+            }
+
+            #[inline]
+            fn common_bounds(volumes: &Self::Volumes) -> Result<BoundingBox, BoundsMismatchError> {
+                let bounds = volumes.0.bounding_box();
+                $(
+                    if volumes.$i.bounding_box() != bounds {
+                        return Err(BoundsMismatchError {
+                            lhs: bounds,
+                            rhs: volumes.$i.bounding_box(),
+                        });
+                    }
+                )+
+
+                Ok(bounds)
+            }
+
+            #[inline]
+            fn get(volumes: &Self::Volumes, idx: [i64; 3]) -> Option<Self::Refs<'_>> {
+                Some(($(volumes.$i.get(idx)?,)+))
+            }
+
+            #[inline]
+            fn get_mut(volumes: &mut Self::Volumes, idx: [i64; 3]) -> Option<Self::Muts<'_>> {
+                Some(($(volumes.$i.get_mut(idx)?,)+))
+            }
+
+            #[inline]
+            fn set(volumes: &mut Self::Volumes, idx: [i64; 3], items: Self) {
+                $(volumes.$i[idx] = items.$i;)+
+            }
+
+            #[inline]
+            fn heap_size(volumes: &Self::Volumes) -> usize {
+                0 $(+ volumes.$i.heap_size())+
+            }
+
+            #[inline]
+            fn validate(volumes: &Self::Volumes) -> Result<(), ValidationError> {
+                $(volumes.$i.validate()?;)+
+                Ok(())
+            }
+        }
+
+        impl_channels!(@at [$($item),+] $($item $i),+);
+    };
+
+    (@at [$($all:ident),+] $item:ident $i:tt $(, $rest:ident $j:tt)*) => {
+        impl<$($all: Clone),+> ChannelAt<$i> for ($($all,)+) {
+            type Item = $item;
+
+            #[inline]
+            fn channel(volumes: &Self::Volumes) -> &HeapVolume<$item> {
+                &volumes.$i
+            }
+
+            #[inline]
+            fn channel_mut(volumes: &mut Self::Volumes) -> &mut HeapVolume<$item> {
+                &mut volumes.$i
+            }
+        }
+
+        impl_channels!(@at [$($all),+] $($rest $j),*);
+    };
+
+    (@at [$($all:ident),+]) => {};
+}
+
+impl_channels!(A 0);
+impl_channels!(A 0, B 1);
+impl_channels!(A 0, B 1, C 2);
+impl_channels!(A 0, B 1, C 2, D 3);
+impl_channels!(A 0, B 1, C 2, D 3, E 4);
+impl_channels!(A 0, B 1, C 2, D 3, E 4, F 5);
+impl_channels!(A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_channels!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+
+/// Several volumes ("channels") over the same bounds, stored as separate [`HeapVolume`]s but accessed together.
+/// The channels are described by a tuple of item types, for example `MultiVolume<(BlockId, LightLevel, Biome)>`.
+///
+/// Individual channels are accessed by their index in the tuple with [`MultiVolume::channel`], which returns a volume of that channel's
+/// item type, so algorithms written for normal volumes can be run on a single channel. Giving the indices names with constants
+/// (e.g. `const LIGHT: usize = 1;` and `vol.channel::<LIGHT>()`) keeps the call sites readable.
+pub struct MultiVolume<C: Channels> {
+    volumes: C::Volumes,
+    bounds: BoundingBox,
+}
+
+impl<C: Channels> MultiVolume<C> {
+    /// Create a volume where every channel is filled with its item from `items`.
+    #[inline]
+    pub fn new(items: C, bounds: impl Into<BoundingBox>) -> Self {
+        let bounds = bounds.into();
+        Self {
+            volumes: C::filled(items, bounds),
+            bounds,
+        }
+    }
+
+    /// Create a volume from existing volumes for every channel. Returns an error if the volumes don't all have the same bounds.
+    #[inline]
+    pub fn from_channels(volumes: C::Volumes) -> Result<Self, BoundsMismatchError> {
+        let bounds = C::common_bounds(&volumes)?;
+        Ok(Self { volumes, bounds })
+    }
+
+    /// Split this volume into the volumes of its channels.
+    #[inline]
+    pub fn into_channels(self) -> C::Volumes {
+        self.volumes
+    }
+
+    #[inline]
+    pub fn bounding_box(&self) -> BoundingBox {
+        self.bounds
+    }
+
+    /// Get references to the items of every channel at the given worldspace index, or [`None`] if the index is out of bounds.
+    #[inline]
+    pub fn get_all<Idx: VolumeIdx>(&self, idx: Idx) -> Option<C::Refs<'_>> {
+        C::get(&self.volumes, idx.array()?)
+    }
+
+    /// Get mutable references to the items of every channel at the given worldspace index, or [`None`] if the index is out of bounds.
+    #[inline]
+    pub fn get_all_mut<Idx: VolumeIdx>(&mut self, idx: Idx) -> Option<C::Muts<'_>> {
+        C::get_mut(&mut self.volumes, idx.array()?)
+    }
+
+    /// Set the items of every channel at the given worldspace index. Returns `false` (and changes nothing) if the index is out of bounds.
+    #[inline]
+    pub fn set_all<Idx: VolumeIdx>(&mut self, idx: Idx, items: C) -> bool {
+        match idx.array::<i64>() {
+            Some(idx) if self.bounds.contains(idx) => {
+                C::set(&mut self.volumes, idx, items);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Get the volume of channel `N`.
+    #[inline]
+    pub fn channel<const N: usize>(&self) -> &HeapVolume<<C as ChannelAt<N>>::Item>
+    where
+        C: ChannelAt<N>,
+    {
+        C::channel(&self.volumes)
+    }
+
+    /// Get the volume of channel `N` mutably. Only the items can be changed, the channel's bounds will always match the other channels.
+    #[inline]
+    pub fn channel_mut<const N: usize>(&mut self) -> ChannelMut<'_, <C as ChannelAt<N>>::Item>
+    where
+        C: ChannelAt<N>,
+    {
+        ChannelMut(C::channel_mut(&mut self.volumes))
+    }
+
+    /// The total [`Volume::heap_size`] of every channel.
+    #[inline]
+    pub fn heap_size(&self) -> usize {
+        C::heap_size(&self.volumes)
+    }
+
+    /// [Validate](Volume::validate) every channel.
+    #[inline]
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        C::validate(&self.volumes)
+    }
+}
+
+/// Mutable access to the items of one channel of a [`MultiVolume`] (or one field of a [`soa_volume!`](crate::soa_volume)).
+/// Returned by [`MultiVolume::channel_mut`].
+///
+/// This is a volume of the channel's items, but unlike a `&mut HeapVolume` it can't be used to replace the whole channel
+/// (which could give it different bounds from the other channels).
+pub struct ChannelMut<'a, T>(&'a mut HeapVolume<T>);
+
+impl<'a, T> ChannelMut<'a, T> {
+    /// Used by [`soa_volume!`](crate::soa_volume).
+    #[doc(hidden)]
+    #[inline]
+    pub fn __new(volume: &'a mut HeapVolume<T>) -> Self {
+        Self(volume)
+    }
+}
+
+impl<'a, T> Volume for ChannelMut<'a, T> {
+    type Item = T;
+
+    #[inline(always)]
+    fn ls_get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        self.0.ls_get(idx)
+    }
+
+    #[inline(always)]
+    fn bounding_box(&self) -> BoundingBox {
+        self.0.bounding_box()
+    }
+}
+
+impl<'a, T> VolumeMut for ChannelMut<'a, T> {
+    #[inline(always)]
+    fn ls_get_mut<Idx: VolumeIdx>(&mut self, idx: Idx) -> Option<&mut Self::Item> {
+        self.0.ls_get_mut(idx)
+    }
+}
+
+impl_indexing!(['a, T], ChannelMut<'a, T>);
@@ -0,0 +1,247 @@
+//! Volumes that notify listeners about changes to their items.
+
+use crate::prelude::*;
+use crate::types::{InsertError, InsertReport, ValidationError};
+use crate::util;
+use std::sync::{mpsc, Mutex};
+
+/// A change to a single item of an [`Observed`] volume.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change<T> {
+    /// The worldspace index of the item that changed.
+    pub idx: [i64; 3],
+    /// The item before the change.
+    pub old: T,
+    /// The item after the change.
+    pub new: T,
+}
+
+/// Handle to a listener registered with [`Observed::on_change`], used to remove it again.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ListenerId(u64);
+
+// Callbacks are only ever called through `&mut self`, so the mutex is never contended (see `Mutex::get_mut`).
+// It's only there to make `Observed` `Sync` without requiring the callbacks to be `Sync`.
+type Callback<T> = Mutex<Box<dyn FnMut(&Change<T>) + Send>>;
+
+enum Listener<T> {
+    Callback(Callback<T>),
+    Channel(mpsc::Sender<Change<T>>),
+}
+
+/// Wrapper around a volume that notifies registered listeners whenever an item is changed through it.
+///
+/// Reading works just like with the inner volume (this type implements [`Volume`]), but since changes made through mutable references
+/// can't be observed, this type doesn't implement [`VolumeMut`]. Instead items are changed with [`Observed::set`], [`Observed::swap`],
+/// and [`Observed::insert`], which notify every listener with a [`Change`] for each item they change, in the order they're changed.
+/// Use [`Observed::inner_mut`] to make changes without notifying anyone.
+pub struct Observed<V: Volume> {
+    inner: V,
+    listeners: Vec<(ListenerId, Listener<V::Item>)>,
+    next_id: u64,
+}
+
+impl<V: Volume> Observed<V> {
+    #[inline]
+    pub fn new(inner: V) -> Self {
+        Self {
+            inner,
+            listeners: Vec::new(),
+            next_id: 0,
+        }
+    }
+
+    #[inline]
+    pub fn inner(&self) -> &V {
+        &self.inner
+    }
+
+    /// Get mutable access to the inner volume. Changes made through it are not reported to the listeners.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut V {
+        &mut self.inner
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> V {
+        self.inner
+    }
+
+    fn add_listener(&mut self, listener: Listener<V::Item>) -> ListenerId {
+        let id = ListenerId(self.next_id);
+        self.next_id += 1;
+        self.listeners.push((id, listener));
+        id
+    }
+
+    /// Register a callback that's called with every change made through this wrapper.
+    #[inline]
+    pub fn on_change<F>(&mut self, callback: F) -> ListenerId
+    where
+        F: FnMut(&Change<V::Item>) + Send + 'static,
+    {
+        self.add_listener(Listener::Callback(Mutex::new(Box::new(callback))))
+    }
+
+    /// Get a channel which receives every change made through this wrapper.
+    /// The channel is unregistered automatically once the receiver is dropped.
+    #[inline]
+    pub fn subscribe(&mut self) -> mpsc::Receiver<Change<V::Item>> {
+        let (tx, rx) = mpsc::channel();
+        self.add_listener(Listener::Channel(tx));
+        rx
+    }
+
+    /// Remove a listener registered with [`Observed::on_change`]. Returns `false` if there was no such listener.
+    #[inline]
+    pub fn remove_listener(&mut self, id: ListenerId) -> bool {
+        let len = self.listeners.len();
+        self.listeners.retain(|(listener, _)| *listener != id);
+        self.listeners.len() != len
+    }
+
+    fn notify(&mut self, change: Change<V::Item>)
+    where
+        V::Item: Clone,
+    {
+        self.listeners.retain_mut(|(_, listener)| match listener {
+            Listener::Callback(callback) => {
+                (callback.get_mut().unwrap_or_else(|e| e.into_inner()))(&change);
+                true
+            }
+            Listener::Channel(tx) => tx.send(change.clone()).is_ok(),
+        });
+    }
+}
+
+impl<V: VolumeMut> Observed<V>
+where
+    V::Item: Clone,
+{
+    /// Set the item at the given worldspace index, returning the previous item.
+    /// Returns [`None`] (and notifies no one) if the index was out of bounds.
+    #[inline]
+    pub fn set<Idx: VolumeIdx>(&mut self, idx: Idx, item: V::Item) -> Option<V::Item> {
+        let pos = idx.array::<i64>()?;
+        let old = self.inner.swap(pos, item.clone())?;
+
+        self.notify(Change {
+            idx: pos,
+            old: old.clone(),
+            new: item,
+        });
+
+        Some(old)
+    }
+
+    /// Same as [`Observed::set`]. Provided to mirror [`VolumeMut::swap`].
+    #[inline]
+    pub fn swap<Idx: VolumeIdx>(&mut self, idx: Idx, item: V::Item) -> Option<V::Item> {
+        self.set(idx, item)
+    }
+
+    /// Insert `rhs` into this volume with its origin at `at`, reporting a change for every inserted item.
+    /// Errors without changing anything if `rhs` would not fit entirely inside of this volume.
+    #[inline]
+    pub fn insert<Idx, Rhs>(&mut self, at: Idx, rhs: &Rhs) -> Result<InsertReport, InsertError>
+    where
+        Rhs: Volume,
+        Rhs::Item: Clone + Into<V::Item>,
+        Idx: VolumeIdx,
+    {
+        util::insert_target(self.bounding_box(), rhs.bounding_box(), at.array())?;
+
+        self.try_insert_clipped(at, rhs)
+    }
+
+    /// Same as [`VolumeMut::insert_clipped`], but reports a change for every inserted item.
+    ///
+    /// # Panics
+    /// Panics if `at` cannot be converted to `[i64; 3]` or if moving `rhs` by it overflows [`i64`].
+    #[inline]
+    pub fn insert_clipped<Idx, Rhs>(&mut self, at: Idx, rhs: &Rhs) -> InsertReport
+    where
+        Rhs: Volume,
+        Rhs::Item: Clone + Into<V::Item>,
+        Idx: VolumeIdx,
+    {
+        self.try_insert_clipped(at, rhs)
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Same as [`VolumeMut::try_insert_clipped`], but reports a change for every inserted item.
+    #[inline]
+    pub fn try_insert_clipped<Idx, Rhs>(
+        &mut self,
+        at: Idx,
+        rhs: &Rhs,
+    ) -> Result<InsertReport, InsertError>
+    where
+        Rhs: Volume,
+        Rhs::Item: Clone + Into<V::Item>,
+        Idx: VolumeIdx,
+    {
+        let (at, target) = util::moved_bounds(rhs.bounding_box(), at.array())?;
+
+        let Some(region) = self.bounding_box().intersection(&target) else {
+            return Ok(InsertReport::default());
+        };
+
+        let mut written = 0;
+        for idx in region {
+            let item = rhs.get(util::sub_ivec3(idx, at)).unwrap().clone().into();
+            self.set(idx, item).unwrap();
+            written += 1;
+        }
+
+        Ok(InsertReport {
+            written,
+            region: (written > 0).then_some(region),
+        })
+    }
+}
+
+impl<V: Volume> Volume for Observed<V> {
+    type Item = V::Item;
+
+    #[inline(always)]
+    fn ls_get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        self.inner.ls_get(idx)
+    }
+
+    #[inline(always)]
+    fn bounding_box(&self) -> BoundingBox {
+        self.inner.bounding_box()
+    }
+
+    #[inline(always)]
+    fn get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        self.inner.get(idx)
+    }
+
+    #[inline]
+    fn validate(&self) -> Result<(), ValidationError> {
+        self.inner.validate()
+    }
+
+    #[inline]
+    fn heap_size(&self) -> usize {
+        let listeners =
+            self.listeners.capacity() * std::mem::size_of::<(ListenerId, Listener<V::Item>)>();
+        self.inner.heap_size() + listeners
+    }
+}
+
+impl<V: Volume + Versioned> Versioned for Observed<V> {
+    #[inline]
+    fn version(&self) -> u64 {
+        self.inner.version()
+    }
+}
+
+impl<V: Volume> From<V> for Observed<V> {
+    #[inline]
+    fn from(inner: V) -> Self {
+        Self::new(inner)
+    }
+}
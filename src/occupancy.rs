@@ -0,0 +1,280 @@
+//! Hierarchical summaries of which positions in a volume are occupied, for quickly skipping empty space.
+
+use crate::prelude::*;
+use crate::util;
+
+/// The amount of children of a node along each axis.
+const BRANCHING: usize = 4;
+
+/// One level of an [`OccupancyTree`]: a grid of nodes, each recording whether any and whether all of the cells it covers are solid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Level {
+    dims: [usize; 3],
+    any: Vec<u64>,
+    all: Vec<u64>,
+}
+
+impl Level {
+    #[inline]
+    fn new(dims: [usize; 3]) -> Self {
+        let words = (dims[0] * dims[1] * dims[2]).div_ceil(64);
+        Self {
+            dims,
+            any: vec![0; words],
+            all: vec![0; words],
+        }
+    }
+
+    #[inline(always)]
+    fn index(&self, node: [usize; 3]) -> usize {
+        node[0] + self.dims[0] * (node[1] + self.dims[1] * node[2])
+    }
+
+    #[inline(always)]
+    fn get(bits: &[u64], i: usize) -> bool {
+        bits[i / 64] & (1 << (i % 64)) != 0
+    }
+
+    #[inline(always)]
+    fn set(bits: &mut [u64], i: usize, value: bool) {
+        match value {
+            true => bits[i / 64] |= 1 << (i % 64),
+            false => bits[i / 64] &= !(1 << (i % 64)),
+        }
+    }
+
+    /// Iterate over the nodes of this level that are children of the given node of the level above.
+    #[inline]
+    fn children(&self, parent: [usize; 3]) -> impl Iterator<Item = [usize; 3]> {
+        let [xs, ys, zs] = [0, 1, 2].map(|axis| {
+            parent[axis] * BRANCHING..((parent[axis] + 1) * BRANCHING).min(self.dims[axis])
+        });
+
+        zs.flat_map(move |z| {
+            let xs = xs.clone();
+            ys.clone()
+                .flat_map(move |y| xs.clone().map(move |x| [x, y, z]))
+        })
+    }
+}
+
+/// Hierarchical bitset summarizing which positions of a volume are solid, built with [`Volume::build_occupancy`].
+///
+/// Every level groups 4×4×4 nodes of the level below into one node, which records whether any and whether all of the cells
+/// it covers are solid. Queries over a box descend only into the nodes on the box's boundary, so checking whether a large
+/// region is empty (or full) takes time roughly logarithmic in its size, rather than linear. This makes it suitable for
+/// broad-phase culling and empty-space skipping while raymarching.
+///
+/// The tree doesn't follow changes to the volume it was built from; keep it in sync with [`OccupancyTree::set`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OccupancyTree {
+    bounds: BoundingBox,
+    /// The cells themselves first, then every coarser level up to (and including) a single root node.
+    levels: Vec<Level>,
+}
+
+impl OccupancyTree {
+    /// Summarize the positions of `vol` for which `is_solid` returns `true`.
+    #[inline]
+    pub fn build<V, F>(vol: &V, mut is_solid: F) -> Self
+    where
+        V: Volume,
+        F: FnMut(&V::Item) -> bool,
+    {
+        let bounds = vol.bounding_box();
+        debug_span!("build_occupancy", %bounds);
+
+        let mut cells = Level::new(util::cast_ivec3(bounds.dimensions()).unwrap());
+        for (idx, item) in vol.iter_indexed() {
+            if is_solid(item) {
+                let i = cells.index(util::cast_ivec3(util::sub_ivec3(idx, bounds.min())).unwrap());
+                Level::set(&mut cells.any, i, true);
+                Level::set(&mut cells.all, i, true);
+            }
+        }
+
+        let mut tree = Self {
+            bounds,
+            levels: vec![cells],
+        };
+
+        while let Some(dims) = tree
+            .levels
+            .last()
+            .map(|level| level.dims)
+            .filter(|dims| dims.iter().any(|&d| d > 1))
+        {
+            let parent = Level::new(dims.map(|d| d.div_ceil(BRANCHING)));
+            let nodes = BoundingBox::new_origin(util::cast_ivec3::<i64, _>(parent.dims).unwrap());
+
+            tree.levels.push(parent);
+            for node in nodes {
+                tree.recompute(tree.levels.len() - 1, util::cast_ivec3(node).unwrap());
+            }
+        }
+
+        tree
+    }
+
+    /// The bounds of the volume this tree was built from. Positions outside of them are never solid.
+    #[inline]
+    pub fn bounding_box(&self) -> BoundingBox {
+        self.bounds
+    }
+
+    /// The amount of levels in this tree, including the level of the cells themselves.
+    #[inline]
+    pub fn depth(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// Check if the position at the given worldspace index is solid.
+    #[inline]
+    pub fn is_solid<Idx: VolumeIdx>(&self, idx: Idx) -> bool {
+        self.local(idx)
+            .is_some_and(|local| Level::get(&self.levels[0].any, self.levels[0].index(local)))
+    }
+
+    /// Mark the position at the given worldspace index as solid or not, updating the summary of every level above it.
+    /// Returns `false` (and does nothing) if the index is out of bounds.
+    #[inline]
+    pub fn set<Idx: VolumeIdx>(&mut self, idx: Idx, solid: bool) -> bool {
+        let Some(mut node) = self.local(idx) else {
+            return false;
+        };
+
+        let cells = &mut self.levels[0];
+        let i = cells.index(node);
+        Level::set(&mut cells.any, i, solid);
+        Level::set(&mut cells.all, i, solid);
+
+        for level in 1..self.levels.len() {
+            node = node.map(|n| n / BRANCHING);
+            self.recompute(level, node);
+        }
+
+        true
+    }
+
+    /// Check if any position in `region` is solid.
+    #[inline]
+    pub fn any_in(&self, region: BoundingBox) -> bool {
+        match util::local_ranges(self.bounds, region) {
+            Some([xs, ys, zs]) if !self.levels[0].any.is_empty() => self.any_node(
+                self.levels.len() - 1,
+                [0, 0, 0],
+                [xs.start, ys.start, zs.start],
+                [xs.end, ys.end, zs.end],
+            ),
+            _ => false,
+        }
+    }
+
+    /// Check if every position in `region` is solid. Always `true` for empty regions, and `false` for regions reaching
+    /// outside of the [bounding box](OccupancyTree::bounding_box).
+    #[inline]
+    pub fn all_in(&self, region: BoundingBox) -> bool {
+        if region.is_empty() {
+            return true;
+        }
+        if region.intersection(&self.bounds) != Some(region) {
+            return false;
+        }
+
+        let [xs, ys, zs] = util::local_ranges(self.bounds, region).unwrap();
+        self.all_node(
+            self.levels.len() - 1,
+            [0, 0, 0],
+            [xs.start, ys.start, zs.start],
+            [xs.end, ys.end, zs.end],
+        )
+    }
+
+    #[inline]
+    fn local<Idx: VolumeIdx>(&self, idx: Idx) -> Option<[usize; 3]> {
+        let pos = idx.array::<i64>()?;
+        match self.bounds.contains(pos) {
+            true => util::cast_ivec3(util::sub_ivec3(pos, self.bounds.min())),
+            false => None,
+        }
+    }
+
+    /// Recompute the summary of a node (above the level of the cells) from its children.
+    fn recompute(&mut self, level: usize, node: [usize; 3]) {
+        let (below, above) = self.levels.split_at_mut(level);
+        let (children, parent) = (&below[level - 1], &mut above[0]);
+
+        let (mut any, mut all) = (false, true);
+        for child in children.children(node) {
+            let i = children.index(child);
+            any |= Level::get(&children.any, i);
+            all &= Level::get(&children.all, i);
+        }
+
+        let i = parent.index(node);
+        Level::set(&mut parent.any, i, any);
+        Level::set(&mut parent.all, i, all);
+    }
+
+    /// How a node relates to a box of cells: `None` if they're disjoint, otherwise whether the node is entirely inside of it.
+    #[inline]
+    fn overlap(
+        &self,
+        level: usize,
+        node: [usize; 3],
+        min: [usize; 3],
+        max: [usize; 3],
+    ) -> Option<bool> {
+        let size = BRANCHING.pow(level as u32);
+        let dims = self.levels[0].dims;
+
+        let mut contained = true;
+        for axis in 0..3 {
+            let start = node[axis] * size;
+            let end = (start + size).min(dims[axis]);
+            if end <= min[axis] || start >= max[axis] {
+                return None;
+            }
+            contained &= start >= min[axis] && end <= max[axis];
+        }
+
+        Some(contained)
+    }
+
+    fn any_node(&self, level: usize, node: [usize; 3], min: [usize; 3], max: [usize; 3]) -> bool {
+        let Some(contained) = self.overlap(level, node, min, max) else {
+            return false;
+        };
+
+        let nodes = &self.levels[level];
+        if !Level::get(&nodes.any, nodes.index(node)) {
+            return false;
+        }
+        if contained {
+            return true;
+        }
+
+        // Cells are either contained or disjoint, so only nodes above them can be partially covered.
+        self.levels[level - 1]
+            .children(node)
+            .any(|child| self.any_node(level - 1, child, min, max))
+    }
+
+    fn all_node(&self, level: usize, node: [usize; 3], min: [usize; 3], max: [usize; 3]) -> bool {
+        let Some(contained) = self.overlap(level, node, min, max) else {
+            return true;
+        };
+
+        let nodes = &self.levels[level];
+        if Level::get(&nodes.all, nodes.index(node)) {
+            return true;
+        }
+        if contained {
+            return false;
+        }
+
+        self.levels[level - 1]
+            .children(node)
+            .all(|child| self.all_node(level - 1, child, min, max))
+    }
+}
@@ -0,0 +1,191 @@
+//! Many volumes of the same shape allocated out of a single buffer, for worlds made of thousands of equally sized chunks.
+
+use crate::prelude::*;
+
+/// Handle to a volume allocated from a [`VolumePool`].
+///
+/// Handles carry a generation, so a handle to a volume that was freed never refers to a volume allocated later in the same slot.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PoolKey {
+    slot: u32,
+    generation: u32,
+}
+
+#[derive(Debug, Clone)]
+struct Slot {
+    generation: u32,
+    /// The minimum of the bounds of the volume in this slot, or [`None`] if the slot is free.
+    min: Option<[i64; 3]>,
+}
+
+/// A fixed number of volumes with the same dimensions, stored next to each other in one allocation.
+///
+/// Allocating and freeing a volume doesn't touch the allocator, and freed slots are recycled in O(1), which avoids the
+/// fragmentation of allocating every chunk of a world separately. The volumes themselves are accessed as [`SliceVolume`]s
+/// and [`SliceVolumeMut`]s borrowing from the pool, and each of them can have its own position in worldspace.
+#[derive(Debug, Clone)]
+pub struct VolumePool<T> {
+    dims: [i64; 3],
+    /// The amount of items in every volume.
+    len: usize,
+    items: Box<[T]>,
+    slots: Vec<Slot>,
+    free: Vec<u32>,
+}
+
+impl<T: Clone> VolumePool<T> {
+    /// Allocate room for `capacity` volumes with the given dimensions, with every item set to `item`.
+    ///
+    /// # Panics
+    /// Panics if any of the dimensions is negative, or if `capacity` doesn't fit in a [`u32`].
+    #[inline]
+    pub fn new(item: T, dimensions: [i64; 3], capacity: usize) -> Self {
+        let len = BoundingBox::new_origin(dimensions).capacity() as usize;
+        let capacity = u32::try_from(capacity).expect("pool capacity must fit in a u32");
+
+        debug_event!(
+            capacity,
+            bytes = (capacity as usize * len * std::mem::size_of::<T>()) as u64,
+            "allocating volume pool",
+        );
+
+        Self {
+            dims: dimensions,
+            len,
+            items: crate::util::boxed_slice(item, len * capacity as usize),
+            slots: vec![
+                Slot {
+                    generation: 0,
+                    min: None,
+                };
+                capacity as usize
+            ],
+            free: (0..capacity).rev().collect(),
+        }
+    }
+
+    /// Allocate a volume with its bounds' minimum at `min`, with every item set to `item`. Returns [`None`] if the pool is full.
+    #[inline]
+    pub fn allocate(&mut self, min: [i64; 3], item: T) -> Option<PoolKey> {
+        let slot = self.free.pop()?;
+        let entry = &mut self.slots[slot as usize];
+        entry.min = Some(min);
+
+        let start = slot as usize * self.len;
+        self.items[start..start + self.len].fill(item);
+
+        Some(PoolKey {
+            slot,
+            generation: entry.generation,
+        })
+    }
+}
+
+impl<T> VolumePool<T> {
+    #[inline]
+    pub fn dimensions(&self) -> [i64; 3] {
+        self.dims
+    }
+
+    /// The amount of volumes the pool has room for.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// The amount of allocated volumes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.slots.len() - self.free.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline]
+    fn live(&self, key: PoolKey) -> Option<[i64; 3]> {
+        let slot = self.slots.get(key.slot as usize)?;
+        match slot.generation == key.generation {
+            true => slot.min,
+            false => None,
+        }
+    }
+
+    #[inline]
+    fn bounds(&self, min: [i64; 3]) -> BoundingBox {
+        BoundingBox::new(min, crate::util::sum_ivec3(min, self.dims))
+    }
+
+    /// Free a volume, making its slot available for the next allocation. Returns `false` if the handle doesn't refer to
+    /// an allocated volume (e.g., because it was already freed).
+    #[inline]
+    pub fn free(&mut self, key: PoolKey) -> bool {
+        if self.live(key).is_none() {
+            return false;
+        }
+
+        let slot = &mut self.slots[key.slot as usize];
+        slot.min = None;
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(key.slot);
+        true
+    }
+
+    /// Get the volume with the given handle, or [`None`] if it was freed.
+    #[inline]
+    pub fn get(&self, key: PoolKey) -> Option<SliceVolume<'_, T>> {
+        let min = self.live(key)?;
+        let start = key.slot as usize * self.len;
+
+        // The slice always holds exactly enough items for the bounds.
+        Some(SliceVolume::new(&self.items[start..start + self.len], self.bounds(min)).unwrap())
+    }
+
+    /// Mutable version of [`VolumePool::get`].
+    #[inline]
+    pub fn get_mut(&mut self, key: PoolKey) -> Option<SliceVolumeMut<'_, T>> {
+        let min = self.live(key)?;
+        let bounds = self.bounds(min);
+        let start = key.slot as usize * self.len;
+
+        Some(SliceVolumeMut::new(&mut self.items[start..start + self.len], bounds).unwrap())
+    }
+
+    /// Iterate over every allocated volume and its handle.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (PoolKey, SliceVolume<'_, T>)> {
+        self.slots.iter().enumerate().filter_map(|(slot, entry)| {
+            let key = PoolKey {
+                slot: slot as u32,
+                generation: entry.generation,
+            };
+            Some((key, self.get(key)?))
+        })
+    }
+
+    /// Iterate mutably over every allocated volume and its handle. The volumes don't overlap, so they can be
+    /// modified at the same time (e.g., from different threads).
+    #[inline]
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (PoolKey, SliceVolumeMut<'_, T>)> {
+        let dims = self.dims;
+
+        // Volumes without any items don't have a chunk of the buffer, so they get empty slices instead.
+        self.items
+            .chunks_mut(self.len.max(1))
+            .chain(std::iter::repeat_with(<&mut [T]>::default))
+            .zip(&self.slots)
+            .enumerate()
+            .filter_map(move |(slot, (items, entry))| {
+                let min = entry.min?;
+                let bounds = BoundingBox::new(min, crate::util::sum_ivec3(min, dims));
+                let key = PoolKey {
+                    slot: slot as u32,
+                    generation: entry.generation,
+                };
+
+                Some((key, SliceVolumeMut::new(items, bounds).unwrap()))
+            })
+    }
+}
@@ -1,4 +1,26 @@
+//! The types and traits needed for typical use of this crate: the volume types, the traits that make them volumes, and bounding boxes.
+//!
+//! Items behind optional features are included when the feature is enabled. Everything else public can be imported from [`crate::full`].
+
+pub use crate::chunked::ChunkedVolume;
+pub use crate::compact::CompactVolume;
 pub use crate::impls::heap_volume::*;
+pub use crate::impls::slice_volume::*;
 pub use crate::impls::stack_volume::*;
-pub use crate::traits::{Volume, VolumeIdx};
-pub use crate::types::BoundingBox;
+pub use crate::impls::uniform_volume::*;
+pub use crate::layered::LayeredVolume;
+pub use crate::observe::Observed;
+pub use crate::registry::DynItemVolume;
+pub use crate::scrolling::ScrollingVolume;
+pub use crate::selection::Selection;
+pub use crate::topology::Face;
+pub use crate::traits::{Bounds, IntoBoundingBox, Region, Versioned, Volume, VolumeIdx, VolumeMut};
+pub use crate::types::{
+    Axis, BoundingBox, BoundingBoxIterator, Order, SignedAxis, StaticBounds, VIdx,
+};
+pub use crate::view::{SubVolume, SubVolumeMut};
+
+#[cfg(feature = "bevy")]
+pub use crate::bevy_support::{VolumeAsset, VolumeComponent};
+#[cfg(feature = "small-bounds")]
+pub use crate::types::{SmallBounds, SmallInt};
@@ -0,0 +1,235 @@
+//! Volumes that start out on the stack and move to the heap once they outgrow their initial bounds.
+
+use crate::prelude::*;
+use crate::types::ValidationError;
+
+/// How much a [`PromotableVolume`] grows when a position outside of its bounds is written to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum GrowthPolicy {
+    /// Grow just enough to include the written position.
+    Exact,
+    /// Grow past the written position by the given amount of positions, along every axis it's outside of the bounds.
+    Padded(i64),
+    /// At least double the dimensions along every axis the written position is outside of the bounds, so a volume growing
+    /// one position at a time is reallocated a logarithmic amount of times.
+    #[default]
+    Doubling,
+}
+
+impl GrowthPolicy {
+    /// The bounds `bounds` grows to when writing to `pos`, or [`None`] if they overflow [`i64`].
+    #[inline]
+    fn grow(self, bounds: BoundingBox, pos: [i64; 3]) -> Option<BoundingBox> {
+        let (mut min, mut max) = (bounds.min(), bounds.max());
+        let dims = bounds.dimensions();
+
+        for axis in 0..3 {
+            let pad = match self {
+                Self::Exact => 0,
+                Self::Padded(n) => n.max(0),
+                Self::Doubling => dims[axis].max(1),
+            };
+
+            if pos[axis] < min[axis] {
+                min[axis] = match self {
+                    Self::Doubling => pos[axis].min(min[axis].saturating_sub(pad)),
+                    _ => pos[axis].checked_sub(pad)?,
+                };
+            }
+            if pos[axis] >= max[axis] {
+                max[axis] = match self {
+                    Self::Doubling => pos[axis].checked_add(1)?.max(max[axis].saturating_add(pad)),
+                    _ => pos[axis].checked_add(1)?.checked_add(pad)?,
+                };
+            }
+        }
+
+        BoundingBox::checked_new(min, max)
+    }
+}
+
+#[derive(Clone, PartialEq)]
+enum Storage<const X: usize, const Y: usize, const Z: usize, T> {
+    Stack(StackVolume<X, Y, Z, T>),
+    Heap(HeapVolume<T>),
+}
+
+/// A volume that starts out as a [`StackVolume`] of `X`×`Y`×`Z` items, and is promoted to a [`HeapVolume`] when
+/// [`PromotableVolume::set`] writes outside of its bounds. Useful for small structures that usually fit in a known size
+/// but occasionally don't, which would otherwise need a heap volume just in case.
+///
+/// Once promoted, the volume keeps growing (according to its [`GrowthPolicy`]) whenever a position outside of its bounds
+/// is written to. Newly covered positions are set to the fill item the volume was created with.
+///
+/// Only [`PromotableVolume::set`] grows the volume. Through [`VolumeMut`] it behaves like any other volume, so e.g.
+/// [`VolumeMut::get_mut`] still returns [`None`] for positions outside of its bounds.
+#[derive(Clone, PartialEq)]
+pub struct PromotableVolume<const X: usize, const Y: usize, const Z: usize, T> {
+    storage: Storage<X, Y, Z, T>,
+    fill: T,
+    policy: GrowthPolicy,
+}
+
+impl_indexing!(
+    [const X: usize, const Y: usize, const Z: usize, T],
+    PromotableVolume<X, Y, Z, T>
+);
+impl_debug!(
+    [const X: usize, const Y: usize, const Z: usize, T],
+    PromotableVolume<X, Y, Z, T>
+);
+
+impl<const X: usize, const Y: usize, const Z: usize, T: Copy> PromotableVolume<X, Y, Z, T> {
+    /// Create a stack allocated volume with every position set to `fill`, which is also the item positions are set to
+    /// when the volume grows.
+    #[inline]
+    pub fn new(fill: T, policy: GrowthPolicy) -> Self {
+        Self {
+            storage: Storage::Stack(StackVolume::filled(fill)),
+            fill,
+            policy,
+        }
+    }
+}
+
+impl<const X: usize, const Y: usize, const Z: usize, T: Clone> PromotableVolume<X, Y, Z, T> {
+    /// Set the item at the given worldspace index, first growing (and promoting) the volume if the index is outside of
+    /// its bounds. Returns the previous item.
+    ///
+    /// # Panics
+    /// Panics if `idx` cannot be converted to `[i64; 3]`, or if the grown bounds overflow [`i64`] or can't be allocated.
+    /// See [`PromotableVolume::try_set`] for a non-panicking version.
+    #[inline]
+    pub fn set<Idx: VolumeIdx>(&mut self, idx: Idx, item: T) -> T {
+        self.try_set(idx, item)
+            .expect("cannot cast index to [i64; 3] or grow the volume to include it")
+    }
+
+    /// Same as [`PromotableVolume::set`], but returns [`None`] (and changes nothing) instead of panicking if `idx` cannot be
+    /// converted to `[i64; 3]`, or if the grown bounds overflow [`i64`] or can't be allocated.
+    #[inline]
+    pub fn try_set<Idx: VolumeIdx>(&mut self, idx: Idx, item: T) -> Option<T> {
+        let pos = idx.array::<i64>()?;
+        if !self.contains(pos) {
+            self.try_grow_to(self.policy.grow(self.bounding_box(), pos)?)?;
+        }
+
+        Some(std::mem::replace(self.get_mut(pos)?, item))
+    }
+
+    /// Grow (and promote) the volume so that its bounds include `region`, ignoring the growth policy.
+    /// Does nothing if they already do.
+    ///
+    /// # Panics
+    /// Panics if the grown bounds overflow [`i64`] or can't be allocated. See [`PromotableVolume::try_reserve`] for a
+    /// non-panicking version.
+    #[inline]
+    pub fn reserve(&mut self, region: BoundingBox) {
+        assert!(
+            self.try_reserve(region),
+            "cannot grow the volume to include {region}"
+        );
+    }
+
+    /// Same as [`PromotableVolume::reserve`], but returns `false` (and changes nothing) instead of panicking if the grown
+    /// bounds overflow [`i64`] or can't be allocated.
+    #[inline]
+    pub fn try_reserve(&mut self, region: BoundingBox) -> bool {
+        let bounds = self.bounding_box();
+        if region.is_empty() || region.intersection(&bounds) == Some(region) {
+            return true;
+        }
+
+        let min = crate::math::min_ivec3(bounds.min(), region.min());
+        let max = crate::math::max_ivec3(bounds.max(), region.max());
+        BoundingBox::checked_new(min, max)
+            .and_then(|bounds| self.try_grow_to(bounds))
+            .is_some()
+    }
+
+    fn try_grow_to(&mut self, bounds: BoundingBox) -> Option<()> {
+        debug_event!(from = %self.bounding_box(), to = %bounds, "growing promotable volume");
+
+        let mut heap = HeapVolume::try_new(self.fill.clone(), bounds).ok()?;
+        match &self.storage {
+            Storage::Stack(stack) => heap.insert([0, 0, 0], stack),
+            Storage::Heap(old) => heap.insert([0, 0, 0], old),
+        }
+        .unwrap();
+
+        self.storage = Storage::Heap(heap);
+        Some(())
+    }
+
+    /// Convert this volume into a heap volume, whether it was promoted or not.
+    #[inline]
+    pub fn into_heap(self) -> HeapVolume<T> {
+        match self.storage {
+            Storage::Stack(stack) => {
+                let mut heap = HeapVolume::new(self.fill, stack.bounding_box());
+                heap.insert([0, 0, 0], &stack).unwrap();
+                heap
+            }
+            Storage::Heap(heap) => heap,
+        }
+    }
+}
+
+impl<const X: usize, const Y: usize, const Z: usize, T> PromotableVolume<X, Y, Z, T> {
+    /// Whether this volume outgrew its initial bounds and was moved to the heap.
+    #[inline]
+    pub fn is_promoted(&self) -> bool {
+        matches!(self.storage, Storage::Heap(_))
+    }
+
+    #[inline]
+    pub fn policy(&self) -> GrowthPolicy {
+        self.policy
+    }
+}
+
+impl<const X: usize, const Y: usize, const Z: usize, T> Volume for PromotableVolume<X, Y, Z, T> {
+    type Item = T;
+
+    #[inline]
+    fn ls_get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        match &self.storage {
+            Storage::Stack(stack) => stack.ls_get(idx),
+            Storage::Heap(heap) => heap.ls_get(idx),
+        }
+    }
+
+    #[inline]
+    fn bounding_box(&self) -> BoundingBox {
+        match &self.storage {
+            Storage::Stack(stack) => stack.bounding_box(),
+            Storage::Heap(heap) => heap.bounding_box(),
+        }
+    }
+
+    #[inline]
+    fn heap_size(&self) -> usize {
+        match &self.storage {
+            Storage::Stack(_) => 0,
+            Storage::Heap(heap) => heap.heap_size(),
+        }
+    }
+
+    #[inline]
+    fn validate(&self) -> Result<(), ValidationError> {
+        match &self.storage {
+            Storage::Stack(stack) => stack.validate(),
+            Storage::Heap(heap) => heap.validate(),
+        }
+    }
+}
+
+impl<const X: usize, const Y: usize, const Z: usize, T> VolumeMut for PromotableVolume<X, Y, Z, T> {
+    #[inline]
+    fn ls_get_mut<Idx: VolumeIdx>(&mut self, idx: Idx) -> Option<&mut Self::Item> {
+        match &mut self.storage {
+            Storage::Stack(stack) => stack.ls_get_mut(idx),
+            Storage::Heap(heap) => heap.ls_get_mut(idx),
+        }
+    }
+}
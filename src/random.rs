@@ -0,0 +1,71 @@
+//! Random number generation for filling volumes. Requires the `rand` feature.
+//!
+//! The `_stable` variants of the random fill operations on [`VolumeMut`](crate::traits::VolumeMut) don't use a shared RNG,
+//! instead every position gets its own [`PositionRng`] derived from a seed and the position. This means that the items
+//! generated at a position only depend on the seed and the position, no matter what volume (or chunk) it's part of.
+
+pub use rand_core::RngCore;
+
+/// Fast, deterministic random number generator (SplitMix64) seeded from a seed and a worldspace position.
+/// Not cryptographically secure.
+#[derive(Debug, Clone)]
+pub struct PositionRng {
+    state: u64,
+}
+
+impl PositionRng {
+    /// Create a generator for the given seed and position. The same seed and position always give the same sequence of numbers.
+    #[inline]
+    pub fn new(seed: u64, pos: [i64; 3]) -> Self {
+        let mut state = mix(seed);
+        for coord in pos {
+            state = mix(state ^ coord as u64);
+        }
+
+        Self { state }
+    }
+
+    /// Create a generator from just a seed.
+    #[inline]
+    pub fn from_seed(seed: u64) -> Self {
+        Self { state: mix(seed) }
+    }
+}
+
+#[inline]
+fn mix(mut z: u64) -> u64 {
+    z = z.wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+impl RngCore for PositionRng {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        mix(self.state)
+    }
+
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        rand_core::impls::fill_bytes_via_next(self, dest)
+    }
+
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+/// A uniformly distributed number in `[0, 1)`.
+#[inline]
+pub(crate) fn unit_f64(rng: &mut dyn RngCore) -> f64 {
+    (rng.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+}
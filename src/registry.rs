@@ -0,0 +1,191 @@
+//! Volumes of item ids resolved through a registry of type-erased items, for data-driven item types that can't be named at compile time.
+
+use crate::prelude::*;
+use crate::types::{RegistryFullError, ValidationError};
+use num_traits::{NumCast, PrimInt};
+use std::any::Any;
+use std::marker::PhantomData;
+
+/// Maps ids (`u16` by default) to type-erased items, assigning ids in the order the items are registered.
+///
+/// Every id maps to exactly one item, but the items don't need to be of the same type: a block registry can hold a
+/// different definition type per mod, and callers downcast to whatever type they expect with [`ItemRegistry::get_as`].
+#[derive(Debug)]
+pub struct ItemRegistry<Id = u16> {
+    items: Vec<Box<dyn Any + Send + Sync>>,
+    _id: PhantomData<fn() -> Id>,
+}
+
+impl<Id> Default for ItemRegistry<Id> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            items: Vec::new(),
+            _id: PhantomData,
+        }
+    }
+}
+
+impl<Id: PrimInt> ItemRegistry<Id> {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The amount of registered items.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Register an item and return its id. Returns an error if every id has already been assigned.
+    #[inline]
+    pub fn register<T: Any + Send + Sync>(&mut self, item: T) -> Result<Id, RegistryFullError> {
+        let id = <Id as NumCast>::from(self.items.len()).ok_or(RegistryFullError {
+            capacity: self.items.len(),
+        })?;
+
+        self.items.push(Box::new(item));
+        Ok(id)
+    }
+
+    /// The item registered with the given id, or [`None`] if there is none.
+    #[inline]
+    pub fn get(&self, id: Id) -> Option<&(dyn Any + Send + Sync)> {
+        self.items.get(id.to_usize()?).map(|item| &**item)
+    }
+
+    /// The item registered with the given id, or [`None`] if there is none or if it's not a `T`.
+    #[inline]
+    pub fn get_as<T: Any>(&self, id: Id) -> Option<&T> {
+        self.get(id)?.downcast_ref()
+    }
+
+    /// Mutable version of [`ItemRegistry::get_as`].
+    #[inline]
+    pub fn get_as_mut<T: Any>(&mut self, id: Id) -> Option<&mut T> {
+        self.items.get_mut(id.to_usize()?)?.downcast_mut()
+    }
+
+    /// Iterate over the ids of the registered items that are a `T`, along with the items.
+    #[inline]
+    pub fn iter_as<T: Any>(&self) -> impl Iterator<Item = (Id, &T)> {
+        self.items.iter().enumerate().filter_map(|(i, item)| {
+            // Every index was a valid id when its item was registered.
+            Some((<Id as NumCast>::from(i).unwrap(), item.downcast_ref()?))
+        })
+    }
+}
+
+/// A volume of item ids paired with the [`ItemRegistry`] they refer to.
+///
+/// As a [`Volume`] its items are the ids themselves, so everything in this crate works on it as it would on a volume of
+/// plain integers (and just as compactly). The typed accessors resolve ids through the registry.
+#[derive(Debug)]
+pub struct DynItemVolume<Id = u16> {
+    ids: HeapVolume<Id>,
+    registry: ItemRegistry<Id>,
+}
+
+impl_indexing!(Id, DynItemVolume<Id>);
+
+impl<Id: PrimInt> DynItemVolume<Id> {
+    /// Create a volume with every position set to `id`.
+    #[inline]
+    pub fn new(registry: ItemRegistry<Id>, id: Id, bounds: impl Into<BoundingBox>) -> Self {
+        Self {
+            ids: HeapVolume::new(id, bounds),
+            registry,
+        }
+    }
+
+    /// Pair an existing volume of ids with the registry they refer to.
+    #[inline]
+    pub fn from_parts(registry: ItemRegistry<Id>, ids: HeapVolume<Id>) -> Self {
+        Self { ids, registry }
+    }
+
+    #[inline]
+    pub fn into_parts(self) -> (ItemRegistry<Id>, HeapVolume<Id>) {
+        (self.registry, self.ids)
+    }
+
+    #[inline]
+    pub fn registry(&self) -> &ItemRegistry<Id> {
+        &self.registry
+    }
+
+    #[inline]
+    pub fn registry_mut(&mut self) -> &mut ItemRegistry<Id> {
+        &mut self.registry
+    }
+
+    /// The registered item at the given worldspace index, or [`None`] if the index is out of bounds or its id isn't registered.
+    #[inline]
+    pub fn get_item<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&(dyn Any + Send + Sync)> {
+        self.registry.get(*self.ids.get(idx)?)
+    }
+
+    /// The registered item at the given worldspace index, or [`None`] if the index is out of bounds, its id isn't registered,
+    /// or the item isn't a `T`.
+    #[inline]
+    pub fn get_item_as<T: Any, Idx: VolumeIdx>(&self, idx: Idx) -> Option<&T> {
+        self.registry.get_as(*self.ids.get(idx)?)
+    }
+
+    /// The first position (in iteration order) holding an id that isn't registered, if there is any.
+    #[inline]
+    pub fn find_unregistered(&self) -> Option<[i64; 3]> {
+        self.ids
+            .iter_indexed()
+            .find(|(_, &id)| self.registry.get(id).is_none())
+            .map(|(idx, _)| idx)
+    }
+}
+
+impl<Id> Volume for DynItemVolume<Id> {
+    type Item = Id;
+
+    #[inline]
+    fn ls_get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        self.ids.ls_get(idx)
+    }
+
+    #[inline(always)]
+    fn bounding_box(&self) -> BoundingBox {
+        self.ids.bounding_box()
+    }
+
+    #[inline]
+    fn get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        self.ids.get(idx)
+    }
+
+    #[inline]
+    fn heap_size(&self) -> usize {
+        self.ids.heap_size()
+            + self.registry.items.capacity() * std::mem::size_of::<Box<dyn Any + Send + Sync>>()
+    }
+
+    #[inline]
+    fn validate(&self) -> Result<(), ValidationError> {
+        self.ids.validate()
+    }
+}
+
+impl<Id> VolumeMut for DynItemVolume<Id> {
+    #[inline]
+    fn ls_get_mut<Idx: VolumeIdx>(&mut self, idx: Idx) -> Option<&mut Self::Item> {
+        self.ids.ls_get_mut(idx)
+    }
+
+    #[inline]
+    fn get_mut<Idx: VolumeIdx>(&mut self, idx: Idx) -> Option<&mut Self::Item> {
+        self.ids.get_mut(idx)
+    }
+}
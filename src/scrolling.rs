@@ -0,0 +1,143 @@
+//! Fixed-size windows into an unbounded world that follow a moving center, like the terrain around a player or a clipmap level.
+
+use crate::prelude::*;
+use crate::types::ValidationError;
+use crate::view::SubVolumeMut;
+
+/// A volume with fixed dimensions covering a window of worldspace that can be moved around with [`ScrollingVolume::recenter`].
+///
+/// The items are stored in a ring buffer: every worldspace position maps to the storage slot at its position modulo the dimensions,
+/// so moving the window only touches the cells that scrolled out of it (which get reused for the newly exposed cells),
+/// instead of shifting every item in the volume.
+#[derive(Clone, PartialEq)]
+pub struct ScrollingVolume<T> {
+    storage: HeapVolume<T>,
+    window: BoundingBox,
+}
+
+impl_indexing!(T, ScrollingVolume<T>);
+impl_debug!(T, ScrollingVolume<T>);
+
+/// The window with the given dimensions around `center`. For even dimensions the center is the first position of the upper half.
+#[inline]
+fn window_around(dimensions: [i64; 3], center: [i64; 3]) -> BoundingBox {
+    let min = [0, 1, 2].map(|axis| center[axis] - dimensions[axis] / 2);
+    BoundingBox::new(min, crate::util::sum_ivec3(min, dimensions))
+}
+
+impl<T: Clone> ScrollingVolume<T> {
+    /// Create a window with the given dimensions around `center`, with every position set to `item`.
+    ///
+    /// # Panics
+    /// Panics if any of the dimensions is less than 1.
+    #[inline]
+    pub fn new(item: T, dimensions: [i64; 3], center: [i64; 3]) -> Self {
+        assert!(
+            dimensions.iter().all(|&d| d > 0),
+            "scrolling volume dimensions must be at least 1"
+        );
+
+        Self {
+            storage: HeapVolume::new(item, BoundingBox::new_origin(dimensions)),
+            window: window_around(dimensions, center),
+        }
+    }
+}
+
+impl<T> ScrollingVolume<T> {
+    #[inline]
+    pub fn dimensions(&self) -> [i64; 3] {
+        self.window.dimensions()
+    }
+
+    /// The center of the window, as passed to [`ScrollingVolume::new`] or [`ScrollingVolume::recenter`].
+    #[inline]
+    pub fn center(&self) -> [i64; 3] {
+        let dims = self.dimensions();
+        [0, 1, 2].map(|axis| self.window.min()[axis] + dims[axis] / 2)
+    }
+
+    /// The storage slot of a worldspace position.
+    #[inline(always)]
+    fn slot(&self, pos: [i64; 3]) -> [i64; 3] {
+        let dims = self.dimensions();
+        [0, 1, 2].map(|axis| pos[axis].rem_euclid(dims[axis]))
+    }
+
+    /// Move the window so that it's centered on `center`. The items at positions that are in both the old and the new window
+    /// are kept, and `fill` is called with a view of every newly exposed box of positions (up to 6 per call of this method).
+    /// Returns the amount of newly exposed positions.
+    ///
+    /// The views passed to `fill` still hold the items that scrolled out of the window, so `fill` should overwrite every item in them.
+    #[inline]
+    pub fn recenter<F>(&mut self, center: [i64; 3], mut fill: F) -> usize
+    where
+        F: FnMut(&mut SubVolumeMut<'_, Self>),
+    {
+        let old = self.window;
+        self.window = window_around(self.dimensions(), center);
+        debug_span!("recenter", from = %old, to = %self.window);
+
+        let exposed = self.window.difference(&old);
+        for &region in &exposed {
+            fill(&mut self.slice_mut(region));
+        }
+
+        exposed
+            .iter()
+            .map(|region| region.capacity() as usize)
+            .sum()
+    }
+}
+
+impl<T> Volume for ScrollingVolume<T> {
+    type Item = T;
+
+    #[inline]
+    fn ls_get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        self.get(idx.array::<i64>()?.checked_add(self.window.min())?)
+    }
+
+    #[inline(always)]
+    fn bounding_box(&self) -> BoundingBox {
+        self.window
+    }
+
+    #[inline]
+    fn get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        let pos = idx.array::<i64>()?;
+        match self.window.contains(pos) {
+            true => self.storage.get(self.slot(pos)),
+            false => None,
+        }
+    }
+
+    #[inline]
+    fn heap_size(&self) -> usize {
+        self.storage.heap_size()
+    }
+
+    #[inline]
+    fn validate(&self) -> Result<(), ValidationError> {
+        self.storage.validate()
+    }
+}
+
+impl<T> VolumeMut for ScrollingVolume<T> {
+    #[inline]
+    fn ls_get_mut<Idx: VolumeIdx>(&mut self, idx: Idx) -> Option<&mut Self::Item> {
+        self.get_mut(idx.array::<i64>()?.checked_add(self.window.min())?)
+    }
+
+    #[inline]
+    fn get_mut<Idx: VolumeIdx>(&mut self, idx: Idx) -> Option<&mut Self::Item> {
+        let pos = idx.array::<i64>()?;
+        match self.window.contains(pos) {
+            true => {
+                let slot = self.slot(pos);
+                self.storage.get_mut(slot)
+            }
+            false => None,
+        }
+    }
+}
@@ -0,0 +1,303 @@
+//! Non-rectangular selections of positions.
+
+use crate::prelude::*;
+use crate::traits::Region;
+use crate::types::BoundingBoxIterator;
+use std::collections::{btree_set, BTreeSet};
+
+/// A set of worldspace positions made up of a union of bounding boxes and individual positions (voxels).
+///
+/// Internally the boxes never overlap each other and no voxel is inside any of the boxes, so every position in the selection
+/// is stored exactly once. This means iteration never yields duplicates and large rectangular regions stay cheap to store,
+/// regardless of how the selection was built up.
+#[derive(Debug, Clone, Default)]
+pub struct Selection {
+    boxes: Vec<BoundingBox>,
+    voxels: BTreeSet<[i64; 3]>,
+}
+
+impl Selection {
+    /// Create an empty selection.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a selection from individual positions.
+    #[inline]
+    pub fn from_voxels(voxels: impl IntoIterator<Item = [i64; 3]>) -> Self {
+        let mut selection = Self::new();
+        for voxel in voxels {
+            selection.add_voxel(voxel);
+        }
+        selection
+    }
+
+    /// Add every position in the bounding box to this selection.
+    #[inline]
+    pub fn add_box(&mut self, bb: BoundingBox) {
+        let mut pieces = vec![bb];
+        for existing in &self.boxes {
+            pieces = pieces
+                .iter()
+                .flat_map(|piece| piece.difference(existing))
+                .collect();
+        }
+
+        self.voxels
+            .retain(|&voxel| !pieces.iter().any(|piece| piece.contains(voxel)));
+        self.boxes
+            .extend(pieces.into_iter().filter(|p| !p.is_empty()));
+    }
+
+    /// Add a single position to this selection.
+    #[inline]
+    pub fn add_voxel(&mut self, voxel: [i64; 3]) {
+        if !self.boxes.iter().any(|bb| bb.contains(voxel)) {
+            self.voxels.insert(voxel);
+        }
+    }
+
+    /// Check if the worldspace index is in this selection.
+    #[inline]
+    pub fn contains<Idx: VolumeIdx>(&self, idx: Idx) -> bool {
+        match idx.array::<i64>() {
+            Some(pos) => self.boxes.iter().any(|bb| bb.contains(pos)) || self.voxels.contains(&pos),
+            None => false,
+        }
+    }
+
+    /// Check if this selection contains no positions.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.boxes.is_empty() && self.voxels.is_empty()
+    }
+
+    /// The amount of positions in this selection.
+    #[inline]
+    pub fn len(&self) -> u128 {
+        self.boxes
+            .iter()
+            .map(|bb| bb.capacity() as u128)
+            .sum::<u128>()
+            + self.voxels.len() as u128
+    }
+
+    /// Iterate over every position in this selection exactly once. Positions in boxes come first (in the order the boxes were added),
+    /// followed by the individually selected positions.
+    #[inline]
+    pub fn iter(&self) -> SelectionIterator<'_> {
+        SelectionIterator {
+            boxes: self.boxes.clone().into_iter(),
+            current: None,
+            voxels: self.voxels.iter(),
+            clip: None,
+        }
+    }
+
+    /// Iterate over every position in this selection that's also in `bounds`.
+    #[inline]
+    pub fn iter_within(&self, bounds: BoundingBox) -> SelectionIterator<'_> {
+        SelectionIterator {
+            boxes: self
+                .boxes
+                .iter()
+                .filter_map(|bb| bb.intersection(&bounds))
+                .collect::<Vec<_>>()
+                .into_iter(),
+            current: None,
+            voxels: self.voxels.iter(),
+            clip: Some(bounds),
+        }
+    }
+
+    /// The smallest bounding box containing every position in this selection, or [`None`] if the selection is empty.
+    #[inline]
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        let voxels = self
+            .voxels
+            .iter()
+            .map(|&v| BoundingBox::new(v, crate::util::sum_ivec3(v, [1, 1, 1])));
+
+        self.boxes.iter().copied().chain(voxels).reduce(|acc, bb| {
+            let (amin, amax, bmin, bmax) = (acc.min(), acc.max(), bb.min(), bb.max());
+            BoundingBox::new(
+                [
+                    amin[0].min(bmin[0]),
+                    amin[1].min(bmin[1]),
+                    amin[2].min(bmin[2]),
+                ],
+                [
+                    amax[0].max(bmax[0]),
+                    amax[1].max(bmax[1]),
+                    amax[2].max(bmax[2]),
+                ],
+            )
+        })
+    }
+
+    /// All positions that are in either selection.
+    #[inline]
+    pub fn union(&self, rhs: &Selection) -> Selection {
+        let mut out = self.clone();
+        for &bb in &rhs.boxes {
+            out.add_box(bb);
+        }
+        for &voxel in &rhs.voxels {
+            out.add_voxel(voxel);
+        }
+        out
+    }
+
+    /// All positions that are in both selections.
+    #[inline]
+    pub fn intersection(&self, rhs: &Selection) -> Selection {
+        let boxes = self
+            .boxes
+            .iter()
+            .flat_map(|a| rhs.boxes.iter().filter_map(|b| a.intersection(b)))
+            .collect();
+
+        let voxels = self
+            .voxels
+            .iter()
+            .filter(|&&v| rhs.contains(v))
+            .chain(rhs.voxels.iter().filter(|&&v| self.contains(v)))
+            .copied()
+            .collect();
+
+        Selection { boxes, voxels }
+    }
+
+    /// All positions that are in this selection but not in `rhs`.
+    #[inline]
+    pub fn difference(&self, rhs: &Selection) -> Selection {
+        let mut boxes = self.boxes.clone();
+        let carved = rhs
+            .voxels
+            .iter()
+            .map(|&v| BoundingBox::new(v, crate::util::sum_ivec3(v, [1, 1, 1])));
+
+        for cut in rhs.boxes.iter().copied().chain(carved) {
+            boxes = boxes.iter().flat_map(|bb| bb.difference(&cut)).collect();
+        }
+
+        let voxels = self
+            .voxels
+            .iter()
+            .filter(|&&v| !rhs.contains(v))
+            .copied()
+            .collect();
+
+        Selection { boxes, voxels }
+    }
+}
+
+impl From<BoundingBox> for Selection {
+    #[inline]
+    fn from(bb: BoundingBox) -> Self {
+        let mut selection = Self::new();
+        selection.add_box(bb);
+        selection
+    }
+}
+
+impl FromIterator<[i64; 3]> for Selection {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = [i64; 3]>>(iter: I) -> Self {
+        Self::from_voxels(iter)
+    }
+}
+
+impl std::ops::BitOr for &Selection {
+    type Output = Selection;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self::Output {
+        self.union(rhs)
+    }
+}
+
+impl std::ops::BitAnd for &Selection {
+    type Output = Selection;
+
+    #[inline]
+    fn bitand(self, rhs: Self) -> Self::Output {
+        self.intersection(rhs)
+    }
+}
+
+impl std::ops::Sub for &Selection {
+    type Output = Selection;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.difference(rhs)
+    }
+}
+
+impl<'a> IntoIterator for &'a Selection {
+    type Item = [i64; 3];
+    type IntoIter = SelectionIterator<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl Region for Selection {
+    type Positions<'a> = SelectionIterator<'a>;
+
+    #[inline]
+    fn contains_position(&self, pos: [i64; 3]) -> bool {
+        self.contains(pos)
+    }
+
+    #[inline]
+    fn enclosing_box(&self) -> Option<BoundingBox> {
+        self.bounding_box()
+    }
+
+    #[inline]
+    fn positions(&self) -> Self::Positions<'_> {
+        self.iter()
+    }
+
+    #[inline]
+    fn positions_within(&self, bounds: BoundingBox) -> Self::Positions<'_> {
+        self.iter_within(bounds)
+    }
+}
+
+/// Iterator over the positions in a [`Selection`].
+pub struct SelectionIterator<'a> {
+    boxes: std::vec::IntoIter<BoundingBox>,
+    current: Option<BoundingBoxIterator>,
+    voxels: btree_set::Iter<'a, [i64; 3]>,
+    clip: Option<BoundingBox>,
+}
+
+impl<'a> Iterator for SelectionIterator<'a> {
+    type Item = [i64; 3];
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(pos) = self.current.as_mut().and_then(|it| it.next()) {
+                return Some(pos);
+            }
+
+            match self.boxes.next() {
+                Some(bb) => self.current = Some(bb.into_iter()),
+                None => {
+                    let clip = self.clip;
+                    return self
+                        .voxels
+                        .find(|&&v| clip.is_none_or(|clip| clip.contains(v)))
+                        .copied();
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,216 @@
+//! Sequences of volumes over time, such as recorded simulations or animated voxel models.
+
+use crate::prelude::*;
+use crate::traits::AsF64;
+use crate::types::BoundsMismatchError;
+
+/// The changes between a frame and the frame before it, sorted by position in X-major order.
+type Delta<T> = Vec<([i64; 3], T)>;
+
+enum Frame<V: Volume> {
+    Key(V),
+    Delta(Delta<V::Item>),
+}
+
+/// Frames of volumes with the same bounds, stored compactly as the changes from one frame to the next.
+///
+/// Every `keyframe_interval` frames a full copy of the volume (a keyframe) is stored, so reconstructing a frame never
+/// requires replaying more than `keyframe_interval - 1` deltas. Items are only stored in a delta if they changed, so
+/// mostly static frames take up very little memory.
+pub struct VolumeSequence<V: Volume> {
+    frames: Vec<Frame<V>>,
+    keyframe_interval: usize,
+    /// The last frame, kept around to compute the delta to the next pushed frame.
+    latest: V,
+}
+
+#[inline(always)]
+fn key([x, y, z]: [i64; 3]) -> [i64; 3] {
+    [z, y, x]
+}
+
+impl<V> VolumeSequence<V>
+where
+    V: VolumeMut + Clone,
+    V::Item: Clone + PartialEq,
+{
+    /// Create a sequence starting with the given frame, storing a keyframe every `keyframe_interval` frames.
+    ///
+    /// # Panics
+    /// Panics if `keyframe_interval` is 0.
+    #[inline]
+    pub fn new(first: V, keyframe_interval: usize) -> Self {
+        assert!(
+            keyframe_interval > 0,
+            "the keyframe interval must be at least 1"
+        );
+
+        Self {
+            frames: vec![Frame::Key(first.clone())],
+            keyframe_interval,
+            latest: first,
+        }
+    }
+
+    /// Append a frame to the end of the sequence. Errors if its bounds are different from the previous frames.
+    #[inline]
+    pub fn push(&mut self, frame: V) -> Result<(), BoundsMismatchError> {
+        let bounds = self.bounding_box();
+        if frame.bounding_box() != bounds {
+            return Err(BoundsMismatchError {
+                lhs: bounds,
+                rhs: frame.bounding_box(),
+            });
+        }
+
+        if self.frames.len().is_multiple_of(self.keyframe_interval) {
+            self.frames.push(Frame::Key(frame.clone()));
+        } else {
+            let delta = bounds
+                .into_iter()
+                .filter_map(|idx| {
+                    let item = frame.get(idx).unwrap();
+                    (self.latest.get(idx).unwrap() != item).then(|| (idx, item.clone()))
+                })
+                .collect();
+
+            self.frames.push(Frame::Delta(delta));
+        }
+
+        self.latest = frame;
+        Ok(())
+    }
+
+    /// Reconstruct the frame at index `i`, or return [`None`] if there's no such frame.
+    #[inline]
+    pub fn frame(&self, i: usize) -> Option<V> {
+        if i >= self.frames.len() {
+            return None;
+        }
+
+        let keyframe = i - i % self.keyframe_interval;
+        let Frame::Key(key) = &self.frames[keyframe] else {
+            unreachable!("frames at multiples of the keyframe interval are keyframes")
+        };
+
+        let mut vol = key.clone();
+        for frame in &self.frames[keyframe + 1..=i] {
+            if let Frame::Delta(delta) = frame {
+                for (idx, item) in delta {
+                    *vol.get_mut(*idx).unwrap() = item.clone();
+                }
+            }
+        }
+
+        Some(vol)
+    }
+
+    /// The item at the given worldspace index in frame `i`, without reconstructing the entire frame.
+    /// Returns [`None`] if there's no such frame or the index is out of bounds.
+    #[inline]
+    pub fn get<Idx: VolumeIdx>(&self, i: usize, idx: Idx) -> Option<&V::Item> {
+        let pos = idx.array::<i64>()?;
+        if i >= self.frames.len() {
+            return None;
+        }
+
+        // Walk back through the deltas until one of them changed the item, or the keyframe is reached.
+        for frame in self.frames[..=i].iter().rev() {
+            match frame {
+                Frame::Key(vol) => return vol.get(pos),
+                Frame::Delta(delta) => {
+                    if let Ok(n) = delta.binary_search_by_key(&key(pos), |(idx, _)| key(*idx)) {
+                        return Some(&delta[n].1);
+                    }
+                }
+            }
+        }
+
+        unreachable!("the first frame is always a keyframe")
+    }
+}
+
+impl<V: Volume> VolumeSequence<V> {
+    /// The amount of frames in the sequence. Always at least 1.
+    #[inline]
+    #[allow(clippy::len_without_is_empty)] // sequences are never empty
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// The bounds shared by every frame.
+    #[inline]
+    pub fn bounding_box(&self) -> BoundingBox {
+        self.latest.bounding_box()
+    }
+
+    #[inline]
+    pub fn keyframe_interval(&self) -> usize {
+        self.keyframe_interval
+    }
+
+    /// The amount of heap memory used by the stored frames, see [`Volume::heap_size`].
+    #[inline]
+    pub fn heap_size(&self) -> usize {
+        let frames = self
+            .frames
+            .iter()
+            .map(|frame| match frame {
+                Frame::Key(vol) => vol.heap_size(),
+                Frame::Delta(delta) => {
+                    delta.capacity() * std::mem::size_of::<([i64; 3], V::Item)>()
+                }
+            })
+            .sum::<usize>();
+
+        frames + self.frames.capacity() * std::mem::size_of::<Frame<V>>() + self.latest.heap_size()
+    }
+}
+
+impl<V> VolumeSequence<V>
+where
+    V: VolumeMut + Clone,
+    V::Item: AsF64 + PartialEq,
+{
+    /// Sample the item at the given worldspace index at time `t` (measured in frames), linearly interpolating between
+    /// the two closest frames. Returns [`None`] if `t` is outside of the sequence or the index is out of bounds.
+    #[inline]
+    pub fn sample<Idx: VolumeIdx>(&self, t: f64, idx: Idx) -> Option<f64> {
+        if !(0.0..=(self.len() - 1) as f64).contains(&t) {
+            return None;
+        }
+
+        let (i, frac) = (t.floor() as usize, t.fract());
+        let a = self.get(i, idx)?.as_f64();
+        if frac == 0.0 {
+            return Some(a);
+        }
+
+        let b = self.get(i + 1, idx)?.as_f64();
+        Some(a + (b - a) * frac)
+    }
+
+    /// Reconstruct the entire volume at time `t` (measured in frames), linearly interpolating between the two closest frames.
+    /// Returns [`None`] if `t` is outside of the sequence.
+    #[inline]
+    pub fn interpolate(&self, t: f64) -> Option<HeapVolume<f64>> {
+        if !(0.0..=(self.len() - 1) as f64).contains(&t) {
+            return None;
+        }
+
+        let (i, frac) = (t.floor() as usize, t.fract());
+        let a = self.frame(i)?;
+        let b = if frac == 0.0 { None } else { self.frame(i + 1) };
+
+        let mut out = HeapVolume::new(0.0, self.bounding_box());
+        for idx in self.bounding_box() {
+            let start = a.get(idx).unwrap().as_f64();
+            out[idx] = match &b {
+                Some(b) => start + (b.get(idx).unwrap().as_f64() - start) * frac,
+                None => start,
+            };
+        }
+
+        Some(out)
+    }
+}
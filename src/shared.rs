@@ -0,0 +1,297 @@
+//! Volumes in memory mapped files, which several processes can map at the same time. Requires the `shared` feature.
+//!
+//! A separate process (like a world generation worker or an inspector GUI) can open the same file with
+//! [`SharedVolume::open`] and read the items directly, without serializing them or sending them through a socket.
+//! On Linux, files in `/dev/shm` are never written to disk, so they work as named shared memory.
+//!
+//! The file starts with a small header holding the bounds and the layout of the items, followed by the items in X-major
+//! order.
+
+use crate::prelude::*;
+use crate::types::SharedVolumeError;
+use bytemuck::Pod;
+use memmap2::MmapMut;
+use std::fs::{File, OpenOptions};
+use std::marker::PhantomData;
+use std::path::Path;
+
+const MAGIC: [u8; 8] = *b"VOLSHM01";
+/// The size of the header. The items start right after it, so it's also the largest supported item alignment
+/// (the mapping itself is page aligned).
+const HEADER_LEN: usize = 128;
+
+/// The header at the start of a shared volume's file. Every field is stored in little endian.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Header {
+    bounds: BoundingBox,
+    item_size: u64,
+    item_align: u64,
+}
+
+impl Header {
+    fn of<T>(bounds: BoundingBox) -> Self {
+        Self {
+            bounds,
+            item_size: std::mem::size_of::<T>() as u64,
+            item_align: std::mem::align_of::<T>() as u64,
+        }
+    }
+
+    fn write(&self, out: &mut [u8]) {
+        let fields = (self.bounds.min().into_iter())
+            .chain(self.bounds.max())
+            .map(|c| c as u64)
+            .chain([self.item_size, self.item_align]);
+
+        out[..8].copy_from_slice(&MAGIC);
+        for (chunk, field) in out[8..].chunks_exact_mut(8).zip(fields) {
+            chunk.copy_from_slice(&field.to_le_bytes());
+        }
+    }
+
+    fn read(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < HEADER_LEN || bytes[..8] != MAGIC {
+            return None;
+        }
+
+        let field = |i: usize| {
+            let start = 8 + i * 8;
+            u64::from_le_bytes(bytes[start..start + 8].try_into().unwrap())
+        };
+        let corner = |i: usize| [field(i) as i64, field(i + 1) as i64, field(i + 2) as i64];
+
+        let (min, max) = (corner(0), corner(3));
+        if (0..3).any(|axis| min[axis] > max[axis]) {
+            return None;
+        }
+
+        Some(Self {
+            bounds: BoundingBox::checked_new(min, max)?,
+            item_size: field(6),
+            item_align: field(7),
+        })
+    }
+
+    /// The size of the whole file, or [`None`] if it doesn't fit in memory.
+    fn file_len(&self) -> Option<usize> {
+        let [x, y, z] = crate::util::cast_ivec3::<usize, _>(self.bounds.dimensions())?;
+        x.checked_mul(y)?
+            .checked_mul(z)?
+            .checked_mul(self.item_size as usize)?
+            .checked_add(HEADER_LEN)
+    }
+}
+
+/// A volume whose items live in a memory mapped file, so other processes mapping the same file see the same items.
+///
+/// Items must be [`Pod`], since they're read straight from the bytes of the file. The file records the bounds and the size
+/// and alignment of the items, and [`SharedVolume::open`] refuses to map a file whose items have a different layout.
+///
+/// Nothing synchronizes access between mappings, and items are read and written in place through plain references, so
+/// consumers have to make sure nobody writes to the file while anything else is accessing it (e.g., with a single writer
+/// that only writes while no reader is reading, and readers that only start reading after being notified through some
+/// other channel, like a lock or a message). See the safety sections of [`SharedVolume::create`] and [`SharedVolume::open`].
+pub struct SharedVolume<T> {
+    map: MmapMut,
+    bounds: BoundingBox,
+    dims: [usize; 3],
+    _items: PhantomData<T>,
+}
+
+impl_indexing!(T, SharedVolume<T>);
+
+impl<T> std::fmt::Debug for SharedVolume<T> {
+    #[inline]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedVolume")
+            .field("bounds", &self.bounds)
+            .field("bytes", &self.map.len())
+            .finish()
+    }
+}
+
+impl<T: Pod> SharedVolume<T> {
+    /// Create (or overwrite) the file at `path` and map it as a volume with the given bounds, with every item set to `item`.
+    ///
+    /// # Panics
+    /// Panics if `T` has an alignment over 128 bytes.
+    ///
+    /// # Safety
+    /// While this volume exists, the file must not be truncated, and must only be modified through [`SharedVolume`]s mapping it
+    /// (in this or other processes). See [`MmapMut::map_mut`].
+    ///
+    /// Writes through any mapping must also be exclusive: while an item is being written through one mapping, no other mapping
+    /// may be reading or writing it, and no reference into the file handed out by another mapping (e.g., by [`Volume::get`] or
+    /// [`SharedVolume::items`]) may be alive. Otherwise those references would see items change under them, which is a data race.
+    /// Accesses from different processes have to be ordered by some synchronization outside of this crate.
+    #[inline]
+    pub unsafe fn create<P: AsRef<Path>>(
+        path: P,
+        item: T,
+        bounds: BoundingBox,
+    ) -> Result<Self, SharedVolumeError> {
+        let header = Header::of::<T>(bounds);
+        let len = header.file_len().ok_or(SharedVolumeError::TooLarge)?;
+
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        file.set_len(len as u64)?;
+
+        debug_event!(%bounds, bytes = len as u64, "creating shared volume");
+
+        let mut vol = Self::map(&file, header)?;
+        header.write(&mut vol.map[..HEADER_LEN]);
+        vol.items_mut().fill(item);
+        Ok(vol)
+    }
+
+    /// Map an existing file created with [`SharedVolume::create`] (possibly by another process).
+    ///
+    /// Returns an error if the file isn't a shared volume, if its items don't have the size and alignment of `T`, or if
+    /// it's too short to hold all of its items.
+    ///
+    /// # Safety
+    /// Same as [`SharedVolume::create`].
+    #[inline]
+    pub unsafe fn open<P: AsRef<Path>>(path: P) -> Result<Self, SharedVolumeError> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+
+        let mut bytes = [0; HEADER_LEN];
+        std::io::Read::read_exact(&mut &file, &mut bytes)
+            .map_err(|_| SharedVolumeError::InvalidHeader)?;
+        let found = Header::read(&bytes).ok_or(SharedVolumeError::InvalidHeader)?;
+
+        let expected = Header::of::<T>(found.bounds);
+        if (found.item_size, found.item_align) != (expected.item_size, expected.item_align) {
+            return Err(SharedVolumeError::ItemLayout {
+                expected: (expected.item_size, expected.item_align),
+                found: (found.item_size, found.item_align),
+            });
+        }
+
+        let len = found.file_len().ok_or(SharedVolumeError::TooLarge)?;
+        let file_len = file.metadata()?.len();
+        if file_len < len as u64 {
+            return Err(SharedVolumeError::Truncated {
+                expected: len as u64,
+                found: file_len,
+            });
+        }
+
+        Self::map(&file, found)
+    }
+
+    unsafe fn map(file: &File, header: Header) -> Result<Self, SharedVolumeError> {
+        assert!(
+            std::mem::align_of::<T>() <= HEADER_LEN,
+            "shared volume items can't have an alignment over {HEADER_LEN} bytes"
+        );
+
+        Ok(Self {
+            map: MmapMut::map_mut(file)?,
+            bounds: header.bounds,
+            dims: crate::util::cast_ivec3(header.bounds.dimensions()).unwrap(),
+            _items: PhantomData,
+        })
+    }
+
+    /// The items of this volume in X-major order.
+    #[inline]
+    pub fn items(&self) -> &[T] {
+        let len = self.dims.iter().product::<usize>() * std::mem::size_of::<T>();
+        bytemuck::cast_slice(&self.map[HEADER_LEN..HEADER_LEN + len])
+    }
+
+    /// Mutable version of [`SharedVolume::items`].
+    #[inline]
+    pub fn items_mut(&mut self) -> &mut [T] {
+        let len = self.dims.iter().product::<usize>() * std::mem::size_of::<T>();
+        bytemuck::cast_slice_mut(&mut self.map[HEADER_LEN..HEADER_LEN + len])
+    }
+
+    /// Borrow the items as a [`SliceVolume`].
+    #[inline]
+    pub fn as_slice_volume(&self) -> SliceVolume<'_, T> {
+        SliceVolume::new(self.items(), self.bounds).unwrap()
+    }
+
+    /// Write changes to the file. Other processes mapping the file see changes without flushing, this only matters for
+    /// files that aren't in memory (i.e., not in `/dev/shm`) and should survive a crash.
+    #[inline]
+    pub fn flush(&self) -> Result<(), SharedVolumeError> {
+        Ok(self.map.flush()?)
+    }
+
+    #[inline(always)]
+    fn linear<Idx: VolumeIdx>(&self, idx: Idx) -> Option<usize> {
+        let [x, y, z] = idx.array::<usize>()?;
+        let [dx, dy, dz] = self.dims;
+        if x >= dx || y >= dy || z >= dz {
+            return None;
+        }
+
+        Some((z * dy + y) * dx + x)
+    }
+}
+
+impl<T: Pod> Volume for SharedVolume<T> {
+    type Item = T;
+
+    #[inline]
+    fn ls_get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        let i = self.linear(idx)?;
+        self.items().get(i)
+    }
+
+    #[inline(always)]
+    fn bounding_box(&self) -> BoundingBox {
+        self.bounds
+    }
+
+    /// Reads a byte from every page holding items in `range`, so page faults happen here instead of in the middle of a
+    /// scan. Useful right after mapping a file that's on disk (or was just written by another process).
+    #[inline]
+    fn prefetch_region<R: IntoBoundingBox>(&self, range: R) {
+        const PAGE_SIZE: usize = 4096;
+
+        let Some([xs, ys, zs]) = range
+            .into_bounding_box()
+            .and_then(|region| crate::util::local_ranges(self.bounds, region))
+        else {
+            return;
+        };
+        if xs.is_empty() {
+            return;
+        }
+
+        let [dx, dy, _] = self.dims;
+        let size = std::mem::size_of::<T>();
+        // Rows along X are contiguous, so touch the pages each one spans.
+        let mut last_page = None;
+        for z in zs {
+            for y in ys.clone() {
+                let row = (z * dy + y) * dx;
+                let start = HEADER_LEN + (row + xs.start) * size;
+                let end = HEADER_LEN + (row + xs.end) * size;
+
+                for page in start / PAGE_SIZE..end.div_ceil(PAGE_SIZE) {
+                    if last_page.replace(page) != Some(page) {
+                        std::hint::black_box(self.map[(page * PAGE_SIZE).max(start)]);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<T: Pod> VolumeMut for SharedVolume<T> {
+    #[inline]
+    fn ls_get_mut<Idx: VolumeIdx>(&mut self, idx: Idx) -> Option<&mut Self::Item> {
+        let i = self.linear(idx)?;
+        self.items_mut().get_mut(i)
+    }
+}
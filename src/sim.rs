@@ -0,0 +1,142 @@
+//! Simple cellular automata built on the rest of the crate, usable as-is for prototypes. Requires the `sim` feature.
+
+use crate::prelude::*;
+use crate::types::Order;
+
+/// The horizontal directions fluid spreads in, in the order they're tried.
+const HORIZONTAL: [[i64; 3]; 4] = [[-1, 0, 0], [1, 0, 0], [0, 0, -1], [0, 0, 1]];
+
+/// The directions granular items move in, in the order they're tried: straight down, then diagonally down.
+const SETTLE: [[i64; 3]; 5] = [[0, -1, 0], [-1, -1, 0], [1, -1, 0], [0, -1, -1], [0, -1, 1]];
+
+/// Move `amount` levels of fluid between two cells inside of the volume.
+#[inline(always)]
+fn transfer<V: VolumeMut<Item = u8>>(vol: &mut V, from: [i64; 3], to: [i64; 3], amount: u8) {
+    *vol.get_mut(from).unwrap() -= amount;
+    *vol.get_mut(to).unwrap() += amount;
+}
+
+/// Advance a falling and spreading fluid by one step. The items of `vol` are the amount of fluid in each cell, from 0 (empty)
+/// to `fluid_levels` (full). Cells for which `is_solid` returns `true` never receive fluid. Gravity points along -Y.
+///
+/// Every cell first lets as much of its fluid as fits fall into the cell below it. If any fluid is left and it can't fall further,
+/// it spreads one level at a time into horizontal neighbors holding at least two levels less. The fluid never leaves the volume,
+/// so the total amount of fluid is conserved.
+///
+/// The step is double-buffered: every cell only moves the fluid it held at the start of the step (limited by how much room its
+/// neighbors have left), so fluid moves at most one cell per step. Returns the amount of cells whose level changed, which is 0
+/// once the fluid has settled.
+///
+/// # Panics
+/// Panics if `fluid_levels` is 0, or if any cell holds more than `fluid_levels`.
+pub fn step_fluid<V, S>(vol: &mut V, is_solid: S, fluid_levels: u8) -> usize
+where
+    V: VolumeMut<Item = u8>,
+    S: Fn([i64; 3]) -> bool,
+{
+    assert!(fluid_levels > 0, "fluid must have at least 1 level");
+
+    let bounds = vol.bounding_box();
+    debug_span!("step_fluid", %bounds);
+
+    let mut front = HeapVolume::new(0u8, bounds);
+    for (idx, &level) in vol.iter_indexed() {
+        assert!(
+            level <= fluid_levels,
+            "cell {} holds more than {fluid_levels} levels",
+            crate::types::VIdx(idx)
+        );
+        front[idx] = level;
+    }
+
+    let open = |pos: [i64; 3]| bounds.contains(pos) && !is_solid(pos);
+
+    for idx in bounds {
+        let mut remaining = front[idx];
+        if remaining == 0 {
+            continue;
+        }
+
+        let below = [idx[0], idx[1] - 1, idx[2]];
+        let mut blocked = true;
+        if open(below) {
+            let space = fluid_levels - *vol.get(below).unwrap();
+            let fall = remaining.min(space);
+            transfer(vol, idx, below, fall);
+            remaining -= fall;
+            blocked = fall == space;
+        }
+
+        if !blocked {
+            continue;
+        }
+
+        for offset in HORIZONTAL {
+            let Some(neighbor) = idx.checked_add(offset).filter(|&n| open(n)) else {
+                continue;
+            };
+
+            if front[neighbor] < remaining.saturating_sub(1)
+                && *vol.get(neighbor).unwrap() < fluid_levels
+            {
+                transfer(vol, idx, neighbor, 1);
+                remaining -= 1;
+            }
+        }
+    }
+
+    front
+        .iter_indexed()
+        .filter(|&(idx, level)| vol.get(idx) != Some(level))
+        .count()
+}
+
+/// Let granular items (for which `is_granular` returns `true`, e.g. sand) settle by one step. Gravity points along -Y.
+///
+/// Cells are visited bottom-up, and every granular item moves into the first empty cell (according to `is_empty`) either
+/// directly below it, or diagonally below it along -X, +X, -Z, and +Z, in that order. Items never leave the volume.
+/// Since the bottom is visited first, a whole column of items falls together, but every item moves at most one cell per step.
+///
+/// Returns the smallest region containing every cell that changed, or [`None`] if nothing moved (i.e., everything has settled).
+/// Only the cells in and directly around that region can move in the next step, which makes it easy to settle incrementally.
+pub fn settle<V, G, E>(vol: &mut V, is_granular: G, is_empty: E) -> Option<BoundingBox>
+where
+    V: VolumeMut,
+    V::Item: Clone,
+    G: Fn(&V::Item) -> bool,
+    E: Fn(&V::Item) -> bool,
+{
+    let bounds = vol.bounding_box();
+    debug_span!("settle", %bounds);
+
+    let mut dirty: Option<([i64; 3], [i64; 3])> = None;
+    let mut mark = |pos: [i64; 3]| {
+        let (min, max) = dirty.get_or_insert((pos, pos));
+        for axis in 0..3 {
+            min[axis] = min[axis].min(pos[axis]);
+            max[axis] = max[axis].max(pos[axis]);
+        }
+    };
+
+    for idx in bounds.iter_order(Order::Xzy) {
+        if !vol.get(idx).is_some_and(&is_granular) {
+            continue;
+        }
+
+        let target = SETTLE
+            .iter()
+            .filter_map(|&offset| idx.checked_add(offset))
+            .find(|&target| vol.get(target).is_some_and(&is_empty));
+
+        if let Some(target) = target {
+            let empty = vol.get(target).unwrap().clone();
+            let grain = std::mem::replace(vol.get_mut(idx).unwrap(), empty);
+            *vol.get_mut(target).unwrap() = grain;
+
+            mark(idx);
+            mark(target);
+        }
+    }
+
+    dirty.map(|(min, max)| BoundingBox::new(min, crate::util::sum_ivec3(max, [1, 1, 1])))
+}
@@ -0,0 +1,247 @@
+//! Volumes that can hand out cheap immutable snapshots of themselves, for reading a consistent copy on another thread
+//! (e.g., to save it in the background) while the original keeps being modified.
+
+use crate::prelude::*;
+use crate::types::ValidationError;
+use crate::util;
+use std::sync::Arc;
+
+/// A volume split into tiles that are shared between a [`CowVolume`] and its snapshots.
+struct Tiles<T> {
+    bounds: BoundingBox,
+    tile_dims: [i64; 3],
+    /// The amount of tiles along every axis.
+    counts: [i64; 3],
+    /// In X-major order, with the tiles on the far edges clipped to the bounds.
+    tiles: Vec<Arc<HeapVolume<T>>>,
+}
+
+// Cloning only shares the tiles, so the items don't need to be `Clone`.
+impl<T> Clone for Tiles<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            bounds: self.bounds,
+            tile_dims: self.tile_dims,
+            counts: self.counts,
+            tiles: self.tiles.clone(),
+        }
+    }
+}
+
+impl<T: Clone> Tiles<T> {
+    fn new(item: T, bounds: BoundingBox, tile_dims: [i64; 3]) -> Self {
+        assert!(
+            tile_dims.iter().all(|&d| d > 0),
+            "tile dimensions must be at least 1"
+        );
+
+        let dims = bounds.dimensions();
+        let counts = [0, 1, 2].map(|axis| match dims[axis] {
+            0 => 0,
+            d => (d - 1) / tile_dims[axis] + 1,
+        });
+
+        let tiles = BoundingBox::new_origin(counts)
+            .into_iter()
+            .map(|tile| {
+                let min = util::sum_ivec3(
+                    bounds.min(),
+                    [0, 1, 2].map(|axis| tile[axis] * tile_dims[axis]),
+                );
+                let max = [0, 1, 2].map(|axis| {
+                    min[axis]
+                        .saturating_add(tile_dims[axis])
+                        .min(bounds.max()[axis])
+                });
+                Arc::new(HeapVolume::new(item.clone(), BoundingBox::new(min, max)))
+            })
+            .collect();
+
+        Self {
+            bounds,
+            tile_dims,
+            counts,
+            tiles,
+        }
+    }
+}
+
+impl<T> Tiles<T> {
+    /// The index of the tile containing the worldspace position, or [`None`] if it's out of bounds.
+    #[inline(always)]
+    fn tile_of(&self, pos: [i64; 3]) -> Option<usize> {
+        if !self.bounds.contains(pos) {
+            return None;
+        }
+
+        let [x, y, z] =
+            [0, 1, 2].map(|axis| (pos[axis] - self.bounds.min()[axis]) / self.tile_dims[axis]);
+        let [cx, cy, _] = self.counts;
+        Some(((z * cy + y) * cx + x) as usize)
+    }
+
+    #[inline(always)]
+    fn get(&self, pos: [i64; 3]) -> Option<&T> {
+        self.tiles[self.tile_of(pos)?].get(pos)
+    }
+
+    fn heap_size(&self) -> usize {
+        self.tiles.capacity() * std::mem::size_of::<Arc<HeapVolume<T>>>()
+    }
+
+    fn validate(&self) -> Result<(), ValidationError> {
+        self.tiles.iter().try_for_each(|tile| tile.validate())
+    }
+}
+
+/// A volume that can make immutable snapshots of itself in O(number of tiles) time, without copying any items.
+///
+/// The items are stored in tiles (of `tile_dims`, except at the far edges of the bounds) that are shared between the volume
+/// and its snapshots. Writing to a tile that's shared with a snapshot copies just that tile first (copy-on-write), so taking
+/// a snapshot is cheap and the cost of copying is spread over the writes that happen while it's alive. Tiles that aren't
+/// written to before the snapshot is dropped are never copied.
+#[derive(Clone)]
+pub struct CowVolume<T> {
+    tiles: Tiles<T>,
+}
+
+impl_indexing!(T, CowVolume<T>);
+impl_debug!(T, CowVolume<T>);
+
+impl<T: Clone> CowVolume<T> {
+    /// Create a volume with every item set to `item`, split into tiles of `tile_dims`.
+    ///
+    /// # Panics
+    /// Panics if any of the tile dimensions is less than 1.
+    #[inline]
+    pub fn new(item: T, bounds: BoundingBox, tile_dims: [i64; 3]) -> Self {
+        Self {
+            tiles: Tiles::new(item, bounds, tile_dims),
+        }
+    }
+}
+
+impl<T> CowVolume<T> {
+    /// Take an immutable snapshot of the items of this volume, which isn't affected by later writes to this volume.
+    #[inline]
+    pub fn snapshot(&self) -> VolumeSnapshot<T> {
+        VolumeSnapshot {
+            tiles: self.tiles.clone(),
+        }
+    }
+
+    #[inline]
+    pub fn tile_dimensions(&self) -> [i64; 3] {
+        self.tiles.tile_dims
+    }
+
+    /// The amount of tiles that are still shared with a snapshot, and would be copied when written to.
+    #[inline]
+    pub fn shared_tiles(&self) -> usize {
+        self.tiles
+            .tiles
+            .iter()
+            .filter(|tile| Arc::strong_count(tile) > 1)
+            .count()
+    }
+}
+
+impl<T> Volume for CowVolume<T> {
+    type Item = T;
+
+    #[inline]
+    fn ls_get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        self.get(idx.array::<i64>()?.checked_add(self.tiles.bounds.min())?)
+    }
+
+    #[inline(always)]
+    fn bounding_box(&self) -> BoundingBox {
+        self.tiles.bounds
+    }
+
+    #[inline]
+    fn get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        self.tiles.get(idx.array()?)
+    }
+
+    #[inline]
+    fn heap_size(&self) -> usize {
+        self.tiles.heap_size()
+            + self
+                .tiles
+                .tiles
+                .iter()
+                .map(|tile| std::mem::size_of::<HeapVolume<T>>() + tile.heap_size())
+                .sum::<usize>()
+    }
+
+    #[inline]
+    fn validate(&self) -> Result<(), ValidationError> {
+        self.tiles.validate()
+    }
+}
+
+impl<T: Clone> VolumeMut for CowVolume<T> {
+    #[inline]
+    fn ls_get_mut<Idx: VolumeIdx>(&mut self, idx: Idx) -> Option<&mut Self::Item> {
+        self.get_mut(idx.array::<i64>()?.checked_add(self.tiles.bounds.min())?)
+    }
+
+    /// Get a mutable reference to the item at the given worldspace index, first copying its tile if it's shared with a snapshot.
+    #[inline]
+    fn get_mut<Idx: VolumeIdx>(&mut self, idx: Idx) -> Option<&mut Self::Item> {
+        let pos = idx.array()?;
+        let tile = self.tiles.tile_of(pos)?;
+
+        Arc::make_mut(&mut self.tiles.tiles[tile]).get_mut(pos)
+    }
+}
+
+/// An immutable snapshot of a [`CowVolume`], taken with [`CowVolume::snapshot`]. Cheap to clone, and can be sent to other
+/// threads (when the items can) while the original volume keeps being modified.
+pub struct VolumeSnapshot<T> {
+    tiles: Tiles<T>,
+}
+
+impl<T> Clone for VolumeSnapshot<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            tiles: self.tiles.clone(),
+        }
+    }
+}
+
+impl_indexing!(T, VolumeSnapshot<T>);
+impl_debug!(T, VolumeSnapshot<T>);
+
+impl<T> Volume for VolumeSnapshot<T> {
+    type Item = T;
+
+    #[inline]
+    fn ls_get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        self.get(idx.array::<i64>()?.checked_add(self.tiles.bounds.min())?)
+    }
+
+    #[inline(always)]
+    fn bounding_box(&self) -> BoundingBox {
+        self.tiles.bounds
+    }
+
+    #[inline]
+    fn get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        self.tiles.get(idx.array()?)
+    }
+
+    /// Only counts the bookkeeping of the snapshot itself, since the tiles are (potentially) shared with the original volume.
+    #[inline]
+    fn heap_size(&self) -> usize {
+        self.tiles.heap_size()
+    }
+
+    #[inline]
+    fn validate(&self) -> Result<(), ValidationError> {
+        self.tiles.validate()
+    }
+}
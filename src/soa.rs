@@ -0,0 +1,168 @@
+//! Struct-of-arrays storage for volumes of structs. See [`soa_volume!`](crate::soa_volume).
+
+/// Define a struct along with a volume which stores each of the struct's fields in a separate [`HeapVolume`](crate::prelude::HeapVolume)
+/// (struct-of-arrays), so that passes which only touch one field don't have to load the other fields.
+///
+/// Besides the struct itself, the macro takes the names of three generated types: the volume, a struct of references
+/// to the fields of one item, and a struct of mutable references to the fields of one item.
+/// Every field must implement [`Clone`], and no field may be named after one of the volume's methods.
+///
+/// ```
+/// volume::soa_volume! {
+///     #[derive(Debug, Copy, Clone, PartialEq)]
+///     pub struct Voxel {
+///         pub id: u16,
+///         pub light: u8,
+///         pub flags: u8,
+///     }
+///
+///     pub struct VoxelVolume(VoxelRef, VoxelMut);
+/// }
+///
+/// use volume::prelude::*;
+///
+/// let empty = Voxel { id: 0, light: 15, flags: 0 };
+/// let mut vol = VoxelVolume::new(empty, BoundingBox::new_origin([16, 16, 16]));
+///
+/// vol.set([1, 2, 3], Voxel { id: 4, light: 0, flags: 1 });
+/// assert_eq!(4, *vol.get([1, 2, 3]).unwrap().id);
+/// *vol.get_mut([1, 2, 3]).unwrap().light = 7;
+///
+/// // Every field is a normal volume.
+/// assert_eq!(7, vol.light()[[1, 2, 3]]);
+/// let (_, mut light, _) = vol.channels_mut();
+/// light[[0, 0, 0]] = 3;
+/// assert_eq!(Some(Voxel { id: 0, light: 3, flags: 0 }), vol.load([0, 0, 0]));
+/// ```
+#[macro_export]
+macro_rules! soa_volume {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $item:ident {
+            $($field_vis:vis $field:ident: $ty:ty),+ $(,)?
+        }
+
+        $(#[$volume_meta:meta])*
+        $volume_vis:vis struct $volume:ident($item_ref:ident, $item_mut:ident);
+    ) => {
+        $(#[$meta])*
+        $vis struct $item {
+            $($field_vis $field: $ty),+
+        }
+
+        #[doc = concat!("References to the fields of a [`", stringify!($item), "`] stored in a [`", stringify!($volume), "`].")]
+        #[derive(Debug, Copy, Clone)]
+        $volume_vis struct $item_ref<'a> {
+            $(pub $field: &'a $ty),+
+        }
+
+        impl<'a> $item_ref<'a> {
+            /// Clone the referenced fields into an owned item.
+            #[inline]
+            pub fn cloned(&self) -> $item {
+                $item {
+                    $($field: ::std::clone::Clone::clone(self.$field)),+
+                }
+            }
+        }
+
+        #[doc = concat!("Mutable references to the fields of a [`", stringify!($item), "`] stored in a [`", stringify!($volume), "`].")]
+        #[derive(Debug)]
+        $volume_vis struct $item_mut<'a> {
+            $(pub $field: &'a mut $ty),+
+        }
+
+        impl<'a> $item_mut<'a> {
+            /// Overwrite every referenced field with the fields of `item`.
+            #[inline]
+            pub fn set(&mut self, item: $item) {
+                $(*self.$field = item.$field;)+
+            }
+        }
+
+        $(#[$volume_meta])*
+        $volume_vis struct $volume {
+            $($field: $crate::prelude::HeapVolume<$ty>,)+
+            bounds: $crate::prelude::BoundingBox,
+        }
+
+        impl $volume {
+            /// Create a volume where every position holds `item`.
+            #[inline]
+            pub fn new(item: $item, bounds: impl ::std::convert::Into<$crate::prelude::BoundingBox>) -> Self {
+                let bounds = bounds.into();
+                Self {
+                    $($field: $crate::prelude::HeapVolume::new(item.$field, bounds),)+
+                    bounds,
+                }
+            }
+
+            #[inline]
+            pub fn bounding_box(&self) -> $crate::prelude::BoundingBox {
+                self.bounds
+            }
+
+            /// Checks if this volume contains the worldspace index.
+            #[inline]
+            pub fn contains<Idx: $crate::prelude::VolumeIdx>(&self, idx: Idx) -> bool {
+                self.bounds.contains(idx)
+            }
+
+            /// Get references to the fields of the item at the given worldspace index. Returns [`None`] if the index is out of bounds.
+            #[inline]
+            pub fn get<Idx: $crate::prelude::VolumeIdx>(&self, idx: Idx) -> ::std::option::Option<$item_ref<'_>> {
+                let idx = idx.array::<i64>()?;
+                ::std::option::Option::Some($item_ref {
+                    $($field: $crate::prelude::Volume::get(&self.$field, idx)?),+
+                })
+            }
+
+            /// Get mutable references to the fields of the item at the given worldspace index. Returns [`None`] if the index is out of bounds.
+            #[inline]
+            pub fn get_mut<Idx: $crate::prelude::VolumeIdx>(&mut self, idx: Idx) -> ::std::option::Option<$item_mut<'_>> {
+                let idx = idx.array::<i64>()?;
+                ::std::option::Option::Some($item_mut {
+                    $($field: $crate::prelude::VolumeMut::get_mut(&mut self.$field, idx)?),+
+                })
+            }
+
+            /// Get a copy of the item at the given worldspace index. Returns [`None`] if the index is out of bounds.
+            #[inline]
+            pub fn load<Idx: $crate::prelude::VolumeIdx>(&self, idx: Idx) -> ::std::option::Option<$item> {
+                self.get(idx).map(|item| item.cloned())
+            }
+
+            /// Set the item at the given worldspace index. Returns `false` (and changes nothing) if the index is out of bounds.
+            #[inline]
+            pub fn set<Idx: $crate::prelude::VolumeIdx>(&mut self, idx: Idx, item: $item) -> bool {
+                match self.get_mut(idx) {
+                    ::std::option::Option::Some(mut slot) => {
+                        slot.set(item);
+                        true
+                    }
+                    ::std::option::Option::None => false,
+                }
+            }
+
+            $(
+                #[doc = concat!("The volume storing the `", stringify!($field), "` field of every item.")]
+                #[inline]
+                pub fn $field(&self) -> &$crate::prelude::HeapVolume<$ty> {
+                    &self.$field
+                }
+            )+
+
+            /// Get mutable access to the volume of every field at once, in the order the fields are declared in.
+            #[inline]
+            pub fn channels_mut(&mut self) -> ($($crate::multi::ChannelMut<'_, $ty>,)+) {
+                ($($crate::multi::ChannelMut::__new(&mut self.$field),)+)
+            }
+
+            /// The total [`Volume::heap_size`]($crate::prelude::Volume::heap_size) of every field's volume.
+            #[inline]
+            pub fn heap_size(&self) -> usize {
+                0 $(+ $crate::prelude::Volume::heap_size(&self.$field))+
+            }
+        }
+    };
+}
@@ -0,0 +1,94 @@
+//! Indices tagged with the coordinate space they're in, so mixing up worldspace and localspace indices is a type error.
+//!
+//! Plain indices passed to [`Volume::get`] are worldspace and those passed to [`Volume::ls_get`] are localspace, which is
+//! easy to get wrong. Wrapping indices in [`World`] or [`Local`] and accessing items with [`Volume::at`] and
+//! [`VolumeMut::at_mut`] picks the right translation from the type of the index instead.
+
+use crate::chunked::ChunkLayout;
+use crate::prelude::*;
+use crate::util;
+
+/// A coordinate space that indices can be tagged with, deciding how they're translated when accessing a volume.
+pub trait IndexSpace {
+    /// Get a reference to the item of `vol` at this index.
+    fn get<V: Volume>(self, vol: &V) -> Option<&V::Item>;
+
+    /// Get a mutable reference to the item of `vol` at this index.
+    fn get_mut<V: VolumeMut>(self, vol: &mut V) -> Option<&mut V::Item>;
+}
+
+/// An index in worldspace, the space of a volume's [bounding box](Volume::bounding_box).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct World<Idx>(pub Idx);
+
+/// An index in the localspace of some volume, where `[0, 0, 0]` is the minimum of its bounding box.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Local<Idx>(pub Idx);
+
+/// The coordinates of a chunk in a [`ChunkLayout`]. Chunk `c` covers the worldspace positions from `c * dimensions` (inclusive)
+/// to `(c + 1) * dimensions` (exclusive).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct ChunkCoord<Idx>(pub Idx);
+
+impl<Idx: VolumeIdx> IndexSpace for World<Idx> {
+    #[inline(always)]
+    fn get<V: Volume>(self, vol: &V) -> Option<&V::Item> {
+        vol.get(self.0)
+    }
+
+    #[inline(always)]
+    fn get_mut<V: VolumeMut>(self, vol: &mut V) -> Option<&mut V::Item> {
+        vol.get_mut(self.0)
+    }
+}
+
+impl<Idx: VolumeIdx> IndexSpace for Local<Idx> {
+    #[inline(always)]
+    fn get<V: Volume>(self, vol: &V) -> Option<&V::Item> {
+        vol.ls_get(self.0)
+    }
+
+    #[inline(always)]
+    fn get_mut<V: VolumeMut>(self, vol: &mut V) -> Option<&mut V::Item> {
+        vol.ls_get_mut(self.0)
+    }
+}
+
+impl<Idx: VolumeIdx> World<Idx> {
+    /// The same position in the localspace of a volume with the given bounds, or [`None`] if it's outside of them.
+    #[inline]
+    pub fn to_local(self, bounds: BoundingBox) -> Option<Local<[i64; 3]>> {
+        let pos = self.0.array::<i64>()?;
+        match bounds.contains(pos) {
+            true => Some(Local(util::sub_ivec3(pos, bounds.min()))),
+            false => None,
+        }
+    }
+
+    /// The coordinates of the chunk containing this position.
+    #[inline]
+    pub fn chunk<L: ChunkLayout>(self, layout: L) -> Option<ChunkCoord<[i64; 3]>> {
+        Some(ChunkCoord(layout.chunk_of(self.0.array()?)))
+    }
+}
+
+impl<Idx: VolumeIdx> Local<Idx> {
+    /// The same position in worldspace, given the bounds of the volume this index is local to. Returns [`None`] if it's
+    /// outside of them.
+    #[inline]
+    pub fn to_world(self, bounds: BoundingBox) -> Option<World<[i64; 3]>> {
+        let pos = self.0.array::<i64>()?.checked_add(bounds.min())?;
+        match bounds.contains(pos) {
+            true => Some(World(pos)),
+            false => None,
+        }
+    }
+}
+
+impl<Idx: VolumeIdx> ChunkCoord<Idx> {
+    /// The worldspace bounds of this chunk, or [`None`] if they overflow [`i64`].
+    #[inline]
+    pub fn bounds<L: ChunkLayout>(self, layout: L) -> Option<BoundingBox> {
+        layout.checked_chunk_bounds(self.0.array()?)
+    }
+}
@@ -0,0 +1,231 @@
+//! Sparse collections of items at arbitrary positions (like spawn points or light sources), with spatial queries.
+
+use crate::prelude::*;
+use std::collections::HashMap;
+
+/// The squared euclidean distance between two positions, which can't overflow even for positions at opposite ends of [`i64`].
+#[inline(always)]
+fn distance_squared(a: [i64; 3], b: [i64; 3]) -> u128 {
+    (0..3)
+        .map(|axis| (a[axis] as i128 - b[axis] as i128).unsigned_abs().pow(2))
+        .sum()
+}
+
+/// Items stored at arbitrary worldspace positions in a hash map, for sets of positions too sparse for any dense volume
+/// (or even a [`ChunkedVolume`]), like the spawn points or light sources of a world.
+///
+/// Besides the items themselves, positions are hashed into a grid of cells of `cell_size`³ positions that's kept up to date
+/// on every insertion and removal. Spatial queries like [`HashVolume::iter_in_box`] and [`HashVolume::nearest_n`] only look
+/// at the cells near the queried region, instead of scanning every item.
+///
+/// Since most positions don't hold an item, this type doesn't implement [`Volume`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct HashVolume<T> {
+    items: HashMap<[i64; 3], T>,
+    cell_size: i64,
+    /// The positions holding an item in every non-empty cell.
+    cells: HashMap<[i64; 3], Vec<[i64; 3]>>,
+}
+
+impl<T> HashVolume<T> {
+    /// Create an empty volume whose spatial index uses cells of `cell_size`³ positions. Pick a cell size roughly matching
+    /// the typical distance between items (or the typical size of a query).
+    ///
+    /// # Panics
+    /// Panics if `cell_size` is less than 1.
+    #[inline]
+    pub fn new(cell_size: i64) -> Self {
+        assert!(cell_size > 0, "cell size must be at least 1");
+
+        Self {
+            items: HashMap::new(),
+            cell_size,
+            cells: HashMap::new(),
+        }
+    }
+
+    #[inline(always)]
+    fn cell_of(&self, pos: [i64; 3]) -> [i64; 3] {
+        pos.map(|c| c.div_euclid(self.cell_size))
+    }
+
+    /// The amount of items.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    #[inline]
+    pub fn cell_size(&self) -> i64 {
+        self.cell_size
+    }
+
+    /// Insert an item at the given worldspace index, returning the item that was there before (if any).
+    #[inline]
+    pub fn insert<Idx: VolumeIdx>(&mut self, idx: Idx, item: T) -> Option<T> {
+        let pos = idx.array::<i64>()?;
+        let previous = self.items.insert(pos, item);
+        if previous.is_none() {
+            self.cells.entry(self.cell_of(pos)).or_default().push(pos);
+        }
+
+        previous
+    }
+
+    /// Remove the item at the given worldspace index, returning it (if any).
+    #[inline]
+    pub fn remove<Idx: VolumeIdx>(&mut self, idx: Idx) -> Option<T> {
+        let pos = idx.array::<i64>()?;
+        let item = self.items.remove(&pos)?;
+
+        let cell = self.cell_of(pos);
+        let positions = self.cells.get_mut(&cell).unwrap();
+        positions.swap_remove(positions.iter().position(|&p| p == pos).unwrap());
+        if positions.is_empty() {
+            self.cells.remove(&cell);
+        }
+
+        Some(item)
+    }
+
+    #[inline]
+    pub fn get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&T> {
+        self.items.get(&idx.array::<i64>()?)
+    }
+
+    #[inline]
+    pub fn get_mut<Idx: VolumeIdx>(&mut self, idx: Idx) -> Option<&mut T> {
+        self.items.get_mut(&idx.array::<i64>()?)
+    }
+
+    /// Iterate over every item and its position, in no particular order.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = ([i64; 3], &T)> {
+        self.items.iter().map(|(&pos, item)| (pos, item))
+    }
+
+    /// The smallest bounding box containing every item, or [`None`] if there are no items.
+    #[inline]
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        let mut positions = self.items.keys();
+        let first = *positions.next()?;
+
+        let (min, max) = positions.fold((first, first), |(min, max), &pos| {
+            (
+                crate::math::min_ivec3(min, pos),
+                crate::math::max_ivec3(max, pos),
+            )
+        });
+        Some(BoundingBox::new(
+            min,
+            crate::util::sum_ivec3(max, [1, 1, 1]),
+        ))
+    }
+
+    /// Iterate over the items within `region` and their positions, in no particular order. Only looks at the cells
+    /// overlapping the region (or at every non-empty cell, if there are fewer of those).
+    #[inline]
+    pub fn iter_in_box(&self, region: BoundingBox) -> impl Iterator<Item = ([i64; 3], &T)> {
+        let cells = match region.is_empty() {
+            true => BoundingBox::new_origin([0, 0, 0]),
+            false => BoundingBox::new(
+                self.cell_of(region.min()),
+                crate::util::sum_ivec3(
+                    self.cell_of(crate::util::sub_ivec3(region.max(), [1, 1, 1])),
+                    [1, 1, 1],
+                ),
+            ),
+        };
+
+        let positions: Vec<&Vec<[i64; 3]>> = match cells.capacity() > self.cells.len() as i128 {
+            true => self
+                .cells
+                .iter()
+                .filter(|(&cell, _)| cells.contains(cell))
+                .map(|(_, positions)| positions)
+                .collect(),
+            false => cells
+                .into_iter()
+                .filter_map(|cell| self.cells.get(&cell))
+                .collect(),
+        };
+
+        positions
+            .into_iter()
+            .flatten()
+            .filter(move |&&pos| region.contains(pos))
+            .map(|pos| (*pos, &self.items[pos]))
+    }
+
+    /// The (up to) `n` items closest to `from` by euclidean distance and their positions, sorted from closest to furthest.
+    /// Items at the same distance are sorted by their position.
+    ///
+    /// Searches outwards from the cell containing `from` one shell of cells at a time, and stops as soon as no unvisited cell
+    /// can hold an item closer than the `n` closest ones found so far.
+    #[inline]
+    pub fn nearest_n(&self, from: [i64; 3], n: usize) -> Vec<([i64; 3], &T)> {
+        let mut found: Vec<(u128, [i64; 3])> = Vec::new();
+        let done = |found: &mut Vec<(u128, [i64; 3])>| {
+            found.sort_unstable();
+            found.truncate(n);
+            found
+                .iter()
+                .map(|&(_, pos)| (pos, &self.items[&pos]))
+                .collect()
+        };
+
+        if n == 0 {
+            return Vec::new();
+        }
+
+        let center = self.cell_of(from);
+        let mut visited = 0;
+        for radius in 0i64.. {
+            // The amount of cells in this shell. Once it exceeds the amount of non-empty cells it's cheaper to
+            // look at every item instead.
+            let side = 2 * radius as i128 + 1;
+            let shell = side.pow(3) - (side - 2).max(0).pow(3);
+            if visited == self.items.len() || shell > self.cells.len() as i128 {
+                break;
+            }
+
+            let shell_bounds =
+                BoundingBox::new(center.map(|c| c - radius), center.map(|c| c + radius + 1));
+            for cell in shell_bounds {
+                let on_shell = (0..3).any(|axis| (cell[axis] - center[axis]).abs() == radius);
+                for &pos in self
+                    .cells
+                    .get(&cell)
+                    .filter(|_| on_shell)
+                    .into_iter()
+                    .flatten()
+                {
+                    found.push((distance_squared(from, pos), pos));
+                    visited += 1;
+                }
+            }
+
+            // Every position in a cell outside of this shell is at least `radius * cell_size` away along some axis.
+            if found.len() >= n {
+                found.sort_unstable();
+                found.truncate(n);
+                let reach = (radius as u128 * self.cell_size as u128).pow(2);
+                if found[n - 1].0 <= reach {
+                    return done(&mut found);
+                }
+            }
+        }
+
+        found = self
+            .items
+            .keys()
+            .map(|&pos| (distance_squared(from, pos), pos))
+            .collect();
+        done(&mut found)
+    }
+}
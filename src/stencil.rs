@@ -0,0 +1,192 @@
+//! Stencil operations, which compute every cell of a new volume from a fixed-size neighborhood around the same cell in
+//! an existing volume. This is the shared machinery behind heat diffusion, fluid, and cellular automata simulations on grids.
+//!
+//! Neighbors outside of the volume are resolved by a [`BoundaryCondition`], so stencils never have to special-case the edges.
+
+use crate::prelude::*;
+
+/// How neighbors outside of a volume's bounds are treated by stencil operations.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BoundaryCondition<T> {
+    /// Positions outside of the volume have the given fixed value.
+    Dirichlet(T),
+    /// Positions outside of the volume have the value of the closest cell inside of it (i.e., a zero gradient across the edges).
+    Neumann,
+    /// The volume wraps around, so positions outside of it have the value of the cell on the opposite side.
+    Periodic,
+}
+
+/// The `N`×`N`×`N` cells centered on the cell a stencil is computing. `N` must be odd.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Neighborhood<T, const N: usize> {
+    /// Indexed as `cells[x][y][z]`, where `[R, R, R]` (with `R = N / 2`) is the center.
+    cells: [[[T; N]; N]; N],
+}
+
+impl<T, const N: usize> Neighborhood<T, N> {
+    /// The distance from the center to the edge of the neighborhood along every axis.
+    pub const RADIUS: i64 = {
+        assert!(N % 2 == 1, "the size of a neighborhood must be odd");
+        (N / 2) as i64
+    };
+
+    /// The item at the given offset from the center.
+    ///
+    /// # Panics
+    /// Panics if any component of `offset` is further than [`Neighborhood::RADIUS`] from 0.
+    #[inline]
+    pub fn get(&self, offset: [i64; 3]) -> &T {
+        let [x, y, z] = offset.map(|c| {
+            assert!(
+                c.abs() <= Self::RADIUS,
+                "offset is outside of the neighborhood"
+            );
+            (c + Self::RADIUS) as usize
+        });
+
+        &self.cells[x][y][z]
+    }
+
+    /// The item in the center of the neighborhood, i.e., the cell being computed.
+    #[inline]
+    pub fn center(&self) -> &T {
+        self.get([0, 0, 0])
+    }
+
+    /// The 6 cells sharing a face with the center, in the order -X, +X, -Y, +Y, -Z, +Z.
+    #[inline]
+    pub fn face_neighbors(&self) -> [&T; 6] {
+        [
+            self.get([-1, 0, 0]),
+            self.get([1, 0, 0]),
+            self.get([0, -1, 0]),
+            self.get([0, 1, 0]),
+            self.get([0, 0, -1]),
+            self.get([0, 0, 1]),
+        ]
+    }
+
+    /// Iterate over every cell in the neighborhood along with its offset from the center, in X-major order.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = ([i64; 3], &T)> {
+        let r = Self::RADIUS;
+        BoundingBox::new([-r, -r, -r], [r + 1, r + 1, r + 1])
+            .into_iter()
+            .map(|offset| (offset, self.get(offset)))
+    }
+}
+
+impl<T: Clone, const N: usize> Neighborhood<T, N> {
+    fn gather<V: Volume<Item = T>>(
+        vol: &V,
+        boundary: &BoundaryCondition<T>,
+        pos: [i64; 3],
+    ) -> Self {
+        let bounds = vol.bounding_box();
+        let (min, dims) = (bounds.min(), bounds.dimensions());
+        let r = Self::RADIUS;
+
+        let cell = |offset: [i64; 3]| -> T {
+            let neighbor = crate::util::sum_ivec3(pos, offset);
+            if let Some(item) = vol.get(neighbor) {
+                return item.clone();
+            }
+
+            let resolved = match boundary {
+                BoundaryCondition::Dirichlet(item) => return item.clone(),
+                BoundaryCondition::Neumann => {
+                    [0, 1, 2].map(|a| neighbor[a].clamp(min[a], min[a] + dims[a] - 1))
+                }
+                BoundaryCondition::Periodic => {
+                    [0, 1, 2].map(|a| min[a] + (neighbor[a] - min[a]).rem_euclid(dims[a]))
+                }
+            };
+
+            vol.get(resolved).unwrap().clone()
+        };
+
+        Self {
+            cells: std::array::from_fn(|x| {
+                std::array::from_fn(|y| {
+                    std::array::from_fn(|z| cell([x as i64 - r, y as i64 - r, z as i64 - r]))
+                })
+            }),
+        }
+    }
+}
+
+/// Compute the YZ plane at X coordinate `x` of the output of a stencil.
+fn plane<V, T, U, F, const N: usize>(
+    vol: &V,
+    boundary: &BoundaryCondition<T>,
+    stencil: &F,
+    x: i64,
+) -> Box<[Box<[U]>]>
+where
+    V: Volume<Item = T>,
+    T: Clone,
+    F: Fn(&Neighborhood<T, N>) -> U,
+{
+    let bounds = vol.bounding_box();
+    let (min, max) = (bounds.min(), bounds.max());
+
+    (min[1]..max[1])
+        .map(|y| {
+            (min[2]..max[2])
+                .map(|z| stencil(&Neighborhood::gather(vol, boundary, [x, y, z])))
+                .collect()
+        })
+        .collect()
+}
+
+/// Apply `stencil` to every cell of `vol`, returning the results as a new volume with the same bounds.
+/// The stencil is given the `N`×`N`×`N` neighborhood around each cell, with neighbors outside of the volume resolved by `boundary`.
+///
+/// Since the output is a separate volume, every cell is computed from the unmodified input (i.e., the update is double-buffered).
+#[inline]
+pub fn apply<V, T, U, F, const N: usize>(
+    vol: &V,
+    boundary: BoundaryCondition<T>,
+    stencil: F,
+) -> HeapVolume<U>
+where
+    V: Volume<Item = T>,
+    T: Clone,
+    F: Fn(&Neighborhood<T, N>) -> U,
+{
+    let bounds = vol.bounding_box();
+    debug_span!("stencil", %bounds, size = N);
+
+    let planes = (bounds.min()[0]..bounds.max()[0])
+        .map(|x| plane(vol, &boundary, &stencil, x))
+        .collect();
+
+    HeapVolume::from_planes(bounds, planes)
+}
+
+/// Same as [`apply`], but computes the output in parallel using rayon. Requires the `rayon` feature.
+#[cfg(feature = "rayon")]
+#[inline]
+pub fn par_apply<V, T, U, F, const N: usize>(
+    vol: &V,
+    boundary: BoundaryCondition<T>,
+    stencil: F,
+) -> HeapVolume<U>
+where
+    V: Volume<Item = T> + Sync,
+    T: Clone + Sync,
+    U: Send,
+    F: Fn(&Neighborhood<T, N>) -> U + Sync,
+{
+    use rayon::prelude::*;
+
+    let bounds = vol.bounding_box();
+    debug_span!("par_stencil", %bounds, size = N);
+
+    let planes = (bounds.min()[0]..bounds.max()[0])
+        .into_par_iter()
+        .map(|x| plane(vol, &boundary, &stencil, x))
+        .collect();
+
+    HeapVolume::from_planes(bounds, planes)
+}
@@ -56,6 +56,122 @@ mod util {
     }
 }
 
+#[cfg(test)]
+mod math {
+    use crate::math;
+    use crate::prelude::*;
+
+    #[test]
+    fn saturating_cast_ivec3() {
+        assert_eq!([255u8, 0, 7], math::saturating_cast_ivec3([300i32, -5, 7]));
+        assert_eq!(
+            [i32::MAX, i32::MIN, 0],
+            math::saturating_cast_ivec3([u64::MAX as i128, i128::MIN, 0])
+        );
+    }
+
+    #[test]
+    fn component_wise() {
+        assert_eq!([-1, 2, 3], math::min_ivec3([-1, 5, 3], [4, 2, 9]));
+        assert_eq!([4, 5, 9], math::max_ivec3([-1, 5, 3], [4, 2, 9]));
+        assert_eq!([-2, 10, 0], math::scale_ivec3([-1, 5, 0], 2));
+    }
+
+    #[test]
+    fn clamp_ivec3() {
+        let bounds = BoundingBox::new([-2, 0, 4], [2, 3, 8]);
+        assert_eq!(Some([1, 0, 5]), math::clamp_ivec3([9, -4, 5], bounds));
+        assert_eq!(Some([-2, 2, 7]), math::clamp_ivec3([-2, 3, 8], bounds));
+        assert_eq!(
+            None,
+            math::clamp_ivec3([0, 0, 0], BoundingBox::new_origin([0, 4, 4]))
+        );
+    }
+}
+
+#[cfg(test)]
+mod prelude {
+    #[test]
+    fn typical_use() {
+        use crate::prelude::*;
+
+        let bounds: BoundingBox = "(0, 0, 0)..(4, 4, 4)".parse().unwrap();
+        let mut chunks = ChunkedVolume::new(0u8, StaticBounds::<4, 4, 4>::new());
+        chunks.set([1, 2, 3], 7);
+
+        let mut vol = Observed::new(HeapVolume::new(0u8, bounds));
+        vol.insert([0, 0, 0], &chunks).unwrap();
+        assert_eq!(Some(&7), vol.slice(bounds).get(VIdx([1, 2, 3])));
+        assert_eq!(
+            64,
+            bounds.to_bounding_box().iter_order(Order::Morton).count()
+        );
+    }
+
+    #[test]
+    fn full() {
+        use crate::full::*;
+
+        let line = line([0, 0, 0], [3, 0, 0]).collect::<Vec<_>>();
+        let selection = Selection::from_voxels(line);
+        assert_eq!(
+            Some(BoundingBox::new_origin([4, 1, 1])),
+            selection.enclosing_box()
+        );
+    }
+}
+
+#[cfg(test)]
+mod volume_idx {
+    use crate::prelude::*;
+
+    #[test]
+    fn offsets() {
+        assert_eq!(Some([3u8, 0, 7]), [4u8, 2, 7].checked_add([-1, -2, 0]));
+        assert_eq!(None, [4u8, 2, 7].checked_add([-5, 0, 0]));
+        assert_eq!(None, [250u8, 0, 0].checked_add([6, 0, 0]));
+        assert_eq!(None, [i64::MAX, 0, 0].checked_add([1, 0, 0]));
+        assert_eq!(Some([1i32, 2, 3]), [2i32, 4, 6].checked_sub([1, 2, 3]));
+
+        assert_eq!([i64::MIN, 1, 1], [i64::MAX, 0, 0].wrapping_add([1, 1, 1]));
+        assert_eq!([0i32, 5, -5], [2i32, 5, -3].wrapping_add([-2, 0, -2]));
+    }
+
+    #[test]
+    fn face_neighbors() {
+        let neighbors = [0u32, 5, 5].face_neighbors().collect::<Vec<_>>();
+        assert_eq!(
+            vec![[1, 5, 5], [0, 4, 5], [0, 6, 5], [0, 5, 4], [0, 5, 6]],
+            neighbors
+        );
+        assert_eq!(6, [0i32, 0, 0].face_neighbors().count());
+    }
+
+    #[test]
+    fn vidx() {
+        use crate::types::ParseIdxError;
+
+        let idx = VIdx::new(-3, 0, 12);
+        assert_eq!("(-3, 0, 12)", idx.to_string());
+        assert_eq!(Ok(idx), idx.to_string().parse());
+        assert_eq!(Ok(idx), "-3,0,12".parse());
+        assert_eq!(Ok(idx), " [ -3 , 0,12 ] ".parse());
+        assert_eq!(Err(ParseIdxError::ComponentCount(2)), "1,2".parse::<VIdx>());
+        assert!(matches!(
+            "1,x,2".parse::<VIdx>(),
+            Err(ParseIdxError::InvalidComponent { component, .. }) if component == "x"
+        ));
+
+        let vol = HeapVolume::new(7u8, BoundingBox::new([-4, 0, 0], [0, 1, 13]));
+        assert_eq!(7, vol[idx]);
+        assert_eq!([-3, 0, 12], <[i64; 3]>::from(idx));
+        assert_eq!(
+            "BoundingBox { min: (-4, 0, 0), max: (0, 1, 13) }",
+            vol.bounding_box().to_string()
+        );
+    }
+}
+
 #[cfg(test)]
 mod heap_volume {
     use crate::prelude::*;
@@ -210,6 +326,623 @@ mod heap_volume {
         assert_eq!(vol1[[9i32, 9, 9]], 20);
         assert_eq!(vol1[[9i32, 4, 9]], 20);
     }
+
+    #[test]
+    fn insertion_errors() {
+        use crate::types::{InsertError, InsertErrorDetails};
+
+        let mut vol = HeapVolume::new(0u8, BoundingBox::new_origin([4, 4, 4]));
+        let small = HeapVolume::new(1u8, BoundingBox::new([-1, -1, -1], [1, 1, 1]));
+
+        let error = vol.insert([1, 4, 0], &small).unwrap_err();
+        assert_eq!(
+            InsertError::VolumeEscapesBounds(Box::new(InsertErrorDetails {
+                destination: vol.bounding_box(),
+                inserted: small.bounding_box(),
+                offset: [1, 4, 0],
+                axis: Axis::Y,
+            })),
+            error
+        );
+        assert!(error.to_string().ends_with("along axis Y"));
+        assert!(matches!(
+            vol.insert([0, 2, 2], &small),
+            Err(InsertError::VolumeEscapesBounds(details)) if details.axis == Axis::X
+        ));
+
+        let huge = UniformVolume::new(1u8, BoundingBox::new_origin([1i64 << 40; 3]));
+        assert_eq!(
+            Err(InsertError::CapacityOverflow {
+                inserted: huge.bounding_box()
+            }),
+            vol.insert([0, 0, 0], &huge)
+        );
+        assert_eq!(
+            InsertError::OffsetOverflow {
+                inserted: small.bounding_box()
+            },
+            vol.insert([i64::MAX, 0, 0], &small).unwrap_err()
+        );
+        assert!(matches!(
+            vol.insert([u64::MAX, 0, 0], &small),
+            Err(InsertError::OffsetOverflow { .. })
+        ));
+        assert!(vol.iter().all(|&item| item == 0));
+    }
+
+    #[test]
+    fn converting_insertion() {
+        #[derive(Debug, Clone, PartialEq)]
+        enum Block {
+            Air,
+            Stone,
+            Unknown(u8),
+        }
+
+        impl From<u8> for Block {
+            fn from(id: u8) -> Self {
+                match id {
+                    0 => Block::Air,
+                    1 => Block::Stone,
+                    id => Block::Unknown(id),
+                }
+            }
+        }
+
+        let mut raw = HeapVolume::new(1u8, BoundingBox::new_origin([2, 2, 2]));
+        raw[[1, 1, 1]] = 9;
+        let mask = HeapVolume::new(true, BoundingBox::new_origin([1, 1, 1]));
+
+        let mut vol = HeapVolume::new(Block::Air, BoundingBox::new_origin([4, 4, 4]));
+        assert_eq!(8, vol.insert([1, 1, 1], &raw).unwrap().written);
+        assert_eq!(Block::Stone, vol[[1, 1, 1]]);
+        assert_eq!(Block::Unknown(9), vol[[2, 2, 2]]);
+        assert_eq!(Block::Air, vol[[0, 0, 0]]);
+
+        vol.insert_anyways([3, 3, 3], &raw);
+        assert_eq!(Block::Stone, vol[[3, 3, 3]]);
+        vol[[0, 0, 0]] = Block::Unknown(7);
+        assert_eq!(
+            1,
+            vol.copy_from_masked(&HeapVolume::new(0u8, mask.bounding_box()), &mask)
+        );
+        assert_eq!(Block::Air, vol[[0, 0, 0]]);
+    }
+
+    #[test]
+    fn heap_volume_clipped_insertion() {
+        use crate::types::InsertReport;
+
+        let mut vol = HeapVolume::new(0u8, BoundingBox::new_origin([4, 4, 4]));
+        let exact = HeapVolume::new(1u8, BoundingBox::new_origin([4, 4, 4]));
+        let report = vol.insert([0, 0, 0], &exact).unwrap();
+        assert_eq!(64, report.written);
+        assert_eq!(Some(vol.bounding_box()), report.region);
+
+        let overhang = HeapVolume::new(2u8, BoundingBox::new([-1, -1, -1], [2, 2, 2]));
+        assert!(vol.insert([3, 0, 0], &overhang).is_err());
+        assert_eq!(1, vol[[3, 0, 0]]);
+
+        let report = vol.insert_clipped([3, 0, 0], &overhang);
+        assert_eq!(Some(BoundingBox::new([2, 0, 0], [4, 2, 2])), report.region);
+        assert_eq!(8, report.written);
+        assert_eq!(2, vol[[2, 1, 1]]);
+        assert_eq!(1, vol[[1, 1, 1]]);
+
+        assert_eq!(
+            InsertReport::default(),
+            vol.insert_clipped([10, 0, 0], &overhang)
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "index (4, -1, 0) is out of bounds for a volume with bounds BoundingBox { min: (0, -1, 0), max: (4, 3, 4) }"
+    )]
+    fn out_of_bounds_index() {
+        let vol = HeapVolume::new(0u8, BoundingBox::new([0, -1, 0], [4, 3, 4]));
+        let _ = vol[[4, -1, 0]];
+    }
+
+    #[test]
+    #[should_panic(expected = "index cannot be converted to [i64; 3]")]
+    fn out_of_bounds_index_mut() {
+        let mut vol = StackVolume::<2, 2, 2, u8>::filled(0);
+        vol[[u64::MAX, 0, 0]] = 1;
+    }
+
+    #[test]
+    fn heap_volume_trim() {
+        let mut vol = HeapVolume::new(0u8, BoundingBox::new([-4, -4, -4], [4, 4, 4]));
+        assert_eq!(None, vol.tight_bounds(|&item| item == 0));
+        assert!(vol.trim(|&item| item == 0).is_none());
+
+        vol[[-2, 1, 0]] = 1;
+        vol[[1, -3, 2]] = 2;
+        let tight = BoundingBox::new([-2, -3, 0], [2, 2, 3]);
+        assert_eq!(Some(tight), vol.tight_bounds(|&item| item == 0));
+
+        let (bounds, trimmed) = vol.trim(|&item| item == 0).unwrap();
+        assert_eq!(tight, bounds);
+        assert_eq!(tight, trimmed.bounding_box());
+        assert_eq!(2, trimmed[[1, -3, 2]]);
+        assert!(tight.into_iter().all(|idx| trimmed[idx] == vol[idx]));
+    }
+
+    #[test]
+    fn fold_region() {
+        use std::ops::ControlFlow;
+
+        let bounds = BoundingBox::new([-2, 0, 1], [3, 4, 5]);
+        let mut vol = HeapVolume::new(0u32, bounds);
+        for (i, idx) in bounds.into_iter().enumerate() {
+            vol[idx] = i as u32;
+        }
+        let stack = StackVolume::<4, 4, 4, u32>::filled(1);
+
+        let region = BoundingBox::new([0, 1, 0], [9, 3, 3]);
+        let expected = region
+            .intersection(&bounds)
+            .unwrap()
+            .into_iter()
+            .map(|idx| vol[idx])
+            .sum::<u32>();
+        assert_eq!(
+            expected,
+            vol.fold_region(region, 0, |acc, &item| acc + item)
+        );
+        assert_eq!(
+            expected,
+            vol.slice(bounds)
+                .fold_region(region, 0, |acc, &item| acc + item)
+        );
+        assert_eq!(24, stack.fold_region(region, 0, |acc, &item| acc + item));
+        assert_eq!(
+            7,
+            vol.fold_region(BoundingBox::new_origin([0, 0, 0]), 7, |acc, _| acc + 1)
+        );
+
+        // Stops at the first item that isn't 0, which is the second one in storage order.
+        let mut visited = 0;
+        let all_zero = vol.try_fold_region(bounds, (), |(), &item| {
+            visited += 1;
+            match item {
+                0 => ControlFlow::Continue(()),
+                _ => ControlFlow::Break(item),
+            }
+        });
+        assert_eq!(ControlFlow::Break(vol[[-2, 0, 2]]), all_zero);
+        assert_eq!(2, visited);
+    }
+
+    #[test]
+    fn prefetch_region() {
+        let bounds = BoundingBox::new([-2, 0, 1], [3, 4, 5]);
+        let vol = HeapVolume::new(3u8, bounds);
+        let version = vol.version();
+
+        // Only a hint, so any range is fine, including ones that are empty or outside of the volume.
+        vol.prefetch_region(bounds);
+        vol.prefetch_region([0, 1, 4]..[9, 9, 9]);
+        vol.prefetch_region([0, 0, 3]..[2, 2, 3]);
+        vol.prefetch_region([50, 50, 50]..=[60, 60, 60]);
+        vol.prefetch_region(BoundingBox::new([i64::MIN; 3], [i64::MAX; 3]));
+
+        assert_eq!(version, vol.version());
+        assert!(vol.iter().all(|&item| item == 3));
+    }
+
+    #[test]
+    fn try_new() {
+        use crate::types::AllocationError;
+
+        let bounds = BoundingBox::new([-3, 0, 2], [4, 5, 6]);
+        assert_eq!(
+            Ok(HeapVolume::new(7u16, bounds)),
+            HeapVolume::try_new(7u16, bounds)
+        );
+        assert_eq!(
+            Ok(0),
+            HeapVolume::try_new(0u8, BoundingBox::new_origin([0, 4, 4])).map(|v| v.iter().count())
+        );
+
+        let huge = BoundingBox::new_origin([1 << 20, 1 << 20, 1 << 20]);
+        assert_eq!(
+            Err(AllocationError { bounds: huge }),
+            HeapVolume::try_new(0u64, huge)
+        );
+    }
+
+    /// Check that every accessor returns [`None`] instead of panicking for indices at the extremes of [`i64`].
+    fn check_extreme_indices<V: VolumeMut>(mut vol: V) {
+        for pos in [
+            [i64::MAX, 0, 0],
+            [0, i64::MIN, 0],
+            [0, 0, i64::MAX],
+            [i64::MIN; 3],
+            [i64::MAX; 3],
+        ] {
+            assert!(vol.get(pos).is_none(), "get({pos:?}) returned Some");
+            assert!(vol.get_mut(pos).is_none(), "get_mut({pos:?}) returned Some");
+            assert!(vol.ls_get(pos).is_none(), "ls_get({pos:?}) returned Some");
+            assert!(
+                vol.ls_get_mut(pos).is_none(),
+                "ls_get_mut({pos:?}) returned Some"
+            );
+        }
+    }
+
+    #[test]
+    fn extreme_indices() {
+        use crate::chunked::DynamicLayout;
+        use crate::meshed::MeshedVolume;
+        use crate::snapshot::CowVolume;
+        use crate::spaces::Local;
+        use crate::view::RebasedView;
+
+        let bounds = BoundingBox::new([-4, -4, -4], [4, 4, 4]);
+        let mut heap = HeapVolume::new(0u8, bounds);
+        check_extreme_indices(HeapVolume::new(0u8, bounds));
+        check_extreme_indices(heap.slice_mut(bounds));
+        check_extreme_indices(heap.view_local_mut(bounds));
+        check_extreme_indices(LayeredVolume::new(HeapVolume::new(0u8, bounds)));
+        check_extreme_indices(MeshedVolume::<_, ()>::new(
+            HeapVolume::new(0u8, bounds),
+            [4, 4, 4],
+        ));
+        check_extreme_indices(ScrollingVolume::new(0u8, [8, 8, 8], [-1, 0, 1]));
+        check_extreme_indices(CowVolume::new(0u8, bounds, [4, 4, 4]));
+        check_extreme_indices(StackVolume::<4, 4, 4, u8>::filled(0));
+        check_extreme_indices(RebasedView::new(HeapVolume::new(0u8, bounds), [-3, 0, 3]));
+        check_extreme_indices(HeapVolume::new(0u8, bounds).permute_axes([
+            Axis::Z,
+            Axis::X,
+            Axis::Y,
+        ]));
+        check_extreme_indices(HeapVolume::new(0u8, bounds).permute_axes([
+            -Axis::Z,
+            Axis::X.into(),
+            -Axis::Y,
+        ]));
+        check_extreme_indices({
+            let mut chunked = ChunkedVolume::new(0u8, DynamicLayout::new([4, 4, 4]));
+            chunked.set([-4, -4, -4], 1);
+            chunked
+        });
+
+        assert_eq!(None, Local([i64::MAX, 0, 0]).to_world(bounds));
+    }
+
+    #[test]
+    fn try_variants_refuse_extreme_indices() {
+        use crate::chunked::{ChunkLayout, DynamicLayout};
+        use crate::promote::{GrowthPolicy, PromotableVolume};
+        use crate::types::{EditBatch, InsertError};
+
+        let bounds = BoundingBox::new([-4, -4, -4], [4, 4, 4]);
+        let stamp = HeapVolume::new(1u8, BoundingBox::new_origin([2, 2, 2]));
+
+        let mut heap = HeapVolume::new(0u8, bounds);
+        let version = heap.version();
+        assert!(matches!(
+            heap.try_insert_clipped([i64::MAX, 0, 0], &stamp),
+            Err(InsertError::OffsetOverflow { .. })
+        ));
+        assert!(matches!(
+            heap.try_insert_clipped([u64::MAX, 0, 0], &stamp),
+            Err(InsertError::OffsetOverflow { .. })
+        ));
+        assert!(!heap.modified_since(version));
+        assert_eq!(
+            Ok(1),
+            heap.try_insert_clipped([3, 3, 3], &stamp)
+                .map(|r| r.written)
+        );
+
+        let version = heap.version();
+        assert!(!heap.try_rebase([i64::MIN, 0, 0]));
+        assert!(!heap.modified_since(version));
+        assert_eq!(bounds, heap.bounding_box());
+        assert!(heap.try_rebase([4, 4, 4]));
+        assert_eq!(BoundingBox::new([-8; 3], [0; 3]), heap.bounding_box());
+        heap.insert_anyways([i64::MAX, 0, 0], &stamp);
+
+        let layout = DynamicLayout::new([4, 4, 4]);
+        assert_eq!(None, layout.checked_chunk_bounds([i64::MAX / 2, 0, 0]));
+        let mut chunked = ChunkedVolume::new(0u8, layout);
+        assert_eq!(None, chunked.try_set([u64::MAX, 0, 0], 1));
+        assert_eq!(Some(0), chunked.try_set([-4, 0, 0], 1));
+        // The chunk itself fits, but the volume's bounds would have to span more than i64 along X.
+        assert_eq!(None, chunked.try_set([i64::MAX - 1, 0, 0], 1));
+        assert_eq!(1, chunked.len());
+        assert!(!chunked.try_rebase([2, 0, 0]));
+        assert!(!chunked.try_rebase([i64::MIN, 0, 0]));
+        assert!(chunked.try_rebase([-4, 0, 0]));
+        assert_eq!(1, chunked[[0, 0, 0]]);
+
+        let mut promotable = PromotableVolume::<2, 2, 2, u8>::new(0, GrowthPolicy::Doubling);
+        assert_eq!(None, promotable.try_set([i64::MAX, 0, 0], 1));
+        assert_eq!(None, promotable.try_set([0, i64::MIN, 0], 1));
+        assert!(!promotable.try_reserve(BoundingBox::new([i64::MIN, 0, 0], [0, 1, 1])));
+        assert!(!promotable.is_promoted());
+        assert_eq!(Some(0), promotable.try_set([3, 0, 0], 1));
+        assert!(promotable.is_promoted());
+
+        let mut edits = EditBatch::new();
+        assert!(!edits.try_push([u64::MAX, 0, 0], 1u8));
+        assert!(edits.try_push([1, 2, 3], 1u8));
+        assert_eq!(1, edits.len());
+    }
+
+    #[test]
+    fn schema() {
+        use crate::traits::SchemaName;
+        use crate::types::{SchemaMismatchError, VolumeSchema};
+
+        let bounds = BoundingBox::new([-2, 0, 0], [3, 4, 1]);
+        let schema = HeapVolume::new(0u32, bounds).schema();
+        assert_eq!(VolumeSchema::VERSION, schema.version);
+        assert_eq!(
+            ("u32", 4, 4, bounds),
+            (
+                schema.item_type.as_str(),
+                schema.item_size,
+                schema.item_align,
+                schema.bounds
+            )
+        );
+
+        // Same size, different type.
+        let floats = HeapVolume::new(0f32, bounds).schema();
+        assert!(matches!(
+            schema.check(&floats),
+            Err(SchemaMismatchError::ItemType { .. })
+        ));
+        // Neither the bounds nor the kind of volume have to match.
+        let expected = VolumeSchema::of::<u32>(BoundingBox::new_origin([1, 1, 1]));
+        assert_eq!(Ok(()), expected.check(&schema));
+        assert_eq!(
+            Ok(()),
+            expected.check(&UniformVolume::new(0u32, bounds).schema())
+        );
+
+        // Item types are told apart by the name they're registered with, which doesn't depend on the module they're in.
+        #[derive(Copy, Clone)]
+        struct Block;
+        impl SchemaName for Block {
+            const NAME: &'static str = "game::Block";
+        }
+        let blocks = HeapVolume::new(Block, bounds).schema();
+        assert_eq!("game::Block", blocks.item_type);
+        assert_eq!(Ok(()), VolumeSchema::of::<Block>(bounds).check(&blocks));
+        assert!(matches!(
+            blocks.check(&schema),
+            Err(SchemaMismatchError::ItemType { .. })
+        ));
+
+        let mut renamed = blocks.clone();
+        renamed.item_type = "u64".to_owned();
+        assert!(matches!(
+            VolumeSchema::of::<u64>(bounds).check(&renamed),
+            Err(SchemaMismatchError::ItemLayout {
+                expected: (8, 8),
+                found: (0, 1)
+            })
+        ));
+        assert!(matches!(
+            schema.check(&VolumeSchema {
+                version: 0,
+                ..schema.clone()
+            }),
+            Err(SchemaMismatchError::Version {
+                expected: 1,
+                found: 0
+            })
+        ));
+    }
+
+    #[test]
+    fn axis_fills() {
+        let bounds = BoundingBox::new([0, -4, 0], [3, 12, 2]);
+        let mut light = HeapVolume::new(0u8, bounds);
+
+        let mut calls = 0;
+        let set = light.fill_with_axis(Axis::Y, |y| {
+            calls += 1;
+            if y >= 8 {
+                15
+            } else {
+                0
+            }
+        });
+        assert_eq!(bounds.capacity() as usize, set);
+        assert_eq!(16, calls);
+        assert_eq!((15, 0), (light[[2, 8, 1]], light[[2, 7, 1]]));
+
+        light.fill_gradient(Axis::Y, 15, 0);
+        let column = (-4..12).map(|y| light[[1, y, 0]]).collect::<Vec<_>>();
+        assert_eq!((0..=15).rev().collect::<Vec<u8>>(), column);
+
+        let mut density = HeapVolume::new(0f32, BoundingBox::new_origin([5, 1, 1]));
+        density.fill_gradient(Axis::X, 0.0, 1.0);
+        assert_eq!(
+            vec![0.0, 0.25, 0.5, 0.75, 1.0],
+            density.iter().copied().collect::<Vec<_>>()
+        );
+
+        // Rounded to the nearest integer, and a single slab gets the starting value.
+        let mut steps = HeapVolume::new(0i32, BoundingBox::new_origin([1, 1, 4]));
+        steps.fill_gradient(Axis::Z, -1, 1);
+        assert_eq!(vec![-1, 0, 0, 1], steps.iter().copied().collect::<Vec<_>>());
+        steps.fill_gradient(Axis::X, 7, 9);
+        assert!(steps.iter().all(|&item| item == 7));
+    }
+
+    #[test]
+    fn scan_columns() {
+        let bounds = BoundingBox::new([0, -2, 0], [2, 4, 3]);
+        let mut vol = HeapVolume::new(0u8, bounds);
+        vol[[1, 1, 2]] = 1;
+
+        // Light falling down Y, blocked by the first opaque item it hits.
+        vol.scan_columns(Axis::Y, true, 15u8, |light, &opaque| match opaque {
+            0 => (light, light),
+            _ => (0, 0),
+        });
+        assert_eq!(15, vol[[1, 3, 2]]);
+        assert_eq!(15, vol[[1, 2, 2]]);
+        assert_eq!(0, vol[[1, 1, 2]]);
+        assert_eq!(0, vol[[1, -2, 2]]);
+        assert_eq!(15, vol[[1, -2, 1]]);
+
+        // Cumulative sums along Z.
+        let mut sums = HeapVolume::new(1u32, bounds);
+        sums.scan_columns(Axis::Z, false, 0, |acc, &item| (acc + item, acc + item));
+        assert_eq!(
+            vec![1, 2, 3],
+            (0..3).map(|z| sums[[1, 0, z]]).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn is_uniform() {
+        let mut vol = HeapVolume::new(3u8, BoundingBox::new([-1, -1, -1], [5, 2, 4]));
+        assert_eq!(Some(&3), vol.is_uniform());
+        assert_eq!(Some(&3), vol.slice(vol.bounding_box()).is_uniform());
+
+        vol[[4, 1, 3]] = 2;
+        assert_eq!(None, vol.is_uniform());
+        assert_eq!(None, vol.slice(vol.bounding_box()).is_uniform());
+        assert_eq!(Some(&3), vol.slice([-1, -1, -1]..[4, 2, 4]).is_uniform());
+
+        assert_eq!(
+            None,
+            HeapVolume::new(3u8, BoundingBox::new_origin([4, 0, 4])).is_uniform()
+        );
+        assert_eq!(
+            Some(&7),
+            Volume::is_uniform(&UniformVolume::new(7u8, BoundingBox::new_origin([2, 2, 2])))
+        );
+        assert_eq!(Some(&1), StackVolume::<2, 3, 4, u8>::filled(1).is_uniform());
+    }
+
+    #[test]
+    fn zip_iter() {
+        let before = HeapVolume::new(0u8, BoundingBox::new([0, 0, 0], [4, 4, 4]));
+        let mut after = HeapVolume::new(0u8, BoundingBox::new([2, -1, 1], [6, 3, 2]));
+        after[[3, 2, 1]] = 5;
+
+        let overlap = BoundingBox::new([2, 0, 1], [4, 3, 2]);
+        let zipped = before.zip_iter(&after).unwrap();
+        assert_eq!(overlap.capacity() as usize, zipped.len());
+        assert_eq!(
+            overlap.into_iter().collect::<Vec<_>>(),
+            zipped.map(|(idx, _, _)| idx).collect::<Vec<_>>()
+        );
+
+        let changed = before
+            .zip_iter(&after)
+            .unwrap()
+            .filter(|(_, a, b)| a != b)
+            .collect::<Vec<_>>();
+        assert_eq!(vec![([3, 2, 1], &0, &5)], changed);
+
+        let apart = HeapVolume::new(0u8, BoundingBox::new([4, 0, 0], [5, 1, 1]));
+        assert!(before.zip_iter(&apart).is_none());
+    }
+}
+
+#[cfg(test)]
+mod arithmetic {
+    use crate::prelude::*;
+
+    #[test]
+    fn volume_and_scalar_ops() {
+        let bounds = BoundingBox::new([-2, -2, -2], [2, 2, 2]);
+        let mut a = HeapVolume::new(0i32, bounds);
+        for [x, y, z] in bounds {
+            a[[x, y, z]] = (x + y + z) as i32;
+        }
+        let b = HeapVolume::new(3i32, bounds);
+
+        let sum = a.clone() + &b;
+        let diff = a.clone() - &b;
+        let product = a.clone() * &b;
+        for [x, y, z] in bounds {
+            let n = (x + y + z) as i32;
+            assert_eq!(sum[[x, y, z]], n + 3);
+            assert_eq!(diff[[x, y, z]], n - 3);
+            assert_eq!(product[[x, y, z]], n * 3);
+        }
+
+        let mut scaled = a.clone() * 2;
+        scaled -= 1;
+        scaled += &a;
+        assert_eq!(scaled[[1, 1, 1]], 3 * 2 - 1 + 3);
+
+        let mut stack = StackVolume::<2, 2, 2, f32>::filled(0.5);
+        stack *= 4.0;
+        stack += &StackVolume::filled(1.0);
+        assert!(stack.iter().all(|&n| n == 3.0));
+    }
+
+    #[test]
+    fn checked_ops() {
+        let a = HeapVolume::new(2u8, BoundingBox::new_origin([2, 2, 2]));
+        let b = HeapVolume::new(3u8, BoundingBox::new_origin([2, 2, 2]));
+        let c = HeapVolume::new(3u8, BoundingBox::new([1, 1, 1], [3, 3, 3]));
+
+        assert!(a.checked_mul(&b).unwrap().iter().all(|&n| n == 6));
+        assert!(b.checked_sub(&a).unwrap().iter().all(|&n| n == 1));
+
+        let err = a.checked_add(&c).unwrap_err();
+        assert_eq!(err.lhs, a.bounding_box());
+        assert_eq!(err.rhs, c.bounding_box());
+    }
+
+    #[test]
+    #[should_panic]
+    fn mismatched_bounds_panic() {
+        let mut a = HeapVolume::new(2u8, BoundingBox::new_origin([2, 2, 2]));
+        a += &HeapVolume::new(3u8, BoundingBox::new_origin([2, 2, 3]));
+    }
+}
+
+#[cfg(test)]
+mod masked {
+    use crate::prelude::*;
+
+    /// Mask over (2, 2, 2) -> (6, 6, 6) that is set where x is even.
+    fn even_x_mask() -> HeapVolume<bool> {
+        let mut mask = HeapVolume::new(false, BoundingBox::new([2, 2, 2], [6, 6, 6]));
+        for [x, y, z] in mask.bounding_box() {
+            mask[[x, y, z]] = x % 2 == 0;
+        }
+        mask
+    }
+
+    #[test]
+    fn masked_operations() {
+        let mask = even_x_mask();
+        let mut vol = HeapVolume::new(0u8, BoundingBox::new_origin([4, 4, 4]));
+
+        // only the overlapping (2, 2, 2) -> (4, 4, 4) region with x == 2 is affected
+        assert_eq!(vol.fill_masked(&mask, 1), 4);
+        assert_eq!(vol[[2, 2, 2]], 1);
+        assert_eq!(vol[[3, 2, 2]], 0);
+        assert_eq!(vol[[0, 0, 0]], 0);
+
+        assert_eq!(vol.apply_masked(&mask, |n| *n += 10), 4);
+        assert_eq!(vol[[2, 3, 3]], 11);
+
+        let src = HeapVolume::new(7u8, BoundingBox::new([0, 0, 0], [4, 4, 3]));
+        assert_eq!(vol.copy_from_masked(&src, &mask), 2);
+        assert_eq!(vol[[2, 2, 2]], 7);
+        assert_eq!(vol[[2, 2, 3]], 11);
+
+        let far_away = HeapVolume::new(true, BoundingBox::new([10, 10, 10], [12, 12, 12]));
+        assert_eq!(vol.fill_masked(&far_away, 0), 0);
+    }
 }
 
 #[cfg(test)]
@@ -258,6 +991,25 @@ mod stack_volume {
         assert_eq!(c, vol.bounding_box().capacity());
     }
 
+    #[test]
+    fn stack_volume_const_construction() {
+        const BOUNDS: BoundingBox = BoundingBox::const_new([4, -2, 0], [0, 2, 1]);
+        static MASK: StackVolume<2, 2, 1, bool> =
+            StackVolume::from_array([[[true], [false]], [[false], [true]]]);
+        static TABLE: StackVolume<4, 4, 4, u8> = StackVolume::filled(3);
+
+        assert_eq!(BOUNDS, BoundingBox::new([0, -2, 0], [4, 2, 1]));
+        assert_eq!(
+            BoundingBox::const_new_origin([1, 2, 3]),
+            BoundingBox::new_origin([1, 2, 3])
+        );
+        assert_eq!(StackVolume::<4, 4, 4, u8>::bounds(), TABLE.bounding_box());
+
+        assert!(MASK[[1, 1, 0]]);
+        assert!(!MASK[[0, 1, 0]]);
+        assert!(TABLE.iter().all(|&n| n == 3));
+    }
+
     #[test]
     fn stack_volume_access() {
         let mut vol = StackVolume::<10, 10, 10, i32>::filled(42);
@@ -277,18 +1029,3678 @@ mod stack_volume {
     }
 }
 
-#[cfg(feature = "nalgebra")]
-#[test]
-fn nalgebra_bounding_box_support() {
-    extern crate nalgebra as na;
+#[cfg(test)]
+mod uniform_volume {
+    use crate::prelude::*;
 
-    let v1 = na::vector![0, 0, 0];
-    let v2 = na::vector![10, 10, 10];
+    #[test]
+    fn upgrade_on_divergent_write() {
+        let bounds = BoundingBox::new([-8, 0, 0], [8, 16, 16]);
+        let mut vol = UniformVolume::new(0u16, bounds);
+        assert_eq!(0, vol.heap_size());
+        assert_eq!(Some(&0), vol.get([-8, 15, 15]));
+        assert_eq!(None, vol.get([8, 0, 0]));
+        assert_eq!(bounds.capacity() as usize, vol.iter().count());
 
-    let vol = HeapVolume::new(10, v1..v2);
+        assert_eq!(Some(0), vol.set([0, 0, 0], 0));
+        assert_eq!(None, vol.set([8, 0, 0], 5));
+        assert_eq!(None, vol.get_mut([8, 0, 0]));
+        assert!(vol.is_stored_uniform());
+        assert_eq!(Some(&0), vol.is_uniform());
 
-    assert_eq!(
-        vol.bounding_box(),
-        BoundingBox::new([0, 0, 0], [10, 10, 10])
-    );
+        assert_eq!(Some(0), vol.set([-1, 2, 3], 5));
+        assert!(!vol.is_stored_uniform());
+        assert_eq!(None, vol.is_uniform());
+        assert_eq!(5, vol[[-1, 2, 3]]);
+        assert!(vol.heap_size() > 0);
+
+        let mut expected = HeapVolume::new(0u16, bounds);
+        expected[[-1, 2, 3]] = 5;
+        assert_eq!(expected, vol.into_heap());
+
+        let mut vol = UniformVolume::new(1u8, BoundingBox::new_origin([2, 2, 2]));
+        vol.materialize();
+        assert!(vol.uniform_item().is_none());
+        assert_eq!(
+            vol,
+            UniformVolume::new(1u8, BoundingBox::new_origin([2, 2, 2]))
+        );
+    }
+}
+
+#[cfg(test)]
+mod slice_volume {
+    use crate::prelude::*;
+    use crate::types::SliceVolumeError;
+
+    #[test]
+    fn slice_volume_layouts() {
+        let data = (0..24).collect::<Vec<i32>>();
+
+        let vol = SliceVolume::new(&data, BoundingBox::new([-1, 0, 0], [3, 3, 2])).unwrap();
+        assert_eq!(vol.get([-1, 0, 0]), Some(&0));
+        assert_eq!(vol.get([0, 0, 0]), Some(&1));
+        assert_eq!(vol.get([-1, 1, 0]), Some(&4));
+        assert_eq!(vol.get([-1, 0, 1]), Some(&12));
+        assert_eq!(vol.get([3, 0, 0]), None);
+        assert!(vol.iter().copied().eq(0..24));
+
+        // the same buffer interpreted as a Z-major (C style [x][y][z]) array
+        let vol = SliceVolume::with_strides(&data, BoundingBox::new_origin([4, 3, 2]), [6, 2, 1])
+            .unwrap();
+        assert_eq!(vol[[0, 0, 1]], 1);
+        assert_eq!(vol[[1, 0, 0]], 6);
+        assert_eq!(vol[[3, 2, 1]], 23);
+
+        assert_eq!(
+            SliceVolume::new(&data, BoundingBox::new_origin([5, 5, 5])).unwrap_err(),
+            SliceVolumeError::BufferTooSmall {
+                required: 125,
+                len: 24
+            }
+        );
+    }
+
+    #[test]
+    fn slice_volume_mut_writes_through() {
+        let mut data = vec![0u8; 27];
+
+        {
+            let mut vol =
+                SliceVolumeMut::new(&mut data, BoundingBox::new_origin([3, 3, 3])).unwrap();
+            vol[[1, 1, 1]] = 5;
+            assert_eq!(vol.swap([2, 2, 2], 7), Some(0));
+
+            let heap = HeapVolume::new(9u8, BoundingBox::new_origin([1, 1, 1]));
+            vol.insert([0, 0, 0], &heap).unwrap();
+
+            assert_eq!(vol.as_slice_volume().get([1, 1, 1]), Some(&5));
+        }
+
+        assert_eq!(data[0], 9);
+        assert_eq!(data[13], 5);
+        assert_eq!(data[26], 7);
+    }
+}
+
+#[cfg(test)]
+mod bounding_box {
+    use crate::prelude::*;
+
+    #[test]
+    fn static_bounds() {
+        use crate::traits::Bounds;
+        use crate::types::StaticBounds;
+
+        type Chunk = StaticBounds<2, 3, 4>;
+
+        fn describe<B: Bounds>(bounds: B) -> ([i64; 3], i128, bool, BoundingBox) {
+            (
+                bounds.dimensions(),
+                bounds.capacity(),
+                bounds.contains([1, 2, 3]),
+                bounds.to_bounding_box(),
+            )
+        }
+
+        assert_eq!(24, Chunk::CAPACITY);
+        assert_eq!([12, 4, 1], Chunk::STRIDES);
+        assert_eq!(Some(23), Chunk::linear_index([1, 2, 3]));
+        assert_eq!(None, Chunk::linear_index([2, 0, 0]));
+        assert_eq!(Chunk::bounding_box(), BoundingBox::new_origin([2, 3, 4]));
+        assert_eq!(
+            describe(Chunk::new()),
+            describe(BoundingBox::new_origin([2, 3, 4]))
+        );
+        assert!(!Chunk::new().contains([0, 3, 0]));
+
+        let vol = HeapVolume::new(0u8, Chunk::new());
+        assert_eq!(vol.bounding_box(), Chunk::bounding_box());
+        assert!(Chunk::new().into_iter().eq(vol.iter_indices()));
+
+        // Stack volumes index their storage through their static bounds.
+        let mut stack = StackVolume::<2, 3, 4, u8>::filled(0);
+        assert_eq!(Chunk::new(), StackVolume::<2, 3, 4, u8>::static_bounds());
+        assert_eq!(Chunk::bounding_box(), stack.bounding_box());
+        stack[[1, 2, 3]] = 5;
+        assert_eq!(Some(&5), stack.ls_get([1, 2, 3]));
+        assert_eq!(None, stack.ls_get([0, 3, 0]));
+        assert_eq!(None, stack.get([-1, 0, 0]));
+        assert_eq!(5, <[[[u8; 4]; 3]; 2]>::from(stack)[1][2][3]);
+
+        // The capacity saturates instead of overflowing, with and without the default implementation.
+        #[derive(Copy, Clone)]
+        struct Huge;
+        impl From<Huge> for BoundingBox {
+            fn from(_: Huge) -> Self {
+                BoundingBox::new_origin([1i64 << 62; 3])
+            }
+        }
+        impl Bounds for Huge {
+            fn min(&self) -> [i64; 3] {
+                [0; 3]
+            }
+            fn max(&self) -> [i64; 3] {
+                [1 << 62; 3]
+            }
+        }
+
+        assert_eq!(i128::MAX, describe(Huge).1);
+        assert_eq!(i128::MAX, describe(BoundingBox::from(Huge)).1);
+    }
+
+    #[test]
+    fn parsing() {
+        use crate::types::{ParseBoundsError, ParseIdxError};
+
+        let bounds = BoundingBox::new([-8, 0, -8], [8, 256, 8]);
+        assert_eq!(Ok(bounds), "-8,0,-8..8,256,8".parse());
+        assert_eq!(Ok(bounds), " (-8, 0, -8) .. [8, 256, 8] ".parse());
+        assert_eq!(Ok(bounds), "8,256,8..-8,0,-8".parse());
+        assert_eq!(Ok(bounds), bounds.to_string().parse());
+        assert_eq!(
+            Ok(BoundingBox::new_origin([0, 0, 0])),
+            BoundingBox::new_origin([0, 0, 0]).to_string().parse()
+        );
+
+        assert_eq!(
+            Err(ParseBoundsError::MissingSeparator),
+            "0,0,0 16,16,16".parse::<BoundingBox>()
+        );
+        assert_eq!(
+            Err(ParseBoundsError::Corner(ParseIdxError::ComponentCount(2))),
+            "0,0..16,16,16".parse::<BoundingBox>()
+        );
+        assert_eq!(
+            Err(ParseBoundsError::TooLarge),
+            format!("{},0,0..{},1,1", i64::MIN, i64::MAX).parse::<BoundingBox>()
+        );
+        assert_eq!(
+            Some(BoundingBox::new_origin([3, 0, 2])),
+            BoundingBox::checked_new_origin([3u8, 0, 2])
+        );
+        assert_eq!(None, BoundingBox::checked_new_origin([3, -1, 2]));
+        assert_eq!(None, BoundingBox::checked_new_origin([0, u64::MAX, 0]));
+        let huge = BoundingBox::checked_new([i64::MIN + 1; 3], [0; 3]).unwrap();
+        assert_eq!(i128::MAX, huge.capacity());
+        assert!(format!("{},0,0..0,1,1", i64::MIN + 1)
+            .parse::<BoundingBox>()
+            .is_ok());
+    }
+
+    #[test]
+    fn minkowski_sum_and_sweep() {
+        let cell = BoundingBox::new([2, 2, 2], [3, 3, 3]);
+        let shape = BoundingBox::new([-1, 0, 0], [2, 3, 1]);
+        assert_eq!(
+            BoundingBox::new([1, 2, 2], [4, 5, 3]),
+            cell.minkowski_sum(&shape)
+        );
+
+        let area = BoundingBox::new([0, 0, 0], [4, 4, 4]);
+        let sum = area.minkowski_sum(&shape);
+        for a in area {
+            for b in shape {
+                assert!(sum.contains(crate::util::sum_ivec3(a, b)));
+            }
+        }
+        assert_eq!(BoundingBox::new([-1, 0, 0], [5, 6, 4]), sum);
+        assert!(area
+            .minkowski_sum(&BoundingBox::new_origin([0, 1, 1]))
+            .is_empty());
+
+        assert_eq!(
+            BoundingBox::new([-3, 0, 0], [4, 6, 4]),
+            area.expand_toward([-3, 2, 0])
+        );
+        assert_eq!(area, area.expand_toward([0, 0, 0]));
+    }
+
+    #[test]
+    fn iterator_random_access() {
+        let bb = BoundingBox::new([-3, 0, 2], [2, 4, 5]);
+        let all = bb.into_iter().collect::<Vec<_>>();
+
+        let mut iter = bb.into_iter();
+        assert_eq!(iter.len(), 5 * 4 * 3);
+
+        assert_eq!(iter.nth(7), Some(all[7]));
+        assert_eq!(iter.next_back(), all.last().copied());
+        assert_eq!(iter.nth_back(3), Some(all[all.len() - 5]));
+        assert_eq!(iter.len(), all.len() - 8 - 5);
+        assert_eq!(bb.into_iter().skip(23).collect::<Vec<_>>(), all[23..]);
+        assert_eq!(bb.into_iter().last(), Some([1, 3, 4]));
+        assert!(bb.into_iter().rev().eq(all.iter().rev().copied()));
+
+        let mut iter = bb.into_iter();
+        assert_eq!(iter.nth(1000), None);
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+
+        // boxes with a zero sized side contain nothing
+        assert_eq!(
+            BoundingBox::new([0, 0, 0], [0, 5, 5]).into_iter().count(),
+            0
+        );
+    }
+
+    #[test]
+    fn splitting() {
+        let bb = BoundingBox::new([0, 0, 0], [4, 5, 1]);
+
+        let parts = bb.split_at([1, 2, 0]);
+        assert_eq!(
+            parts,
+            vec![
+                BoundingBox::new([0, 0, 0], [1, 2, 1]),
+                BoundingBox::new([1, 0, 0], [4, 2, 1]),
+                BoundingBox::new([0, 2, 0], [1, 5, 1]),
+                BoundingBox::new([1, 2, 0], [4, 5, 1]),
+            ]
+        );
+        assert_eq!(bb.split_at([9, -1, 0]), vec![bb]);
+
+        // odd Y length, Z isn't split at all
+        let octants = bb.octants();
+        assert_eq!(bb.center(), [2, 2, 0]);
+        assert_eq!(octants.len(), 4);
+        assert_eq!(octants[3], BoundingBox::new([2, 2, 0], [4, 5, 1]));
+
+        let parts = BoundingBox::new([-5, 0, 0], [2, 3, 1]).subdivide(3);
+        assert_eq!(parts.len(), 3 * 3);
+        assert_eq!(parts[0], BoundingBox::new([-5, 0, 0], [-2, 1, 1]));
+        assert_eq!(parts[1], BoundingBox::new([-2, 0, 0], [0, 1, 1]));
+        assert_eq!(parts[2], BoundingBox::new([0, 0, 0], [2, 1, 1]));
+
+        for parts in [bb.octants(), bb.subdivide(2), bb.subdivide(7)] {
+            assert_eq!(
+                parts.iter().map(|p| p.capacity()).sum::<i128>(),
+                bb.capacity()
+            );
+            for (i, a) in parts.iter().enumerate() {
+                assert!(parts[i + 1..].iter().all(|b| !a.overlaps(b)));
+            }
+        }
+    }
+
+    #[test]
+    fn chunked_iteration() {
+        let bb = BoundingBox::new([-4, 0, 0], [6, 8, 3]);
+        let chunks = bb.iter_chunked(4).collect::<Vec<_>>();
+
+        assert_eq!(chunks.len(), 6);
+        assert_eq!(bb.iter_chunked(4).len(), chunks.len());
+        assert_eq!(chunks[0], BoundingBox::new([-4, 0, 0], [0, 4, 3]));
+        assert_eq!(chunks[2], BoundingBox::new([4, 0, 0], [6, 4, 3]));
+
+        let mut covered = chunks
+            .iter()
+            .flat_map(|c| c.into_iter())
+            .collect::<Vec<_>>();
+        covered.sort();
+        let mut expected = bb.into_iter().collect::<Vec<_>>();
+        expected.sort();
+        assert_eq!(covered, expected);
+    }
+}
+
+#[cfg(test)]
+mod ordering {
+    use crate::prelude::*;
+    use crate::types::Order;
+
+    #[test]
+    fn axis_orders() {
+        let bb = BoundingBox::new([1, 1, 1], [3, 4, 2]);
+
+        assert!(bb.iter_order(Order::Xyz).eq(bb.into_iter()));
+
+        let yxz = bb.iter_order(Order::Yxz).collect::<Vec<_>>();
+        assert_eq!(&yxz[..4], &[[1, 1, 1], [1, 2, 1], [1, 3, 1], [2, 1, 1]]);
+        assert_eq!(yxz.len(), 6);
+
+        let zyx = bb.iter_order(Order::Zyx).collect::<Vec<_>>();
+        assert_eq!(&zyx[..3], &[[1, 1, 1], [1, 2, 1], [1, 3, 1]]);
+        assert_eq!(zyx.last(), Some(&[2, 3, 1]));
+    }
+
+    #[test]
+    fn morton_order() {
+        let bb = BoundingBox::new_origin([2, 2, 2]);
+        let morton = bb.iter_order(Order::Morton).collect::<Vec<_>>();
+        assert_eq!(&morton[..4], &[[0, 0, 0], [1, 0, 0], [0, 1, 0], [1, 1, 0]]);
+
+        // every order visits every position exactly once, including for odd and elongated boxes
+        let bb = BoundingBox::new([-3, 2, 0], [4, 3, 19]);
+        let mut expected = bb.into_iter().collect::<Vec<_>>();
+        expected.sort();
+
+        for order in [Order::Xzy, Order::Yzx, Order::Zxy, Order::Morton] {
+            let mut visited = bb.iter_order(order).collect::<Vec<_>>();
+            visited.sort();
+            assert_eq!(visited, expected);
+        }
+    }
+
+    #[test]
+    fn volume_ordered_iteration() {
+        let mut vol = HeapVolume::new(0, BoundingBox::new_origin([2, 3, 1]));
+        for (i, idx) in vol.bounding_box().into_iter().enumerate() {
+            vol[idx] = i;
+        }
+
+        let yxz = vol.iter_ordered(Order::Yxz).copied().collect::<Vec<_>>();
+        assert_eq!(yxz, vec![0, 2, 4, 1, 3, 5]);
+    }
+
+    #[test]
+    fn flat_buffers() {
+        use crate::types::LengthMismatchError;
+
+        let bounds = BoundingBox::new([-1, 2, 0], [1, 5, 4]);
+        let mut vol = HeapVolume::new(0, bounds);
+        for (i, idx) in bounds.into_iter().enumerate() {
+            vol[idx] = i;
+        }
+
+        // Laid out like a C-ordered numpy array of shape (2, 3, 4), so Z changes fastest.
+        let zyx = vol.to_vec_in_order(Order::Zyx);
+        assert_eq!(&zyx[..5], &[0, 6, 12, 18, 2]);
+
+        for order in [Order::Xyz, Order::Yzx, Order::Zyx, Order::Morton] {
+            let flat = vol.to_vec_in_order(order);
+            assert_eq!(
+                Ok(&vol),
+                HeapVolume::from_vec_in_order(bounds, order, flat).as_ref()
+            );
+        }
+
+        assert_eq!(
+            Err(LengthMismatchError {
+                expected: 24,
+                found: 23
+            }),
+            HeapVolume::from_vec_in_order(bounds, Order::Xyz, vec![0; 23])
+        );
+
+        let empty = HeapVolume::<u8>::from_vec_in_order(
+            BoundingBox::new_origin([3, 0, 1]),
+            Order::Xyz,
+            vec![],
+        )
+        .unwrap();
+        assert_eq!(Ok(()), empty.validate());
+    }
+}
+
+#[cfg(test)]
+mod chunked {
+    use crate::chunked::{ChunkLayout, ChunkedVolume, Cubic16, DynamicLayout, Slab16x256};
+    use crate::prelude::*;
+
+    #[test]
+    fn layouts() {
+        assert_eq!([-1, 0, 2], Cubic16::new().chunk_of([-1, 15, 32]));
+        assert_eq!(
+            BoundingBox::new([-16, 0, 16], [0, 256, 32]),
+            Slab16x256::new().chunk_bounds([-1, 0, 1])
+        );
+
+        let layout = DynamicLayout::new([4, 2, 8]);
+        assert_eq!(
+            vec![[-1, 0, 0], [0, 0, 0], [-1, 1, 0], [0, 1, 0]],
+            layout
+                .chunks_overlapping(BoundingBox::new([-1, 1, 0], [1, 3, 8]))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            0,
+            layout
+                .chunks_overlapping(BoundingBox::new_origin([0, 4, 4]))
+                .count()
+        );
+    }
+
+    #[test]
+    fn chunked_volume() {
+        let mut vol = ChunkedVolume::new(0u8, DynamicLayout::new([4, 4, 4]));
+        assert!(vol.bounding_box().is_empty());
+        assert_eq!(None, vol.get([0, 0, 0]));
+
+        assert_eq!(0, vol.set([-1, 0, 0], 3));
+        assert_eq!(0, vol.set([5, 1, 0], 7));
+        assert_eq!(2, vol.len());
+        assert_eq!(BoundingBox::new([-4, 0, 0], [8, 4, 4]), vol.bounding_box());
+
+        // The chunk in the middle isn't allocated, but reads as the default and is allocated when written to.
+        assert_eq!(Some(&0), vol.get([2, 2, 2]));
+        vol[[2, 2, 2]] = 1;
+        assert_eq!(3, vol.len());
+        assert_eq!(3, vol[[-1, 0, 0]]);
+        assert_eq!(Some(&7), vol.ls_get([9, 1, 0]));
+        assert_eq!(11, vol.iter().map(|&i| i as u32).sum::<u32>());
+
+        let chunks = vol.iter_chunks().map(|(pos, _)| pos).collect::<Vec<_>>();
+        assert_eq!(vec![[-1, 0, 0], [0, 0, 0], [1, 0, 0]], chunks);
+
+        vol.remove_chunk([-1, 0, 0]);
+        assert_eq!(BoundingBox::new([0, 0, 0], [8, 4, 4]), vol.bounding_box());
+        assert_eq!(None, vol.get([-1, 0, 0]));
+    }
+
+    #[test]
+    fn clear_and_prune() {
+        let mut vol = ChunkedVolume::new(0u8, DynamicLayout::new([4, 4, 4]));
+        for x in [-3, 1, 5, 9] {
+            vol.set([x, 1, 1], 1);
+        }
+        vol.set([6, 3, 3], 2);
+        assert_eq!(4, vol.len());
+        let chunk_bytes = vol.heap_size() / 4;
+
+        // Fully covers the chunks at -1 and 0, and partially covers the chunk at 1 without emptying it.
+        assert_eq!(
+            2 * chunk_bytes,
+            vol.clear_region(BoundingBox::new([-8, 0, 0], [6, 4, 4]))
+        );
+        assert_eq!(2 * chunk_bytes, vol.heap_size());
+        assert_eq!(Some(&0), vol.get([5, 1, 1]));
+        assert_eq!(Some(&2), vol.get([6, 3, 3]));
+        assert_eq!(BoundingBox::new([4, 0, 0], [12, 4, 4]), vol.bounding_box());
+        assert_eq!(
+            0,
+            vol.clear_region(BoundingBox::new([100, 0, 0], [104, 4, 4]))
+        );
+
+        // Partially covering a chunk can empty it too.
+        vol.set([6, 3, 3], 2);
+        assert_eq!(
+            chunk_bytes,
+            vol.clear_region(BoundingBox::new([6, 3, 3], [7, 4, 4]))
+        );
+
+        vol.set([4, 0, 0], 0);
+        assert_eq!(chunk_bytes, vol.prune(|&item| item == 0));
+        assert_eq!(
+            vec![[2, 0, 0]],
+            vol.iter_chunks().map(|(pos, _)| pos).collect::<Vec<_>>()
+        );
+        assert_eq!(chunk_bytes, vol.prune(|&item| item <= 1));
+        assert!(vol.is_empty());
+        assert_eq!(BoundingBox::new_origin([0, 0, 0]), vol.bounding_box());
+    }
+}
+
+#[cfg(test)]
+mod compact {
+    use crate::compact::{CompactVolume, PaletteVolume, RleVolume};
+    use crate::prelude::*;
+
+    #[test]
+    fn representations() {
+        let bounds = BoundingBox::new([-4, 0, 2], [12, 8, 10]);
+        let mut vol = HeapVolume::new(0u32, bounds);
+        for idx in bounds {
+            vol[idx] = (idx[0] * 7 + idx[1] * 3 + idx[2]).rem_euclid(5) as u32;
+        }
+
+        let rle = RleVolume::encode(&vol);
+        let palette = PaletteVolume::encode(&vol, 16).unwrap();
+        assert!(PaletteVolume::encode(&vol, 4).is_none());
+        assert_eq!(5, palette.palette().len());
+
+        for idx in bounds {
+            assert_eq!(vol.get(idx), rle.get(idx));
+            assert_eq!(vol.get(idx), palette.get(idx));
+        }
+        assert_eq!(None, palette.get([12, 0, 2]));
+        assert!(rle.validate().is_ok());
+        assert!(palette.validate().is_ok());
+    }
+
+    #[test]
+    fn compression() {
+        let bounds = BoundingBox::new_origin([16, 16, 16]);
+
+        let uniform = HeapVolume::new(1u64, bounds).compress();
+        assert!(matches!(&uniform, CompactVolume::Uniform(vol) if vol.uniform_item() == Some(&1)));
+        assert_eq!(0, uniform.heap_size());
+        assert_eq!(Some(&1), uniform.get([15, 15, 15]));
+
+        // Two layers along Z, which are only 2 long runs in X-major order.
+        let mut layered = HeapVolume::new(1u64, bounds);
+        for idx in BoundingBox::new([0, 0, 8], [16, 16, 16]) {
+            layered[idx] = 2;
+        }
+        let compressed = layered.clone().compress();
+        assert!(matches!(compressed, CompactVolume::Rle(_)));
+        assert_eq!(layered, compressed.clone().decompress());
+
+        // Few distinct items in short runs.
+        let mut noisy = HeapVolume::new(0u64, bounds);
+        for (i, idx) in bounds.into_iter().enumerate() {
+            noisy[idx] = (i * 2654435761 % 7) as u64;
+        }
+        let compressed = noisy.clone().compress();
+        assert!(matches!(compressed, CompactVolume::Palette(_)));
+        assert!(compressed.heap_size() < noisy.heap_size());
+        assert_eq!(noisy, compressed.decompress());
+
+        // Every item is distinct.
+        let mut unique = HeapVolume::new(0u64, bounds);
+        for (i, idx) in bounds.into_iter().enumerate() {
+            unique[idx] = (i * 2654435761 % 4099) as u64;
+        }
+        assert!(matches!(unique.compress(), CompactVolume::Dense(_)));
+    }
+}
+
+#[cfg(test)]
+mod sequence {
+    use crate::prelude::*;
+    use crate::sequence::VolumeSequence;
+
+    #[test]
+    fn frames_and_deltas() {
+        let bounds = BoundingBox::new([-2, 0, 0], [2, 4, 4]);
+        let mut frames = vec![HeapVolume::new(0u8, bounds)];
+        for i in 1..7u8 {
+            let mut frame = frames.last().unwrap().clone();
+            frame[[-2 + (i as i64 % 4), 1, 2]] = i;
+            frames.push(frame);
+        }
+
+        let mut seq = VolumeSequence::new(frames[0].clone(), 3);
+        for frame in &frames[1..] {
+            seq.push(frame.clone()).unwrap();
+        }
+        assert!(seq
+            .push(HeapVolume::new(0, BoundingBox::new_origin([4, 4, 4])))
+            .is_err());
+
+        assert_eq!(7, seq.len());
+        for (i, frame) in frames.iter().enumerate() {
+            assert_eq!(Some(frame), seq.frame(i).as_ref());
+            for idx in bounds {
+                assert_eq!(frame.get(idx), seq.get(i, idx));
+            }
+        }
+        assert_eq!(None, seq.frame(7));
+        assert!(seq.heap_size() < frames.iter().map(|f| f.heap_size()).sum());
+    }
+
+    #[test]
+    fn interpolation() {
+        let bounds = BoundingBox::new_origin([2, 1, 1]);
+        let mut seq = VolumeSequence::new(HeapVolume::new(0i32, bounds), 4);
+        seq.push(HeapVolume::new(10, bounds)).unwrap();
+        seq.push(HeapVolume::new(-10, bounds)).unwrap();
+
+        assert_eq!(Some(2.5), seq.sample(0.25, [1, 0, 0]));
+        assert_eq!(Some(10.0), seq.sample(1.0, [0, 0, 0]));
+        assert_eq!(Some(0.0), seq.sample(1.5, [0, 0, 0]));
+        assert_eq!(None, seq.sample(2.5, [0, 0, 0]));
+        assert_eq!(None, seq.sample(0.5, [2, 0, 0]));
+
+        let vol = seq.interpolate(1.75).unwrap();
+        assert_eq!(vec![-5.0, -5.0], vol.iter().copied().collect::<Vec<_>>());
+    }
+}
+
+#[cfg(test)]
+mod stencil {
+    use crate::prelude::*;
+    use crate::stencil::{self, BoundaryCondition, Neighborhood};
+
+    #[test]
+    fn boundary_conditions() {
+        let mut vol = HeapVolume::new(0i32, BoundingBox::new([-1, 0, 0], [3, 1, 1]));
+        for (i, x) in (-1..3).enumerate() {
+            vol[[x, 0, 0]] = i as i32 + 1;
+        }
+
+        // Sum of the two X neighbors.
+        let sum_x = |n: &Neighborhood<i32, 3>| n.get([-1, 0, 0]) + n.get([1, 0, 0]);
+        let row = |out: HeapVolume<i32>| out.iter().copied().collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![2, 4, 6, 3],
+            row(stencil::apply(&vol, BoundaryCondition::Dirichlet(0), sum_x))
+        );
+        assert_eq!(
+            vec![3, 4, 6, 7],
+            row(stencil::apply(&vol, BoundaryCondition::Neumann, sum_x))
+        );
+        assert_eq!(
+            vec![6, 4, 6, 4],
+            row(stencil::apply(&vol, BoundaryCondition::Periodic, sum_x))
+        );
+    }
+
+    #[test]
+    fn neighborhoods() {
+        let mut vol = HeapVolume::new(0u32, BoundingBox::new_origin([3, 3, 3]));
+        for idx in vol.bounding_box() {
+            vol[idx] = (idx[0] * 9 + idx[1] * 3 + idx[2]) as u32;
+        }
+
+        let out = stencil::apply(
+            &vol,
+            BoundaryCondition::Neumann,
+            |n: &Neighborhood<u32, 5>| {
+                assert_eq!(125, n.iter().count());
+                *n.center() + *n.get([2, 0, -2])
+            },
+        );
+        // [1, 1, 1] + [3 -> 2, 1, -1 -> 0]
+        assert_eq!(13 + 21, out[[1, 1, 1]]);
+
+        let laplacian = stencil::apply(
+            &vol,
+            BoundaryCondition::Periodic,
+            |n: &Neighborhood<u32, 3>| n.face_neighbors().into_iter().sum::<u32>(),
+        );
+        assert_eq!(6 * 13, laplacian[[1, 1, 1]]);
+        assert_eq!(vol.bounding_box(), laplacian.bounding_box());
+    }
+}
+
+#[cfg(test)]
+mod collide {
+    use crate::collide::{sweep_aabb, Aabb};
+    use crate::prelude::*;
+
+    /// A floor at y = 0 and a wall at x = 5.
+    fn world() -> HeapVolume<bool> {
+        let mut vol = HeapVolume::new(false, BoundingBox::new([-8, -2, -8], [8, 8, 8]));
+        for idx in vol.bounding_box() {
+            if idx[1] == 0 || idx[0] == 5 {
+                vol[idx] = true;
+            }
+        }
+        vol
+    }
+
+    #[test]
+    fn sweep() {
+        let vol = world();
+        let player = Aabb::new([0.2, 1.0, 0.2], [0.8, 2.8, 0.8]);
+
+        // sliding along the floor doesn't hit it
+        let slide = sweep_aabb(&vol, player, [3.0, 0.0, 2.0], |&s| s);
+        assert!(!slide.hit());
+        assert_eq!(1.0, slide.time);
+
+        // falling onto the floor from above
+        let fall = sweep_aabb(
+            &vol,
+            player.translated([0.0, 2.0, 0.0]),
+            [0.0, -4.0, 0.0],
+            |&s| s,
+        );
+        assert!(fall.hit());
+        assert_eq!(0.5, fall.time);
+        assert_eq!([0.0, 1.0, 0.0], fall.normal);
+        assert_eq!(0, fall.cell.unwrap()[1]);
+
+        // walking into the wall
+        let walk = sweep_aabb(&vol, player, [8.0, 0.0, 0.0], |&s| s);
+        assert!(walk.hit());
+        assert!((walk.time - 4.2 / 8.0).abs() < 1e-12);
+        assert_eq!([-1.0, 0.0, 0.0], walk.normal);
+        assert_eq!(5, walk.cell.unwrap()[0]);
+
+        // touching the wall and moving away from it
+        let away = sweep_aabb(
+            &vol,
+            Aabb::new([4.0, 1.0, 0.0], [5.0, 2.0, 1.0]),
+            [-1.0, 0.0, 0.0],
+            |&s| s,
+        );
+        assert!(!away.hit());
+
+        // diagonally into the corner between the floor and the wall hits the floor first
+        let diagonal = sweep_aabb(
+            &vol,
+            player.translated([3.0, 0.5, 0.0]),
+            [2.0, -1.0, 0.0],
+            |&s| s,
+        );
+        assert_eq!([0.0, 1.0, 0.0], diagonal.normal);
+        assert_eq!(0.5, diagonal.time);
+    }
+}
+
+#[cfg(test)]
+mod visibility {
+    use crate::prelude::*;
+    use crate::visibility::{line_of_sight, traverse_line, visible_cells_from};
+
+    #[test]
+    fn line_traversal() {
+        let mut cells = Vec::new();
+        traverse_line([0, 0, 0], [3, 0, 0], |pos| {
+            cells.push(pos);
+            true
+        });
+        assert_eq!(vec![[1, 0, 0], [2, 0, 0]], cells);
+
+        // exact diagonals step through the corners
+        cells.clear();
+        traverse_line([0, 0, 0], [3, 3, 3], |pos| {
+            cells.push(pos);
+            true
+        });
+        assert_eq!(vec![[1, 1, 1], [2, 2, 2]], cells);
+
+        for (a, b) in [
+            ([0, 0, 0], [5, 2, -3]),
+            ([-4, 7, 1], [3, -2, 2]),
+            ([1, 1, 1], [1, 1, 1]),
+        ] {
+            let mut forward = Vec::new();
+            let mut backward = Vec::new();
+            traverse_line(a, b, |pos| {
+                forward.push(pos);
+                true
+            });
+            traverse_line(b, a, |pos| {
+                backward.push(pos);
+                true
+            });
+            backward.reverse();
+            assert_eq!(forward, backward);
+
+            // consecutive cells are always neighbors
+            let mut path = vec![a];
+            path.extend(forward);
+            path.push(b);
+            for pair in path.windows(2) {
+                assert!((0..3).all(|i| (pair[0][i] - pair[1][i]).abs() <= 1));
+            }
+        }
+    }
+
+    #[test]
+    fn occlusion() {
+        let mut vol = HeapVolume::new(false, BoundingBox::new([-8, -8, -8], [8, 8, 8]));
+        // a wall at x = 2 with a hole at y = 3
+        for idx in vol.bounding_box() {
+            if idx[0] == 2 && idx[1] != 3 {
+                vol[idx] = true;
+            }
+        }
+
+        assert!(line_of_sight(&vol, [0, 0, 0], [1, 5, 0], |&o| o));
+        assert!(!line_of_sight(&vol, [0, 0, 0], [4, 0, 0], |&o| o));
+        assert!(line_of_sight(&vol, [0, 3, 0], [6, 3, 0], |&o| o));
+        // the wall itself is visible
+        assert!(line_of_sight(&vol, [0, 0, 0], [2, 0, 0], |&o| o));
+
+        let visible = visible_cells_from(&vol, [0, 0, 0], 5.0, |&o| o);
+        assert!(visible.contains(&[2, 0, 0]));
+        assert!(!visible.contains(&[3, 0, 0]));
+        assert!(visible.contains(&[-4, 0, 0]));
+        assert!(!visible.contains(&[0, 6, 0]));
+    }
+}
+
+#[cfg(test)]
+mod generate {
+    use crate::cache::CacheCapacity;
+    use crate::generate::{per_position, VolumeGenerator};
+    use crate::prelude::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn pipeline() {
+        // flat terrain: stone below y = 0, air above
+        let terrain = per_position(|[_, y, _]| if y < 0 { 1u8 } else { 0 });
+        let pipeline = terrain
+            .overlay(per_position(|[x, y, z]| {
+                (x == 0 && z == 0 && (0..3).contains(&y)).then_some(2u8)
+            }))
+            .then(|vol: &mut HeapVolume<u8>| {
+                let bounds = vol.bounding_box();
+                if bounds.contains([5, -1, 5]) {
+                    vol[[5, -1, 5]] = 0;
+                }
+            });
+
+        let bounds = BoundingBox::new([-8, -8, -8], [8, 8, 8]);
+        let vol = pipeline.generate(bounds);
+        assert_eq!(1, vol[[3, -3, 3]]);
+        assert_eq!(0, vol[[3, 3, 3]]);
+        assert_eq!(2, vol[[0, 2, 0]]);
+        assert_eq!(0, vol[[0, 3, 0]]);
+        assert_eq!(0, vol[[5, -1, 5]]);
+
+        // generating in pieces gives the same items
+        for piece in bounds.octants() {
+            let part = pipeline.generate(piece);
+            assert!(piece.into_iter().all(|idx| part[idx] == vol[idx]));
+        }
+    }
+
+    #[test]
+    fn caching() {
+        let calls = AtomicUsize::new(0);
+        let generator = |bounds: BoundingBox| {
+            calls.fetch_add(1, Ordering::Relaxed);
+            HeapVolume::new(7u16, bounds)
+        };
+        let cached = generator.cached(CacheCapacity::Count(4));
+
+        let a = BoundingBox::new_origin([4, 4, 4]);
+        let b = BoundingBox::new([4, 0, 0], [8, 4, 4]);
+        assert_eq!(cached.generate(a), cached.generate(a));
+        assert_eq!(1, calls.load(Ordering::Relaxed));
+        cached.generate(b);
+        assert_eq!(2, calls.load(Ordering::Relaxed));
+
+        cached.clear();
+        cached.generate(a);
+        assert_eq!(3, calls.load(Ordering::Relaxed));
+    }
+}
+
+#[cfg(test)]
+mod geometry {
+    use crate::geometry::Plane;
+    use crate::prelude::*;
+
+    #[test]
+    fn plane_intersection() {
+        let bb = BoundingBox::new([0, 0, 0], [4, 4, 4]);
+
+        // x = 2
+        assert!(bb.intersects_plane([1.0, 0.0, 0.0], -2.0));
+        // x = 4 touches the far face of the last cells
+        assert!(bb.intersects_plane([1.0, 0.0, 0.0], -4.0));
+        assert!(!bb.intersects_plane([1.0, 0.0, 0.0], -4.5));
+        // x + y + z = 11.5 cuts off the corner at (4, 4, 4)
+        assert!(bb.intersects_plane([1.0, 1.0, 1.0], -11.5));
+        assert!(!bb.intersects_plane([1.0, 1.0, 1.0], -12.5));
+    }
+
+    #[test]
+    fn frustum_culling() {
+        // axis aligned "frustum" covering 0 < x, y, z < 10
+        let planes = [
+            Plane::new([1.0, 0.0, 0.0], 0.0),
+            Plane::new([-1.0, 0.0, 0.0], 10.0),
+            Plane::new([0.0, 1.0, 0.0], 0.0),
+            Plane::new([0.0, -1.0, 0.0], 10.0),
+            Plane::new([0.0, 0.0, 1.0], 0.0),
+            Plane::new([0.0, 0.0, -1.0], 10.0),
+        ];
+
+        assert!(BoundingBox::new([2, 2, 2], [3, 3, 3]).intersects_frustum(&planes));
+        assert!(BoundingBox::new([-5, -5, -5], [1, 1, 1]).intersects_frustum(&planes));
+        assert!(BoundingBox::new([-50, -50, -50], [50, 50, 50]).intersects_frustum(&planes));
+        assert!(!BoundingBox::new([11, 2, 2], [12, 3, 3]).intersects_frustum(&planes));
+        assert!(!BoundingBox::new([-3, -3, 2], [-1, -1, 3]).intersects_frustum(&planes));
+    }
+
+    #[test]
+    fn sphere_iteration() {
+        let bb = BoundingBox::new([-10, -10, -10], [10, 10, 10]);
+
+        for (center, radius) in [
+            ([0.0, 0.0, 0.0], 3.0),
+            ([0.5, 0.5, 0.5], 2.0),
+            ([-8.3, 2.1, 9.7], 4.5),
+            ([1.0, 2.0, 3.0], 0.0),
+            ([0.0, 0.0, 0.0], 50.0),
+        ] {
+            let naive = bb
+                .into_iter()
+                .filter(|&[x, y, z]| {
+                    let d = [
+                        x as f64 + 0.5 - center[0],
+                        y as f64 + 0.5 - center[1],
+                        z as f64 + 0.5 - center[2],
+                    ];
+                    d[0] * d[0] + d[1] * d[1] + d[2] * d[2] <= radius * radius
+                })
+                .collect::<Vec<_>>();
+
+            assert_eq!(
+                naive,
+                bb.iter_within_sphere(center, radius).collect::<Vec<_>>()
+            );
+        }
+
+        assert_eq!(0, bb.iter_within_sphere([0.0; 3], -1.0).count());
+        assert_eq!(0, bb.iter_within_sphere([100.0, 0.0, 0.0], 5.0).count());
+
+        let mut vol = HeapVolume::new(0u8, bb);
+        vol[[2, 2, 2]] = 1;
+        let hits = vol
+            .iter_sphere([2.5, 2.5, 2.5], 1.0)
+            .filter(|(_, &item)| item == 1)
+            .map(|(idx, _)| idx)
+            .collect::<Vec<_>>();
+        assert_eq!(vec![[2, 2, 2]], hits);
+        assert_eq!(7, vol.iter_sphere([2.5, 2.5, 2.5], 1.0).count());
+    }
+}
+
+#[cfg(test)]
+mod queries {
+    use crate::prelude::*;
+
+    #[test]
+    fn nearest() {
+        let bb = BoundingBox::new([-6, -6, -6], [6, 6, 6]);
+        let mut vol = HeapVolume::new(false, bb);
+        for idx in [[5, 5, 5], [-3, 1, 2], [0, -6, 4], [2, 2, -5]] {
+            vol[idx] = true;
+        }
+
+        for from in [[0, 0, 0], [5, 5, 4], [-20, 0, 0], [3, -6, 3], [0, 40, 0]] {
+            let brute = vol
+                .iter_indices()
+                .filter(|&idx| vol[idx])
+                .map(|idx| (0..3).map(|a| (idx[a] - from[a]).pow(2)).sum::<i64>())
+                .min()
+                .unwrap();
+
+            let found = vol.nearest(from, 100, |&item| item).unwrap();
+            let found_distance = (0..3).map(|a| (found[a] - from[a]).pow(2)).sum::<i64>();
+            assert!(vol[found]);
+            assert_eq!(brute, found_distance);
+        }
+
+        assert_eq!(Some([5, 5, 5]), vol.nearest([5, 5, 5], 0, |&item| item));
+        assert_eq!(None, vol.nearest([0, 0, 0], 2, |&item| item));
+        assert_eq!(None, vol.nearest([0, 0, 0], 100, |_| false));
+    }
+
+    #[test]
+    fn content_hash() {
+        let bb = BoundingBox::new([-2, -2, -2], [2, 2, 2]);
+        let mut a = HeapVolume::new(0u16, bb);
+        let mut b = HeapVolume::new(0u16, bb);
+        assert_eq!(a.content_hash(), b.content_hash());
+
+        a[[1, 0, -1]] = 5;
+        assert_ne!(a.content_hash(), b.content_hash());
+        b[[1, 0, -1]] = 5;
+        assert_eq!(a.content_hash(), b.content_hash());
+
+        // the bounds are part of the hash
+        let shifted = HeapVolume::new(0u16, BoundingBox::new([0, 0, 0], [4, 4, 4]));
+        assert_ne!(
+            HeapVolume::new(0u16, bb).content_hash(),
+            shifted.content_hash()
+        );
+
+        // the hash doesn't depend on the volume type
+        let stack = StackVolume::<2, 2, 2, u16>::filled(3);
+        let heap = HeapVolume::new(3u16, BoundingBox::new_origin([2, 2, 2]));
+        assert_eq!(stack.content_hash(), heap.content_hash());
+
+        let region = BoundingBox::new([0, 0, -1], [2, 1, 1]);
+        let mut sub = HeapVolume::new(0u16, region);
+        sub[[1, 0, -1]] = 5;
+        assert_eq!(Some(sub.content_hash()), a.content_hash_region(region));
+        assert_eq!(
+            None,
+            a.content_hash_region(BoundingBox::new([0, 0, 0], [3, 3, 3]))
+        );
+
+        // pinned so that accidental changes to the hash function are caught
+        assert_eq!(
+            0x1d31a1f624d52f9f,
+            HeapVolume::new(1u8, BoundingBox::new_origin([1, 1, 1])).content_hash()
+        );
+    }
+
+    #[test]
+    fn validation() {
+        let vol = HeapVolume::new(0u8, BoundingBox::new([-1, 2, 3], [4, 4, 4]));
+        assert!(vol.validate().is_ok());
+        assert!(StackVolume::<2, 3, 4, u8>::filled(0).validate().is_ok());
+
+        let data = [0u8; 8];
+        let slice = SliceVolume::new(&data, BoundingBox::new_origin([2, 2, 2])).unwrap();
+        assert!(slice.validate().is_ok());
+    }
+
+    #[test]
+    fn heap_size() {
+        use std::mem::size_of;
+
+        let vol = HeapVolume::new(0u32, BoundingBox::new([-1, -1, -1], [3, 2, 1]));
+        let expected =
+            4 * size_of::<Box<[Box<[u32]>]>>() + 4 * 3 * size_of::<Box<[u32]>>() + 24 * 4;
+        assert_eq!(expected, vol.heap_size());
+
+        assert_eq!(0, StackVolume::<4, 4, 4, u32>::filled(0).heap_size());
+    }
+
+    #[test]
+    fn indexed_iteration() {
+        let bb = BoundingBox::new([-1, 0, 2], [2, 2, 4]);
+        let mut vol = HeapVolume::new(0i64, bb);
+        for idx in bb {
+            vol[idx] = idx[0] * 100 + idx[1] * 10 + idx[2];
+        }
+
+        let mut iter = vol.iter_indexed();
+        assert_eq!(12, iter.len());
+        assert_eq!(Some(([-1, 0, 2], &-98)), iter.next());
+        assert_eq!((11, Some(11)), iter.size_hint());
+        assert!(iter.all(|(idx, &item)| item == idx[0] * 100 + idx[1] * 10 + idx[2]));
+
+        let mut items = vol.iter();
+        assert_eq!(12, items.len());
+        assert_eq!(Some(&12), items.nth(4));
+        assert_eq!(7, items.len());
+        assert_eq!(7, items.by_ref().count());
+        assert_eq!(None, items.next());
+    }
+
+    #[test]
+    fn reverse_and_strided_iteration() {
+        let bb = BoundingBox::new([-2, 0, 0], [3, 3, 2]);
+        let mut vol = HeapVolume::new(0usize, bb);
+        for (i, idx) in bb.into_iter().enumerate() {
+            vol[idx] = i;
+        }
+
+        let forward = vol.iter().copied().collect::<Vec<_>>();
+        let mut backward = vol.iter().rev().copied().collect::<Vec<_>>();
+        backward.reverse();
+        assert_eq!(forward, backward);
+        assert_eq!(Some(([2, 2, 1], &29)), vol.iter_indexed().next_back());
+
+        let strided = bb.iter_strided([2, 2, 3]).collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                [-2, 0, 0],
+                [0, 0, 0],
+                [2, 0, 0],
+                [-2, 2, 0],
+                [0, 2, 0],
+                [2, 2, 0]
+            ],
+            strided
+        );
+        assert_eq!(6, bb.iter_strided([2, 2, 3]).len());
+        assert_eq!(Some([2, 2, 0]), bb.iter_strided([2, 2, 3]).next_back());
+        assert_eq!(bb.capacity() as usize, bb.iter_strided([1, 1, 1]).count());
+        assert_eq!(
+            vec![[0, 0, 0]],
+            BoundingBox::new_origin([4, 4, 4])
+                .iter_strided([usize::MAX; 3])
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            0,
+            BoundingBox::new_origin([4, 0, 4])
+                .iter_strided([usize::MAX; 3])
+                .count()
+        );
+
+        let items = vol
+            .iter_strided([4, 1, 1])
+            .rev()
+            .map(|(_, &i)| i)
+            .collect::<Vec<_>>();
+        assert_eq!(vec![29, 25, 24, 20, 19, 15, 14, 10, 9, 5, 4, 0], items);
+    }
+
+    #[test]
+    fn batch_get_set() {
+        let mut vol = HeapVolume::new(0u32, BoundingBox::new([-2, -2, -2], [2, 2, 2]));
+
+        let set = vol.set_many([
+            ([1, 1, 1], 1),
+            ([-2, 0, 1], 2),
+            ([5, 5, 5], 3),
+            ([1, 1, 1], 4),
+            ([0, -1, -2], 5),
+        ]);
+        assert_eq!(4, set);
+        assert_eq!(4, vol[[1, 1, 1]]);
+        assert_eq!(2, vol[[-2, 0, 1]]);
+        assert_eq!(5, vol[[0, -1, -2]]);
+
+        let items = vol.get_many(&[[0, -1, -2], [9, 0, 0], [1, 1, 1], [-2, 0, 1], [0, 0, 0]]);
+        assert_eq!(vec![Some(&5), None, Some(&4), Some(&2), Some(&0)], items);
+    }
+
+    #[test]
+    fn snapshot_and_edits() {
+        use crate::types::{EditBatch, EditOutOfBoundsError};
+
+        let bounds = BoundingBox::new_origin([6, 1, 1]);
+        let mut vol = HeapVolume::new(0u8, bounds);
+        vol[[2, 0, 0]] = 9;
+
+        // Spread every cell to its neighbors for one step, reading from a snapshot so the order of the writes doesn't matter.
+        let snapshot = vol.read_copy_region(bounds).unwrap();
+        let mut edits = EditBatch::new();
+        for pos in bounds.into_iter().rev() {
+            let spread = pos
+                .face_neighbors()
+                .filter_map(|n| snapshot.get(n))
+                .chain(snapshot.get(pos))
+                .max();
+            edits.push(pos, *spread.unwrap());
+        }
+        assert_eq!(Ok(6), vol.apply_edits(edits));
+        assert_eq!(
+            vec![0, 9, 9, 9, 0, 0],
+            vol.iter().copied().collect::<Vec<_>>()
+        );
+        assert_eq!(0, snapshot[[1, 0, 0]]);
+
+        let copy = vol
+            .read_copy_region(BoundingBox::new([4, -5, -5], [10, 5, 5]))
+            .unwrap();
+        assert_eq!(BoundingBox::new([4, 0, 0], [6, 1, 1]), copy.bounding_box());
+        assert!(vol
+            .read_copy_region(BoundingBox::new([6, 0, 0], [7, 1, 1]))
+            .is_none());
+
+        let edits = [([0, 0, 0], 1), ([6, 0, 0], 1)]
+            .into_iter()
+            .collect::<EditBatch<_>>();
+        assert_eq!(
+            Err(EditOutOfBoundsError {
+                idx: [6, 0, 0],
+                bounds
+            }),
+            vol.apply_edits(edits)
+        );
+        assert_eq!(0, vol[[0, 0, 0]]);
+    }
+}
+
+#[cfg(test)]
+mod cache {
+    use crate::cache::{CacheCapacity, VolumeCache};
+    use crate::prelude::*;
+    use std::sync::{Arc, Mutex};
+
+    fn chunk(item: u32) -> HeapVolume<u32> {
+        HeapVolume::new(item, BoundingBox::new_origin([4, 4, 4]))
+    }
+
+    #[test]
+    fn lru_eviction() {
+        let mut cache = VolumeCache::new(CacheCapacity::Count(2));
+        let evicted = Arc::new(Mutex::new(Vec::new()));
+        cache.on_evict({
+            let evicted = evicted.clone();
+            move |key, vol: HeapVolume<u32>| evicted.lock().unwrap().push((key, vol[[0, 0, 0]]))
+        });
+
+        cache.insert([0, 0, 0], chunk(0));
+        cache.insert([1, 0, 0], chunk(1));
+        assert!(cache.get(&[0, 0, 0]).is_some());
+        cache.insert([2, 0, 0], chunk(2));
+
+        // [1, 0, 0] was the least recently used
+        assert_eq!(vec![([1, 0, 0], 1)], *evicted.lock().unwrap());
+        assert!(!cache.contains_key(&[1, 0, 0]));
+        assert_eq!(2, cache.len());
+
+        // replacing and removing doesn't evict
+        assert_eq!(
+            Some(2),
+            cache.insert([2, 0, 0], chunk(3)).map(|v| v[[0, 0, 0]])
+        );
+        assert!(cache.remove(&[0, 0, 0]).is_some());
+        assert_eq!(1, evicted.lock().unwrap().len());
+
+        *cache
+            .get_or_insert_with([5, 0, 0], || chunk(5))
+            .get_mut([0, 0, 0])
+            .unwrap() += 1;
+        assert_eq!(6, cache.peek(&[5, 0, 0]).unwrap()[[0, 0, 0]]);
+
+        cache.flush();
+        assert!(cache.is_empty());
+        assert_eq!(
+            vec![([1, 0, 0], 1), ([2, 0, 0], 3), ([5, 0, 0], 6)],
+            *evicted.lock().unwrap()
+        );
+    }
+
+    #[test]
+    fn byte_capacity() {
+        let weight = VolumeCache::<u8, HeapVolume<u32>>::weight(&chunk(0));
+        let mut cache = VolumeCache::new(CacheCapacity::Bytes(weight * 3));
+
+        for key in 0..5u8 {
+            cache.insert(key, chunk(key as u32));
+        }
+        assert_eq!(3, cache.len());
+        assert_eq!(weight * 3, cache.resident_bytes());
+        assert!(!cache.contains_key(&1) && cache.contains_key(&2));
+
+        cache.set_capacity(CacheCapacity::Bytes(weight));
+        assert_eq!(1, cache.len());
+        assert!(cache.contains_key(&4));
+
+        // a single volume larger than the capacity is still kept when inserted
+        cache.insert(9, HeapVolume::new(0, BoundingBox::new_origin([8, 8, 8])));
+        assert_eq!(1, cache.len());
+        assert!(cache.contains_key(&9));
+    }
+
+    #[test]
+    fn weight_uses_heap_size() {
+        let heap = chunk(0);
+        assert_eq!(
+            std::mem::size_of::<HeapVolume<u32>>() + heap.heap_size(),
+            VolumeCache::<u8, HeapVolume<u32>>::weight(&heap)
+        );
+        assert!(VolumeCache::<u8, HeapVolume<u32>>::weight(&heap) >= 64 * 4);
+
+        // A huge uniform volume barely uses any memory, so it doesn't count as huge.
+        let uniform =
+            UniformVolume::new(0u32, BoundingBox::new_origin([1 << 20, 1 << 20, 1 << 20]));
+        assert_eq!(
+            std::mem::size_of::<UniformVolume<u32>>(),
+            VolumeCache::<u8, UniformVolume<u32>>::weight(&uniform)
+        );
+    }
+
+    #[test]
+    fn weak_handles_and_pins() {
+        let mut cache = VolumeCache::new(CacheCapacity::Count(2));
+        cache.insert(1, chunk(1));
+        cache.insert(2, chunk(2));
+
+        let weak = cache.downgrade(&1).unwrap();
+        let pin = cache.pin(&2).unwrap();
+        assert!(cache.downgrade(&3).is_none());
+        assert!(cache.is_pinned(&2));
+        assert_eq!(Some(&chunk(1)), cache.upgrade(&weak));
+
+        // 2 is the least recently used but pinned, so 1 is evicted instead.
+        cache.insert(3, chunk(3));
+        assert!(cache.upgrade(&weak).is_none());
+        assert!(cache.contains_key(&2));
+
+        // Pinned volumes stay even when over capacity, and handles don't resolve to replacements.
+        cache.insert(1, chunk(10));
+        assert_eq!(2, cache.len());
+        assert!(cache.upgrade(&weak).is_none());
+        assert_eq!(Some(&chunk(2)), cache.upgrade(pin.handle()));
+
+        let clone = pin.clone();
+        drop(pin);
+        assert!(cache.is_pinned(&2));
+        drop(clone);
+        assert!(!cache.is_pinned(&2));
+
+        cache.flush();
+        assert!(cache.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod multi {
+    use crate::multi::MultiVolume;
+    use crate::prelude::*;
+
+    const ID: usize = 0;
+    const LIGHT: usize = 1;
+
+    #[test]
+    fn channels() {
+        let bb = BoundingBox::new([-2, -2, -2], [2, 2, 2]);
+        let mut vol = MultiVolume::new((0u16, 15u8, 'p'), bb);
+
+        assert_eq!(Some((&0, &15, &'p')), vol.get_all([1, 1, 1]));
+        assert_eq!(None, vol.get_all([2, 1, 1]));
+
+        assert!(vol.set_all([1, 1, 1], (3, 4, 'd')));
+        assert!(!vol.set_all([1, 9, 1], (3, 4, 'd')));
+        {
+            let (id, light, _) = vol.get_all_mut([0, 0, 0]).unwrap();
+            *id = 9;
+            *light = 1;
+        }
+
+        assert_eq!(3, vol.channel::<ID>()[[1, 1, 1]]);
+        assert_eq!(1, vol.channel::<LIGHT>()[[0, 0, 0]]);
+        vol.channel_mut::<LIGHT>()[[-2, -2, -2]] = 0;
+        assert_eq!(Some((&0, &0, &'p')), vol.get_all([-2, -2, -2]));
+
+        // single channels work with everything that takes a volume
+        assert_eq!(
+            61,
+            vol.channel::<LIGHT>().iter().filter(|&&l| l == 15).count()
+        );
+        assert!(vol.validate().is_ok());
+        assert!(vol.heap_size() > 0);
+
+        let (ids, lights, biomes) = vol.into_channels();
+        assert!(
+            MultiVolume::<(u16, u8, char)>::from_channels((ids.clone(), lights, biomes)).is_ok()
+        );
+        let other = HeapVolume::new(0u8, BoundingBox::new_origin([4, 4, 4]));
+        assert!(MultiVolume::<(u16, u8)>::from_channels((ids, other)).is_err());
+    }
+}
+
+#[cfg(test)]
+mod soa {
+    use crate::prelude::*;
+
+    crate::soa_volume! {
+        #[derive(Debug, Copy, Clone, PartialEq)]
+        struct Voxel {
+            id: u16,
+            light: u8,
+            flags: u8,
+        }
+
+        struct VoxelVolume(VoxelRef, VoxelMut);
+    }
+
+    #[test]
+    fn struct_of_arrays() {
+        let air = Voxel {
+            id: 0,
+            light: 15,
+            flags: 0,
+        };
+        let stone = Voxel {
+            id: 1,
+            light: 0,
+            flags: 2,
+        };
+        let mut vol = VoxelVolume::new(air, BoundingBox::new([-4, -4, -4], [4, 4, 4]));
+
+        assert!(vol.set([1, 2, 3], stone));
+        assert!(!vol.set([4, 0, 0], stone));
+        assert_eq!(Some(stone), vol.load([1, 2, 3]));
+        assert_eq!(Some(air), vol.load([0, 0, 0]));
+        assert!(vol.get([-5, 0, 0]).is_none());
+
+        *vol.get_mut([0, 0, 0]).unwrap().flags = 9;
+        assert_eq!(9, vol.flags()[[0, 0, 0]]);
+        assert_eq!(1, *vol.get([1, 2, 3]).unwrap().id);
+
+        let (_, mut light, _) = vol.channels_mut();
+        light.fill_region(&BoundingBox::new([-4, -4, -4], [0, 0, 0]), 3);
+        assert_eq!(3, vol.load([-1, -1, -1]).unwrap().light);
+        assert_eq!(64, vol.light().iter().filter(|&&l| l == 3).count());
+        assert!(vol.contains([-4, 3, 0]) && !vol.contains([4, 3, 0]));
+        assert_eq!(
+            vol.id().heap_size() + vol.light().heap_size() + vol.flags().heap_size(),
+            vol.heap_size()
+        );
+        assert_eq!(vol.bounding_box(), vol.id().bounding_box());
+    }
+}
+
+#[cfg(test)]
+mod view {
+    use crate::prelude::*;
+
+    #[derive(Debug, Copy, Clone, PartialEq)]
+    struct Voxel {
+        id: u16,
+        light: u8,
+    }
+
+    #[test]
+    fn projection() {
+        let mut vol = HeapVolume::new(
+            Voxel { id: 0, light: 15 },
+            BoundingBox::new_origin([4, 4, 4]),
+        );
+        vol[[1, 1, 1]] = Voxel { id: 3, light: 2 };
+
+        let light = vol.project(|voxel| &voxel.light);
+        assert_eq!(vol.bounding_box(), light.bounding_box());
+        assert_eq!(2, light[[1, 1, 1]]);
+        assert_eq!(Some(&15), light.get([0, 0, 0]));
+        assert_eq!(None, light.get([4, 0, 0]));
+        assert_eq!(63, light.iter().filter(|&&l| l == 15).count());
+
+        // algorithms for scalar volumes work on the projected field
+        assert_eq!(Some([1, 1, 1]), light.nearest([3, 3, 3], 10, |&l| l < 15));
+        assert_eq!(light.sample_trilinear([1.0, 1.0, 1.0]), Some(2.0));
+        assert_eq!(
+            Some(3),
+            vol.project(|voxel| &voxel.id).get([1, 1, 1]).copied()
+        );
+    }
+
+    #[test]
+    fn slices() {
+        let mut vol = HeapVolume::new(0u8, BoundingBox::new([-2, -2, -2], [4, 4, 4]));
+        assert_eq!(8, vol.fill_range([0, 0, 0]..[2, 2, 2], 1));
+        assert_eq!(27, vol.fill_range([-1, -1, -1]..=[1, 1, 1], 1));
+        assert_eq!(0, vol.fill_range([3, 0, 0]..[1, 4, 4], 9));
+        assert_eq!(4, vol.fill_range([3, 2, 2]..[10, 10, 10], 2));
+
+        let slice = vol.slice([-1, -1, -1]..[2, 2, 2]);
+        assert_eq!(
+            BoundingBox::new([-1, -1, -1], [2, 2, 2]),
+            slice.bounding_box()
+        );
+        assert!(slice.iter().all(|&item| item == 1));
+        assert_eq!(None, slice.get([2, 2, 2]));
+        assert_eq!(Some(&1), slice.ls_get([0, 0, 0]));
+
+        let clipped = vol.slice(BoundingBox::new([2, 2, 2], [10, 10, 10]));
+        assert_eq!(
+            BoundingBox::new([2, 2, 2], [4, 4, 4]),
+            clipped.bounding_box()
+        );
+        assert!(vol
+            .slice([10, 10, 10]..[20, 20, 20])
+            .bounding_box()
+            .is_empty());
+
+        let mut slice = vol.slice_mut([3, 3, 3]..=[3, 3, 3]);
+        assert_eq!(1, slice.bounding_box().capacity());
+        slice[[3, 3, 3]] = 5;
+        assert_eq!(None, slice.get_mut([2, 3, 3]));
+        assert_eq!(5, vol[[3, 3, 3]]);
+    }
+
+    #[test]
+    fn local_views() {
+        let mut vol = HeapVolume::new(0i64, BoundingBox::new([-8, 0, 0], [8, 8, 8]));
+        for idx in vol.bounding_box() {
+            vol[idx] = idx[0] * 100 + idx[1] * 10 + idx[2];
+        }
+
+        let view = vol.view_local([-3, 2, 4]..[10, 4, 6]);
+        assert_eq!(BoundingBox::new([-3, 2, 4], [8, 4, 6]), view.window());
+        assert_eq!(BoundingBox::new_origin([11, 2, 2]), view.bounding_box());
+        assert_eq!(-300 + 20 + 4, view[[0, 0, 0]]);
+        assert_eq!(Some(&(700 + 30 + 5)), view.ls_get([10, 1, 1]));
+        assert_eq!(None, view.get([-1, 0, 0]));
+        assert_eq!(None, view.get([11, 0, 0]));
+
+        // A kernel written for origin-based volumes runs on the window without translating indices.
+        let kernel = StackVolume::<2, 2, 2, i64>::filled(1);
+        let mut local = vol.view_local_mut([4, 4, 4]..[6, 6, 6]);
+        for idx in kernel.bounding_box() {
+            local[idx] += kernel[idx];
+        }
+        assert_eq!(None, local.get_mut([2, 0, 0]));
+        assert_eq!(400 + 40 + 4 + 1, vol[[4, 4, 4]]);
+        assert_eq!(600 + 60 + 6, vol[[6, 6, 6]]);
+    }
+
+    #[test]
+    fn torus() {
+        use crate::view::TorusVolume;
+
+        let mut vol = HeapVolume::new(0i64, BoundingBox::new([-2, 0, 5], [2, 3, 8]));
+        for idx in vol.bounding_box() {
+            vol[idx] = idx[0] * 100 + idx[1] * 10 + idx[2];
+        }
+
+        let mut torus = TorusVolume::new(vol.clone(), [true, false, true]);
+        assert_eq!(vol.bounding_box(), torus.bounding_box());
+        assert_eq!(torus[[-2, 1, 5]], torus[[2, 1, 5]]);
+        assert_eq!(torus[[1, 2, 7]], torus[[1 - 4 * 1000, 2, 7 + 3 * 7]]);
+        assert_eq!(Some([-1, 0, 6]), torus.wrap_index([i64::MAX, 0, 6]));
+        assert_eq!(None, torus.get([0, 3, 5]));
+        assert!(torus.contains([100, 1, -100]) && !torus.contains([0, -1, 5]));
+
+        // localspace indices wrap the same way
+        assert_eq!(torus.ls_get([4, 0, 3]), torus.get([-2, 0, 5]));
+
+        torus[[6, 0, 8]] = -1;
+        assert_eq!(-1, torus.inner()[[-2, 0, 5]]);
+        assert!(torus.iter().eq(torus.inner().iter()));
+    }
+
+    #[test]
+    fn rebase() {
+        use crate::chunked::DynamicLayout;
+        use crate::view::RebasedView;
+
+        let far = 1 << 40;
+        let mut vol = HeapVolume::new(0i64, BoundingBox::new([far, 0, far], [far + 4, 2, far + 4]));
+        vol[[far + 1, 1, far + 2]] = 7;
+
+        let mut view = RebasedView::new(vol.clone(), [far, 0, far]);
+        assert_eq!(BoundingBox::new_origin([4, 2, 4]), view.bounding_box());
+        assert_eq!(7, view[[1, 1, 2]]);
+        assert_eq!(view.ls_get([1, 1, 2]), view.get([1, 1, 2]));
+        assert_eq!(None, view.get([i64::MAX, 0, 0]));
+
+        let version = view.version();
+        assert!(view.rebase([1, 0, 0]));
+        assert!(view.modified_since(version));
+        view[[0, 1, 2]] = 8;
+        assert_eq!(8, view.inner()[[far + 1, 1, far + 2]]);
+
+        // Rebases that would overflow are refused without touching the view.
+        let version = view.version();
+        assert!(!view.rebase([i64::MAX, 0, 0]));
+        assert!(!view.rebase([0, i64::MIN, 0]));
+        assert!(!view.modified_since(version));
+        assert_eq!([far + 1, 0, far], view.origin());
+        assert!(RebasedView::try_new(vol.clone(), [i64::MIN, 0, 0]).is_none());
+        assert!(RebasedView::try_new(vol.clone(), [far, i64::MAX - 1, far]).is_some());
+
+        let version = vol.version();
+        vol.rebase([far, 0, far]);
+        assert!(vol.modified_since(version));
+        assert_eq!(BoundingBox::new_origin([4, 2, 4]), vol.bounding_box());
+        assert_eq!(7, vol[[1, 1, 2]]);
+
+        let mut chunked = ChunkedVolume::new(0u8, DynamicLayout::new([4, 4, 4]));
+        chunked.set([far + 5, -1, 3], 1);
+        chunked.set([far, 9, 0], 2);
+        chunked.rebase([far, 4, 0]);
+        assert_eq!(
+            BoundingBox::new([0, -8, 0], [8, 8, 4]),
+            chunked.bounding_box()
+        );
+        assert_eq!((1, 2), (chunked[[5, -5, 3]], chunked[[0, 5, 0]]));
+        assert!(chunked.chunk([1, -2, 0]).is_some());
+        assert!(chunked.validate().is_ok());
+    }
+
+    #[test]
+    fn permute_axes() {
+        // A Z-up volume with a pillar along Z, viewed as Y-up.
+        let mut vol = HeapVolume::new(0u8, BoundingBox::new([-2, 0, 1], [3, 4, 7]));
+        for z in 1..7 {
+            vol[[1, 2, z]] = z as u8;
+        }
+
+        let view = vol
+            .slice([-2, 0, 1]..[3, 4, 7])
+            .permute_axes([Axis::X, Axis::Z, Axis::Y]);
+        assert_eq!(BoundingBox::new([-2, 1, 0], [3, 7, 4]), view.bounding_box());
+        assert_eq!(3, view[[1, 3, 2]]);
+        assert_eq!(view.get([1, 3, 2]), view.ls_get([3, 2, 2]));
+        assert_eq!(None, view.get([1, 2, 5]));
+        assert_eq!(
+            (1..7).collect::<Vec<u8>>(),
+            view.iter()
+                .copied()
+                .filter(|&item| item != 0)
+                .collect::<Vec<_>>()
+        );
+
+        let mut view = vol.permute_axes([Axis::Z, Axis::X, Axis::Y]);
+        assert_eq!([0, 0, 3], view.to_inner([3, 0, 0]));
+        assert_eq!([3, 0, 0], view.from_inner([0, 0, 3]));
+        view[[6, 1, 2]] = 9;
+        assert_eq!(9, view.into_inner()[[1, 2, 6]]);
+    }
+
+    #[test]
+    fn flip_axes() {
+        // The same Z-up pillar, converted to right-handed Y-up with (x, y, z) -> (x, z, -y).
+        let mut vol = HeapVolume::new(0u8, BoundingBox::new([-2, 0, 1], [3, 4, 7]));
+        for z in 1..7 {
+            vol[[1, 2, z]] = z as u8;
+        }
+
+        let mut view = vol
+            .clone()
+            .permute_axes([Axis::X.into(), Axis::Z.into(), -Axis::Y]);
+        assert_eq!(
+            [SignedAxis::PosX, SignedAxis::PosZ, SignedAxis::NegY],
+            view.axes()
+        );
+        assert_eq!(
+            BoundingBox::new([-2, 1, -4], [3, 7, 0]),
+            view.bounding_box()
+        );
+        assert_eq!([1, 2, 3], view.to_inner([1, 3, -3]));
+        assert_eq!([1, 3, -3], view.from_inner([1, 2, 3]));
+        assert_eq!(3, view[[1, 3, -3]]);
+        assert_eq!(None, view.get([1, 3, 0]));
+
+        // Localspace indices count from the minimum corner of the view, which is the far end of the inner volume.
+        let min = view.bounding_box().min();
+        for idx in view.bounding_box() {
+            let local = crate::util::sub_ivec3(idx, min);
+            assert_eq!(view.get(idx), view.ls_get(local), "at {idx:?}");
+        }
+        assert!(view
+            .iter()
+            .eq(view.bounding_box().into_iter().map(|idx| &view[idx])));
+
+        *view.ls_get_mut([3, 0, 3]).unwrap() = 9;
+        assert_eq!(9, view.inner()[[1, 0, 1]]);
+
+        // Flipping every axis twice gives back the inner volume.
+        let twice = vol
+            .clone()
+            .permute_axes([-Axis::X, -Axis::Y, -Axis::Z])
+            .permute_axes([-Axis::X, -Axis::Y, -Axis::Z]);
+        assert_eq!(vol.bounding_box(), twice.bounding_box());
+        assert!(twice.iter().eq(vol.iter()));
+
+        // Along a flipped axis, a volume reaching down to i64::MIN can't have its bounding box represented exactly.
+        let edge = HeapVolume::new(
+            1u8,
+            BoundingBox::new([i64::MIN, 0, 0], [i64::MIN + 2, 1, 1]),
+        );
+        let edge = edge.permute_axes([-Axis::X, Axis::Y.into(), Axis::Z.into()]);
+        assert_eq!(
+            BoundingBox::new([i64::MAX - 1, 0, 0], [i64::MAX, 1, 1]),
+            edge.bounding_box()
+        );
+        assert_eq!(
+            (Some(&1), Some(&1)),
+            (edge.get([i64::MAX - 1, 0, 0]), edge.get([i64::MAX, 0, 0]))
+        );
+    }
+
+    #[test]
+    fn scaled() {
+        use crate::view::{ScalePolicy, ScaledView};
+
+        let mut vol = HeapVolume::new(0u8, BoundingBox::new([-3, 0, 0], [4, 2, 2]));
+        vol[[-3, 0, 0]] = 1;
+        vol[[-2, 1, 1]] = 2;
+        vol[[-1, 0, 1]] = 2;
+        vol[[0, 0, 0]] = 5;
+
+        let first = ScaledView::<_, 2>::new(vol.clone(), ScalePolicy::First);
+        assert_eq!(
+            BoundingBox::new([-2, 0, 0], [2, 1, 1]),
+            first.bounding_box()
+        );
+        assert_eq!(
+            Some(BoundingBox::new([-3, 0, 0], [-2, 2, 2])),
+            first.block([-2, 0, 0])
+        );
+        assert_eq!(1, first[[-2, 0, 0]]);
+        assert_eq!(0, first[[-1, 0, 0]]);
+        assert_eq!(Some(&5), first.ls_get([2, 0, 0]));
+        assert_eq!(None, first.get([2, 0, 0]));
+
+        let majority = ScaledView::<_, 2>::new(vol.clone(), ScalePolicy::Majority);
+        assert_eq!(0, majority[[-1, 0, 0]]);
+        // four of each, so the tie goes to the item occurring first
+        let mut tie = vol.clone();
+        tie[[-1, 1, 0]] = 2;
+        tie[[-2, 0, 1]] = 2;
+        let mut tie = ScaledView::<_, 2>::new(tie, ScalePolicy::Majority);
+        assert_eq!(0, tie[[-1, 0, 0]]);
+        tie.inner_mut()[[-1, 1, 1]] = 2;
+        assert_eq!(2, tie[[-1, 0, 0]]);
+
+        let any = ScaledView::<_, 2>::new(vol, ScalePolicy::Any(|&item| item > 1));
+        assert_eq!(1, any[[-2, 0, 0]]);
+        assert_eq!(2, any[[-1, 0, 0]]);
+
+        let mut fill = any;
+        assert_eq!(8, fill.fill([0, 0, 0], 7));
+        assert_eq!(4, fill.fill([-2, 0, 0], 7));
+        assert_eq!(0, fill.fill([5, 0, 0], 7));
+        assert_eq!(12, fill.inner().iter().filter(|&&item| item == 7).count());
+    }
+}
+
+#[cfg(test)]
+mod observe {
+    use crate::observe::{Change, Observed};
+    use crate::prelude::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn listeners() {
+        let mut vol = Observed::new(HeapVolume::new(0u8, BoundingBox::new_origin([4, 4, 4])));
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let id = vol.on_change({
+            let seen = seen.clone();
+            move |change| seen.lock().unwrap().push(change.clone())
+        });
+        let rx = vol.subscribe();
+
+        assert_eq!(Some(0), vol.set([1, 2, 3], 7));
+        assert_eq!(None, vol.set([4, 0, 0], 7));
+        assert_eq!(Some(7), vol.swap([1, 2, 3], 8));
+        assert_eq!(8, *vol.get([1, 2, 3]).unwrap());
+
+        let expected = vec![
+            Change {
+                idx: [1, 2, 3],
+                old: 0,
+                new: 7,
+            },
+            Change {
+                idx: [1, 2, 3],
+                old: 7,
+                new: 8,
+            },
+        ];
+        assert_eq!(expected, *seen.lock().unwrap());
+        assert_eq!(expected, rx.try_iter().collect::<Vec<_>>());
+
+        assert!(vol.remove_listener(id));
+        assert!(!vol.remove_listener(id));
+        drop(rx);
+
+        vol.insert(
+            [2, 2, 2],
+            &HeapVolume::new(1u8, BoundingBox::new_origin([2, 2, 2])),
+        )
+        .unwrap();
+        assert!(vol
+            .insert(
+                [3, 3, 3],
+                &HeapVolume::new(1u8, BoundingBox::new_origin([2, 2, 2]))
+            )
+            .is_err());
+        assert_eq!(2, seen.lock().unwrap().len());
+
+        let rx = vol.subscribe();
+        vol.inner_mut()[[0, 0, 0]] = 5;
+        vol.insert(
+            [0, 0, 0],
+            &HeapVolume::new(1u8, BoundingBox::new_origin([1, 1, 2])),
+        )
+        .unwrap();
+        let changes = rx.try_iter().collect::<Vec<_>>();
+        assert_eq!(2, changes.len());
+        assert_eq!(5, changes[0].old);
+    }
+
+    #[test]
+    fn versions() {
+        let mut vol = HeapVolume::new(0u8, BoundingBox::new_origin([4, 4, 4]));
+        let v0 = vol.version();
+
+        let _ = vol[[1, 1, 1]];
+        let _ = vol.get_many(&[[0, 0, 0], [3, 3, 3]]);
+        assert!(!vol.modified_since(v0));
+
+        vol[[1, 1, 1]] = 1;
+        assert!(vol.modified_since(v0));
+        let v1 = vol.version();
+
+        // out of bounds writes don't touch anything
+        assert!(vol.get_mut([9, 9, 9]).is_none());
+        assert!(!vol.modified_since(v1));
+
+        vol += 1;
+        assert!(vol.modified_since(v1));
+        assert_eq!(vol.version(), vol.clone().version());
+
+        let mut observed = Observed::new(vol);
+        let v2 = observed.version();
+        observed.set([0, 0, 0], 5);
+        assert!(observed.modified_since(v2));
+    }
+}
+
+#[cfg(test)]
+mod selection {
+    use crate::prelude::*;
+
+    fn sorted(selection: &Selection) -> Vec<[i64; 3]> {
+        let mut v = selection.iter().collect::<Vec<_>>();
+        v.sort();
+        v
+    }
+
+    #[test]
+    fn box_difference() {
+        let a = BoundingBox::new_origin([4, 4, 4]);
+        let b = BoundingBox::new([1, 1, 1], [3, 3, 3]);
+
+        let pieces = a.difference(&b);
+        assert_eq!(pieces.len(), 6);
+        assert_eq!(pieces.iter().map(|p| p.capacity()).sum::<i128>(), 64 - 8);
+        assert!(pieces.iter().all(|p| !p.overlaps(&b)));
+
+        assert_eq!(b.difference(&a), vec![]);
+        assert_eq!(
+            a.difference(&BoundingBox::new([9, 9, 9], [10, 10, 10])),
+            vec![a]
+        );
+    }
+
+    #[test]
+    fn selection_building() {
+        let mut sel = Selection::from(BoundingBox::new_origin([3, 3, 3]));
+        sel.add_box(BoundingBox::new([1, 1, 1], [4, 4, 4]));
+        sel.add_voxel([1, 1, 1]);
+        sel.add_voxel([10, 0, 0]);
+
+        assert_eq!(sel.len(), 27 + 27 - 8 + 1);
+        assert!(sel.contains([3, 3, 3]));
+        assert!(sel.contains([10, 0, 0]));
+        assert!(!sel.contains([3, 0, 0]));
+
+        // no duplicates even though the boxes overlapped
+        let positions = sorted(&sel);
+        assert_eq!(positions.len() as u128, sel.len());
+        assert!(positions.windows(2).all(|w| w[0] != w[1]));
+
+        assert_eq!(
+            sel.bounding_box(),
+            Some(BoundingBox::new([0, 0, 0], [11, 4, 4]))
+        );
+    }
+
+    #[test]
+    fn selection_boolean_ops() {
+        let a = Selection::from(BoundingBox::new_origin([4, 1, 1]));
+        let mut b = Selection::from(BoundingBox::new([2, 0, 0], [6, 1, 1]));
+        b.add_voxel([0, 0, 0]);
+
+        let expected = |xs: &[i64]| xs.iter().map(|&x| [x, 0, 0]).collect::<Vec<_>>();
+
+        assert_eq!(sorted(&(&a | &b)), expected(&[0, 1, 2, 3, 4, 5]));
+        assert_eq!(sorted(&(&a & &b)), expected(&[0, 2, 3]));
+        assert_eq!(sorted(&(&a - &b)), expected(&[1]));
+        assert_eq!(sorted(&(&b - &a)), expected(&[4, 5]));
+    }
+
+    #[test]
+    fn fill_region() {
+        let mut vol = HeapVolume::new(0, BoundingBox::new_origin([4, 4, 4]));
+
+        let mut sel = Selection::from(BoundingBox::new([2, 2, 2], [6, 6, 6]));
+        sel.add_voxel([0, 0, 0]);
+        sel.add_voxel([-1, 0, 0]);
+
+        assert_eq!(vol.fill_region(&sel, 1), 9);
+        assert_eq!(
+            vol.fill_region(&BoundingBox::new([0, 3, 0], [1, 9, 1]), 2),
+            1
+        );
+
+        assert_eq!(vol[[0, 0, 0]], 1);
+        assert_eq!(vol[[3, 3, 3]], 1);
+        assert_eq!(vol[[0, 3, 0]], 2);
+        assert_eq!(vol.iter().filter(|&&n| n != 0).count(), 10);
+    }
+}
+
+#[cfg(test)]
+mod sampling {
+    use crate::prelude::*;
+
+    /// A 2x2x2 volume where each item is `x + 2y + 4z`.
+    fn corner_volume() -> HeapVolume<u8> {
+        let mut vol = HeapVolume::new(0u8, BoundingBox::new_origin([2, 2, 2]));
+        for [x, y, z] in vol.bounding_box() {
+            vol[[x, y, z]] = (x + 2 * y + 4 * z) as u8;
+        }
+        vol
+    }
+
+    #[test]
+    fn trilinear_corner_convention() {
+        let vol = corner_volume();
+
+        assert_eq!(vol.sample_trilinear([0.0, 0.0, 0.0]), Some(0.0));
+        assert_eq!(vol.sample_trilinear([1.0, 1.0, 1.0]), Some(7.0));
+        assert_eq!(vol.sample_trilinear([0.5, 0.0, 0.0]), Some(0.5));
+        assert_eq!(vol.sample_trilinear([0.5, 0.5, 0.5]), Some(3.5));
+        assert_eq!(vol.sample_trilinear([0.25, 1.0, 0.5]), Some(4.25));
+
+        assert_eq!(vol.sample_trilinear([1.5, 0.0, 0.0]), None);
+        assert_eq!(vol.sample_trilinear([-0.5, 0.0, 0.0]), None);
+        assert_eq!(vol.sample_trilinear([f64::NAN, 0.0, 0.0]), None);
+    }
+
+    #[test]
+    fn trilinear_center_convention() {
+        let vol = corner_volume();
+
+        assert_eq!(vol.sample_trilinear_centered([0.5, 0.5, 0.5]), Some(0.0));
+        assert_eq!(vol.sample_trilinear_centered([1.5, 1.5, 1.5]), Some(7.0));
+        assert_eq!(vol.sample_trilinear_centered([1.0, 1.0, 1.0]), Some(3.5));
+        assert_eq!(vol.sample_trilinear_centered([0.0, 0.5, 0.5]), None);
+    }
+
+    #[test]
+    fn gradient() {
+        // f(x, y, z) = 3x - 2y, constant along Z
+        let mut vol = HeapVolume::new(0i32, BoundingBox::new([-2, -2, -2], [3, 3, 3]));
+        for [x, y, z] in vol.bounding_box() {
+            vol[[x, y, z]] = (3 * x - 2 * y) as i32;
+        }
+
+        // interior uses central differences, edges use one sided differences. Both are exact for linear functions.
+        assert_eq!(vol.gradient([0, 0, 0]), Some([3.0, -2.0, 0.0]));
+        assert_eq!(vol.gradient([-2, -2, -2]), Some([3.0, -2.0, 0.0]));
+        assert_eq!(vol.gradient([2, 2, 2]), Some([3.0, -2.0, 0.0]));
+        assert_eq!(vol.gradient([3, 0, 0]), None);
+
+        let field = vol.gradient_field();
+        assert_eq!(field.bounding_box(), vol.bounding_box());
+        assert!(field.iter().all(|g| g == &[3.0, -2.0, 0.0]));
+
+        let flat = HeapVolume::new(1u8, BoundingBox::new_origin([1, 1, 1]));
+        assert_eq!(flat.gradient([0, 0, 0]), Some([0.0; 3]));
+    }
+}
+
+#[cfg(test)]
+mod morph {
+    use crate::morph::{self, Connectivity, StructuringElement};
+    use crate::prelude::*;
+
+    fn single_voxel() -> HeapVolume<bool> {
+        let mut vol = HeapVolume::new(false, BoundingBox::new([-5, -5, -5], [6, 6, 6]));
+        vol[[0, 0, 0]] = true;
+        vol
+    }
+
+    fn count(vol: &HeapVolume<bool>) -> usize {
+        vol.iter().filter(|&&c| c).count()
+    }
+
+    #[test]
+    fn dilate_shapes() {
+        let vol = single_voxel();
+
+        let cube = morph::dilate(&vol, StructuringElement::new(Connectivity::TwentySix, 2));
+        assert_eq!(count(&cube), 5 * 5 * 5);
+        assert!(cube[[2, -2, 2]]);
+        assert!(!cube[[3, 0, 0]]);
+
+        let octahedron = morph::dilate(&vol, StructuringElement::new(Connectivity::Six, 2));
+        // 1 + 6 + 18 cells within manhattan distance 2
+        assert_eq!(count(&octahedron), 25);
+        assert!(octahedron[[1, 1, 0]]);
+        assert!(!octahedron[[1, 1, 1]]);
+    }
+
+    #[test]
+    fn erode_and_compound_ops() {
+        let element = StructuringElement::new(Connectivity::TwentySix, 1);
+        let cube = morph::dilate(
+            &single_voxel(),
+            StructuringElement::new(Connectivity::TwentySix, 2),
+        );
+
+        let eroded = morph::erode(&cube, element);
+        assert_eq!(count(&eroded), 3 * 3 * 3);
+
+        // opening removes the lone voxel entirely, closing leaves it alone
+        assert_eq!(count(&morph::open(&single_voxel(), element)), 0);
+        assert_eq!(morph::close(&single_voxel(), element), single_voxel());
+        assert_eq!(morph::open(&cube, element), cube);
+
+        // out of bounds cells are ignored so a full volume doesn't erode from its edges
+        let full = HeapVolume::new(true, BoundingBox::new_origin([4, 4, 4]));
+        assert_eq!(morph::erode(&full, element), full);
+    }
+
+    #[test]
+    fn progress_and_cancellation() {
+        use std::ops::ControlFlow;
+
+        let vol = HeapVolume::new(false, BoundingBox::new_origin([32, 32, 32]));
+        let element = StructuringElement::new(Connectivity::Six, 3);
+
+        let mut reports = Vec::new();
+        morph::close_with_progress(&vol, element, |p| {
+            reports.push(p);
+            ControlFlow::Continue(())
+        })
+        .unwrap();
+
+        assert!(reports.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(reports.last(), Some(&1.0));
+
+        let result = morph::dilate_with_progress(&vol, element, |p| {
+            if p > 0.5 {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+        assert_eq!(result, Err(crate::types::Cancelled));
+    }
+}
+
+/// Every volume, view, wrapper, and iterator must be `Send` and `Sync` when its items (and other type parameters) are,
+/// so that they can be shared with and sent to other threads (e.g., rayon tasks).
+#[cfg(test)]
+mod occupancy {
+    use crate::prelude::*;
+
+    fn brute_force(vol: &HeapVolume<bool>, region: BoundingBox) -> (bool, bool) {
+        let any = region.into_iter().any(|idx| vol.get(idx) == Some(&true));
+        let all = region.into_iter().all(|idx| vol.get(idx) == Some(&true));
+        (any, all)
+    }
+
+    #[test]
+    fn queries_match_brute_force() {
+        let bounds = BoundingBox::new([-5, 0, 3], [14, 9, 25]);
+        let mut vol = HeapVolume::new(false, bounds);
+        for idx in bounds {
+            // A solid slab, plus a sparse pattern of solid cells above it.
+            vol[idx] = idx[1] < 3 || (idx[0] * 7 + idx[1] * 13 + idx[2] * 5) % 23 == 0;
+        }
+
+        let mut tree = vol.build_occupancy(|&solid| solid);
+        assert_eq!(4, tree.depth());
+
+        let regions = [
+            BoundingBox::new([-5, 0, 3], [14, 3, 25]),
+            BoundingBox::new([0, 3, 4], [4, 7, 8]),
+            BoundingBox::new([-20, -2, 0], [0, 1, 9]),
+            BoundingBox::new([1, 4, 10], [2, 5, 11]),
+            BoundingBox::new([3, 5, 6], [13, 9, 24]),
+            BoundingBox::new([40, 0, 0], [41, 1, 1]),
+            BoundingBox::new_origin([0, 0, 0]),
+        ];
+
+        for _ in 0..2 {
+            for region in regions {
+                assert_eq!(
+                    brute_force(&vol, region),
+                    (tree.any_in(region), tree.all_in(region)),
+                    "{region}"
+                );
+            }
+
+            // Clear the slab and punch a hole in it, then check that the tree followed.
+            for idx in BoundingBox::new([-5, 3, 3], [14, 9, 25]) {
+                vol[idx] = false;
+                assert!(tree.set(idx, false));
+            }
+            vol[[6, 1, 12]] = false;
+            tree.set([6, 1, 12], false);
+        }
+
+        assert!(!tree.is_solid([6, 1, 12]));
+        assert!(tree.is_solid([6, 2, 12]));
+        assert!(!tree.set([100, 0, 0], true));
+    }
+}
+
+#[cfg(test)]
+mod scrolling {
+    use crate::prelude::*;
+    use crate::scrolling::ScrollingVolume;
+
+    fn terrain(pos: [i64; 3]) -> i64 {
+        pos[0] * 100 + pos[1] * 10 + pos[2]
+    }
+
+    #[test]
+    fn recenter() {
+        let mut vol = ScrollingVolume::new(0, [4, 3, 2], [0, 0, 0]);
+        assert_eq!(
+            BoundingBox::new([-2, -1, -1], [2, 2, 1]),
+            vol.bounding_box()
+        );
+        assert_eq!([0, 0, 0], vol.center());
+
+        let mut generated = 0;
+        let mut fill = |view: &mut crate::view::SubVolumeMut<'_, ScrollingVolume<i64>>| {
+            for idx in view.bounding_box() {
+                view[idx] = terrain(idx);
+                generated += 1;
+            }
+        };
+
+        // Moving far away exposes the whole window.
+        assert_eq!(24, vol.recenter([50, 50, 50], &mut fill));
+        for (center, exposed) in [([51, 50, 50], 6), ([51, 48, 49], 20), ([51, 48, 49], 0)] {
+            assert_eq!(exposed, vol.recenter(center, &mut fill));
+            assert_eq!(center, vol.center());
+            assert!(vol.iter_indexed().all(|(idx, &item)| item == terrain(idx)));
+        }
+
+        assert_eq!(50, generated);
+        assert_eq!(None, vol.get([0, 0, 0]));
+        assert_eq!(Ok(()), vol.validate());
+    }
+}
+
+#[cfg(test)]
+mod layered {
+    use crate::prelude::*;
+
+    #[test]
+    fn reads_resolve_through_layers() {
+        let base = HeapVolume::new(1u8, BoundingBox::new([-2, -2, -2], [2, 2, 2]));
+        let mut vol = LayeredVolume::new(base);
+
+        vol.fill_range([-1, -1, -1]..[1, 1, 1], 2);
+        vol.push_layer();
+        vol[[0, 0, 0]] = 3;
+        // Mutable access copies the resolved item into the top layer.
+        *vol.get_mut([-1, 0, 0]).unwrap() += 5;
+
+        assert_eq!(
+            (3, 7, 2, 1),
+            (
+                vol[[0, 0, 0]],
+                vol[[-1, 0, 0]],
+                vol[[-1, -1, -1]],
+                vol[[1, 1, 1]]
+            )
+        );
+        assert_eq!(
+            Some(Some(7)),
+            vol.layer(1).unwrap().get([-1, 0, 0]).copied()
+        );
+        assert!(vol.base().iter().all(|&item| item == 1));
+
+        let flat = vol.flatten();
+        assert_eq!(vol.bounding_box(), flat.bounding_box());
+        assert!(vol.iter_indexed().all(|(idx, item)| flat[idx] == *item));
+
+        // Throwing away the preview layer brings back what was below it.
+        assert!(vol.pop_layer().is_some());
+        assert_eq!((2, 2), (vol[[0, 0, 0]], vol[[-1, 0, 0]]));
+    }
+
+    #[test]
+    fn merge_top() {
+        let mut vol = LayeredVolume::new(HeapVolume::new(0i32, BoundingBox::new_origin([3, 3, 3])));
+        vol[[0, 0, 0]] = 1;
+        vol.push_layer();
+        vol[[0, 0, 0]] = 2;
+        vol[[1, 1, 1]] = 3;
+
+        assert_eq!(Some(2), vol.merge_top());
+        assert_eq!(Some(&Some(2)), vol.layer(0).unwrap().get([0, 0, 0]));
+        assert_eq!(Some(2), vol.merge_top());
+        assert_eq!(None, vol.merge_top());
+        assert_eq!(0, vol.layers());
+
+        assert_eq!(
+            (2, 3, 0),
+            (
+                vol.base()[[0, 0, 0]],
+                vol.base()[[1, 1, 1]],
+                vol.base()[[2, 2, 2]]
+            )
+        );
+    }
+
+    #[test]
+    fn write_after_discarding_every_layer() {
+        let mut vol = LayeredVolume::new(HeapVolume::new(0u8, BoundingBox::new_origin([3, 3, 3])));
+        vol[[1, 1, 1]] = 4;
+        assert!(vol.pop_layer().is_some());
+        assert_eq!(0, vol.layers());
+
+        // Writing pushes a fresh layer instead of panicking, and still leaves the base alone.
+        assert_eq!(8, vol.fill_range([0, 0, 0]..[2, 2, 2], 5));
+        assert_eq!(1, vol.layers());
+        assert_eq!((5, 0), (vol[[1, 1, 1]], vol[[2, 2, 2]]));
+        assert!(vol.base().iter().all(|&item| item == 0));
+        assert_eq!(None, vol.get_mut([3, 0, 0]));
+    }
+}
+
+#[cfg(test)]
+mod meshed {
+    use crate::meshed::MeshedVolume;
+    use crate::prelude::*;
+
+    /// Count the faces of solid items in `tile` that border a non-solid (or out of bounds) position.
+    fn faces(vol: &HeapVolume<bool>, tile: BoundingBox) -> usize {
+        let mut count = 0;
+        for idx in tile {
+            if !vol[idx] {
+                continue;
+            }
+            for axis in 0..3 {
+                for step in [-1, 1] {
+                    let mut neighbor = idx;
+                    neighbor[axis] += step;
+                    count += usize::from(!vol.get(neighbor).copied().unwrap_or(false));
+                }
+            }
+        }
+
+        count
+    }
+
+    #[test]
+    fn remeshes_dirty_tiles() {
+        let vol = HeapVolume::new(false, BoundingBox::new([-4, 0, 0], [8, 8, 5]));
+        let mut meshed = MeshedVolume::new(vol, [4, 4, 4]);
+        // 3 * 2 * 2 tiles, the ones along Z being 1 deep.
+        assert_eq!(12, meshed.dirty_tiles());
+        assert_eq!(12, meshed.mesh(faces));
+        assert_eq!(0, meshed.mesh(faces));
+
+        // Inside of a tile.
+        meshed[[-3, 1, 1]] = true;
+        assert_eq!(1, meshed.dirty_tiles());
+        // On the edge between two tiles along X.
+        meshed[[3, 5, 1]] = true;
+        assert_eq!(3, meshed.dirty_tiles());
+        // On the corner of 8 tiles (2 of which are already dirty).
+        meshed[[4, 4, 4]] = true;
+        assert_eq!(9, meshed.mesh(faces));
+
+        let total = meshed.meshes().map(|(_, &faces)| faces).sum::<usize>();
+        assert_eq!(18, total);
+        assert_eq!(12, meshed.meshes().count());
+        assert_eq!(faces(meshed.inner(), meshed.bounding_box()), total);
+
+        // Changes made through the inner volume need to be marked by hand.
+        meshed.inner_mut()[[0, 0, 0]] = true;
+        meshed.mark_dirty(BoundingBox::new([0, 0, 0], [1, 1, 1]));
+        assert_eq!(2, meshed.mesh(faces));
+        assert_eq!(24, meshed.meshes().map(|(_, &faces)| faces).sum::<usize>());
+    }
+}
+
+#[cfg(test)]
+mod flags {
+    use crate::flags::FlagVolume;
+    use crate::prelude::*;
+
+    const NEEDS_UPDATE: usize = 0;
+    const MODIFIED: usize = 1;
+    const WATERLOGGED: usize = 2;
+
+    #[test]
+    fn set_and_query() {
+        let bounds = BoundingBox::new([-70, -2, 0], [70, 3, 2]);
+        let mut flags = FlagVolume::<3>::new(bounds);
+
+        assert!(flags.set_flag([65, 1, 1], NEEDS_UPDATE));
+        assert!(flags.set_flag([-70, -2, 0], NEEDS_UPDATE));
+        assert!(flags.set_flag([65, 1, 1], WATERLOGGED));
+        assert!(!flags.set_flag([70, 0, 0], MODIFIED));
+
+        assert!(flags.get_flag([65, 1, 1], NEEDS_UPDATE));
+        assert!(!flags.get_flag([65, 1, 1], MODIFIED));
+        assert!(!flags.get_flag([64, 1, 1], NEEDS_UPDATE));
+        assert_eq!(2, flags.count_flag(NEEDS_UPDATE));
+        assert_eq!(
+            vec![[-70, -2, 0], [65, 1, 1]],
+            flags.iter_flag(NEEDS_UPDATE).collect::<Vec<_>>()
+        );
+
+        // Regions spanning several words per row, and ending right before or after the set flag.
+        assert!(flags.any_flag_in(BoundingBox::new([-60, 0, 0], [66, 3, 2]), NEEDS_UPDATE));
+        assert!(!flags.any_flag_in(BoundingBox::new([-60, 0, 0], [65, 3, 2]), NEEDS_UPDATE));
+        assert!(!flags.any_flag_in(BoundingBox::new([-69, -2, 0], [66, 1, 2]), NEEDS_UPDATE));
+        assert!(!flags.any_flag_in(bounds, MODIFIED));
+        assert!(!flags.any_flag_in(BoundingBox::new([100, 0, 0], [200, 1, 1]), NEEDS_UPDATE));
+    }
+
+    #[test]
+    fn bulk_clearing() {
+        let bounds = BoundingBox::new([0, 0, 0], [100, 4, 3]);
+        let mut flags = FlagVolume::<2>::new(bounds);
+        for idx in bounds {
+            flags.set_flag(idx, MODIFIED);
+        }
+        flags.set_flag([5, 0, 0], NEEDS_UPDATE);
+
+        let region = BoundingBox::new([3, 1, 1], [97, 3, 2]);
+        flags.clear_flag_in(region, MODIFIED);
+        assert!(!flags.any_flag_in(region, MODIFIED));
+        assert_eq!(
+            bounds.capacity() - region.capacity(),
+            flags.count_flag(MODIFIED) as i128
+        );
+        assert!(flags.get_flag([2, 1, 1], MODIFIED) && flags.get_flag([97, 1, 1], MODIFIED));
+
+        flags.clear_flag_everywhere(MODIFIED);
+        assert_eq!(
+            (0, 1),
+            (flags.count_flag(MODIFIED), flags.count_flag(NEEDS_UPDATE))
+        );
+        flags.clear_all();
+        assert_eq!(0, flags.count_flag(NEEDS_UPDATE));
+    }
+
+    #[test]
+    #[should_panic]
+    fn flag_out_of_range() {
+        FlagVolume::<2>::new(BoundingBox::new_origin([2, 2, 2])).get_flag([0, 0, 0], 2);
+    }
+}
+
+#[cfg(test)]
+mod spaces {
+    use crate::chunked::Cubic16;
+    use crate::prelude::*;
+    use crate::spaces::{ChunkCoord, Local, World};
+
+    #[test]
+    fn tagged_indices() {
+        let bounds = BoundingBox::new([10, -4, 2], [14, 0, 6]);
+        let mut vol = HeapVolume::new(0u8, bounds);
+        vol[[11, -3, 2]] = 5;
+
+        assert_eq!(Some(&5), vol.at(World([11, -3, 2])));
+        assert_eq!(Some(&5), vol.at(Local([1u8, 1, 0])));
+        assert_eq!(None, vol.at(Local([11, -3, 2])));
+
+        *vol.at_mut(Local([3, 3, 3])).unwrap() = 7;
+        assert_eq!(7, vol[[13, -1, 5]]);
+
+        assert_eq!(Some(Local([1, 1, 0])), World([11, -3, 2]).to_local(bounds));
+        assert_eq!(Some(World([11, -3, 2])), Local([1, 1, 0]).to_world(bounds));
+        assert_eq!(None, World([9, -3, 2]).to_local(bounds));
+        assert_eq!(None, Local([4, 0, 0]).to_world(bounds));
+    }
+
+    #[test]
+    fn chunk_coords() {
+        let chunk = World([-1, 17, 15]).chunk(Cubic16::default()).unwrap();
+        assert_eq!(ChunkCoord([-1, 1, 0]), chunk);
+        assert_eq!(
+            Some(BoundingBox::new([-16, 16, 0], [0, 32, 16])),
+            chunk.bounds(Cubic16::default())
+        );
+    }
+}
+
+#[cfg(test)]
+mod pool {
+    use crate::pool::VolumePool;
+    use crate::prelude::*;
+
+    #[test]
+    fn allocate_and_recycle() {
+        let mut pool = VolumePool::new(0u16, [4, 2, 3], 3);
+        let a = pool.allocate([0, 0, 0], 1).unwrap();
+        let b = pool.allocate([-4, 0, 0], 2).unwrap();
+        let c = pool.allocate([0, 10, 0], 3).unwrap();
+        assert_eq!(None, pool.allocate([0, 0, 0], 4));
+        assert_eq!((3, 3), (pool.len(), pool.capacity()));
+
+        pool.get_mut(b).unwrap()[[-1, 1, 2]] = 20;
+        let vol = pool.get(b).unwrap();
+        assert_eq!(BoundingBox::new([-4, 0, 0], [0, 2, 3]), vol.bounding_box());
+        assert_eq!((2, 20), (vol[[-4, 0, 0]], vol[[-1, 1, 2]]));
+        assert!(pool.get(c).unwrap().iter().all(|&item| item == 3));
+
+        assert!(pool.free(a));
+        assert!(!pool.free(a));
+        assert!(pool.get(a).is_none());
+
+        // The freed slot is reused, but the old handle stays invalid.
+        let d = pool.allocate([8, 8, 8], 5).unwrap();
+        assert_ne!(a, d);
+        assert!(pool.get(a).is_none());
+        assert!(pool.get(d).unwrap().iter().all(|&item| item == 5));
+
+        for (_, mut vol) in pool.iter_mut() {
+            let min = vol.bounding_box().min();
+            vol[min] += 100;
+        }
+        let mut firsts = pool
+            .iter()
+            .map(|(key, vol)| (key, vol[vol.bounding_box().min()]))
+            .collect::<Vec<_>>();
+        let mut expected = vec![(b, 102), (c, 103), (d, 105)];
+        firsts.sort();
+        expected.sort();
+        assert_eq!(expected, firsts);
+    }
+}
+
+#[cfg(test)]
+mod sparse {
+    use crate::prelude::*;
+    use crate::sparse::HashVolume;
+
+    #[test]
+    fn insert_remove_and_iter_in_box() {
+        let mut vol = HashVolume::new(4);
+        assert_eq!(None, vol.insert([0, 0, 0], 'a'));
+        assert_eq!(None, vol.insert([-5, 3, 9], 'b'));
+        assert_eq!(None, vol.insert([3, 3, 3], 'c'));
+        assert_eq!(Some('c'), vol.insert([3, 3, 3], 'd'));
+        assert_eq!(None, vol.insert([100, -100, 7], 'e'));
+        assert_eq!(4, vol.len());
+        assert_eq!(
+            Some(BoundingBox::new([-5, -100, 0], [101, 4, 10])),
+            vol.bounding_box()
+        );
+
+        let mut found = vol
+            .iter_in_box(BoundingBox::new([-5, 0, 0], [4, 4, 10]))
+            .collect::<Vec<_>>();
+        found.sort();
+        assert_eq!(
+            vec![([-5, 3, 9], &'b'), ([0, 0, 0], &'a'), ([3, 3, 3], &'d')],
+            found
+        );
+
+        assert_eq!(Some('d'), vol.remove([3, 3, 3]));
+        assert_eq!(None, vol.remove([3, 3, 3]));
+        let found = vol
+            .iter_in_box(BoundingBox::new([0, 0, 0], [4, 4, 4]))
+            .collect::<Vec<_>>();
+        assert_eq!(vec![([0, 0, 0], &'a')], found);
+
+        // A huge region only looks at the non-empty cells.
+        let everything = BoundingBox::new([i64::MIN / 2; 3], [i64::MAX / 2; 3]);
+        assert_eq!(3, vol.iter_in_box(everything).count());
+    }
+
+    #[test]
+    fn nearest_n() {
+        let mut vol = HashVolume::new(3);
+        for x in -20i64..20 {
+            for z in [-7i64, 2, 11] {
+                vol.insert([x * 2, x.rem_euclid(5), z], (x, z));
+            }
+        }
+        vol.insert([10_000, 0, 0], (0, 0));
+
+        for from in [[0, 0, 0], [13, -4, 6], [-60, 2, 2], [9_000, 0, 0]] {
+            for n in [0, 1, 5, 200] {
+                let mut expected = vol.iter().collect::<Vec<_>>();
+                expected.sort_by_key(|&(pos, _)| {
+                    let d = (0..3).map(|i| (pos[i] - from[i]).pow(2)).sum::<i64>();
+                    (d, pos)
+                });
+                expected.truncate(n);
+
+                assert_eq!(expected, vol.nearest_n(from, n));
+            }
+        }
+
+        assert!(HashVolume::<u8>::new(1).nearest_n([0, 0, 0], 3).is_empty());
+    }
+}
+
+#[cfg(test)]
+mod topology {
+    use crate::prelude::*;
+    use crate::topology::{euler_characteristic, extract_interface, find_enclosed_voids, Face};
+
+    #[test]
+    fn water_surface() {
+        // Water below y = 2, air above it, and a stone pillar at x = 0 z = 0 going through both.
+        let mut vol = HeapVolume::new('w', BoundingBox::new([0, 0, 0], [3, 4, 3]));
+        for pos in vol.bounding_box() {
+            vol[pos] = match pos {
+                [0, _, 0] => 's',
+                [_, y, _] if y >= 2 => 'a',
+                _ => 'w',
+            };
+        }
+
+        let surface = extract_interface(&vol, |&c| c == 'w', |&c| c == 'a');
+        assert_eq!(8, surface.len());
+        assert!(surface
+            .iter()
+            .all(|&(pos, face)| pos[1] == 1 && face == Face::PosY));
+
+        // Every water face touching air is matched by an air face touching water.
+        let mut reverse = extract_interface(&vol, |&c| c == 'a', |&c| c == 'w')
+            .into_iter()
+            .map(|(pos, face)| (face.neighbor(pos).unwrap(), face.opposite()))
+            .collect::<Vec<_>>();
+        reverse.sort();
+        let mut surface = surface;
+        surface.sort();
+        assert_eq!(surface, reverse);
+
+        let pillar = extract_interface(&vol, |&c| c == 's', |&c| c != 's');
+        assert_eq!(8, pillar.len());
+        assert!(pillar.contains(&([0, 3, 0], Face::PosX)));
+        assert!(!pillar.contains(&([0, 3, 0], Face::PosY)));
+    }
+
+    /// A 9×9×9 volume with a hollow 5×5×5 box in the middle of it.
+    fn hollow_box() -> HeapVolume<bool> {
+        let mut vol = HeapVolume::new(false, BoundingBox::new([-2, -2, -2], [7, 7, 7]));
+        for pos in BoundingBox::new_origin([5, 5, 5]) {
+            vol[pos] = pos.iter().any(|&c| c == 0 || c == 4);
+        }
+        vol
+    }
+
+    #[test]
+    fn enclosed_voids() {
+        let mut vol = hollow_box();
+        // Split the inside of the box into two 3×1×3 voids.
+        for pos in BoundingBox::new([1, 2, 1], [4, 3, 4]) {
+            vol[pos] = true;
+        }
+
+        let voids = find_enclosed_voids(&vol, |&solid| solid);
+        assert_eq!(2, voids.len());
+        assert_eq!(9, voids[0].len());
+        assert!(voids[0].contains([1, 1, 1]));
+        assert_eq!(9, voids[1].len());
+        assert!(voids[1].contains([3, 3, 3]));
+
+        // Opening a hole in the box connects the upper void to the outside.
+        vol[[2, 4, 2]] = false;
+        let voids = find_enclosed_voids(&vol, |&solid| solid);
+        assert_eq!(1, voids.len());
+        assert!(voids[0].contains([1, 1, 1]));
+
+        // Non-solid cells on the edge of the bounds are always outside.
+        let vol = HeapVolume::new(false, BoundingBox::new_origin([3, 3, 3]));
+        assert!(find_enclosed_voids(&vol, |&solid| solid).is_empty());
+    }
+
+    #[test]
+    fn euler_characteristics() {
+        let solid = |&solid: &bool| solid;
+
+        let vol = HeapVolume::new(true, BoundingBox::new_origin([3, 2, 4]));
+        assert_eq!(1, euler_characteristic(&vol, solid));
+
+        let mut vol = hollow_box();
+        assert_eq!(2, euler_characteristic(&vol, solid));
+
+        // Two separate pieces.
+        vol[[-2, -2, -2]] = true;
+        assert_eq!(3, euler_characteristic(&vol, solid));
+
+        // A ring has a tunnel through it.
+        let mut ring = HeapVolume::new(false, BoundingBox::new_origin([3, 1, 3]));
+        for pos in ring.bounding_box() {
+            ring[pos] = pos != [1, 0, 1];
+        }
+        assert_eq!(0, euler_characteristic(&ring, solid));
+
+        assert_eq!(0, euler_characteristic(&ring, |_| false));
+    }
+
+    #[test]
+    fn asymmetric_shell() {
+        // A 5×3×3 solid shell around a 3×1×1 void, in a volume that looks different with any two axes swapped.
+        let mut vol = HeapVolume::new(false, BoundingBox::new([-1, 2, 0], [6, 6, 4]));
+        for pos in BoundingBox::new([0, 2, 0], [5, 5, 3]) {
+            vol[pos] = true;
+        }
+        for pos in BoundingBox::new([1, 3, 1], [4, 4, 2]) {
+            vol[pos] = false;
+        }
+
+        let voids = find_enclosed_voids(&vol, |&solid| solid);
+        assert_eq!(1, voids.len());
+        assert_eq!(3, voids[0].len());
+        assert!(voids[0].contains([3, 3, 1]));
+        assert_eq!(2, euler_characteristic(&vol, |&solid| solid));
+    }
+}
+
+#[cfg(test)]
+mod migrate {
+    use crate::migrate::Migrations;
+    use crate::prelude::*;
+    use crate::types::MigrationError;
+
+    #[test]
+    fn chained_migrations() {
+        // Version 3 renumbered block 5 to 50, and version 4 merged blocks 1 and 2.
+        let mut migrations = Migrations::<u16>::new(2);
+        assert_eq!(3, migrations.register(|id| if id == 5 { 50 } else { id }));
+        assert_eq!(4, migrations.register(|id| if id == 2 { 1 } else { id }));
+
+        assert_eq!(Ok(50), migrations.migrate_item(5u16, 2));
+        assert_eq!(Ok(5), migrations.migrate_item(5u16, 4));
+        assert_eq!(Ok(1), migrations.migrate_item(2u16, 3));
+
+        let mut vol = HeapVolume::new(0u16, BoundingBox::new_origin([2, 1, 2]));
+        vol[[0, 0, 0]] = 5;
+        vol[[1, 0, 1]] = 2;
+        let migrated = migrations.migrate(vol.clone(), 2).unwrap();
+        assert_eq!(
+            vec![50, 0, 0, 1],
+            migrated.iter().copied().collect::<Vec<_>>()
+        );
+
+        let error = MigrationError::UnsupportedVersion {
+            found: 1,
+            first: 2,
+            current: 4,
+        };
+        assert_eq!(Err(error), migrations.migrate(vol, 1));
+        assert_eq!(
+            Err(MigrationError::UnsupportedVersion {
+                found: 5,
+                first: 2,
+                current: 4
+            }),
+            migrations.migrate_item(0u16, 5)
+        );
+    }
+
+    #[test]
+    fn typed_migrations() {
+        #[derive(Debug, Clone, PartialEq)]
+        struct Block {
+            id: u32,
+            solid: bool,
+        }
+
+        // Version 1 stored raw u8 IDs, version 2 widened them, and version 3 moved to a block struct.
+        let migrations = Migrations::<u8>::new(1)
+            .then(|id| id as u32 + 1000)
+            .then(|id| Block {
+                id,
+                solid: id != 1000,
+            });
+        assert_eq!(3, migrations.current_version());
+
+        let block = |id, solid| Block { id, solid };
+        assert_eq!(Ok(block(1000, false)), migrations.migrate_item(0u8, 1));
+        assert_eq!(Ok(block(7, true)), migrations.migrate_item(7u32, 2));
+        assert_eq!(
+            Ok(block(7, false)),
+            migrations.migrate_item(block(7, false), 3)
+        );
+
+        let mut v1 = HeapVolume::new(0u8, BoundingBox::new([-1, 0, 0], [1, 1, 2]));
+        v1[[0, 0, 1]] = 3;
+        let migrated = migrations.migrate(v1.clone(), 1).unwrap();
+        assert_eq!(v1.bounding_box(), migrated.bounding_box());
+        assert_eq!(block(1003, true), migrated[[0, 0, 1]]);
+        assert_eq!(block(1000, false), migrated[[-1, 0, 0]]);
+
+        // Items have to be of the type of the version they're migrated from.
+        assert_eq!(
+            Err(MigrationError::ItemType {
+                version: 2,
+                expected: "u32",
+                found: "u8"
+            }),
+            migrations.migrate(v1, 2).map(|_| ())
+        );
+        assert!(matches!(
+            migrations.migrate_item(0u8, 3),
+            Err(MigrationError::ItemType { version: 3, .. })
+        ));
+    }
+}
+
+#[cfg(test)]
+mod snapshot {
+    use crate::prelude::*;
+    use crate::snapshot::CowVolume;
+
+    #[test]
+    fn copy_on_write() {
+        let bounds = BoundingBox::new([-4, 0, 0], [6, 5, 4]);
+        let mut vol = CowVolume::new(0u32, bounds, [4, 4, 4]);
+        vol[[5, 4, 3]] = 7;
+
+        let snapshot = vol.snapshot();
+        assert_eq!(bounds, snapshot.bounding_box());
+        assert_eq!(
+            vol.iter().collect::<Vec<_>>(),
+            snapshot.iter().collect::<Vec<_>>()
+        );
+        // 3 tiles along X, 2 along Y, and 1 along Z.
+        assert_eq!(6, vol.shared_tiles());
+
+        vol[[-4, 0, 0]] = 1;
+        vol[[-3, 1, 2]] = 2;
+        assert_eq!(5, vol.shared_tiles());
+        assert_eq!(Some(&0), snapshot.get([-4, 0, 0]));
+        assert_eq!(Some(&7), snapshot.ls_get([9, 4, 3]));
+        assert_eq!(Some(&2), vol.get([-3, 1, 2]));
+        assert_eq!(None, snapshot.get([6, 0, 0]));
+
+        drop(snapshot);
+        assert_eq!(0, vol.shared_tiles());
+        vol.validate().unwrap();
+
+        // A single tile covers everything, however large the tile dimensions are.
+        let mut vol = CowVolume::new(0u32, bounds, [i64::MAX; 3]);
+        let _snapshot = vol.snapshot();
+        assert_eq!(1, vol.shared_tiles());
+        vol[[5, 4, 3]] = 1;
+        assert_eq!(0, vol.shared_tiles());
+    }
+
+    #[test]
+    fn read_on_other_thread() {
+        let bounds = BoundingBox::new_origin([16, 16, 16]);
+        let mut vol = CowVolume::new(1u8, bounds, [8, 8, 8]);
+
+        let snapshot = vol.snapshot();
+        std::thread::scope(|scope| {
+            let reader = scope.spawn(|| snapshot.iter().map(|&item| item as u64).sum::<u64>());
+            for pos in bounds {
+                vol[pos] = 2;
+            }
+            assert_eq!(16 * 16 * 16, reader.join().unwrap());
+        });
+
+        assert!(vol.iter().all(|&item| item == 2));
+    }
+}
+
+#[cfg(test)]
+mod promote {
+    use crate::prelude::*;
+    use crate::promote::{GrowthPolicy, PromotableVolume};
+
+    #[test]
+    fn promotes_when_outgrown() {
+        let mut vol = PromotableVolume::<4, 4, 4, u8>::new(0, GrowthPolicy::Exact);
+        assert_eq!(0, vol.set([3, 3, 3], 1));
+        assert!(!vol.is_promoted());
+        assert_eq!(0, vol.heap_size());
+
+        assert_eq!(0, vol.set([-1, 2, 5], 2));
+        assert!(vol.is_promoted());
+        assert_eq!(BoundingBox::new([-1, 0, 0], [4, 4, 6]), vol.bounding_box());
+        assert_eq!(
+            (1, 2, 0),
+            (vol[[3, 3, 3]], vol[[-1, 2, 5]], vol[[-1, 0, 0]])
+        );
+
+        // Writing through `VolumeMut` doesn't grow the volume.
+        assert_eq!(None, vol.get_mut([10, 0, 0]));
+        assert_eq!(Ok(()), vol.validate());
+
+        let heap = vol.clone().into_heap();
+        assert!(vol.iter_indexed().all(|(idx, item)| heap[idx] == *item));
+    }
+
+    #[test]
+    fn growth_policies() {
+        let grown = |policy, pos: [i64; 3]| {
+            let mut vol = PromotableVolume::<2, 2, 2, u8>::new(0, policy);
+            vol.set(pos, 1);
+            vol.bounding_box()
+        };
+
+        assert_eq!(
+            BoundingBox::new([0, 0, 0], [2, 2, 4]),
+            grown(GrowthPolicy::Doubling, [0, 0, 2])
+        );
+        assert_eq!(
+            BoundingBox::new([-7, 0, 0], [2, 2, 2]),
+            grown(GrowthPolicy::Doubling, [-7, 1, 1])
+        );
+        assert_eq!(
+            BoundingBox::new([0, -3, 0], [2, 2, 7]),
+            grown(GrowthPolicy::Padded(2), [1, -1, 4])
+        );
+
+        let mut vol = PromotableVolume::<2, 2, 2, u8>::new(9, GrowthPolicy::default());
+        vol.reserve(BoundingBox::new([1, 1, 1], [3, 2, 2]));
+        assert_eq!(BoundingBox::new([0, 0, 0], [3, 2, 2]), vol.bounding_box());
+        assert!(vol.iter().all(|&item| item == 9));
+    }
+}
+
+#[cfg(test)]
+mod registry {
+    use crate::prelude::*;
+    use crate::registry::ItemRegistry;
+    use crate::types::RegistryFullError;
+
+    #[derive(Debug, PartialEq)]
+    struct Block {
+        name: &'static str,
+        hardness: f32,
+    }
+
+    #[test]
+    fn dyn_item_volume() {
+        let mut registry = ItemRegistry::<u16>::new();
+        let air = registry.register(()).unwrap();
+        let stone = registry
+            .register(Block {
+                name: "stone",
+                hardness: 1.5,
+            })
+            .unwrap();
+        let fluid = registry.register("water").unwrap();
+
+        let mut vol = DynItemVolume::new(registry, air, BoundingBox::new([-2, 0, -2], [2, 4, 2]));
+        vol.fill_range([-2, 0, -2]..[2, 2, 2], stone);
+        vol[[0, 2, 0]] = fluid;
+
+        assert_eq!(
+            Some(1.5),
+            vol.get_item_as::<Block, _>([1, 1, 1]).map(|b| b.hardness)
+        );
+        assert_eq!(None, vol.get_item_as::<Block, _>([1, 3, 1]));
+        assert_eq!(Some(&"water"), vol.get_item_as::<&str, _>([0, 2, 0]));
+        assert!(vol.get_item([0, 3, 0]).unwrap().is::<()>());
+
+        let blocks = vol.registry().iter_as::<Block>().collect::<Vec<_>>();
+        assert_eq!(1, blocks.len());
+        assert_eq!((stone, "stone"), (blocks[0].0, blocks[0].1.name));
+
+        assert_eq!(None, vol.find_unregistered());
+        vol[[-1, 3, 1]] = 7;
+        assert_eq!(Some([-1, 3, 1]), vol.find_unregistered());
+    }
+
+    #[test]
+    fn full_registry() {
+        let mut registry = ItemRegistry::<u8>::new();
+        for i in 0..256 {
+            assert_eq!(Ok(i as u8), registry.register(i));
+        }
+        assert_eq!(
+            Err(RegistryFullError { capacity: 256 }),
+            registry.register(0)
+        );
+        assert_eq!(256, registry.len());
+    }
+}
+
+#[cfg(test)]
+mod thread_safety {
+    use crate::cache::{VolumeCache, VolumePin, WeakVolumeHandle};
+    use crate::chunked::{ChunkedVolume, DynamicLayout};
+    use crate::compact::{CompactVolume, PaletteVolume, RleVolume};
+    use crate::draw::LineIterator;
+    use crate::flags::FlagVolume;
+    use crate::generate::Cached;
+    use crate::geometry::{SphereIterator, VolumeSphereIterator};
+    use crate::meshed::MeshedVolume;
+    use crate::migrate::Migrations;
+    use crate::multi::{ChannelMut, MultiVolume};
+    use crate::observe::Observed;
+    use crate::occupancy::OccupancyTree;
+    use crate::pool::{PoolKey, VolumePool};
+    use crate::prelude::*;
+    use crate::promote::PromotableVolume;
+    use crate::registry::ItemRegistry;
+    use crate::scrolling::ScrollingVolume;
+    use crate::selection::SelectionIterator;
+    use crate::sequence::VolumeSequence;
+    use crate::snapshot::{CowVolume, VolumeSnapshot};
+    use crate::sparse::HashVolume;
+    use crate::stencil::Neighborhood;
+    use crate::tile::TileMut;
+    use crate::types::*;
+    use crate::view::{
+        LocalView, LocalViewMut, PermutedView, Projection, RebasedView, ScaledView, SubVolume,
+        SubVolumeMut, TorusVolume,
+    };
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+    use std::rc::Rc;
+
+    type Heap = HeapVolume<u32>;
+    type Project = fn(&u32) -> &u32;
+
+    assert_impl_all!(Heap: Send, Sync);
+    assert_impl_all!(StackVolume<2, 2, 2, u32>: Send, Sync);
+    assert_impl_all!(SliceVolume<'static, u32>: Send, Sync);
+    assert_impl_all!(SliceVolumeMut<'static, u32>: Send, Sync);
+    assert_impl_all!(UniformVolume<u32>: Send, Sync);
+    assert_impl_all!(ChunkedVolume<u32>: Send, Sync);
+    assert_impl_all!(ChunkedVolume<u32, DynamicLayout>: Send, Sync);
+    assert_impl_all!(CompactVolume<u32>: Send, Sync);
+    assert_impl_all!(RleVolume<u32>: Send, Sync);
+    assert_impl_all!(PaletteVolume<u32>: Send, Sync);
+    assert_impl_all!(MultiVolume<(u32, f32)>: Send, Sync);
+    assert_impl_all!(ChannelMut<'static, u32>: Send, Sync);
+    assert_impl_all!(TileMut<'static, u32>: Send, Sync);
+    assert_impl_all!(ScrollingVolume<u32>: Send, Sync);
+    assert_impl_all!(DynItemVolume<u16>: Send, Sync);
+    assert_impl_all!(LayeredVolume<Heap>: Send, Sync);
+    assert_impl_all!(FlagVolume<8>: Send, Sync);
+    assert_impl_all!(PromotableVolume<4, 4, 4, u32>: Send, Sync);
+    assert_impl_all!(VolumePool<u32>: Send, Sync);
+    assert_impl_all!(PoolKey: Send, Sync);
+    assert_impl_all!(HashVolume<u32>: Send, Sync);
+    assert_impl_all!(CowVolume<u32>: Send, Sync);
+    assert_impl_all!(VolumeSnapshot<u32>: Send, Sync);
+    assert_impl_all!(MeshedVolume<Heap, Vec<[f32; 3]>>: Send, Sync);
+    assert_impl_all!(ItemRegistry<u32>: Send, Sync);
+    assert_impl_all!(Migrations<u32>: Send, Sync);
+    assert_impl_all!(LocalView<'static, Heap>: Send, Sync);
+    assert_impl_all!(RebasedView<Heap>: Send, Sync);
+    assert_impl_all!(PermutedView<Heap>: Send, Sync);
+    assert_impl_all!(LocalViewMut<'static, Heap>: Send, Sync);
+    assert_impl_all!(OccupancyTree: Send, Sync);
+    assert_impl_all!(Observed<Heap>: Send, Sync);
+    assert_impl_all!(VolumeSequence<Heap>: Send, Sync);
+    assert_impl_all!(VolumeCache<u64, Heap>: Send, Sync);
+    assert_impl_all!(WeakVolumeHandle<u64>: Send, Sync);
+    assert_impl_all!(VolumePin<u64>: Send, Sync);
+    assert_impl_all!(Cached<fn(BoundingBox) -> Heap>: Send, Sync);
+    assert_impl_all!(Projection<'static, Heap, Project>: Send, Sync);
+    assert_impl_all!(TorusVolume<Heap>: Send, Sync);
+    assert_impl_all!(SubVolume<'static, Heap>: Send, Sync);
+    assert_impl_all!(SubVolumeMut<'static, Heap>: Send, Sync);
+    assert_impl_all!(ScaledView<Heap, 4>: Send, Sync);
+    assert_impl_all!(Neighborhood<u32, 3>: Send, Sync);
+    assert_impl_all!(Selection: Send, Sync);
+
+    assert_impl_all!(BoundingBoxIterator: Send, Sync);
+    assert_impl_all!(StridedIterator: Send, Sync);
+    assert_impl_all!(OrderedIterator: Send, Sync);
+    assert_impl_all!(SphereIterator: Send, Sync);
+    assert_impl_all!(LineIterator: Send, Sync);
+    assert_impl_all!(SelectionIterator<'static>: Send, Sync);
+    assert_impl_all!(VolumeIterator<'static, Heap>: Send, Sync);
+    assert_impl_all!(IndexedVolumeIterator<'static, Heap>: Send, Sync);
+    assert_impl_all!(StridedVolumeIterator<'static, Heap>: Send, Sync);
+    assert_impl_all!(OrderedVolumeIterator<'static, Heap>: Send, Sync);
+    assert_impl_all!(VolumeSphereIterator<'static, Heap>: Send, Sync);
+
+    assert_not_impl_any!(HeapVolume<Rc<u32>>: Send, Sync);
+    assert_not_impl_any!(UniformVolume<Rc<u32>>: Send, Sync);
+    assert_not_impl_any!(ChunkedVolume<Rc<u32>>: Send, Sync);
+}
+
+#[cfg(test)]
+mod draw {
+    use crate::draw::{box_outline, line};
+    use crate::prelude::*;
+
+    #[test]
+    fn lines() {
+        assert_eq!(
+            vec![[0, 0, 0]],
+            line([0, 0, 0], [0, 0, 0]).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![[0, 0, 0], [1, 1, 0], [2, 1, -1], [3, 2, -1]],
+            line([0, 0, 0], [3, 2, -1]).collect::<Vec<_>>()
+        );
+
+        let points = line([5, -3, 7], [-4, 6, 1]).collect::<Vec<_>>();
+        assert_eq!(10, points.len());
+        assert_eq!(Some(&[-4, 6, 1]), points.last());
+        assert!(points
+            .windows(2)
+            .all(|w| (0..3).all(|axis| (w[0][axis] - w[1][axis]).abs() <= 1)));
+
+        let mut vol = HeapVolume::new(0u8, BoundingBox::new_origin([4, 4, 4]));
+        assert_eq!(4, vol.draw_line([-2, -2, -2], [5, 5, 5], 1));
+        assert!((0..4).all(|i| vol[[i, i, i]] == 1));
+        assert_eq!(4, vol.iter().filter(|&&item| item == 1).count());
+    }
+
+    #[test]
+    fn boxes() {
+        let bounds = BoundingBox::new([-1, 0, 2], [3, 3, 7]);
+        let outline = box_outline(bounds);
+        let expected = bounds
+            .into_iter()
+            .filter(|&pos| {
+                (0..3)
+                    .filter(|&axis| {
+                        pos[axis] == bounds.min()[axis] || pos[axis] == bounds.max()[axis] - 1
+                    })
+                    .count()
+                    >= 2
+            })
+            .count();
+        assert_eq!(expected, outline.len());
+        assert!(outline.iter().all(|&pos| bounds.contains(pos)));
+
+        assert_eq!(3, box_outline(BoundingBox::new_origin([1, 3, 1])).len());
+        assert!(box_outline(BoundingBox::new_origin([0, 3, 1])).is_empty());
+
+        let mut vol = HeapVolume::new(0u8, BoundingBox::new_origin([4, 4, 4]));
+        assert_eq!(32, vol.draw_box_outline(vol.bounding_box(), 1));
+        assert_eq!(0, vol[[1, 1, 0]]);
+        assert_eq!(
+            8,
+            vol.draw_box_filled(BoundingBox::new([1, 1, 1], [3, 3, 3]), 2)
+        );
+        assert_eq!(32 + 8, vol.iter().filter(|&&item| item != 0).count());
+    }
+
+    #[test]
+    fn text() {
+        use crate::draw::{AxisPlane, VoxelFont};
+
+        let font = VoxelFont::tiny();
+        assert_eq!(font.glyph('a'), font.glyph('A'));
+        assert_eq!(None, font.glyph('~'));
+
+        // "1" is 010/110/010/010/111 from top to bottom, so its bottom row is full.
+        let one = font.rasterize([10, 20, 30], AxisPlane::Xy, "1");
+        assert_eq!(8, one.len());
+        assert!(one.contains(&[10, 20, 30]) && one.contains(&[12, 20, 30]));
+        assert!(one.contains(&[11, 24, 30]) && !one.contains(&[10, 24, 30]));
+
+        let rotated = font.rasterize([0, 0, 0], AxisPlane::Zy, "1");
+        assert!(rotated.iter().all(|pos| pos[0] == 0) && rotated.contains(&[0, 0, 2]));
+
+        let lines = font.rasterize([0, 0, 0], AxisPlane::Xz, "1 1\n-");
+        assert_eq!(8 * 2 + 3, lines.len());
+        assert!(lines.contains(&[8, 0, 0]) && lines.contains(&[0, 0, -4]));
+
+        let mut vol = HeapVolume::new(0u8, BoundingBox::new_origin([7, 5, 1]));
+        assert_eq!(
+            7 + 13,
+            vol.draw_text([0, 0, 0], AxisPlane::Xy, "10", &font, 1)
+        );
+        assert_eq!(0, vol[[3, 0, 0]]);
+    }
+}
+
+#[cfg(test)]
+mod grow {
+    use crate::grow::region_grow;
+    use crate::prelude::*;
+
+    #[test]
+    fn bounded_region_growth() {
+        // A 5x1x1 corridor of air along X, walled off everywhere else.
+        let mut vol = HeapVolume::new(false, BoundingBox::new_origin([8, 3, 3]));
+        for x in 0..5 {
+            vol[[x, 1, 1]] = true;
+        }
+
+        let full = region_grow(&vol, [[2, 1, 1]], |&air| air, 100);
+        assert!(!full.truncated);
+        assert_eq!(5, full.region.len());
+        assert!(full.region.contains([0, 1, 1]) && !full.region.contains([5, 1, 1]));
+
+        let partial = region_grow(&vol, [[2, 1, 1]], |&air| air, 3);
+        assert!(partial.truncated);
+        assert_eq!(3, partial.region.len());
+        assert!(partial.region.contains([1, 1, 1]) && partial.region.contains([3, 1, 1]));
+
+        let exact = region_grow(&vol, [[2, 1, 1]], |&air| air, 5);
+        assert!(!exact.truncated);
+
+        let skipped = region_grow(&vol, [[0, 0, 0], [-1, 1, 1]], |&air| air, 100);
+        assert!(skipped.region.is_empty() && !skipped.truncated);
+    }
+}
+
+#[cfg(test)]
+mod tile {
+    use crate::prelude::*;
+
+    #[test]
+    fn tiles_cover_volume() {
+        let bounds = BoundingBox::new([-3, 2, 0], [5, 7, 4]);
+        let mut vol = HeapVolume::new(0u32, bounds);
+
+        let mut tiles = vol.tiles_mut([3, 2, 4]);
+        assert_eq!(tiles.len(), 3 * 3);
+        assert_eq!(
+            tiles[0].bounding_box(),
+            BoundingBox::new([-3, 2, 0], [0, 4, 4])
+        );
+        assert_eq!(
+            tiles[8].bounding_box(),
+            BoundingBox::new([3, 6, 0], [5, 7, 4])
+        );
+
+        for tile in &mut tiles {
+            for idx in tile.bounding_box() {
+                tile[idx] += 1;
+            }
+            assert_eq!(tile.get([-4, 2, 0]), None);
+        }
+
+        assert!(vol.iter().all(|&item| item == 1));
+        assert!(HeapVolume::new(0u32, BoundingBox::new_origin([0, 4, 4]))
+            .tiles_mut([1, 1, 1])
+            .is_empty());
+    }
+}
+
+#[cfg(test)]
+mod conformance {
+    use crate::layered::LayeredVolume;
+    use crate::meshed::MeshedVolume;
+    use crate::prelude::*;
+
+    crate::conformance_tests!(heap, HeapVolume<u32>, |bounds| HeapVolume::new(0, bounds));
+    crate::conformance_tests!(meshed, MeshedVolume<HeapVolume<u32>, ()>, |bounds| {
+        MeshedVolume::new(HeapVolume::new(0, bounds), [2, 2, 2])
+    });
+    crate::conformance_tests!(layered, LayeredVolume<HeapVolume<u32>>, |bounds| {
+        let mut vol = LayeredVolume::new(HeapVolume::new(0, bounds));
+        vol.push_layer();
+        vol
+    });
+}
+
+#[cfg(all(test, feature = "rand"))]
+mod random {
+    use crate::prelude::*;
+    use crate::random::PositionRng;
+
+    #[test]
+    fn seeded_fill() {
+        let bb = BoundingBox::new_origin([8, 8, 8]);
+
+        let mut a = HeapVolume::new(0u32, bb);
+        let mut b = HeapVolume::new(0u32, bb);
+        a.fill_random(&mut PositionRng::from_seed(1), |rng| rng.next_u32());
+        b.fill_random(&mut PositionRng::from_seed(1), |rng| rng.next_u32());
+        assert_eq!(a, b);
+        b.fill_random(&mut PositionRng::from_seed(2), |rng| rng.next_u32());
+        assert_ne!(a, b);
+
+        let mut sparse = HeapVolume::new(false, bb);
+        let count = sparse.scatter(0.25, &mut PositionRng::from_seed(3), true);
+        assert_eq!(count, sparse.iter().filter(|&&b| b).count());
+        assert!((64..192).contains(&count));
+        assert_eq!(
+            0,
+            HeapVolume::new(false, bb).scatter(0.0, &mut PositionRng::from_seed(3), true)
+        );
+    }
+
+    #[test]
+    fn position_stable_fill() {
+        let whole = BoundingBox::new([-4, -4, -4], [4, 4, 4]);
+        let mut vol = HeapVolume::new(0u64, whole);
+        vol.fill_random_stable(42, |rng| rng.next_u64());
+
+        for octant in whole.octants() {
+            let mut chunk = HeapVolume::new(0u64, octant);
+            chunk.fill_random_stable(42, |rng| rng.next_u64());
+            assert!(octant.into_iter().all(|idx| chunk[idx] == vol[idx]));
+
+            let mut scattered = HeapVolume::new(false, octant);
+            let mut whole_scattered = HeapVolume::new(false, whole);
+            scattered.scatter_stable(0.5, 7, true);
+            whole_scattered.scatter_stable(0.5, 7, true);
+            assert!(octant
+                .into_iter()
+                .all(|idx| scattered[idx] == whole_scattered[idx]));
+        }
+    }
+}
+
+#[cfg(all(test, feature = "wgpu"))]
+mod gpu {
+    use crate::gpu::{texture_data, texture_extent};
+    use crate::prelude::*;
+
+    #[test]
+    fn texture_layout() {
+        let bb = BoundingBox::new([-1, -1, -1], [2, 1, 1]);
+        let mut vol = HeapVolume::new(0u16, bb);
+        for (i, idx) in bb.into_iter().enumerate() {
+            vol[idx] = i as u16;
+        }
+
+        let extent = texture_extent(bb).unwrap();
+        assert_eq!(
+            [3, 2, 2],
+            [extent.width, extent.height, extent.depth_or_array_layers]
+        );
+
+        let (tight, layout) = texture_data(&vol, false).unwrap();
+        assert_eq!(Some(6), layout.bytes_per_row);
+        assert_eq!(Some(2), layout.rows_per_image);
+        assert_eq!(
+            (0..12u16).flat_map(|i| i.to_ne_bytes()).collect::<Vec<_>>(),
+            tight
+        );
+
+        let (padded, layout) = texture_data(&vol, true).unwrap();
+        assert_eq!(Some(256), layout.bytes_per_row);
+        assert_eq!(256 * 4, padded.len());
+        for row in 0..4 {
+            assert_eq!(
+                &tight[row * 6..row * 6 + 6],
+                &padded[row * 256..row * 256 + 6]
+            );
+            assert!(padded[row * 256 + 6..(row + 1) * 256]
+                .iter()
+                .all(|&b| b == 0));
+        }
+    }
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod tracing {
+    use crate::morph::{self, Connectivity, StructuringElement};
+    use crate::prelude::*;
+    use std::sync::{Arc, Mutex};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata};
+
+    /// Records the names of created spans and the amount of events.
+    #[derive(Default, Clone)]
+    struct Recorder {
+        spans: Arc<Mutex<Vec<&'static str>>>,
+        events: Arc<Mutex<usize>>,
+    }
+
+    impl tracing::Subscriber for Recorder {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            let mut spans = self.spans.lock().unwrap();
+            spans.push(span.metadata().name());
+            Id::from_u64(spans.len() as u64)
+        }
+
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, _: &Event<'_>) {
+            *self.events.lock().unwrap() += 1;
+        }
+
+        fn enter(&self, _: &Id) {}
+        fn exit(&self, _: &Id) {}
+    }
+
+    #[test]
+    fn spans_and_events() {
+        let recorder = Recorder::default();
+
+        tracing::subscriber::with_default(recorder.clone(), || {
+            let mut vol = HeapVolume::new(false, BoundingBox::new_origin([4, 4, 4]));
+            vol.insert(
+                [1, 1, 1],
+                &HeapVolume::new(true, BoundingBox::new_origin([2, 2, 2])),
+            )
+            .unwrap();
+            morph::dilate(&vol, StructuringElement::new(Connectivity::Six, 1));
+        });
+
+        assert_eq!(vec!["insert", "dilate"], *recorder.spans.lock().unwrap());
+        // 3 allocations and 1 insertion.
+        assert_eq!(4, *recorder.events.lock().unwrap());
+    }
+}
+
+#[cfg(all(test, feature = "rayon"))]
+mod rayon {
+    use crate::prelude::*;
+    use crate::stencil::{self, BoundaryCondition, Neighborhood};
+
+    #[test]
+    fn par_stencil() {
+        let mut vol = HeapVolume::new(0i64, BoundingBox::new([-3, 2, 0], [5, 7, 4]));
+        for idx in vol.bounding_box() {
+            vol[idx] = idx[0] * 31 + idx[1] * 7 - idx[2];
+        }
+
+        let blur = |n: &Neighborhood<i64, 3>| n.iter().map(|(_, &item)| item).sum::<i64>();
+        assert_eq!(
+            stencil::apply(&vol, BoundaryCondition::Periodic, blur),
+            stencil::par_apply(&vol, BoundaryCondition::Periodic, blur)
+        );
+    }
+
+    #[test]
+    fn par_apply_tiled() {
+        let run = |threads: usize| {
+            let mut vol = HeapVolume::new(0i64, BoundingBox::new([-3, 2, 0], [13, 9, 6]));
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap();
+
+            pool.install(|| {
+                vol.par_apply_tiled([4, 3, 5], |tile| {
+                    let min = tile.bounding_box().min();
+                    for idx in tile.bounding_box() {
+                        tile[idx] = idx[0] * 31 + idx[1] * 7 - idx[2] + min[0] * min[1];
+                    }
+                })
+            });
+            vol
+        };
+
+        let serial = run(1);
+        assert_eq!(serial, run(4));
+        assert_eq!(serial[[12, 8, 5]], 12 * 31 + 8 * 7 - 5 + 9 * 8);
+    }
+
+    #[test]
+    fn par_scan_columns() {
+        let mut vol = HeapVolume::new(0i64, BoundingBox::new([-3, 2, 0], [5, 7, 4]));
+        for idx in vol.bounding_box() {
+            vol[idx] = idx[0] * 31 + idx[1] * 7 - idx[2];
+        }
+
+        let f = |acc: i64, &item: &i64| (acc * 3 + item, acc - item);
+        for axis in Axis::ALL {
+            for descending in [false, true] {
+                let (mut serial, mut parallel) = (vol.clone(), vol.clone());
+                serial.scan_columns(axis, descending, 1, f);
+                parallel.par_scan_columns(axis, descending, 1, f);
+                assert_eq!(serial, parallel, "axis {axis:?}, descending: {descending}");
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod mock {
+    use crate::mock::{Access, MockVolume};
+    use crate::prelude::*;
+    use static_assertions::assert_impl_all;
+
+    assert_impl_all!(MockVolume<u32>: Send, Sync);
+    crate::conformance_tests!(MockVolume<u32>, |bounds| MockVolume::new(0, bounds));
+
+    #[test]
+    fn records_accesses() {
+        let mut vol = MockVolume::new(0u8, BoundingBox::new([-1, 0, 0], [1, 1, 2]));
+        vol.respond([0, 0, 1], 7);
+
+        assert_eq!(vec![0, 0, 0, 7], vol.iter().copied().collect::<Vec<_>>());
+        assert_eq!(Some(7), vol.swap([0, 0, 1], 3));
+        assert_eq!(None, vol.get([5, 5, 5]));
+        assert_eq!(None, vol.ls_get([2, 0, 0]));
+
+        vol.expect(Access::Get([-1, 0, 0]))
+            .expect(Access::Get([0, 0, 0]))
+            .expect(Access::Get([-1, 0, 1]))
+            .expect(Access::Get([0, 0, 1]))
+            .expect(Access::GetMut([0, 0, 1]))
+            .expect(Access::Get([5, 5, 5]))
+            .expect(Access::Get([1, 0, 0]));
+        vol.verify();
+
+        vol.clear_accesses();
+        assert!(vol.accesses().is_empty());
+        assert_eq!(3, vol.into_inner()[[0, 0, 1]]);
+    }
+
+    #[test]
+    #[should_panic(expected = "access #1 doesn't match")]
+    fn unexpected_access() {
+        let vol = MockVolume::new(0u8, BoundingBox::new_origin([2, 2, 2]));
+        vol.get([0, 0, 0]);
+        vol.get([1, 0, 0]);
+
+        let mut vol = vol;
+        vol.expect(Access::Get([0, 0, 0]))
+            .expect(Access::Get([0, 1, 0]));
+        vol.verify();
+    }
+}
+
+#[cfg(all(test, feature = "shared"))]
+mod shared {
+    use crate::prelude::*;
+    use crate::shared::SharedVolume;
+    use crate::types::SharedVolumeError;
+    use static_assertions::assert_impl_all;
+
+    assert_impl_all!(SharedVolume<u32>: Send, Sync);
+
+    #[test]
+    fn mapped_twice() {
+        let path = std::env::temp_dir().join(format!("volume-shared-{}", std::process::id()));
+        let bounds = BoundingBox::new([-2, 0, 5], [2, 3, 8]);
+
+        // Two mappings of the same file behave like two processes sharing the volume.
+        let mut writer = unsafe { SharedVolume::create(&path, 1u32, bounds) }.unwrap();
+        let reader = unsafe { SharedVolume::<u32>::open(&path) }.unwrap();
+        assert_eq!(bounds, reader.bounding_box());
+        assert!(reader.iter().all(|&item| item == 1));
+
+        writer[[-1, 2, 6]] = 42;
+        assert_eq!(42, reader[[-1, 2, 6]]);
+        reader.prefetch_region([0, 1, 6]..[9, 9, 9]);
+        reader.prefetch_region(BoundingBox::new([5, 5, 5], [6, 6, 6]));
+        assert_eq!(None, reader.get([2, 0, 5]));
+        assert!(reader.as_slice_volume().iter().eq(writer.iter()));
+        writer.flush().unwrap();
+
+        let error = unsafe { SharedVolume::<u16>::open(&path) }.unwrap_err();
+        assert!(matches!(
+            error,
+            SharedVolumeError::ItemLayout {
+                expected: (2, 2),
+                found: (4, 4)
+            }
+        ));
+
+        drop((writer, reader));
+        std::fs::write(&path, b"not a volume").unwrap();
+        let error = unsafe { SharedVolume::<u32>::open(&path) }.unwrap_err();
+        assert!(matches!(error, SharedVolumeError::InvalidHeader));
+
+        // Bounds whose sides don't fit in an i64.
+        let mut header = b"VOLSHM01".to_vec();
+        for field in [
+            i64::MIN,
+            i64::MIN,
+            i64::MIN,
+            i64::MAX,
+            i64::MAX,
+            i64::MAX,
+            4,
+            4,
+        ] {
+            header.extend(field.to_le_bytes());
+        }
+        header.resize(128, 0);
+        std::fs::write(&path, header).unwrap();
+        let error = unsafe { SharedVolume::<u32>::open(&path) }.unwrap_err();
+        assert!(matches!(error, SharedVolumeError::InvalidHeader));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
+
+#[cfg(all(test, feature = "sim"))]
+mod sim {
+    use crate::prelude::*;
+    use crate::sim::step_fluid;
+
+    #[test]
+    fn falling_fluid() {
+        let mut vol = HeapVolume::new(0u8, BoundingBox::new_origin([1, 4, 1]));
+        vol[[0, 3, 0]] = 8;
+
+        assert_eq!(2, step_fluid(&mut vol, |_| false, 8));
+        assert_eq!(8, vol[[0, 2, 0]]);
+
+        while step_fluid(&mut vol, |_| false, 8) > 0 {}
+        assert_eq!(8, vol[[0, 0, 0]]);
+        assert_eq!(8u32, vol.iter().map(|&l| l as u32).sum());
+    }
+
+    #[test]
+    fn spreading_fluid() {
+        // A floor at y = 0 with a wall at x = 4, and fluid poured in at one spot.
+        let bounds = BoundingBox::new_origin([6, 3, 1]);
+        let solid = |pos: [i64; 3]| pos[1] == 0 || pos[0] == 4;
+        let mut vol = HeapVolume::new(0u8, bounds);
+        vol[[1, 2, 0]] = 8;
+
+        let mut steps = 0;
+        while step_fluid(&mut vol, solid, 8) > 0 {
+            steps += 1;
+            assert!(steps < 100, "fluid never settled");
+        }
+
+        assert_eq!(8u32, vol.iter().map(|&l| l as u32).sum());
+        assert!(bounds
+            .into_iter()
+            .filter(|&p| solid(p))
+            .all(|p| vol[p] == 0));
+
+        // Neighboring cells of settled fluid differ by at most one level.
+        let floor = (0..4).map(|x| vol[[x, 1, 0]]).collect::<Vec<_>>();
+        assert!(floor.windows(2).all(|w| w[0].abs_diff(w[1]) <= 1));
+        assert!(floor.iter().all(|&level| level > 0));
+        assert_eq!(0, vol[[5, 1, 0]]);
+    }
+
+    #[test]
+    fn full_fluid_levels() {
+        // Full cells next to each other with the largest possible amount of levels.
+        let mut vol = HeapVolume::new(255u8, BoundingBox::new_origin([3, 1, 1]));
+        vol[[2, 0, 0]] = 0;
+
+        assert_eq!(2, step_fluid(&mut vol, |_| false, 255));
+        assert_eq!(vec![255, 254, 1], vol.iter().copied().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn settling_sand() {
+        #[derive(Copy, Clone, PartialEq, Debug)]
+        enum Cell {
+            Air,
+            Sand,
+            Stone,
+        }
+
+        let mut vol = HeapVolume::new(Cell::Air, BoundingBox::new_origin([3, 4, 1]));
+        for x in 0..3 {
+            vol[[x, 0, 0]] = Cell::Stone;
+        }
+        vol[[1, 2, 0]] = Cell::Sand;
+        vol[[1, 3, 0]] = Cell::Sand;
+
+        let settle = |vol: &mut HeapVolume<Cell>| {
+            crate::sim::settle(vol, |&c| c == Cell::Sand, |&c| c == Cell::Air)
+        };
+
+        // The column falls onto the floor together.
+        assert_eq!(
+            Some(BoundingBox::new([1, 1, 0], [2, 4, 1])),
+            settle(&mut vol)
+        );
+        assert_eq!(
+            [Cell::Sand, Cell::Sand, Cell::Air],
+            [1, 2, 3].map(|y| vol[[1, y, 0]])
+        );
+
+        // The top grain slides off diagonally, since the one below it can't move.
+        assert_eq!(
+            Some(BoundingBox::new([0, 1, 0], [2, 3, 1])),
+            settle(&mut vol)
+        );
+        assert_eq!(None, settle(&mut vol));
+        assert_eq!(
+            [Cell::Sand, Cell::Sand, Cell::Air],
+            [0, 1, 2].map(|x| vol[[x, 1, 0]])
+        );
+    }
+}
+
+#[cfg(all(test, feature = "bevy"))]
+mod bevy {
+    use crate::bevy_support::{VolumeAsset, VolumeComponent};
+    use crate::prelude::*;
+    use bevy_math::IVec3;
+    use bevy_reflect::{Struct, TypePath};
+
+    #[test]
+    fn integration() {
+        let vol = HeapVolume::new(
+            1u8,
+            BoundingBox::from(IVec3::new(-2, -2, -2)..IVec3::new(2, 2, 2)),
+        );
+        assert_eq!(
+            BoundingBox::new([-2, -2, -2], [2, 2, 2]),
+            vol.bounding_box()
+        );
+        assert_eq!(Some(&1), vol.get(IVec3::new(-2, 1, 0)));
+
+        let bb = vol.bounding_box();
+        let min = bb.field("min").unwrap().downcast_ref::<[i64; 3]>().unwrap();
+        assert_eq!([-2, -2, -2], *min);
+
+        let asset = VolumeAsset(vol.clone());
+        assert_eq!(1, asset[[0, 0, 0]]);
+        assert!(VolumeAsset::<HeapVolume<u8>>::type_path().contains("HeapVolume<u8>"));
+        assert_ne!(
+            VolumeAsset::<HeapVolume<u8>>::type_path(),
+            VolumeComponent::<HeapVolume<u8>>::type_path()
+        );
+        assert_ne!(
+            VolumeAsset::<HeapVolume<u8>>::type_path(),
+            VolumeAsset::<HeapVolume<u16>>::type_path()
+        );
+    }
+}
+
+#[cfg(feature = "nalgebra")]
+#[test]
+fn nalgebra_bounding_box_support() {
+    extern crate nalgebra as na;
+
+    let v1 = na::vector![0, 0, 0];
+    let v2 = na::vector![10, 10, 10];
+
+    let vol = HeapVolume::new(10, v1..v2);
+
+    assert_eq!(
+        vol.bounding_box(),
+        BoundingBox::new([0, 0, 0], [10, 10, 10])
+    );
+}
+
+#[cfg(all(test, feature = "small-bounds"))]
+mod small_bounds {
+    use crate::prelude::*;
+    use crate::traits::Bounds;
+    use crate::types::{SmallBounds, SmallBoundsError};
+
+    #[test]
+    fn conversions() {
+        static_assertions::assert_eq_size!(SmallBounds, [i16; 6]);
+        static_assertions::assert_eq_size!(SmallBounds<i32>, [i32; 6]);
+
+        let bb = BoundingBox::new([-16, 0, 32], [0, 16, 48]);
+        let small = SmallBounds::<i16>::try_from(bb).unwrap();
+        assert_eq!(BoundingBox::from(small), bb);
+        assert_eq!(small.to_string(), bb.to_string());
+        assert_eq!(small.capacity(), bb.capacity());
+        assert_eq!(
+            small.into_iter().collect::<Vec<_>>(),
+            bb.into_iter().collect::<Vec<_>>()
+        );
+
+        let wide = BoundingBox::new([0, 0, 0], [1, 40000, 1]);
+        assert_eq!(
+            SmallBounds::<i16>::try_from(wide),
+            Err(SmallBoundsError(wide))
+        );
+        assert!(SmallBounds::<i32>::try_from(wide).is_ok());
+
+        let vol = HeapVolume::new(0u8, small);
+        assert_eq!(vol.bounding_box(), bb);
+    }
+
+    #[test]
+    fn linear_index() {
+        let small = SmallBounds::new([2i16, 3, 4], [-2, -1, 0]);
+        assert_eq!(small.small_min(), [-2, -1, 0]);
+        assert_eq!(small.linear_index([-2, -1, 0]), Some(0));
+        assert_eq!(small.linear_index([-2, -1, 3]), Some(3));
+        assert_eq!(small.linear_index([1, 2, 3]), Some(63));
+        assert_eq!(small.linear_index([2, 2, 3]), None);
+
+        assert!(small.contains([1i64, 2, 3]));
+        assert!(!small.contains([1i64, 2, 4]));
+        assert!(!small.contains([0i64, 0, 100_000]));
+
+        let extreme = SmallBounds::new([i16::MIN; 3], [i16::MAX; 3]);
+        assert_eq!(extreme.linear_index([i16::MIN; 3]), Some(0));
+        assert!(extreme.contains([0i64, 0, 0]));
+
+        let unsigned = SmallBounds::new([u16::MAX - 2; 3], [u16::MAX; 3]);
+        assert_eq!(unsigned.linear_index([u16::MAX - 1; 3]), Some(7));
+        assert!(unsigned.contains([u16::MAX as i64 - 2; 3]));
+
+        // The index of the last position overflows a `usize`, but it still mustn't panic.
+        let widest = SmallBounds::new([i32::MIN; 3], [i32::MAX; 3]);
+        assert_eq!(widest.linear_index([i32::MIN; 3]), Some(0));
+        assert_eq!(widest.linear_index([i32::MAX - 1; 3]), None);
+        assert!(widest.contains([0i64, 0, 0]));
+    }
 }
@@ -0,0 +1,120 @@
+//! Splitting volumes into disjoint tiles which can be modified independently, for example in parallel.
+
+use crate::prelude::*;
+
+/// Mutable view of a rectangular tile of a [`HeapVolume`], created by [`HeapVolume::tiles_mut`].
+/// Tiles of the same volume never overlap, so they can be modified at the same time (e.g., from different threads).
+///
+/// Uses the same worldspace indices as the volume it was created from.
+pub struct TileMut<'a, T> {
+    /// The rows of the tile along the Z axis, indexed as `rows[x * dims[1] + y]` in localspace.
+    rows: Vec<&'a mut [T]>,
+    bounds: BoundingBox,
+    dims: [usize; 3],
+}
+
+impl_indexing!(['a, T], TileMut<'a, T>);
+impl_debug!(['a, T], TileMut<'a, T>);
+
+impl<T> Volume for TileMut<'_, T> {
+    type Item = T;
+
+    #[inline]
+    fn ls_get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        let [x, y, z] = idx.array::<usize>()?;
+        if x >= self.dims[0] || y >= self.dims[1] {
+            return None;
+        }
+
+        self.rows[x * self.dims[1] + y].get(z)
+    }
+
+    #[inline]
+    fn bounding_box(&self) -> BoundingBox {
+        self.bounds
+    }
+}
+
+impl<T> VolumeMut for TileMut<'_, T> {
+    #[inline]
+    fn ls_get_mut<Idx: VolumeIdx>(&mut self, idx: Idx) -> Option<&mut Self::Item> {
+        let [x, y, z] = idx.array::<usize>()?;
+        if x >= self.dims[0] || y >= self.dims[1] {
+            return None;
+        }
+
+        self.rows[x * self.dims[1] + y].get_mut(z)
+    }
+}
+
+impl<T> HeapVolume<T> {
+    /// Split this volume into disjoint tiles of (up to) `tile_dims` items, returned in X-major order.
+    /// Tiles on the upper edges of the volume are smaller if its dimensions aren't multiples of `tile_dims`.
+    ///
+    /// # Panics
+    /// Panics if any of the tile dimensions is 0.
+    #[inline]
+    pub fn tiles_mut(&mut self, tile_dims: [usize; 3]) -> Vec<TileMut<'_, T>> {
+        assert!(
+            tile_dims.iter().all(|&d| d > 0),
+            "tile dimensions must be at least 1"
+        );
+
+        let bounds = self.bounding_box();
+        let dims: [usize; 3] = crate::util::cast_ivec3(bounds.dimensions()).unwrap();
+        let counts = [0, 1, 2].map(|axis| dims[axis].div_ceil(tile_dims[axis]));
+
+        let mut tiles = BoundingBox::new_origin(counts)
+            .into_iter()
+            .map(|tile| {
+                let [min, max] = [0, 1].map(|corner| {
+                    [0, 1, 2].map(|axis| {
+                        let pos = (tile[axis] as usize + corner) * tile_dims[axis];
+                        bounds.min()[axis] + pos.min(dims[axis]) as i64
+                    })
+                });
+                let tile_bounds = BoundingBox::new(min, max);
+
+                TileMut {
+                    rows: Vec::new(),
+                    bounds: tile_bounds,
+                    dims: crate::util::cast_ivec3(tile_bounds.dimensions()).unwrap(),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        for (x, plane) in self.planes_mut().iter_mut().enumerate() {
+            for (y, row) in plane.iter_mut().enumerate() {
+                for (tz, segment) in row.chunks_mut(tile_dims[2]).enumerate() {
+                    let [tx, ty] = [x / tile_dims[0], y / tile_dims[1]];
+                    tiles[tx + counts[0] * (ty + counts[1] * tz)]
+                        .rows
+                        .push(segment);
+                }
+            }
+        }
+
+        tiles
+    }
+
+    /// Split this volume into disjoint tiles of (up to) `tile_dims` items (see [`HeapVolume::tiles_mut`]) and call `f` on
+    /// every tile in parallel using rayon. Requires the `rayon` feature.
+    ///
+    /// Since `f` can only access the tile it's given, the result is deterministic and independent of the amount of threads
+    /// (as long as `f` itself is deterministic).
+    ///
+    /// # Panics
+    /// Panics if any of the tile dimensions is 0.
+    #[cfg(feature = "rayon")]
+    #[inline]
+    pub fn par_apply_tiled<F>(&mut self, tile_dims: [usize; 3], f: F)
+    where
+        T: Send,
+        F: Fn(&mut TileMut<'_, T>) + Send + Sync,
+    {
+        use rayon::prelude::*;
+
+        debug_span!("par_apply_tiled", bounds = %self.bounding_box(), ?tile_dims);
+        self.tiles_mut(tile_dims).par_iter_mut().for_each(f);
+    }
+}
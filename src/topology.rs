@@ -0,0 +1,259 @@
+//! Topological analysis of volumes, like finding the surfaces between two kinds of cells or the voids enclosed by solid cells.
+//!
+//! Cells outside of a volume's bounds are treated as if they didn't exist, so they never border or touch any cell.
+
+use crate::prelude::*;
+
+/// One of the 6 faces of a cell, named after the direction it faces.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Face {
+    NegX,
+    PosX,
+    NegY,
+    PosY,
+    NegZ,
+    PosZ,
+}
+
+impl Face {
+    /// Every face, in the order -X, +X, -Y, +Y, -Z, +Z.
+    pub const ALL: [Face; 6] = [
+        Face::NegX,
+        Face::PosX,
+        Face::NegY,
+        Face::PosY,
+        Face::NegZ,
+        Face::PosZ,
+    ];
+
+    /// The offset from a cell to the neighbor sharing this face with it.
+    #[inline]
+    pub const fn normal(self) -> [i64; 3] {
+        match self {
+            Face::NegX => [-1, 0, 0],
+            Face::PosX => [1, 0, 0],
+            Face::NegY => [0, -1, 0],
+            Face::PosY => [0, 1, 0],
+            Face::NegZ => [0, 0, -1],
+            Face::PosZ => [0, 0, 1],
+        }
+    }
+
+    /// The face of the neighbor that this face touches.
+    #[inline]
+    pub const fn opposite(self) -> Face {
+        match self {
+            Face::NegX => Face::PosX,
+            Face::PosX => Face::NegX,
+            Face::NegY => Face::PosY,
+            Face::PosY => Face::NegY,
+            Face::NegZ => Face::PosZ,
+            Face::PosZ => Face::NegZ,
+        }
+    }
+
+    /// The neighbor of `pos` sharing this face with it, or [`None`] if it would overflow.
+    #[inline]
+    pub fn neighbor(self, pos: [i64; 3]) -> Option<[i64; 3]> {
+        let normal = self.normal();
+        Some([
+            pos[0].checked_add(normal[0])?,
+            pos[1].checked_add(normal[1])?,
+            pos[2].checked_add(normal[2])?,
+        ])
+    }
+}
+
+/// Find every face where a cell matching `is_a` touches a cell matching `is_b` (e.g., the surface between water and air),
+/// returning the worldspace index of the `is_a` cell and which of its faces touches the `is_b` cell.
+///
+/// Faces are listed in X-major order of their cells, and in the order of [`Face::ALL`] for each cell. Every face is listed
+/// exactly once, so wherever the `is_a` cells border `is_b` cells the result is a watertight surface without gaps or
+/// duplicate faces.
+#[inline]
+pub fn extract_interface<V, A, B>(vol: &V, mut is_a: A, mut is_b: B) -> Vec<([i64; 3], Face)>
+where
+    V: Volume,
+    A: FnMut(&V::Item) -> bool,
+    B: FnMut(&V::Item) -> bool,
+{
+    let mut faces = Vec::new();
+
+    for pos in vol.bounding_box() {
+        if !is_a(vol.get(pos).unwrap()) {
+            continue;
+        }
+
+        for face in Face::ALL {
+            let touches_b = face
+                .neighbor(pos)
+                .and_then(|neighbor| vol.get(neighbor))
+                .is_some_and(&mut is_b);
+
+            if touches_b {
+                faces.push((pos, face));
+            }
+        }
+    }
+
+    faces
+}
+
+/// The solid cells of a volume in a dense buffer in X-major order (the same order as [`BoundingBox`] iteration).
+struct Solids {
+    bounds: BoundingBox,
+    dims: [i64; 3],
+    cells: Vec<bool>,
+}
+
+impl Solids {
+    fn new<V: Volume, F: FnMut(&V::Item) -> bool>(vol: &V, mut is_solid: F) -> Self {
+        let bounds = vol.bounding_box();
+        Self {
+            bounds,
+            dims: bounds.dimensions(),
+            cells: bounds
+                .into_iter()
+                .map(|pos| is_solid(vol.get(pos).unwrap()))
+                .collect(),
+        }
+    }
+
+    /// The index of a worldspace position into the buffer, or [`None`] if it's out of bounds.
+    #[inline(always)]
+    fn index(&self, pos: [i64; 3]) -> Option<usize> {
+        let [x, y, z] = crate::util::sub_ivec3(pos, self.bounds.min());
+        let [dx, dy, dz] = self.dims;
+        match (0..dx).contains(&x) && (0..dy).contains(&y) && (0..dz).contains(&z) {
+            true => Some(((z * dy + y) * dx + x) as usize),
+            false => None,
+        }
+    }
+
+    /// Whether the cell at `pos` is solid. Cells outside of the bounds aren't.
+    #[inline(always)]
+    fn solid(&self, pos: [i64; 3]) -> bool {
+        self.index(pos).is_some_and(|i| self.cells[i])
+    }
+}
+
+/// The Euler characteristic of the shape formed by the cells matching `is_solid` (treating every cell as a unit cube).
+///
+/// For a shape made of `c` separate pieces with `t` tunnels through them in total (like the hole of a torus) and `v`
+/// enclosed voids, this is `c - t + v`. A solid cube has an Euler characteristic of 1, a ring 0, and a hollow box 2.
+/// Cells only touching along an edge or at a corner are considered connected.
+pub fn euler_characteristic<V, F>(vol: &V, is_solid: F) -> i64
+where
+    V: Volume,
+    F: FnMut(&V::Item) -> bool,
+{
+    let solids = Solids::new(vol, is_solid);
+    let bounds = solids.bounds;
+    if bounds.is_empty() {
+        return 0;
+    }
+
+    let (mut vertices, mut edges, mut faces, mut cubes) = (0i64, 0i64, 0i64, 0i64);
+    let corners = BoundingBox::new(
+        bounds.min(),
+        crate::util::sum_ivec3(bounds.max(), [1, 1, 1]),
+    );
+
+    // Every vertex, edge, and face of the cubical complex is counted once, from the grid point at its minimum corner.
+    for p in corners {
+        let offset = |o: [i64; 3]| crate::util::sub_ivec3(p, o);
+
+        if BoundingBox::new_origin([2, 2, 2])
+            .into_iter()
+            .any(|o| solids.solid(offset(o)))
+        {
+            vertices += 1;
+        }
+
+        for axis in 0..3 {
+            let (a, b) = ((axis + 1) % 3, (axis + 2) % 3);
+            let unit = |i: usize| {
+                let mut o = [0; 3];
+                o[i] = 1;
+                o
+            };
+
+            let edge_cells = [
+                [0; 3],
+                unit(a),
+                unit(b),
+                crate::util::sum_ivec3(unit(a), unit(b)),
+            ];
+            if edge_cells.iter().any(|&o| solids.solid(offset(o))) {
+                edges += 1;
+            }
+
+            if solids.solid(p) || solids.solid(offset(unit(axis))) {
+                faces += 1;
+            }
+        }
+
+        if solids.solid(p) {
+            cubes += 1;
+        }
+    }
+
+    vertices - edges + faces - cubes
+}
+
+/// Find the regions of non-solid cells that are completely enclosed by solid cells (e.g., sealed rooms in a player-built
+/// base, or air pockets left behind by cave generation).
+///
+/// Non-solid cells are connected when they share a face, and every non-solid cell connected to the edge of the volume's
+/// bounds is considered part of the outside. Every other connected region of non-solid cells is returned as its own
+/// [`Selection`], ordered by their first cell in X-major order.
+pub fn find_enclosed_voids<V, F>(vol: &V, is_solid: F) -> Vec<Selection>
+where
+    V: Volume,
+    F: FnMut(&V::Item) -> bool,
+{
+    let solids = Solids::new(vol, is_solid);
+    let bounds = solids.bounds;
+    let mut visited = solids.cells.clone();
+
+    let fill = |start: [i64; 3], visited: &mut Vec<bool>| -> Vec<[i64; 3]> {
+        let mut region = Vec::new();
+        let mut stack = vec![start];
+        visited[solids.index(start).unwrap()] = true;
+
+        while let Some(pos) = stack.pop() {
+            region.push(pos);
+            for face in Face::ALL {
+                let Some(i) = face.neighbor(pos).and_then(|n| solids.index(n)) else {
+                    continue;
+                };
+
+                if !visited[i] {
+                    visited[i] = true;
+                    stack.push(face.neighbor(pos).unwrap());
+                }
+            }
+        }
+
+        region
+    };
+
+    let on_edge = |pos: [i64; 3]| {
+        (0..3).any(|axis| pos[axis] == bounds.min()[axis] || pos[axis] == bounds.max()[axis] - 1)
+    };
+
+    for pos in bounds {
+        if on_edge(pos) && !visited[solids.index(pos).unwrap()] {
+            fill(pos, &mut visited);
+        }
+    }
+
+    let mut voids = Vec::new();
+    for pos in bounds {
+        if !visited[solids.index(pos).unwrap()] {
+            voids.push(Selection::from_voxels(fill(pos, &mut visited)));
+        }
+    }
+
+    voids
+}
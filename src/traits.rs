@@ -1,10 +1,29 @@
+use crate::geometry::VolumeSphereIterator;
+use crate::impls::heap_volume::HeapVolume;
+use crate::occupancy::OccupancyTree;
+#[cfg(feature = "rand")]
+use crate::random::RngCore;
+use crate::spaces::IndexSpace;
 use crate::types::*;
-use crate::util;
+use crate::util::{self, ProgressTracker};
+use crate::view::{LocalView, LocalViewMut, PermutedView, Projection, SubVolume, SubVolumeMut};
 use num_traits::NumCast;
 use num_traits::PrimInt;
+use std::hash::{Hash, Hasher};
+use std::ops::ControlFlow;
 
-pub(crate) trait BasicCloneFill<T: Clone> {
-    fn clone_fill(size: [usize; 3], item: T) -> Self;
+/// Lossy conversion of numeric items to [`f64`], used by the sampling and interpolation functions of [`Volume`].
+pub trait AsF64: Copy {
+    fn as_f64(self) -> f64;
+}
+
+/// Item types that can be described by a [`VolumeSchema`], identified by a name of the implementor's choosing.
+///
+/// The name is stored with persisted volumes, so it must never change once volumes have been saved with it, and it should
+/// be unique among the item types of an application (e.g., by prefixing it with the crate name, like `"mygame::Block"`).
+/// Implemented for the primitive types, named after the type (e.g., `"u16"`).
+pub trait SchemaName {
+    const NAME: &'static str;
 }
 
 pub trait VolumeIdx: Sized + Copy {
@@ -16,7 +35,80 @@ pub trait VolumeIdx: Sized + Copy {
     /// Cast this index to an array of an integer type.
     /// Returns `None` if the cast failed.
     fn array<T: NumCast + PrimInt>(self) -> Option<[T; 3]>;
+
+    /// Create a new index from X, Y, and Z components, returning [`None`] if they can't be represented by `Self`.
+    ///
+    /// The default implementation calls [`VolumeIdx::from_xyz`] and may therefore panic instead of returning [`None`].
+    /// Implementors should override it if `from_xyz` can panic.
+    #[inline]
+    fn try_from_xyz<N: PrimInt>(x: N, y: N, z: N) -> Option<Self> {
+        Some(Self::from_xyz(x, y, z))
+    }
+
+    /// Add a signed offset to this index, returning [`None`] if the result can't be represented by `Self`
+    /// (or if this index can't be converted to `[i64; 3]`).
+    #[inline]
+    fn checked_add(self, offset: [i64; 3]) -> Option<Self> {
+        let [x, y, z] = self.array::<i64>()?;
+
+        Self::try_from_xyz(
+            x.checked_add(offset[0])?,
+            y.checked_add(offset[1])?,
+            z.checked_add(offset[2])?,
+        )
+    }
+
+    /// Subtract a signed offset from this index, returning [`None`] if the result can't be represented by `Self`
+    /// (or if this index can't be converted to `[i64; 3]`).
+    #[inline]
+    fn checked_sub(self, offset: [i64; 3]) -> Option<Self> {
+        let [x, y, z] = self.array::<i64>()?;
+
+        Self::try_from_xyz(
+            x.checked_sub(offset[0])?,
+            y.checked_sub(offset[1])?,
+            z.checked_sub(offset[2])?,
+        )
+    }
+
+    /// Add a signed offset to this index, wrapping around at the bounds of [`i64`].
+    ///
+    /// # Panics
+    /// Panics if this index can't be converted to `[i64; 3]`, or if the result can't be represented by `Self`
+    /// (e.g., a negative result for an unsigned index type). Use [`VolumeIdx::checked_add`] if that's a possibility.
+    #[inline]
+    fn wrapping_add(self, offset: [i64; 3]) -> Self {
+        let [x, y, z] = self.array::<i64>().expect("cannot cast index to [i64; 3]");
+
+        Self::from_xyz(
+            x.wrapping_add(offset[0]),
+            y.wrapping_add(offset[1]),
+            z.wrapping_add(offset[2]),
+        )
+    }
+
+    /// The (up to 6) indices sharing a face with this one, in the order -X, +X, -Y, +Y, -Z, +Z.
+    /// Neighbors which can't be represented by `Self` are skipped.
+    #[inline]
+    fn face_neighbors(self) -> impl Iterator<Item = Self> {
+        const OFFSETS: [[i64; 3]; 6] = [
+            [-1, 0, 0],
+            [1, 0, 0],
+            [0, -1, 0],
+            [0, 1, 0],
+            [0, 0, -1],
+            [0, 0, 1],
+        ];
+
+        OFFSETS
+            .into_iter()
+            .filter_map(move |offset| self.checked_add(offset))
+    }
 }
+/// Read access to a 3D volume of items. See [`VolumeMut`] for write access.
+///
+/// Every volume, view, and iterator in this crate is [`Send`] and [`Sync`] whenever its items (and other type parameters) are,
+/// so volumes can be freely shared with and sent to other threads.
 pub trait Volume: Sized {
     type Item;
 
@@ -29,29 +121,34 @@ pub trait Volume: Sized {
     /// to the appropriate implementation.
     fn ls_get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item>;
 
-    /// Get a mutable reference to the item at the given index in localspace.
-    /// Implementors must make sure that this function returns [`None`] if the index is out of bounds.
-    ///
-    /// This function is used by [`Volume::get_mut`], which internally (by default) uses the volume's bounding box's minimum
-    /// to convert the index to localspace (index - bounding box minimum). This is assumed to be the case for a type implementing volume.
-    /// If this for whatever reason is not the case for your volume (which is already a sign of problems), then override [`Volume::get_mut`]
-    /// to the appropriate implementation.
-    fn ls_get_mut<Idx: VolumeIdx>(&mut self, idx: Idx) -> Option<&mut Self::Item>;
-
     /// Get a bounding box representing this volume's bounds. Implementors must assume that any position within the bounding box is a valid worldspace index
-    /// so that [`Volume::get`] and [`Volume::get_mut`] do not return [`None`] when given the index.
+    /// so that [`Volume::get`] and [`VolumeMut::get_mut`] do not return [`None`] when given the index.
     ///
     /// Much like an iterator's size hint, unsafe code SHOULD NOT rely on [`Volume::bounding_box`] for anything potentially bad.
     fn bounding_box(&self) -> BoundingBox;
 
+    /// Check that the internal structure of this volume is consistent, e.g., that its storage matches its bounds.
+    /// Useful after deserializing a volume or in debug assertions, since accessing an inconsistent volume may panic or return garbage.
+    ///
+    /// Volumes that can't be inconsistent don't need to override this, the default implementation always succeeds.
+    #[inline]
+    fn validate(&self) -> Result<(), ValidationError> {
+        Ok(())
+    }
+
+    /// The amount of bytes this volume has allocated on the heap, including any bookkeeping overhead but excluding memory owned by the items themselves.
+    /// Volumes that don't own any heap memory (like stack allocated volumes or volumes borrowing their items) don't need to override this,
+    /// the default implementation returns 0.
+    #[inline]
+    fn heap_size(&self) -> usize {
+        0
+    }
+
     /// Converts a worldspace index to a localspace index by using this volume's bounding box's minimum.
     /// Returns [`None`] if the conversion was unsucessful (e.g., if the index is less than the bounding box's minimum, making it OOB).
     #[inline(always)]
     fn to_ls<Idx: VolumeIdx>(&self, idx: Idx) -> Option<[u64; 3]> {
-        let [x, y, z] = idx.array::<i64>()?;
-        let min = self.bounding_box().min();
-        let ls_idx = util::sub_ivec3([x, y, z], min);
-
+        let ls_idx = idx.array::<i64>()?.checked_sub(self.bounding_box().min())?;
         util::cast_ivec3(ls_idx)
     }
 
@@ -64,6 +161,629 @@ pub trait Volume: Sized {
         self.ls_get(ls_idx)
     }
 
+    /// Get a reference to the item at an index tagged with its coordinate space, like [`World`](crate::spaces::World) or
+    /// [`Local`](crate::spaces::Local). Returns [`None`] if the index was invalid (e.g., out of bounds).
+    #[inline(always)]
+    fn at<S: IndexSpace>(&self, idx: S) -> Option<&Self::Item> {
+        idx.get(self)
+    }
+
+    /// Checks if this volume contains the worldspace index.
+    #[inline(always)]
+    fn contains<Idx: VolumeIdx>(&self, idx: Idx) -> bool {
+        self.bounding_box().contains::<Idx>(idx)
+    }
+
+    /// Iterate over the worldspace indices of this volume.
+    #[inline(always)]
+    fn iter_indices(&self) -> BoundingBoxIterator {
+        self.bounding_box().into_iter()
+    }
+
+    /// Iterate over the elements in this volume.
+    #[inline(always)]
+    fn iter(&self) -> VolumeIterator<'_, Self> {
+        VolumeIterator {
+            volume: self,
+            bb_iterator: self.iter_indices(),
+        }
+    }
+
+    /// Iterate over the worldspace indices and elements of this volume, in the same order as [`Volume::iter`].
+    #[inline(always)]
+    fn iter_indexed(&self) -> IndexedVolumeIterator<'_, Self> {
+        IndexedVolumeIterator {
+            volume: self,
+            bb_iterator: self.iter_indices(),
+        }
+    }
+
+    /// Iterate over every `step[axis]`th worldspace index (and its element) along each axis of this volume.
+    /// See [`BoundingBox::iter_strided`].
+    ///
+    /// # Panics
+    /// Panics if any step is 0.
+    #[inline(always)]
+    fn iter_strided(&self, step: [usize; 3]) -> StridedVolumeIterator<'_, Self> {
+        StridedVolumeIterator {
+            volume: self,
+            indices: self.bounding_box().iter_strided(step),
+        }
+    }
+
+    /// Iterate over the elements in this volume in the given order.
+    #[inline(always)]
+    fn iter_ordered(&self, order: Order) -> OrderedVolumeIterator<'_, Self> {
+        OrderedVolumeIterator {
+            volume: self,
+            indices: self.bounding_box().iter_order(order),
+        }
+    }
+
+    /// Fold every item in the part of `region` inside of this volume into an accumulator. Like [`Volume::try_fold_region`],
+    /// but without early exit.
+    #[inline]
+    fn fold_region<B, F>(&self, region: BoundingBox, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, &Self::Item) -> B,
+    {
+        let folded = self.try_fold_region(region, init, |acc, item| {
+            ControlFlow::<std::convert::Infallible, B>::Continue(f(acc, item))
+        });
+
+        match folded {
+            ControlFlow::Continue(acc) => acc,
+            ControlFlow::Break(never) => match never {},
+        }
+    }
+
+    /// Hint that the items in `range` are about to be scanned, so the volume can start loading their memory (e.g., by touching
+    /// the pages of a memory mapped file) before they're needed. In-memory volumes don't implement it, since prefetching a
+    /// region larger than the CPU cache up front evicts its start before it's read.
+    ///
+    /// Purely a performance hint that never changes any items. Does nothing by default. The bulk algorithms of this crate
+    /// that always walk a whole region (like [`Volume::read_copy_region`]) call it first, while ones that can stop early
+    /// (like [`Volume::try_fold_region`]) don't, so stopping early stays cheap.
+    #[inline]
+    fn prefetch_region<R: IntoBoundingBox>(&self, range: R) {
+        let _ = range;
+    }
+
+    /// Fold every item in the part of `region` inside of this volume into an accumulator, stopping as soon as `f` returns
+    /// [`ControlFlow::Break`]. For example, checking if a region is entirely air can stop at the first item that isn't.
+    ///
+    /// The items are visited in an unspecified order. Dense volumes visit them in storage order, walking their rows directly
+    /// instead of looking up every position.
+    #[inline]
+    fn try_fold_region<B, C, F>(&self, region: BoundingBox, init: B, mut f: F) -> ControlFlow<C, B>
+    where
+        F: FnMut(B, &Self::Item) -> ControlFlow<C, B>,
+    {
+        let Some(region) = region.intersection(&self.bounding_box()) else {
+            return ControlFlow::Continue(init);
+        };
+
+        let mut acc = init;
+        for idx in region {
+            acc = f(acc, self.get(idx).unwrap())?;
+        }
+
+        ControlFlow::Continue(acc)
+    }
+
+    /// The item filling this volume if every item in it is equal, or [`None`] if they aren't (or the volume is empty).
+    /// Useful for deciding whether a chunk can be stored as a [`UniformVolume`](crate::prelude::UniformVolume), or skipped while meshing.
+    #[inline]
+    fn is_uniform(&self) -> Option<&Self::Item>
+    where
+        Self::Item: PartialEq,
+    {
+        let first = self.get(self.bounding_box().min())?;
+        let uniform =
+            self.try_fold_region(self.bounding_box(), (), |(), item| match item == first {
+                true => ControlFlow::Continue(()),
+                false => ControlFlow::Break(()),
+            });
+
+        uniform.is_continue().then_some(first)
+    }
+
+    /// Describe this volume's item type and bounds, e.g. to store next to its items when persisting them.
+    /// See [`VolumeSchema`].
+    #[inline]
+    fn schema(&self) -> VolumeSchema
+    where
+        Self::Item: SchemaName,
+    {
+        VolumeSchema::of::<Self::Item>(self.bounding_box())
+    }
+
+    /// Build a hierarchical summary of the positions in this volume for which `is_solid` returns `true`, for quickly checking
+    /// whether regions are empty or full. See [`OccupancyTree`].
+    #[inline]
+    fn build_occupancy<F>(&self, is_solid: F) -> OccupancyTree
+    where
+        F: FnMut(&Self::Item) -> bool,
+    {
+        OccupancyTree::build(self, is_solid)
+    }
+
+    /// Copy the items of this volume into a flat buffer in the given order, e.g., [`Order::Zyx`] for a C-ordered (row-major)
+    /// numpy array of shape `(x, y, z)`. The inverse of [`HeapVolume::from_vec_in_order`].
+    #[inline]
+    fn to_vec_in_order(&self, order: Order) -> Vec<Self::Item>
+    where
+        Self::Item: Clone,
+    {
+        self.iter_ordered(order).cloned().collect()
+    }
+
+    /// Iterate over the worldspace indices in the intersection of the bounds of this volume and `other`, along with the items
+    /// of both volumes at each of them, in X-major order. Returns [`None`] if the bounds don't overlap.
+    ///
+    /// Useful for comparing two versions of a volume, or combining volumes that store different data about the same space.
+    #[inline]
+    fn zip_iter<'a, B: Volume>(&'a self, other: &'a B) -> Option<ZipVolumeIterator<'a, Self, B>> {
+        let overlap = self.bounding_box().intersection(&other.bounding_box())?;
+
+        Some(ZipVolumeIterator {
+            lhs: self,
+            rhs: other,
+            bb_iterator: overlap.into_iter(),
+        })
+    }
+
+    /// Get references to the items at many worldspace indices at once. The returned items are in the same order as `idxs`,
+    /// with [`None`] for indices that are out of bounds.
+    ///
+    /// The lookups are performed in storage order (the indices are sorted by X, then Y, then Z, matching the layout of
+    /// the volumes in this crate) rather than in the order they're given, which is considerably faster for large scattered index lists.
+    #[inline]
+    fn get_many(&self, idxs: &[[i64; 3]]) -> Vec<Option<&Self::Item>> {
+        let mut order = (0..idxs.len()).collect::<Vec<_>>();
+        order.sort_unstable_by_key(|&i| idxs[i]);
+
+        let mut items = vec![None; idxs.len()];
+        for i in order {
+            items[i] = self.get(idxs[i]);
+        }
+
+        items
+    }
+
+    /// Get a read-only view of the part of every item selected by `f`, for example one field of a struct item.
+    /// The view is a volume with the same bounds as this volume, and nothing is copied.
+    #[inline(always)]
+    fn project<U, F>(&self, f: F) -> Projection<'_, Self, F>
+    where
+        F: Fn(&Self::Item) -> &U,
+    {
+        Projection { volume: self, f }
+    }
+
+    /// Iterate over the worldspace indices and items of this volume whose cell centers are within `radius` of `center`.
+    /// See [`BoundingBox::iter_within_sphere`] for details.
+    #[inline(always)]
+    fn iter_sphere(&self, center: [f64; 3], radius: f64) -> VolumeSphereIterator<'_, Self> {
+        VolumeSphereIterator {
+            volume: self,
+            indices: self.bounding_box().iter_within_sphere(center, radius),
+        }
+    }
+
+    /// Find the worldspace index of the item closest to `from` (by euclidean distance) for which `pred` returns true.
+    /// Only items within `max_radius` of `from` are considered. `from` doesn't have to be inside the volume.
+    ///
+    /// The search checks cubic shells of increasing size around `from` and stops as soon as no unchecked item can be closer than the best match,
+    /// so nearby matches are found without visiting the rest of the volume. If several matches are equally close,
+    /// the one found first is returned (this is deterministic, but otherwise unspecified).
+    #[inline]
+    fn nearest<P>(&self, from: [i64; 3], max_radius: u64, mut pred: P) -> Option<[i64; 3]>
+    where
+        P: FnMut(&Self::Item) -> bool,
+    {
+        let bounds = self.bounding_box();
+        if bounds.is_empty() {
+            return None;
+        }
+
+        let max_radius = i64::try_from(max_radius).unwrap_or(i64::MAX);
+        let max_distance = (max_radius as i128).pow(2);
+        let distance = |pos: [i64; 3]| -> i128 {
+            (0..3)
+                .map(|axis| (pos[axis] as i128 - from[axis] as i128).pow(2))
+                .sum()
+        };
+
+        // Shells that don't reach the volume can be skipped entirely.
+        let (min, max) = (bounds.min(), bounds.max());
+        let first_shell = (0..3)
+            .map(|axis| {
+                if from[axis] < min[axis] {
+                    min[axis] - from[axis]
+                } else if from[axis] >= max[axis] {
+                    from[axis] - (max[axis] - 1)
+                } else {
+                    0
+                }
+            })
+            .max()
+            .unwrap();
+
+        let mut best: Option<([i64; 3], i128)> = None;
+        let mut check = |pos: [i64; 3], best: &mut Option<([i64; 3], i128)>| {
+            let d = distance(pos);
+            if d <= max_distance
+                && best.is_none_or(|(_, best_d)| d < best_d)
+                && self.get(pos).is_some_and(&mut pred)
+            {
+                *best = Some((pos, d));
+            }
+        };
+
+        for r in first_shell..=max_radius {
+            // Every item in this shell (and all following shells) is at least `r` away.
+            if best.is_some_and(|(_, best_d)| (r as i128).pow(2) > best_d) {
+                break;
+            }
+
+            let shell = BoundingBox::new(
+                from.map(|c| c.saturating_sub(r)),
+                from.map(|c| c.saturating_add(r).saturating_add(1)),
+            );
+            let Some(clipped) = shell.intersection(&bounds) else {
+                continue;
+            };
+            let (cmin, cmax) = (clipped.min(), clipped.max());
+
+            for z in cmin[2]..cmax[2] {
+                for y in cmin[1]..cmax[1] {
+                    if (z - from[2]).abs() == r || (y - from[1]).abs() == r {
+                        for x in cmin[0]..cmax[0] {
+                            check([x, y, z], &mut best);
+                        }
+                    } else {
+                        // Inside the shell's faces only the two ends of the row are on the shell.
+                        for x in [from[0].saturating_sub(r), from[0].saturating_add(r)] {
+                            if x >= cmin[0] && x < cmax[0] {
+                                check([x, y, z], &mut best);
+                            }
+                        }
+                    }
+                }
+            }
+
+            if clipped == bounds {
+                break;
+            }
+        }
+
+        best.map(|(pos, _)| pos)
+    }
+
+    /// Get a read-only view of the items in `range` (e.g., `vol.slice([0, 0, 0]..[4, 4, 4])`), using the same worldspace indices as
+    /// this volume. The view's bounds are the part of the range inside of this volume, so it's empty if they don't overlap.
+    /// See [`IntoBoundingBox`] for the accepted ranges.
+    #[inline]
+    fn slice<R: IntoBoundingBox>(&self, range: R) -> SubVolume<'_, Self> {
+        SubVolume {
+            bounds: crate::view::clip_range(self.bounding_box(), range),
+            volume: self,
+        }
+    }
+
+    /// Get a read-only view of the items in `range`, like [`Volume::slice`], but with indices re-based so the view's bounds start
+    /// at `[0, 0, 0]`. Index `[0, 0, 0]` of the view is the minimum corner of the part of the range inside of this volume.
+    #[inline]
+    fn view_local<R: IntoBoundingBox>(&self, range: R) -> LocalView<'_, Self> {
+        LocalView {
+            window: crate::view::clip_range(self.bounding_box(), range),
+            volume: self,
+        }
+    }
+
+    /// View this volume (or a view of it, like a [slice](Volume::slice)) with its axes permuted, where `axes[i]` is the axis of
+    /// this volume that axis `i` of the view reads along. Axes can also be flipped by passing [`SignedAxis`]es. See [`PermutedView`].
+    ///
+    /// # Panics
+    /// Panics if `axes` isn't a permutation of X, Y, and Z.
+    #[inline]
+    fn permute_axes<A: Into<SignedAxis>>(self, axes: [A; 3]) -> PermutedView<Self>
+    where
+        Self: Sized,
+    {
+        PermutedView::new(self, axes)
+    }
+
+    /// Compute a stable 64-bit hash of this volume's bounds and items.
+    /// Two volumes with equal bounds and equal items (in the same positions) always have the same hash, regardless of their type.
+    ///
+    /// The hash is stable across runs and platforms (as long as the items' [`Hash`] implementations are), which makes it suitable for
+    /// deduplicating data on disk or validating transfers. It's *not* a cryptographic hash, so don't rely on it for untrusted data.
+    #[inline]
+    fn content_hash(&self) -> u64
+    where
+        Self::Item: Hash,
+    {
+        self.content_hash_region(self.bounding_box()).unwrap()
+    }
+
+    /// Compute a stable hash (see [`Volume::content_hash`]) of the items within `region`.
+    /// The result is the same as the [`Volume::content_hash`] of a volume with `region` as its bounds holding those items.
+    ///
+    /// Returns [`None`] if `region` is not entirely inside of this volume.
+    #[inline]
+    fn content_hash_region(&self, region: BoundingBox) -> Option<u64>
+    where
+        Self::Item: Hash,
+    {
+        if !region.is_empty() && self.bounding_box().intersection(&region) != Some(region) {
+            return None;
+        }
+
+        self.prefetch_region(region);
+
+        let mut hasher = util::StableHasher::new();
+        region.min().hash(&mut hasher);
+        region.max().hash(&mut hasher);
+        for idx in region {
+            self.get(idx)?.hash(&mut hasher);
+        }
+
+        Some(hasher.finish())
+    }
+
+    /// The smallest bounding box containing every item for which `is_empty` returns `false`,
+    /// or [`None`] if every item is empty.
+    #[inline]
+    fn tight_bounds<F>(&self, mut is_empty: F) -> Option<BoundingBox>
+    where
+        F: FnMut(&Self::Item) -> bool,
+    {
+        let mut bounds: Option<([i64; 3], [i64; 3])> = None;
+        for (idx, item) in self.iter_indexed() {
+            if is_empty(item) {
+                continue;
+            }
+
+            let (min, max) = bounds.get_or_insert((idx, idx));
+            for axis in 0..3 {
+                min[axis] = min[axis].min(idx[axis]);
+                max[axis] = max[axis].max(idx[axis]);
+            }
+        }
+
+        bounds.map(|(min, max)| BoundingBox::new(min, util::sum_ivec3(max, [1, 1, 1])))
+    }
+
+    /// Crop this volume to its [tight bounds](Volume::tight_bounds), returning the bounds and a copy of the items within them.
+    /// The copy keeps the worldspace positions of the items. Returns [`None`] if every item is empty.
+    #[inline]
+    fn trim<F>(&self, is_empty: F) -> Option<(BoundingBox, HeapVolume<Self::Item>)>
+    where
+        F: FnMut(&Self::Item) -> bool,
+        Self::Item: Clone,
+    {
+        let bounds = self.tight_bounds(is_empty)?;
+        Some((bounds, self.read_copy_region(bounds)?))
+    }
+
+    /// Copy the items in the part of `region` that's inside of this volume into a new volume, which keeps the worldspace
+    /// positions of the items. Returns [`None`] if `region` doesn't overlap this volume.
+    ///
+    /// The copy is a snapshot that later writes to this volume don't affect, see [`EditBatch`] for how to use it.
+    #[inline]
+    fn read_copy_region(&self, region: BoundingBox) -> Option<HeapVolume<Self::Item>>
+    where
+        Self::Item: Clone,
+    {
+        let bounds = self.bounding_box().intersection(&region)?;
+        let (min, max) = (bounds.min(), bounds.max());
+        self.prefetch_region(bounds);
+
+        let planes = (min[0]..max[0])
+            .map(|x| {
+                (min[1]..max[1])
+                    .map(|y| {
+                        (min[2]..max[2])
+                            .map(|z| self.get([x, y, z]).unwrap().clone())
+                            .collect()
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Some(HeapVolume::from_planes(bounds, planes))
+    }
+
+    /// Upload the items of this volume to a new GPU buffer with the given usage (plus [`wgpu::BufferUsages::COPY_DST`]).
+    /// The items are tightly packed in X-major order, see [`gpu`](crate::gpu) for details. Requires the `wgpu` feature.
+    #[cfg(feature = "wgpu")]
+    #[inline]
+    fn upload_to_buffer(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        usage: wgpu::BufferUsages,
+    ) -> Result<wgpu::Buffer, TextureError>
+    where
+        Self::Item: bytemuck::Pod,
+    {
+        crate::gpu::upload_to_buffer(self, device, queue, usage, false).map(|(buffer, _)| buffer)
+    }
+
+    /// Upload the items of this volume to a new GPU buffer laid out for copying into a 3D texture with [`wgpu::CommandEncoder::copy_buffer_to_texture`].
+    /// Rows are padded to the alignment wgpu requires for such copies, and the returned layout describes the padding.
+    /// Requires the `wgpu` feature.
+    #[cfg(feature = "wgpu")]
+    #[inline]
+    fn upload_to_texture_buffer(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        usage: wgpu::BufferUsages,
+    ) -> Result<(wgpu::Buffer, wgpu::ImageDataLayout), TextureError>
+    where
+        Self::Item: bytemuck::Pod,
+    {
+        crate::gpu::upload_to_buffer(self, device, queue, usage, true)
+    }
+
+    /// Write the items of this volume into a 3D texture with the same dimensions, whose texels are the same size as the items.
+    /// Requires the `wgpu` feature.
+    #[cfg(feature = "wgpu")]
+    #[inline]
+    fn write_texture_3d(
+        &self,
+        queue: &wgpu::Queue,
+        texture: &wgpu::Texture,
+    ) -> Result<(), TextureError>
+    where
+        Self::Item: bytemuck::Pod,
+    {
+        crate::gpu::write_texture_3d(self, queue, texture)
+    }
+
+    /// Sample this volume at a fractional worldspace position using trilinear interpolation.
+    ///
+    /// This uses the *corner* convention: the item at index `[x, y, z]` is located exactly at the position `(x, y, z)`,
+    /// so sampling at an integer position returns that item unchanged, and the sampleable region spans from the bounding box's
+    /// minimum up to (and including) `max - 1` on every axis. See [`Volume::sample_trilinear_centered`] for the cell-center convention.
+    ///
+    /// Returns [`None`] if any of the items needed for the interpolation are out of bounds, or if `pos` is not finite.
+    #[inline]
+    fn sample_trilinear(&self, pos: [f64; 3]) -> Option<f64>
+    where
+        Self::Item: AsF64,
+    {
+        if !pos.iter().all(|c| c.is_finite()) {
+            return None;
+        }
+
+        let base = [pos[0].floor(), pos[1].floor(), pos[2].floor()];
+        let frac = [pos[0] - base[0], pos[1] - base[1], pos[2] - base[2]];
+        let base = [base[0] as i64, base[1] as i64, base[2] as i64];
+
+        let mut acc = 0.0;
+        for corner in 0..8 {
+            let offset = [corner & 1, (corner >> 1) & 1, (corner >> 2) & 1];
+
+            let mut weight = 1.0;
+            for axis in 0..3 {
+                weight *= if offset[axis] == 1 {
+                    frac[axis]
+                } else {
+                    1.0 - frac[axis]
+                };
+            }
+
+            // Taps that don't contribute are skipped so that sampling exactly on the upper edge
+            // doesn't require the (out of bounds) neighbor past it.
+            if weight == 0.0 {
+                continue;
+            }
+
+            let item = self.get(util::sum_ivec3(base, offset))?;
+            acc += weight * item.as_f64();
+        }
+
+        Some(acc)
+    }
+
+    /// Sample this volume at a fractional worldspace position using trilinear interpolation.
+    ///
+    /// This uses the *cell-center* convention: the item at index `[x, y, z]` is located at the center of its cell, `(x + 0.5, y + 0.5, z + 0.5)`.
+    /// Sampling anywhere between two cell centers interpolates between the two cells.
+    /// Equivalent to calling [`Volume::sample_trilinear`] with `pos - 0.5`.
+    #[inline]
+    fn sample_trilinear_centered(&self, pos: [f64; 3]) -> Option<f64>
+    where
+        Self::Item: AsF64,
+    {
+        self.sample_trilinear([pos[0] - 0.5, pos[1] - 0.5, pos[2] - 0.5])
+    }
+
+    /// Estimate the gradient of this (scalar) volume at the given worldspace index using central differences.
+    /// On the edges of the volume, where one of the neighbors along an axis is out of bounds, a one-sided difference is used instead.
+    /// If both neighbors along an axis are out of bounds (i.e., the volume is 1 item thick along that axis) the gradient along that axis is 0.
+    ///
+    /// Returns [`None`] if the index itself is out of bounds.
+    #[inline]
+    fn gradient<Idx: VolumeIdx>(&self, idx: Idx) -> Option<[f64; 3]>
+    where
+        Self::Item: AsF64,
+    {
+        let idx = idx.array::<i64>()?;
+        let center = self.get(idx)?.as_f64();
+
+        let neighbor = |axis: usize, delta: i64| -> Option<f64> {
+            let mut pos = idx;
+            pos[axis] = pos[axis].checked_add(delta)?;
+            self.get(pos).map(|item| item.as_f64())
+        };
+
+        let mut gradient = [0.0; 3];
+        for (axis, component) in gradient.iter_mut().enumerate() {
+            *component = match (neighbor(axis, -1), neighbor(axis, 1)) {
+                (Some(prev), Some(next)) => (next - prev) / 2.0,
+                (None, Some(next)) => next - center,
+                (Some(prev), None) => center - prev,
+                (None, None) => 0.0,
+            };
+        }
+
+        Some(gradient)
+    }
+
+    /// Compute the gradient (see [`Volume::gradient`]) of every item in this volume, returning a new volume of gradients with the same bounds.
+    #[inline]
+    fn gradient_field(&self) -> HeapVolume<[f32; 3]>
+    where
+        Self::Item: AsF64,
+    {
+        self.gradient_field_with_progress(util::no_progress)
+            .unwrap()
+    }
+
+    /// Same as [`Volume::gradient_field`], but reports progress to the given callback which may cancel the operation
+    /// by returning [`ControlFlow::Break`].
+    #[inline]
+    fn gradient_field_with_progress<F>(
+        &self,
+        progress: F,
+    ) -> Result<HeapVolume<[f32; 3]>, Cancelled>
+    where
+        Self::Item: AsF64,
+        F: FnMut(f32) -> ControlFlow<()>,
+    {
+        let bounds = self.bounding_box();
+        debug_span!("gradient_field", %bounds);
+
+        let mut progress = ProgressTracker::new(bounds.capacity() as u64, progress);
+        let mut field = HeapVolume::new([0.0f32; 3], bounds);
+
+        for idx in self.iter_indices() {
+            let [x, y, z] = self.gradient(idx).unwrap();
+            field[idx] = [x as f32, y as f32, z as f32];
+
+            progress.advance(1)?;
+        }
+
+        Ok(field)
+    }
+}
+
+/// Write access to a 3D volume of items.
+pub trait VolumeMut: Volume {
+    /// Get a mutable reference to the item at the given index in localspace.
+    /// Implementors must make sure that this function returns [`None`] if the index is out of bounds.
+    ///
+    /// This function is used by [`VolumeMut::get_mut`], which internally (by default) uses the volume's bounding box's minimum
+    /// to convert the index to localspace (index - bounding box minimum). This is assumed to be the case for a type implementing volume.
+    /// If this for whatever reason is not the case for your volume (which is already a sign of problems), then override [`VolumeMut::get_mut`]
+    /// to the appropriate implementation.
+    fn ls_get_mut<Idx: VolumeIdx>(&mut self, idx: Idx) -> Option<&mut Self::Item>;
+
     /// Get a mutable reference to the item at the given worldpace index. Returns [`None`] if the index was invalid (e.g., out of bounds).
     /// Uses [`Volume::to_ls`] internally to convert the worldspace index to a localspace index, after which the implementor must handle it.
     #[inline(always)]
@@ -73,10 +793,16 @@ pub trait Volume: Sized {
         self.ls_get_mut(ls_idx)
     }
 
+    /// Get a mutable reference to the item at an index tagged with its coordinate space. See [`Volume::at`].
+    #[inline(always)]
+    fn at_mut<S: IndexSpace>(&mut self, idx: S) -> Option<&mut Self::Item> {
+        idx.get_mut(self)
+    }
+
     /// Swap the item at the given worldspace index with the provided `item`, returning the previous item.
     /// Returns [`None`] if the index was invalid (e.g., out of bounds).
     ///
-    /// Relies on [`Volume::get_mut`] internally.
+    /// Relies on [`VolumeMut::get_mut`] internally.
     #[inline(always)]
     fn swap<Idx: VolumeIdx>(&mut self, idx: Idx, item: Self::Item) -> Option<Self::Item> {
         let slot = self.get_mut(idx)?;
@@ -84,66 +810,683 @@ pub trait Volume: Sized {
         Some(std::mem::replace(slot, item))
     }
 
-    /// Checks if this volume contains the worldspace index.
-    #[inline(always)]
-    fn contains<Idx: VolumeIdx>(&self, idx: Idx) -> bool {
-        self.bounding_box().contains::<Idx>(idx)
+    /// Set the items at many worldspace indices at once. Pairs with an out of bounds index are ignored.
+    /// If the same index appears several times the last pair wins, just like setting them one by one.
+    /// Returns how many items were set.
+    ///
+    /// The writes are performed in storage order (see [`Volume::get_many`]), which is considerably faster for large scattered edit lists.
+    #[inline]
+    fn set_many<I>(&mut self, pairs: I) -> usize
+    where
+        I: IntoIterator<Item = ([i64; 3], Self::Item)>,
+    {
+        let mut pairs = pairs.into_iter().collect::<Vec<_>>();
+        // Stable so that duplicate indices are still written in the order they were given.
+        pairs.sort_by_key(|&(idx, _)| idx);
+
+        let mut count = 0;
+        for (idx, item) in pairs {
+            if let Some(slot) = self.get_mut(idx) {
+                *slot = item;
+                count += 1;
+            }
+        }
+
+        count
     }
 
-    /// Iterate over the worldspace indices of this volume.
-    #[inline(always)]
-    fn iter_indices(&self) -> BoundingBoxIterator {
-        self.bounding_box().into_iter()
+    /// Apply a batch of queued writes all at once. If any of the writes is out of bounds, none of them are applied and an
+    /// error is returned. Otherwise returns how many items were written (see [`VolumeMut::set_many`]).
+    #[inline]
+    fn apply_edits(&mut self, edits: EditBatch<Self::Item>) -> Result<usize, EditOutOfBoundsError> {
+        let bounds = self.bounding_box();
+        if let Some((idx, _)) = edits.iter().find(|&(idx, _)| !self.contains(idx)) {
+            return Err(EditOutOfBoundsError { idx, bounds });
+        }
+
+        Ok(self.set_many(edits))
     }
 
-    /// Iterate over the elements in this volume.
-    #[inline(always)]
-    fn iter(&self) -> VolumeIterator<'_, Self> {
-        VolumeIterator {
+    /// Set every item where `mask` is `true` to `item`. Only the positions where this volume and the mask overlap are affected.
+    /// Returns how many items were set.
+    #[inline]
+    fn fill_masked<M>(&mut self, mask: &M, item: Self::Item) -> usize
+    where
+        M: Volume<Item = bool>,
+        Self::Item: Clone,
+    {
+        self.apply_masked(mask, |slot| *slot = item.clone())
+    }
+
+    /// Copy items from `src` into this volume (at the same worldspace positions) where `mask` is `true`.
+    /// Only the positions where this volume, `src`, and the mask all overlap are affected.
+    /// Returns how many items were copied.
+    #[inline]
+    fn copy_from_masked<S, M>(&mut self, src: &S, mask: &M) -> usize
+    where
+        S: Volume,
+        S::Item: Clone + Into<Self::Item>,
+        M: Volume<Item = bool>,
+    {
+        let region = match self
+            .bounding_box()
+            .intersection(&src.bounding_box())
+            .and_then(|bb| bb.intersection(&mask.bounding_box()))
+        {
+            Some(region) => region,
+            None => return 0,
+        };
+
+        let mut count = 0;
+        for idx in region {
+            if *mask.get(idx).unwrap() {
+                *self.get_mut(idx).unwrap() = src.get(idx).unwrap().clone().into();
+                count += 1;
+            }
+        }
+
+        count
+    }
+
+    /// Call `f` with every item where `mask` is `true`. Only the positions where this volume and the mask overlap are affected.
+    /// Returns how many items `f` was called with.
+    #[inline]
+    fn apply_masked<M, F>(&mut self, mask: &M, mut f: F) -> usize
+    where
+        M: Volume<Item = bool>,
+        F: FnMut(&mut Self::Item),
+    {
+        let region = match self.bounding_box().intersection(&mask.bounding_box()) {
+            Some(region) => region,
+            None => return 0,
+        };
+
+        let mut count = 0;
+        for idx in region {
+            if *mask.get(idx).unwrap() {
+                f(self.get_mut(idx).unwrap());
+                count += 1;
+            }
+        }
+
+        count
+    }
+
+    /// Get a mutable view of the items in `range`, like [`Volume::slice`].
+    #[inline]
+    fn slice_mut<R: IntoBoundingBox>(&mut self, range: R) -> SubVolumeMut<'_, Self> {
+        SubVolumeMut {
+            bounds: crate::view::clip_range(self.bounding_box(), range),
             volume: self,
-            bb_iterator: self.iter_indices(),
         }
     }
 
+    /// Get a mutable view of the items in `range` with indices re-based to start at `[0, 0, 0]`, like [`Volume::view_local`].
+    #[inline]
+    fn view_local_mut<R: IntoBoundingBox>(&mut self, range: R) -> LocalViewMut<'_, Self> {
+        LocalViewMut {
+            window: crate::view::clip_range(self.bounding_box(), range),
+            volume: self,
+        }
+    }
+
+    /// Set every item in `range` (e.g., `vol.fill_range([0, 0, 0]..[4, 4, 4], item)`) to `item`. Positions in the range
+    /// that are outside of this volume are ignored. Returns how many items were set.
+    #[inline]
+    fn fill_range<R: IntoBoundingBox>(&mut self, range: R, item: Self::Item) -> usize
+    where
+        Self::Item: Clone,
+    {
+        let bounds = crate::view::clip_range(self.bounding_box(), range);
+        self.fill_region(&bounds, item)
+    }
+
+    /// Set every item to `f(c)`, where `c` is the item's coordinate along `axis`. `f` is called once
+    /// per slab perpendicular to the axis rather than once per item, so it's cheap to use for e.g. initializing sky light by height.
+    /// Returns how many items were set.
+    #[inline]
+    fn fill_with_axis<F>(&mut self, axis: Axis, mut f: F) -> usize
+    where
+        F: FnMut(i64) -> Self::Item,
+        Self::Item: Clone,
+    {
+        let axis = axis.index();
+        let bounds = self.bounding_box();
+        let mut count = 0;
+        for c in bounds.min()[axis]..bounds.max()[axis] {
+            let (mut min, mut max) = (bounds.min(), bounds.max());
+            (min[axis], max[axis]) = (c, c + 1);
+
+            count += self.fill_range(BoundingBox::new(min, max), f(c));
+        }
+
+        count
+    }
+
+    /// Run a scan down every column of items along `axis`, e.g. for light attenuation or
+    /// cumulative density. Every column starts with `init` as its accumulator, then for each item `f(acc, &item)` returns the
+    /// next accumulator and the item's replacement. Columns are walked from the lowest coordinate to the highest, or from
+    /// the highest to the lowest if `descending` is `true` (like sunlight falling down the Y axis).
+    ///
+    /// Columns are independent of each other, so the order in which they're visited is unspecified.
+    /// See [`HeapVolume::par_scan_columns`] for a parallel version.
+    #[inline]
+    fn scan_columns<A, F>(&mut self, axis: Axis, descending: bool, init: A, mut f: F)
+    where
+        A: Clone,
+        F: FnMut(A, &Self::Item) -> (A, Self::Item),
+    {
+        let axis = axis.index();
+        let bounds = self.bounding_box();
+        let (start, end) = (bounds.min()[axis], bounds.max()[axis]);
+        if start >= end {
+            return;
+        }
+
+        let mut heads = (bounds.min(), bounds.max());
+        heads.1[axis] = start + 1;
+
+        for mut pos in BoundingBox::new(heads.0, heads.1) {
+            let mut acc = init.clone();
+            for i in 0..end - start {
+                pos[axis] = if descending { end - 1 - i } else { start + i };
+
+                let item = self.get_mut(pos).unwrap();
+                let (next, replacement) = f(acc, item);
+                *item = replacement;
+                acc = next;
+            }
+        }
+    }
+
+    /// Fill this volume with a linear gradient along `axis`, going from `from` at the lowest
+    /// coordinate to `to` at the highest. Integer items are rounded to the nearest integer. Returns how many items were set.
+    #[inline]
+    fn fill_gradient(&mut self, axis: Axis, from: Self::Item, to: Self::Item) -> usize
+    where
+        Self::Item: AsF64 + NumCast,
+    {
+        let bounds = self.bounding_box();
+        let (start, steps) = (
+            bounds.min()[axis.index()],
+            bounds.dimensions()[axis.index()] - 1,
+        );
+        let (from, to) = (from.as_f64(), to.as_f64());
+        // Casting 0.5 to an integer type truncates it, which is how integer items are told apart from floating point ones.
+        let integer = <Self::Item as NumCast>::from(0.5).map(AsF64::as_f64) != Some(0.5);
+
+        self.fill_with_axis(axis, |c| {
+            let t = match steps {
+                0 => 0.0,
+                steps => (c - start) as f64 / steps as f64,
+            };
+            let value = from + (to - from) * t;
+
+            // The value is between `from` and `to`, so it always fits in the item type.
+            <Self::Item as NumCast>::from(if integer { value.round() } else { value }).unwrap()
+        })
+    }
+
+    /// Set every item in the region to `item`. Positions in the region that are outside of this volume are ignored.
+    /// Returns how many items were set.
+    #[inline]
+    fn fill_region<R: Region>(&mut self, region: &R, item: Self::Item) -> usize
+    where
+        Self::Item: Clone,
+    {
+        let mut count = 0;
+        for pos in region.positions_within(self.bounding_box()) {
+            *self.get_mut(pos).unwrap() = item.clone();
+            count += 1;
+        }
+
+        count
+    }
+
+    /// Set every item on the line from `a` to `b` (both inclusive, see [`draw::line`](crate::draw::line)) to `item`.
+    /// Positions on the line that are outside of this volume are ignored. Returns how many items were set.
+    #[inline]
+    fn draw_line(&mut self, a: [i64; 3], b: [i64; 3], item: Self::Item) -> usize
+    where
+        Self::Item: Clone,
+    {
+        let mut count = 0;
+        for pos in crate::draw::line(a, b) {
+            if let Some(slot) = self.get_mut(pos) {
+                *slot = item.clone();
+                count += 1;
+            }
+        }
+
+        count
+    }
+
+    /// Set every item on the 12 edges of `bounds` to `item`, drawing a wireframe of the box.
+    /// Positions outside of this volume are ignored. Returns how many items were set.
+    #[inline]
+    fn draw_box_outline(&mut self, bounds: BoundingBox, item: Self::Item) -> usize
+    where
+        Self::Item: Clone,
+    {
+        let mut count = 0;
+        for pos in crate::draw::box_outline(bounds) {
+            if let Some(slot) = self.get_mut(pos) {
+                *slot = item.clone();
+                count += 1;
+            }
+        }
+
+        count
+    }
+
+    /// Set every item in `bounds` to `item`. Positions outside of this volume are ignored. Returns how many items were set.
+    #[inline]
+    fn draw_box_filled(&mut self, bounds: BoundingBox, item: Self::Item) -> usize
+    where
+        Self::Item: Clone,
+    {
+        self.fill_region(&bounds, item)
+    }
+
+    /// Draw `text` with `font` into this volume by setting every item covered by a glyph to `item`, see
+    /// [`VoxelFont::rasterize`](crate::draw::VoxelFont::rasterize) for the layout. Positions outside of this volume are ignored.
+    /// Returns how many items were set.
+    #[inline]
+    fn draw_text(
+        &mut self,
+        origin: [i64; 3],
+        plane: crate::draw::AxisPlane,
+        text: &str,
+        font: &crate::draw::VoxelFont,
+        item: Self::Item,
+    ) -> usize
+    where
+        Self::Item: Clone,
+    {
+        let mut count = 0;
+        for pos in font.rasterize(origin, plane, text) {
+            if let Some(slot) = self.get_mut(pos) {
+                *slot = item.clone();
+                count += 1;
+            }
+        }
+
+        count
+    }
+
+    /// Set every item in this volume to a value produced by `sampler`, which is given `rng` to draw random numbers from.
+    /// Items are visited in the order of [`Volume::iter_indices`], so the result only depends on the state of `rng`.
+    #[cfg(feature = "rand")]
+    #[inline]
+    fn fill_random<R, S>(&mut self, rng: &mut R, sampler: S)
+    where
+        R: RngCore,
+        S: Fn(&mut dyn RngCore) -> Self::Item,
+    {
+        for idx in self.iter_indices() {
+            *self.get_mut(idx).unwrap() = sampler(rng);
+        }
+    }
+
+    /// Set each item in this volume to `item` with a probability of `density` (from 0.0 to 1.0).
+    /// Returns how many items were set.
+    #[cfg(feature = "rand")]
+    #[inline]
+    fn scatter<R>(&mut self, density: f64, rng: &mut R, item: Self::Item) -> usize
+    where
+        R: RngCore,
+        Self::Item: Clone,
+    {
+        let mut count = 0;
+        for idx in self.iter_indices() {
+            if crate::random::unit_f64(rng) < density {
+                *self.get_mut(idx).unwrap() = item.clone();
+                count += 1;
+            }
+        }
+
+        count
+    }
+
+    /// Same as [`VolumeMut::fill_random`], but every position gets its own RNG derived from `seed` and the worldspace position
+    /// (see [`PositionRng`](crate::random::PositionRng)). The item at a position is the same regardless of this volume's bounds,
+    /// so adjacent chunks filled with the same seed line up seamlessly.
+    #[cfg(feature = "rand")]
+    #[inline]
+    fn fill_random_stable<S>(&mut self, seed: u64, sampler: S)
+    where
+        S: Fn(&mut dyn RngCore) -> Self::Item,
+    {
+        for idx in self.iter_indices() {
+            let mut rng = crate::random::PositionRng::new(seed, idx);
+            *self.get_mut(idx).unwrap() = sampler(&mut rng);
+        }
+    }
+
+    /// Same as [`VolumeMut::scatter`], but position-stable like [`VolumeMut::fill_random_stable`].
+    #[cfg(feature = "rand")]
+    #[inline]
+    fn scatter_stable(&mut self, density: f64, seed: u64, item: Self::Item) -> usize
+    where
+        Self::Item: Clone,
+    {
+        let mut count = 0;
+        for idx in self.iter_indices() {
+            let mut rng = crate::random::PositionRng::new(seed, idx);
+            if crate::random::unit_f64(&mut rng) < density {
+                *self.get_mut(idx).unwrap() = item.clone();
+                count += 1;
+            }
+        }
+
+        count
+    }
+
+    /// Insert `rhs` into this volume with its origin at `at`. Errors without changing anything if `rhs` would not
+    /// fit entirely inside of this volume, or if the offset overflows.
+    ///
+    /// The items of `rhs` are converted with [`Into`] while they're copied, so e.g. a volume of raw `u8` block IDs can be
+    /// inserted directly into a volume of a block type implementing `From<u8>`.
     #[inline(always)]
-    fn insert<Idx, Rhs>(&mut self, at: Idx, rhs: &Rhs) -> Result<(), InsertError>
+    fn insert<Idx, Rhs>(&mut self, at: Idx, rhs: &Rhs) -> Result<InsertReport, InsertError>
     where
-        Rhs: Volume<Item = Self::Item>,
+        Rhs: Volume,
+        Rhs::Item: Clone + Into<Self::Item>,
         Idx: VolumeIdx,
-        Self::Item: Copy,
     {
-        let at = at.array::<i64>().unwrap();
+        util::insert_target(self.bounding_box(), rhs.bounding_box(), at.array())?;
 
-        let rhs_min = rhs.bounding_box().min();
-        let rhs_max = rhs.bounding_box().max();
+        self.try_insert_clipped(at, rhs)
+    }
 
-        if !self.contains(util::sum_ivec3(at, rhs_min))
-            || !self.contains(util::sum_ivec3(at, rhs_max))
-        {
-            return Err(InsertError::VolumeEscapesBounds);
-        }
+    /// Insert the parts of `rhs` that fit inside of this volume with its origin at `at`, ignoring the rest.
+    /// Useful for pasting structures that hang over the edges of the destination (e.g., across chunk boundaries).
+    ///
+    /// # Panics
+    /// Panics if `at` cannot be converted to `[i64; 3]` or if moving `rhs` by it overflows [`i64`].
+    /// See [`VolumeMut::try_insert_clipped`] for a non-panicking version.
+    #[inline]
+    fn insert_clipped<Idx, Rhs>(&mut self, at: Idx, rhs: &Rhs) -> InsertReport
+    where
+        Rhs: Volume,
+        Rhs::Item: Clone + Into<Self::Item>,
+        Idx: VolumeIdx,
+    {
+        self.try_insert_clipped(at, rhs)
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
 
-        for rhs_idx in rhs.iter_indices() {
-            let item = *rhs.get(rhs_idx).unwrap();
-            self.swap(util::sum_ivec3(at, rhs_idx), item).unwrap();
+    /// Same as [`VolumeMut::insert_clipped`], but errors with [`InsertError::OffsetOverflow`] (without changing anything)
+    /// instead of panicking if `at` cannot be converted to `[i64; 3]` or moving `rhs` by it overflows [`i64`].
+    #[inline]
+    fn try_insert_clipped<Idx, Rhs>(
+        &mut self,
+        at: Idx,
+        rhs: &Rhs,
+    ) -> Result<InsertReport, InsertError>
+    where
+        Rhs: Volume,
+        Rhs::Item: Clone + Into<Self::Item>,
+        Idx: VolumeIdx,
+    {
+        let rhs_bounds = rhs.bounding_box();
+        let (at, target) = util::moved_bounds(rhs_bounds, at.array())?;
+
+        debug_span!("insert", ?at, source = %rhs_bounds, destination = %self.bounding_box());
+
+        let Some(region) = self.bounding_box().intersection(&target) else {
+            return Ok(InsertReport::default());
+        };
+        rhs.prefetch_region(BoundingBox::new(
+            util::sub_ivec3(region.min(), at),
+            util::sub_ivec3(region.max(), at),
+        ));
+
+        let mut written = 0;
+        for idx in region {
+            let item = rhs.get(util::sub_ivec3(idx, at)).unwrap().clone().into();
+            *self.get_mut(idx).unwrap() = item;
+            written += 1;
         }
+        debug_event!(written, %region, "inserted volume");
 
-        Ok(())
+        Ok(InsertReport {
+            written,
+            region: (written > 0).then_some(region),
+        })
     }
 
     #[inline(always)]
     fn insert_anyways<Idx, Rhs>(&mut self, at: Idx, rhs: &Rhs)
     where
-        Rhs: Volume<Item = Self::Item>,
+        Rhs: Volume,
+        Rhs::Item: Clone + Into<Self::Item>,
         Idx: VolumeIdx,
-        Self::Item: Copy,
     {
-        let at = at.array::<i64>().unwrap();
+        let Some(at) = at.array::<i64>() else {
+            return;
+        };
 
         for rhs_idx in rhs.iter_indices() {
-            if let Some(&item) = rhs.get(rhs_idx) {
-                self.swap(util::sum_ivec3(at, rhs_idx), item);
+            if let (Some(item), Some(idx)) = (rhs.get(rhs_idx), rhs_idx.checked_add(at)) {
+                self.swap(idx, item.clone().into());
             }
         }
     }
 }
+
+/// Volumes that keep track of a version number which increases every time they're (potentially) modified.
+/// This allows caches of derived data (meshes, collision shapes, etc.) to cheaply check if a volume changed.
+///
+/// The version is bumped whenever the volume's items are accessed mutably, even if nothing was actually written,
+/// so a changed version means the volume *may* have changed while an unchanged version means it definitely didn't.
+pub trait Versioned {
+    /// The current version of this volume.
+    fn version(&self) -> u64;
+
+    /// Check if this volume was (potentially) modified since it had the given version.
+    #[inline]
+    fn modified_since(&self, version: u64) -> bool {
+        self.version() != version
+    }
+}
+
+/// Rectangular bounds, either known at runtime ([`BoundingBox`]) or at compile time ([`StaticBounds`]).
+/// Code that's generic over this trait can be used with either, while still benefitting from constant dimensions when possible.
+pub trait Bounds: Copy + Into<BoundingBox> {
+    /// The minimum (inclusive) corner.
+    fn min(&self) -> [i64; 3];
+
+    /// The maximum (exclusive) corner.
+    fn max(&self) -> [i64; 3];
+
+    #[inline]
+    fn dimensions(&self) -> [i64; 3] {
+        util::sub_ivec3(self.max(), self.min())
+    }
+
+    /// The amount of positions in the bounds. Saturates at [`i128::MAX`], like [`BoundingBox::capacity`].
+    #[inline]
+    fn capacity(&self) -> i128 {
+        let [x, y, z] = self.dimensions();
+        (x as i128)
+            .saturating_mul(y as i128)
+            .saturating_mul(z as i128)
+    }
+
+    #[inline]
+    fn contains<Idx: VolumeIdx>(&self, idx: Idx) -> bool {
+        let (min, max) = (self.min(), self.max());
+        match idx.array::<i64>() {
+            Some(pos) => (0..3).all(|axis| (min[axis]..max[axis]).contains(&pos[axis])),
+            None => false,
+        }
+    }
+
+    #[inline]
+    fn to_bounding_box(&self) -> BoundingBox {
+        (*self).into()
+    }
+}
+
+impl Bounds for BoundingBox {
+    #[inline(always)]
+    fn min(&self) -> [i64; 3] {
+        BoundingBox::min(self)
+    }
+
+    #[inline(always)]
+    fn max(&self) -> [i64; 3] {
+        BoundingBox::max(self)
+    }
+
+    #[inline(always)]
+    fn capacity(&self) -> i128 {
+        BoundingBox::capacity(self)
+    }
+
+    #[inline(always)]
+    fn contains<Idx: VolumeIdx>(&self, idx: Idx) -> bool {
+        BoundingBox::contains(self, idx)
+    }
+}
+
+impl<const X: usize, const Y: usize, const Z: usize> Bounds for StaticBounds<X, Y, Z> {
+    #[inline(always)]
+    fn min(&self) -> [i64; 3] {
+        [0, 0, 0]
+    }
+
+    #[inline(always)]
+    fn max(&self) -> [i64; 3] {
+        Self::DIMENSIONS
+    }
+
+    #[inline(always)]
+    fn dimensions(&self) -> [i64; 3] {
+        Self::DIMENSIONS
+    }
+
+    #[inline(always)]
+    fn capacity(&self) -> i128 {
+        Self::CAPACITY as i128
+    }
+
+    #[inline(always)]
+    fn contains<Idx: VolumeIdx>(&self, idx: Idx) -> bool {
+        idx.array::<i64>()
+            .is_some_and(|pos| Self::linear_index(pos).is_some())
+    }
+}
+
+#[cfg(feature = "small-bounds")]
+impl<N: SmallInt> Bounds for SmallBounds<N> {
+    #[inline(always)]
+    fn min(&self) -> [i64; 3] {
+        util::cast_ivec3(self.small_min()).unwrap()
+    }
+
+    #[inline(always)]
+    fn max(&self) -> [i64; 3] {
+        util::cast_ivec3(self.small_max()).unwrap()
+    }
+
+    #[inline(always)]
+    fn contains<Idx: VolumeIdx>(&self, idx: Idx) -> bool {
+        // Not `linear_index`, since that's `None` for positions in bounds whose index overflows a `usize`.
+        idx.array::<N>().is_some_and(|pos| {
+            (0..3).all(|axis| {
+                self.small_min()[axis] <= pos[axis] && pos[axis] < self.small_max()[axis]
+            })
+        })
+    }
+}
+
+/// Ranges of worldspace indices describing a box of positions, accepted by [`Volume::slice`], [`VolumeMut::slice_mut`],
+/// and [`VolumeMut::fill_range`].
+///
+/// Ranges work per axis, so `a..b` contains every position `p` with `a[axis] <= p[axis] < b[axis]` along every axis (and `a..=b` with
+/// `p[axis] <= b[axis]`). Along axes where the end is before the start the range is empty, just like ranges of integers.
+pub trait IntoBoundingBox {
+    /// Convert to a bounding box, or [`None`] if the indices can't be converted to `[i64; 3]`.
+    fn into_bounding_box(self) -> Option<BoundingBox>;
+}
+
+impl IntoBoundingBox for BoundingBox {
+    #[inline(always)]
+    fn into_bounding_box(self) -> Option<BoundingBox> {
+        Some(self)
+    }
+}
+
+impl<Idx: VolumeIdx> IntoBoundingBox for std::ops::Range<Idx> {
+    #[inline]
+    fn into_bounding_box(self) -> Option<BoundingBox> {
+        let [start, end] = [self.start.array::<i64>()?, self.end.array::<i64>()?];
+        let end = [0, 1, 2].map(|axis| end[axis].max(start[axis]));
+
+        Some(BoundingBox::new(start, end))
+    }
+}
+
+impl<Idx: VolumeIdx> IntoBoundingBox for std::ops::RangeInclusive<Idx> {
+    #[inline]
+    fn into_bounding_box(self) -> Option<BoundingBox> {
+        let [start, end] = [self.start().array::<i64>()?, self.end().array::<i64>()?];
+        if (0..3).any(|axis| end[axis] < start[axis]) {
+            return Some(BoundingBox::new(start, start));
+        }
+
+        BoundingBox::checked_new(start, end.map(|c| c.saturating_add(1)))
+    }
+}
+
+/// A set of worldspace positions, such as a [`BoundingBox`] or a [`Selection`](crate::selection::Selection).
+/// Operations that work on a region of a volume accept any type implementing this trait.
+pub trait Region {
+    type Positions<'a>: Iterator<Item = [i64; 3]>
+    where
+        Self: 'a;
+
+    /// Check if the worldspace position is in this region.
+    fn contains_position(&self, pos: [i64; 3]) -> bool;
+
+    /// The smallest bounding box containing every position in this region, or [`None`] if the region is empty.
+    fn enclosing_box(&self) -> Option<BoundingBox>;
+
+    /// Iterate over every position in this region exactly once.
+    fn positions(&self) -> Self::Positions<'_>;
+
+    /// Iterate over every position in this region that is also inside `bounds` exactly once.
+    fn positions_within(&self, bounds: BoundingBox) -> Self::Positions<'_>;
+}
+
+impl Region for BoundingBox {
+    type Positions<'a> = BoundingBoxIterator;
+
+    #[inline]
+    fn contains_position(&self, pos: [i64; 3]) -> bool {
+        self.contains(pos)
+    }
+
+    #[inline]
+    fn enclosing_box(&self) -> Option<BoundingBox> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(*self)
+        }
+    }
+
+    #[inline]
+    fn positions(&self) -> Self::Positions<'_> {
+        self.into_iter()
+    }
+
+    #[inline]
+    fn positions_within(&self, bounds: BoundingBox) -> Self::Positions<'_> {
+        self.intersection(&bounds)
+            .unwrap_or(BoundingBox::const_new_origin([0, 0, 0]))
+            .into_iter()
+    }
+}
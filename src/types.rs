@@ -1,12 +1,341 @@
 use crate::prelude::*;
+use crate::traits::SchemaName;
 use crate::util;
 use num_traits::{NumCast, PrimInt};
 
-#[derive(te::Error, Debug)]
+#[derive(te::Error, Debug, Clone, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum InsertError {
-    #[error("inserting volume A into volume B at the given index would cause parts of A to be outside of B")]
-    VolumeEscapesBounds,
+    /// The inserted volume would be partially outside of the destination volume.
+    #[error("inserting a volume with bounds {} at offset {} would place it outside of the destination bounds {} along axis {:?}", .0.inserted, VIdx(.0.offset), .0.destination, .0.axis)]
+    VolumeEscapesBounds(Box<InsertErrorDetails>),
+    /// The offset could not be converted to `[i64; 3]`, or moving the inserted volume by it would overflow [`i64`].
+    #[error("the insertion offset of a volume with bounds {inserted} overflows the index type")]
+    OffsetOverflow { inserted: BoundingBox },
+    /// The inserted volume holds more items than fit in a [`usize`], so the amount of written items can't be counted
+    /// (see [`InsertReport::written`]).
+    #[error("a volume with bounds {inserted} holds more items than fit in a usize")]
+    CapacityOverflow { inserted: BoundingBox },
+}
+
+/// Where an insertion that didn't fit was attempted, see [`InsertError::VolumeEscapesBounds`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct InsertErrorDetails {
+    /// The bounds of the volume being inserted into.
+    pub destination: BoundingBox,
+    /// The bounds of the inserted volume, before being moved by `offset`.
+    pub inserted: BoundingBox,
+    pub offset: [i64; 3],
+    /// The first axis along which the inserted volume escapes the destination.
+    pub axis: Axis,
+}
+
+#[derive(te::Error, Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SliceVolumeError {
+    #[error("the buffer holds {len} items but the bounds and strides require at least {required}")]
+    BufferTooSmall { required: usize, len: usize },
+    #[error("the bounds are too large to be addressed in memory")]
+    BoundsTooLarge,
+}
+
+/// Returned by operations that require two volumes to have the same bounds.
+#[derive(te::Error, Debug, Copy, Clone, PartialEq, Eq)]
+#[error("expected volumes with equal bounds, but got {lhs} and {rhs}")]
+pub struct BoundsMismatchError {
+    pub lhs: BoundingBox,
+    pub rhs: BoundingBox,
+}
+
+/// Returned by [`Volume::validate`] when a volume's internal structure is inconsistent (e.g., after loading corrupted data).
+#[derive(te::Error, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ValidationError {
+    #[error("expected the storage to have {expected} items along axis {axis}, but found {found}")]
+    StorageMismatch {
+        axis: usize,
+        expected: usize,
+        found: usize,
+    },
+    #[error(
+        "expected the runs to cover {expected} items in increasing order, but they cover {found}"
+    )]
+    RunLengthMismatch { expected: usize, found: usize },
+    #[error("found index {index} into a palette of {len} items")]
+    PaletteIndexOutOfRange { index: usize, len: usize },
+    #[error(transparent)]
+    Slice(#[from] SliceVolumeError),
+}
+
+/// Returned when building a volume from a flat buffer whose length doesn't match the capacity of the bounds.
+#[derive(te::Error, Debug, Copy, Clone, PartialEq, Eq)]
+#[error("expected {expected} items to fill the bounds, but got {found}")]
+pub struct LengthMismatchError {
+    pub expected: usize,
+    pub found: usize,
+}
+
+/// Returned by the functions uploading volumes to wgpu textures.
+#[cfg(feature = "wgpu")]
+#[derive(te::Error, Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TextureError {
+    #[error("the volume has dimensions {volume:?} but the texture has dimensions {texture:?}")]
+    SizeMismatch { volume: [u32; 3], texture: [u32; 3] },
+    #[error("the texture must be 3D")]
+    NotThreeDimensional,
+    #[error("the items are {item} bytes large, which doesn't match the texel size of the texture's format ({texel:?})")]
+    TexelSizeMismatch { item: usize, texel: Option<u32> },
+    #[error("the volume is too large to be uploaded to the GPU")]
+    TooLarge,
+}
+
+/// Returned by [`VolumeMut::apply_edits`] when an edit is outside of the volume's bounds. None of the edits are applied.
+#[derive(te::Error, Debug, Copy, Clone, PartialEq, Eq)]
+#[error("edit at {} is outside of the volume's bounds {bounds}", VIdx(*.idx))]
+pub struct EditOutOfBoundsError {
+    pub idx: [i64; 3],
+    pub bounds: BoundingBox,
+}
+
+/// Writes queued up to be applied to a volume all at once with [`VolumeMut::apply_edits`].
+///
+/// Together with [`Volume::read_copy_region`] this lets algorithms that read the neighbors of the cells they write (like
+/// cellular automata or erosion) read a consistent snapshot while queuing their writes, so their result doesn't depend
+/// on the order cells are visited in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EditBatch<T> {
+    edits: Vec<([i64; 3], T)>,
+}
+
+impl<T> Default for EditBatch<T> {
+    #[inline]
+    fn default() -> Self {
+        Self { edits: Vec::new() }
+    }
+}
+
+impl<T> EditBatch<T> {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a write of `item` to the given worldspace index. If the same index is written several times, the last write wins.
+    ///
+    /// # Panics
+    /// Panics if `idx` cannot be converted to `[i64; 3]`. See [`EditBatch::try_push`] for a non-panicking version.
+    #[inline]
+    pub fn push<Idx: VolumeIdx>(&mut self, idx: Idx, item: T) {
+        assert!(self.try_push(idx, item), "cannot cast index to [i64; 3]");
+    }
+
+    /// Same as [`EditBatch::push`], but returns `false` (and queues nothing) instead of panicking if `idx` cannot be
+    /// converted to `[i64; 3]`.
+    #[inline]
+    pub fn try_push<Idx: VolumeIdx>(&mut self, idx: Idx, item: T) -> bool {
+        let Some(idx) = idx.array() else {
+            return false;
+        };
+
+        self.edits.push((idx, item));
+        true
+    }
+
+    /// The amount of queued writes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.edits.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.edits.is_empty()
+    }
+
+    /// Discard every queued write.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.edits.clear();
+    }
+
+    /// Iterate over the queued writes in the order they were queued.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = ([i64; 3], &T)> {
+        self.edits.iter().map(|(idx, item)| (*idx, item))
+    }
+}
+
+impl<T> FromIterator<([i64; 3], T)> for EditBatch<T> {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = ([i64; 3], T)>>(iter: I) -> Self {
+        Self {
+            edits: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<T> Extend<([i64; 3], T)> for EditBatch<T> {
+    #[inline]
+    fn extend<I: IntoIterator<Item = ([i64; 3], T)>>(&mut self, iter: I) {
+        self.edits.extend(iter);
+    }
+}
+
+impl<T> IntoIterator for EditBatch<T> {
+    type Item = ([i64; 3], T);
+    type IntoIter = std::vec::IntoIter<([i64; 3], T)>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.edits.into_iter()
+    }
+}
+
+/// Summary of an insertion, returned by [`VolumeMut::insert`] and [`VolumeMut::insert_clipped`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct InsertReport {
+    /// The amount of cells that were written.
+    pub written: usize,
+    /// The worldspace region of the destination volume that was written to, or [`None`] if nothing was written.
+    pub region: Option<BoundingBox>,
+}
+
+/// Returned when creating or opening a [`SharedVolume`](crate::shared::SharedVolume). Requires the `shared` feature.
+#[cfg(feature = "shared")]
+#[derive(te::Error, Debug)]
+#[non_exhaustive]
+pub enum SharedVolumeError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("the file doesn't start with a shared volume header")]
+    InvalidHeader,
+    #[error("expected items of {expected:?} bytes (size, alignment), but the file holds items of {found:?} bytes")]
+    ItemLayout {
+        expected: (u64, u64),
+        found: (u64, u64),
+    },
+    #[error("the file should be {expected} bytes long to hold every item, but it's only {found} bytes long")]
+    Truncated { expected: u64, found: u64 },
+    #[error("the volume is too large to be mapped into memory")]
+    TooLarge,
+}
+
+/// Returned by long-running operations when their progress callback requested cancellation.
+#[derive(te::Error, Debug, Copy, Clone, PartialEq, Eq)]
+#[error("the operation was cancelled by its progress callback")]
+pub struct Cancelled;
+
+/// Returned by the fallible volume constructors (like [`HeapVolume::try_new`]) when the volume can't be allocated.
+#[derive(te::Error, Debug, Copy, Clone, PartialEq, Eq)]
+#[error("failed to allocate a volume with bounds {bounds}")]
+pub struct AllocationError {
+    pub bounds: BoundingBox,
+}
+
+/// Returned by [`VolumeSchema::check`] when a stored schema doesn't describe the volume type it's being loaded as.
+#[derive(te::Error, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SchemaMismatchError {
+    #[error("expected schema version {expected}, but found version {found}")]
+    Version { expected: u32, found: u32 },
+    #[error("expected items of type {expected}, but found items of type {found}")]
+    ItemType { expected: String, found: String },
+    #[error("expected items of {expected:?} bytes (size, alignment), but found items of {found:?} bytes")]
+    ItemLayout {
+        expected: (usize, usize),
+        found: (usize, usize),
+    },
+}
+
+/// Returned by [`Migrations`](crate::migrate::Migrations) when asked to migrate data it has no converters for.
+#[derive(te::Error, Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MigrationError {
+    /// The data is of a version older than the first version or newer than the current one.
+    #[error(
+        "can't migrate from version {found}, only versions {first} through {current} are supported"
+    )]
+    UnsupportedVersion {
+        found: u32,
+        first: u32,
+        current: u32,
+    },
+    /// The items aren't of the type the converters of their version take.
+    #[error("items of version {version} are of type {expected}, but got items of type {found}")]
+    ItemType {
+        version: u32,
+        expected: &'static str,
+        found: &'static str,
+    },
+}
+
+/// Describes what a volume holds, returned by [`Volume::schema`]. Meant to be stored next to a volume's items when persisting
+/// them, so that loading them as the wrong item type (e.g., one that happens to have the same size) is caught with
+/// [`VolumeSchema::check`] instead of silently reinterpreting the items.
+///
+/// Item types are identified by their [`SchemaName`], which is chosen by whoever implements it and stays the same across
+/// compiler versions and refactors, unlike [`std::any::type_name`]. The kind of volume isn't part of the schema, so the items
+/// can be loaded into a different kind of volume than the one they were saved from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VolumeSchema {
+    /// The version of the schema format, [`VolumeSchema::VERSION`] for schemas created by this version of the crate.
+    pub version: u32,
+    /// The [`SchemaName::NAME`] of the item type.
+    pub item_type: String,
+    pub item_size: usize,
+    pub item_align: usize,
+    pub bounds: BoundingBox,
+}
+
+impl VolumeSchema {
+    pub const VERSION: u32 = 1;
+
+    /// Describe a volume of items of type `T` with the given bounds.
+    #[inline]
+    pub fn of<T: SchemaName>(bounds: BoundingBox) -> Self {
+        Self {
+            version: Self::VERSION,
+            item_type: T::NAME.to_owned(),
+            item_size: std::mem::size_of::<T>(),
+            item_align: std::mem::align_of::<T>(),
+            bounds,
+        }
+    }
+
+    /// Check that `found` (e.g., a schema read from a file) describes the same item type as this schema, comparing the
+    /// item types' names as well as their size and alignment. The bounds aren't compared, since they're part of the data
+    /// rather than the type.
+    #[inline]
+    pub fn check(&self, found: &VolumeSchema) -> Result<(), SchemaMismatchError> {
+        if self.version != found.version {
+            return Err(SchemaMismatchError::Version {
+                expected: self.version,
+                found: found.version,
+            });
+        }
+        if self.item_type != found.item_type {
+            return Err(SchemaMismatchError::ItemType {
+                expected: self.item_type.clone(),
+                found: found.item_type.clone(),
+            });
+        }
+        if (self.item_size, self.item_align) != (found.item_size, found.item_align) {
+            return Err(SchemaMismatchError::ItemLayout {
+                expected: (self.item_size, self.item_align),
+                found: (found.item_size, found.item_align),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Returned by [`ItemRegistry::register`](crate::registry::ItemRegistry::register) when every id has already been assigned.
+#[derive(te::Error, Debug, Copy, Clone, PartialEq, Eq)]
+#[error("the registry is full, its id type can't address more than {capacity} items")]
+pub struct RegistryFullError {
+    pub capacity: usize,
 }
 
 impl<N: PrimInt> VolumeIdx for [N; 3] {
@@ -27,9 +356,196 @@ impl<N: PrimInt> VolumeIdx for [N; 3] {
             <N as NumCast>::from(z).expect("cannot cast Z to this array's type"),
         ]
     }
+
+    #[inline]
+    fn try_from_xyz<T: PrimInt>(x: T, y: T, z: T) -> Option<Self> {
+        Some([
+            <N as NumCast>::from(x)?,
+            <N as NumCast>::from(y)?,
+            <N as NumCast>::from(z)?,
+        ])
+    }
+}
+
+/// One of the three axes of a volume's index space.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+impl Axis {
+    pub const ALL: [Self; 3] = [Self::X, Self::Y, Self::Z];
+
+    /// The index of this axis' component in an index (0 = X, 1 = Y, 2 = Z).
+    #[inline]
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// One of the three axes of a volume's index space, pointing either along the axis or against it. Used to flip axes in a
+/// [`PermutedView`](crate::view::PermutedView). An [`Axis`] converts into its positive direction, and negating it (`-Axis::Y`)
+/// gives its negative direction.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum SignedAxis {
+    PosX,
+    NegX,
+    PosY,
+    NegY,
+    PosZ,
+    NegZ,
+}
+
+impl SignedAxis {
+    /// The axis this points along, regardless of its direction.
+    #[inline]
+    pub fn axis(self) -> Axis {
+        match self {
+            Self::PosX | Self::NegX => Axis::X,
+            Self::PosY | Self::NegY => Axis::Y,
+            Self::PosZ | Self::NegZ => Axis::Z,
+        }
+    }
+
+    #[inline]
+    pub fn is_negative(self) -> bool {
+        matches!(self, Self::NegX | Self::NegY | Self::NegZ)
+    }
+}
+
+impl From<Axis> for SignedAxis {
+    #[inline]
+    fn from(axis: Axis) -> Self {
+        match axis {
+            Axis::X => Self::PosX,
+            Axis::Y => Self::PosY,
+            Axis::Z => Self::PosZ,
+        }
+    }
+}
+
+impl std::ops::Neg for Axis {
+    type Output = SignedAxis;
+
+    #[inline]
+    fn neg(self) -> SignedAxis {
+        match self {
+            Axis::X => SignedAxis::NegX,
+            Axis::Y => SignedAxis::NegY,
+            Axis::Z => SignedAxis::NegZ,
+        }
+    }
+}
+
+/// A worldspace index with consistent formatting. Displayed as `(x, y, z)`, and parsed from `x,y,z` (optionally wrapped in
+/// parentheses or brackets, with any whitespace around the components).
+///
+/// Implements [`VolumeIdx`] so it can be used to index volumes directly, and converts to and from `[i64; 3]`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct VIdx(pub [i64; 3]);
+
+impl VIdx {
+    #[inline]
+    pub const fn new(x: i64, y: i64, z: i64) -> Self {
+        Self([x, y, z])
+    }
+}
+
+impl VolumeIdx for VIdx {
+    #[inline]
+    fn array<T: NumCast + PrimInt>(self) -> Option<[T; 3]> {
+        self.0.array()
+    }
+
+    #[inline]
+    fn from_xyz<N: PrimInt>(x: N, y: N, z: N) -> Self {
+        Self(<[i64; 3]>::from_xyz(x, y, z))
+    }
+
+    #[inline]
+    fn try_from_xyz<N: PrimInt>(x: N, y: N, z: N) -> Option<Self> {
+        <[i64; 3]>::try_from_xyz(x, y, z).map(Self)
+    }
+}
+
+impl From<[i64; 3]> for VIdx {
+    #[inline]
+    fn from(pos: [i64; 3]) -> Self {
+        Self(pos)
+    }
+}
+
+impl From<VIdx> for [i64; 3] {
+    #[inline]
+    fn from(idx: VIdx) -> Self {
+        idx.0
+    }
+}
+
+impl std::fmt::Display for VIdx {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let [x, y, z] = self.0;
+        write!(f, "({x}, {y}, {z})")
+    }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+/// Returned when parsing a [`VIdx`] from a string fails.
+#[derive(te::Error, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseIdxError {
+    #[error("expected 3 comma separated components, but found {0}")]
+    ComponentCount(usize),
+    #[error("invalid component {component:?}: {source}")]
+    InvalidComponent {
+        component: String,
+        source: std::num::ParseIntError,
+    },
+}
+
+impl std::str::FromStr for VIdx {
+    type Err = ParseIdxError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let inner = [('(', ')'), ('[', ']')]
+            .iter()
+            .find_map(|&(open, close)| s.strip_prefix(open)?.strip_suffix(close))
+            .unwrap_or(s);
+
+        let components = inner.split(',').map(str::trim).collect::<Vec<_>>();
+        let [x, y, z] = components[..] else {
+            return Err(ParseIdxError::ComponentCount(components.len()));
+        };
+
+        let parse = |component: &str| {
+            component
+                .parse::<i64>()
+                .map_err(|source| ParseIdxError::InvalidComponent {
+                    component: component.to_string(),
+                    source,
+                })
+        };
+
+        Ok(Self([parse(x)?, parse(y)?, parse(z)?]))
+    }
+}
+
+/// Returned when parsing a [`BoundingBox`] from a string fails.
+#[derive(te::Error, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseBoundsError {
+    #[error("expected two corners separated by `..`")]
+    MissingSeparator,
+    #[error("the box is too large, the length of every side must fit in an i64")]
+    TooLarge,
+    #[error(transparent)]
+    Corner(#[from] ParseIdxError),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bevy", derive(bevy_reflect::Reflect))]
 pub struct BoundingBox {
     min: [i64; 3],
     max: [i64; 3],
@@ -61,6 +577,22 @@ impl BoundingBox {
         }
     }
 
+    /// Construct a new bounding box spanning the two positions, like [`BoundingBox::new`], but return [`None`] instead of panicking
+    /// if the positions can't be cast to `[i64; 3]`, or if the length of any side doesn't fit in an [`i64`].
+    ///
+    /// Every method of [`BoundingBox`] assumes its side lengths fit in an [`i64`], so this is the constructor to use for untrusted input.
+    #[inline]
+    pub fn checked_new<N: PrimInt>(pos1: [N; 3], pos2: [N; 3]) -> Option<Self> {
+        let [pos1, pos2]: [[i64; 3]; 2] = [util::cast_ivec3(pos1)?, util::cast_ivec3(pos2)?];
+        if (0..3).any(|axis| pos1[axis].checked_sub(pos2[axis]).is_none())
+            || (0..3).any(|axis| pos2[axis].checked_sub(pos1[axis]).is_none())
+        {
+            return None;
+        }
+
+        Some(Self::new(pos1, pos2))
+    }
+
     /// Construct a new bounding box sitting at the origin (0, 0, 0) and expanding into +X, +Y, +Z. Basically a shorthand for `BoundingBox::new([0, 0, 0], [x, y, z])`
     /// where x, y, and z not negative.
     ///
@@ -78,10 +610,71 @@ impl BoundingBox {
         Self::new([0, 0, 0], [x, y, z])
     }
 
+    /// Construct a new bounding box at the origin, like [`BoundingBox::new_origin`], but return [`None`] instead of panicking
+    /// if any element of `dimensions` is negative or can't be cast to [`i64`].
+    #[inline]
+    pub fn checked_new_origin<N: PrimInt>(dimensions: [N; 3]) -> Option<Self> {
+        let dimensions = util::cast_ivec3::<i64, _>(dimensions)?;
+        match dimensions.iter().all(|&d| d >= 0) {
+            true => Some(Self::new([0, 0, 0], dimensions)),
+            false => None,
+        }
+    }
+
+    /// Const version of [`BoundingBox::new`] for positions that are already `[i64; 3]`,
+    /// which allows building bounding boxes at compile time (e.g., in constants and statics).
+    #[inline(always)]
+    pub const fn const_new(pos1: [i64; 3], pos2: [i64; 3]) -> Self {
+        const fn min(a: i64, b: i64) -> i64 {
+            if a < b {
+                a
+            } else {
+                b
+            }
+        }
+
+        const fn max(a: i64, b: i64) -> i64 {
+            if a > b {
+                a
+            } else {
+                b
+            }
+        }
+
+        Self {
+            min: [
+                min(pos1[0], pos2[0]),
+                min(pos1[1], pos2[1]),
+                min(pos1[2], pos2[2]),
+            ],
+            max: [
+                max(pos1[0], pos2[0]),
+                max(pos1[1], pos2[1]),
+                max(pos1[2], pos2[2]),
+            ],
+        }
+    }
+
+    /// Const version of [`BoundingBox::new_origin`] for dimensions that are already `[i64; 3]`.
+    ///
+    /// # Panics
+    /// Panics (or fails to compile, when evaluated at compile time) if any element of `dimensions` is less than 0.
+    #[inline(always)]
+    pub const fn const_new_origin(dimensions: [i64; 3]) -> Self {
+        assert!(dimensions[0] >= 0);
+        assert!(dimensions[1] >= 0);
+        assert!(dimensions[2] >= 0);
+
+        Self::const_new([0, 0, 0], dimensions)
+    }
+
+    /// The amount of positions in this bounding box. Saturates at [`i128::MAX`] for (absurdly large) boxes with more positions than that.
     #[inline(always)]
-    pub fn capacity(&self) -> i128 {
-        let [x, y, z] = util::sub_ivec3(self.max(), self.min());
-        (x as i128) * (y as i128) * (z as i128)
+    pub const fn capacity(&self) -> i128 {
+        let [x, y, z] = self.dimensions();
+        (x as i128)
+            .saturating_mul(y as i128)
+            .saturating_mul(z as i128)
     }
 
     /// Check if the index is a position inside this bounding box.
@@ -98,6 +691,26 @@ impl BoundingBox {
             && (self.min[2]..self.max[2]).contains(&z)
     }
 
+    /// This bounding box in a worldspace whose origin was moved to `new_origin`, i.e., with `new_origin` subtracted from
+    /// both corners.
+    ///
+    /// # Panics
+    /// Panics if the result overflows [`i64`].
+    #[inline]
+    pub fn rebased(&self, new_origin: [i64; 3]) -> Self {
+        self.checked_rebased(new_origin)
+            .expect("rebased bounding box overflows i64")
+    }
+
+    /// Same as [`BoundingBox::rebased`], but returns [`None`] instead of panicking if the result overflows [`i64`].
+    #[inline]
+    pub fn checked_rebased(&self, new_origin: [i64; 3]) -> Option<Self> {
+        Some(Self {
+            min: self.min.checked_sub(new_origin)?,
+            max: self.max.checked_sub(new_origin)?,
+        })
+    }
+
     #[inline(always)]
     pub fn intersection(&self, rhs: &BoundingBox) -> Option<Self> {
         use std::cmp::{max, min};
@@ -121,112 +734,894 @@ impl BoundingBox {
         Some(Self::new(pos1, pos2))
     }
 
-    #[inline(always)]
-    pub fn overlaps(&self, rhs: &BoundingBox) -> bool {
-        self.min[0] < rhs.max[0]
-            && self.max[0] > rhs.min[0]
-            && self.min[1] < rhs.max[1]
-            && self.max[1] > rhs.min[1]
-            && self.min[2] < rhs.max[2]
-            && self.max[2] > rhs.min[2]
-    }
+    /// The parts of this bounding box that are not in `rhs`, as up to 6 non-overlapping boxes.
+    /// Returns an empty [`Vec`] if `rhs` covers this box entirely.
+    #[inline]
+    pub fn difference(&self, rhs: &BoundingBox) -> Vec<BoundingBox> {
+        let i = match self.intersection(rhs) {
+            Some(i) => i,
+            None if self.is_empty() => return Vec::new(),
+            None => return vec![*self],
+        };
 
-    #[inline(always)]
-    pub fn max(&self) -> [i64; 3] {
-        self.max
-    }
+        let (min, max) = (self.min, self.max);
+        let pieces = [
+            // slabs along X, spanning all of Y and Z
+            ([min[0], min[1], min[2]], [i.min[0], max[1], max[2]]),
+            ([i.max[0], min[1], min[2]], [max[0], max[1], max[2]]),
+            // slabs along Y, within the intersection's X range
+            ([i.min[0], min[1], min[2]], [i.max[0], i.min[1], max[2]]),
+            ([i.min[0], i.max[1], min[2]], [i.max[0], max[1], max[2]]),
+            // slabs along Z, within the intersection's X and Y ranges
+            ([i.min[0], i.min[1], min[2]], [i.max[0], i.max[1], i.min[2]]),
+            ([i.min[0], i.min[1], i.max[2]], [i.max[0], i.max[1], max[2]]),
+        ];
 
-    #[inline(always)]
-    pub fn min(&self) -> [i64; 3] {
-        self.min
+        pieces
+            .into_iter()
+            .map(|(pos1, pos2)| Self::new(pos1, pos2))
+            .filter(|bb| !bb.is_empty())
+            .collect()
     }
 
-    /// Length of the X side of this bounding box.
-    /// /// Should not be negative.
-    #[inline(always)]
-    pub fn x_span(&self) -> i64 {
-        i64::abs(self.max[0] - self.min[0])
-    }
+    /// Split this bounding box with axis aligned planes through `point`, returning the (up to 8) non-empty sub-boxes.
+    /// Following the exclusive maximum convention, `point` becomes the minimum of the "upper" sub-boxes, so the position
+    /// `point` itself ends up in the sub-box on its positive side along every axis.
+    /// Components of `point` outside of the box (or on its edges) don't split the box along that axis.
+    ///
+    /// The sub-boxes are returned in X-major order (the lower X half comes before the upper X half, then Y, then Z).
+    #[inline]
+    pub fn split_at(&self, point: [i64; 3]) -> Vec<BoundingBox> {
+        let ranges = [0, 1, 2].map(|axis| {
+            let (min, max) = (self.min[axis], self.max[axis]);
+            if point[axis] > min && point[axis] < max {
+                vec![(min, point[axis]), (point[axis], max)]
+            } else {
+                vec![(min, max)]
+            }
+        });
 
-    /// Length of the Y side of this bounding box.
-    /// /// Should not be negative.
-    #[inline(always)]
-    pub fn y_span(&self) -> i64 {
-        i64::abs(self.max[1] - self.min[1])
-    }
+        let mut out = Vec::with_capacity(8);
+        for &(z0, z1) in &ranges[2] {
+            for &(y0, y1) in &ranges[1] {
+                for &(x0, x1) in &ranges[0] {
+                    out.push(Self::new([x0, y0, z0], [x1, y1, z1]));
+                }
+            }
+        }
 
-    /// Length of the Z side of this bounding box.
-    /// Should not be negative.
-    #[inline(always)]
-    pub fn z_span(&self) -> i64 {
-        i64::abs(self.max[2] - self.min[2])
+        out.retain(|bb| !bb.is_empty());
+        out
     }
 
-    /// The bounding box's dimensions, in the form of `[x, y, z]`.
-    /// Equal to [[`BoundingBox::x_span, BoundingBox::y_span, BoundingBox::z_span`]]
-    #[inline(always)]
-    pub fn dimensions(&self) -> [i64; 3] {
-        [self.x_span(), self.y_span(), self.z_span()]
+    /// The center of this bounding box used by [`BoundingBox::octants`], rounded down along axes with an odd length.
+    #[inline]
+    pub const fn center(&self) -> [i64; 3] {
+        [
+            self.min[0] + self.x_span() / 2,
+            self.min[1] + self.y_span() / 2,
+            self.min[2] + self.z_span() / 2,
+        ]
     }
-}
+
+    /// Split this bounding box into octants around its [center](BoundingBox::center). Along axes with an odd length
+    /// the upper half gets the extra position. Axes with a length of 1 aren't split, so fewer than 8 boxes may be returned.
+    ///
+    /// The octants are returned in X-major order, see [`BoundingBox::split_at`].
+    #[inline]
+    pub fn octants(&self) -> Vec<BoundingBox> {
+        self.split_at(self.center())
+    }
+
+    /// Subdivide this bounding box into `n` parts along every axis, returning up to `n * n * n` non-empty sub-boxes in X-major order.
+    /// When an axis' length isn't divisible by `n`, the first `length % n` parts along that axis are one position longer than the rest.
+    ///
+    /// # Panics
+    /// Panics if `n` is 0.
+    #[inline]
+    pub fn subdivide(&self, n: u32) -> Vec<BoundingBox> {
+        assert!(n > 0, "cannot subdivide into 0 parts");
+
+        let n = n as i64;
+        let splits = [0, 1, 2].map(|axis| {
+            let (min, len) = (self.min[axis], self.dimensions()[axis]);
+            let (base, extra) = (len / n, len % n);
+
+            (0..n)
+                .map(|i| {
+                    let start = min + i * base + i.min(extra);
+                    let end = start + base + if i < extra { 1 } else { 0 };
+                    (start, end)
+                })
+                .filter(|(start, end)| start < end)
+                .collect::<Vec<_>>()
+        });
+
+        let mut out = Vec::new();
+        for &(z0, z1) in &splits[2] {
+            for &(y0, y1) in &splits[1] {
+                for &(x0, x1) in &splits[0] {
+                    out.push(Self::new([x0, y0, z0], [x1, y1, z1]));
+                }
+            }
+        }
+
+        out
+    }
+
+    /// The Minkowski sum of this box and `other`, i.e., the box containing every position `a + b` where `a` is in this box
+    /// and `b` is in `other`. If `other` holds the offsets of a shape relative to its origin, this is the region that shape
+    /// covers when its origin is placed anywhere inside of this box.
+    ///
+    /// Since the upper bounds are exclusive, the resulting box is one position shorter along every axis than simply
+    /// adding the bounds together. The result is empty (sitting at `self.min() + other.min()`) if either box is empty.
+    #[inline]
+    pub fn minkowski_sum(&self, other: &BoundingBox) -> BoundingBox {
+        let min = util::sum_ivec3(self.min, other.min);
+        if self.is_empty() || other.is_empty() {
+            return Self { min, max: min };
+        }
+
+        Self {
+            min,
+            max: util::sub_ivec3(util::sum_ivec3(self.max, other.max), [1, 1, 1]),
+        }
+    }
+
+    /// The box swept out by moving this box by `direction`, i.e., the smallest box containing both this box and this box
+    /// translated by `direction`. Every side facing along `direction` is pushed outwards while the other sides stay put.
+    /// Empty boxes are returned unchanged.
+    #[inline]
+    pub fn expand_toward(&self, direction: [i64; 3]) -> BoundingBox {
+        if self.is_empty() {
+            return *self;
+        }
+
+        let (mut min, mut max) = (self.min, self.max);
+        for axis in 0..3 {
+            if direction[axis] < 0 {
+                min[axis] += direction[axis];
+            } else {
+                max[axis] += direction[axis];
+            }
+        }
+
+        Self { min, max }
+    }
+
+    /// Check if this bounding box contains no positions (i.e., at least one of its sides has a length of 0).
+    #[inline(always)]
+    pub const fn is_empty(&self) -> bool {
+        self.min[0] == self.max[0] || self.min[1] == self.max[1] || self.min[2] == self.max[2]
+    }
+
+    #[inline(always)]
+    pub const fn overlaps(&self, rhs: &BoundingBox) -> bool {
+        self.min[0] < rhs.max[0]
+            && self.max[0] > rhs.min[0]
+            && self.min[1] < rhs.max[1]
+            && self.max[1] > rhs.min[1]
+            && self.min[2] < rhs.max[2]
+            && self.max[2] > rhs.min[2]
+    }
+
+    #[inline(always)]
+    pub const fn max(&self) -> [i64; 3] {
+        self.max
+    }
+
+    #[inline(always)]
+    pub const fn min(&self) -> [i64; 3] {
+        self.min
+    }
+
+    /// Length of the X side of this bounding box.
+    /// /// Should not be negative.
+    #[inline(always)]
+    pub const fn x_span(&self) -> i64 {
+        i64::abs(self.max[0] - self.min[0])
+    }
+
+    /// Length of the Y side of this bounding box.
+    /// /// Should not be negative.
+    #[inline(always)]
+    pub const fn y_span(&self) -> i64 {
+        i64::abs(self.max[1] - self.min[1])
+    }
+
+    /// Length of the Z side of this bounding box.
+    /// Should not be negative.
+    #[inline(always)]
+    pub const fn z_span(&self) -> i64 {
+        i64::abs(self.max[2] - self.min[2])
+    }
+
+    /// The bounding box's dimensions, in the form of `[x, y, z]`.
+    /// Equal to [[`BoundingBox::x_span, BoundingBox::y_span, BoundingBox::z_span`]]
+    #[inline(always)]
+    pub const fn dimensions(&self) -> [i64; 3] {
+        [self.x_span(), self.y_span(), self.z_span()]
+    }
+
+    /// Iterate over the positions in this bounding box in the given order.
+    #[inline]
+    pub fn iter_order(&self, order: Order) -> OrderedIterator {
+        OrderedIterator::new(*self, order)
+    }
+
+    /// Split this bounding box into cubic sub-boxes with sides of length `n`, aligned to this box's minimum.
+    /// Sub-boxes on the maximum edges are clipped to fit in this box. The sub-boxes are yielded in X-major order
+    /// and together cover every position in this box exactly once, which makes them suitable for splitting up work.
+    ///
+    /// # Panics
+    /// Panics if `n` is 0.
+    #[inline]
+    pub fn iter_chunked(&self, n: u64) -> BoundingBoxChunks {
+        assert!(n > 0, "chunk size must be greater than 0");
+
+        let chunk_size = i64::try_from(n).unwrap_or(i64::MAX);
+        let counts = self.dimensions().map(|d| {
+            let full = d / chunk_size;
+            if d % chunk_size == 0 {
+                full
+            } else {
+                full + 1
+            }
+        });
+
+        BoundingBoxChunks {
+            chunk_size,
+            bounding_box: *self,
+            chunks: BoundingBox::new_origin(counts).into_iter(),
+        }
+    }
+
+    /// Iterate over every `step[axis]`th position along each axis, starting at the minimum of this box, in X-major order.
+    /// For example a step of `[2, 2, 2]` visits one position in every 2x2x2 block of positions.
+    ///
+    /// # Panics
+    /// Panics if any step is 0.
+    #[inline]
+    pub fn iter_strided(&self, step: [usize; 3]) -> StridedIterator {
+        assert!(!step.contains(&0), "step must be greater than 0");
+
+        let step = step.map(|s| i64::try_from(s).unwrap_or(i64::MAX));
+        let counts = [0, 1, 2].map(|a| {
+            let d = self.dimensions()[a];
+            if d == 0 {
+                0
+            } else {
+                (d - 1) / step[a] + 1
+            }
+        });
+
+        StridedIterator {
+            min: self.min,
+            step,
+            positions: BoundingBox::new_origin(counts).into_iter(),
+        }
+    }
+}
+
+/// Parses the two corners of the box separated by `..`, with each corner written like a [`VIdx`] (e.g., `0,0,0..16,16,16`
+/// or `(-8, 0, -8)..(8, 256, 8)`). Also parses the [`Display`](std::fmt::Display) output of a bounding box, so formatting and
+/// parsing round-trips.
+///
+/// Like [`BoundingBox::new`], the corners can be given in any order.
+impl std::str::FromStr for BoundingBox {
+    type Err = ParseBoundsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let displayed = s
+            .strip_prefix("BoundingBox {")
+            .and_then(|s| s.strip_suffix('}'))
+            .and_then(|s| s.trim().strip_prefix("min:"))
+            .and_then(|s| s.split_once(", max:"));
+
+        let (min, max) = match displayed {
+            Some(corners) => corners,
+            None => s
+                .split_once("..")
+                .ok_or(ParseBoundsError::MissingSeparator)?,
+        };
+
+        let [min, max] = [min.parse::<VIdx>()?, max.parse::<VIdx>()?];
+        Self::checked_new(min.0, max.0).ok_or(ParseBoundsError::TooLarge)
+    }
+}
 
 impl std::fmt::Display for BoundingBox {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let [sx, sy, sz] = self.min();
-        let [bx, by, bz] = self.max();
-
         write!(
             f,
-            "BoundingBox {{ min: ({sx}, {sy}, {sz}), max: ({bx}, {by}, {bz}) }}"
+            "BoundingBox {{ min: {}, max: {} }}",
+            VIdx(self.min()),
+            VIdx(self.max())
         )
     }
 }
 
+/// Bounds sitting at the origin (0, 0, 0) with dimensions known at compile time. Interchangeable with an equivalent
+/// [`BoundingBox`] through the [`Bounds`] trait and the [`From`] conversion, but the capacity and strides are constants,
+/// and it guarantees at the type level that every volume using it has the same dimensions (e.g., uniform chunk sizes).
+/// [`StackVolume`]s use the static bounds of their dimensions for their bounds and indexing.
+///
+/// Using dimensions that don't fit in an [`i64`] (or whose capacity doesn't fit in a [`usize`]) fails to compile.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct StaticBounds<const X: usize, const Y: usize, const Z: usize>;
+
+impl<const X: usize, const Y: usize, const Z: usize> StaticBounds<X, Y, Z> {
+    pub const DIMENSIONS: [i64; 3] = {
+        assert!(
+            X <= i64::MAX as usize && Y <= i64::MAX as usize && Z <= i64::MAX as usize,
+            "static bounds dimensions must fit in an i64"
+        );
+        [X as i64, Y as i64, Z as i64]
+    };
+
+    /// The amount of positions in these bounds.
+    pub const CAPACITY: usize = X * Y * Z;
+
+    /// The distance (in items) between neighboring positions along each axis in a densely packed `[x][y][z]` array,
+    /// which is the storage layout of [`HeapVolume`] and [`StackVolume`].
+    pub const STRIDES: [usize; 3] = [Y * Z, Z, 1];
+
+    #[inline]
+    pub const fn new() -> Self {
+        let _ = Self::DIMENSIONS;
+        Self
+    }
+
+    /// The equivalent [`BoundingBox`].
+    #[inline]
+    pub const fn bounding_box() -> BoundingBox {
+        BoundingBox::const_new_origin(Self::DIMENSIONS)
+    }
+
+    /// The index into a densely packed `[x][y][z]` array of the given localspace position, or [`None`] if it's out of bounds.
+    #[inline(always)]
+    pub const fn linear_index(pos: [i64; 3]) -> Option<usize> {
+        let [x, y, z] = pos;
+        let [dx, dy, dz] = Self::DIMENSIONS;
+        if x < 0 || y < 0 || z < 0 || x >= dx || y >= dy || z >= dz {
+            return None;
+        }
+
+        Some(x as usize * Self::STRIDES[0] + y as usize * Self::STRIDES[1] + z as usize)
+    }
+}
+
+impl<const X: usize, const Y: usize, const Z: usize> Default for StaticBounds<X, Y, Z> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const X: usize, const Y: usize, const Z: usize> From<StaticBounds<X, Y, Z>> for BoundingBox {
+    #[inline]
+    fn from(_: StaticBounds<X, Y, Z>) -> Self {
+        StaticBounds::<X, Y, Z>::bounding_box()
+    }
+}
+
+impl<const X: usize, const Y: usize, const Z: usize> std::fmt::Display for StaticBounds<X, Y, Z> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "StaticBounds<{X}, {Y}, {Z}>")
+    }
+}
+
+impl<const X: usize, const Y: usize, const Z: usize> IntoIterator for StaticBounds<X, Y, Z> {
+    type Item = [i64; 3];
+    type IntoIter = BoundingBoxIterator;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        Self::bounding_box().into_iter()
+    }
+}
+
+/// Returned when converting a [`BoundingBox`] to [`SmallBounds`] whose integer type can't represent its corners.
+#[cfg(feature = "small-bounds")]
+#[derive(te::Error, Debug, Copy, Clone, PartialEq, Eq)]
+#[error("the corners of bounds {0} don't fit in the integer type of the small bounds")]
+pub struct SmallBoundsError(pub BoundingBox);
+
+#[cfg(feature = "small-bounds")]
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// The integer types [`SmallBounds`] can store their corners as. Only implemented for integers at most 32 bits wide, so
+/// every corner (and the offset between any two of them) fits in an `i64`.
+#[cfg(feature = "small-bounds")]
+pub trait SmallInt: PrimInt + sealed::Sealed {}
+
+#[cfg(feature = "small-bounds")]
+macro_rules! impl_small_int {
+    ($($t:ty),+) => {
+        $(
+            impl sealed::Sealed for $t {}
+            impl SmallInt for $t {}
+        )+
+    };
+}
+
+#[cfg(feature = "small-bounds")]
+impl_small_int!(i8, i16, i32, u8, u16);
+
+/// Bounds storing their corners as a narrower integer type (`i16` by default, or `i32`). Interchangeable with an equivalent
+/// [`BoundingBox`] through the [`Bounds`] trait and the [`From`]/[`TryFrom`] conversions, but takes up 12 (or 24) bytes
+/// instead of 48, which adds up for chunk-local metadata kept around for millions of chunks.
+#[cfg(feature = "small-bounds")]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct SmallBounds<N: SmallInt = i16> {
+    min: [N; 3],
+    max: [N; 3],
+}
+
+#[cfg(feature = "small-bounds")]
+impl<N: SmallInt> SmallBounds<N> {
+    /// Construct new bounds spanning the two positions, like [`BoundingBox::new`].
+    #[inline]
+    pub fn new(pos1: [N; 3], pos2: [N; 3]) -> Self {
+        Self {
+            min: [0, 1, 2].map(|axis| pos1[axis].min(pos2[axis])),
+            max: [0, 1, 2].map(|axis| pos1[axis].max(pos2[axis])),
+        }
+    }
+
+    /// The minimum (inclusive) corner, in the narrow integer type.
+    #[inline(always)]
+    pub fn small_min(&self) -> [N; 3] {
+        self.min
+    }
+
+    /// The maximum (exclusive) corner, in the narrow integer type.
+    #[inline(always)]
+    pub fn small_max(&self) -> [N; 3] {
+        self.max
+    }
+
+    /// The index into a densely packed `[x][y][z]` array (the storage layout of [`HeapVolume`] and [`StackVolume`])
+    /// of the given worldspace position, or [`None`] if it's out of bounds.
+    #[inline(always)]
+    pub fn linear_index(&self, pos: [N; 3]) -> Option<usize> {
+        if (0..3).any(|axis| pos[axis] < self.min[axis] || pos[axis] >= self.max[axis]) {
+            return None;
+        }
+
+        // `SmallInt`s are at most 32 bits wide, so the offsets fit in an `i64` even when they'd overflow `N`. The index
+        // itself can still overflow a `usize` for very large `i32` bounds (or on 32-bit targets).
+        let offset = |to: [N; 3], axis: usize| {
+            usize::try_from(to[axis].to_i64()? - self.min[axis].to_i64()?).ok()
+        };
+
+        offset(pos, 0)?
+            .checked_mul(offset(self.max, 1)?)?
+            .checked_add(offset(pos, 1)?)?
+            .checked_mul(offset(self.max, 2)?)?
+            .checked_add(offset(pos, 2)?)
+    }
+}
+
+#[cfg(feature = "small-bounds")]
+impl<N: SmallInt> From<SmallBounds<N>> for BoundingBox {
+    #[inline]
+    fn from(bounds: SmallBounds<N>) -> Self {
+        BoundingBox::new(bounds.min, bounds.max)
+    }
+}
+
+#[cfg(feature = "small-bounds")]
+impl<N: SmallInt> TryFrom<BoundingBox> for SmallBounds<N> {
+    type Error = SmallBoundsError;
+
+    #[inline]
+    fn try_from(bounds: BoundingBox) -> Result<Self, Self::Error> {
+        match (
+            util::cast_ivec3(bounds.min()),
+            util::cast_ivec3(bounds.max()),
+        ) {
+            (Some(min), Some(max)) => Ok(Self { min, max }),
+            _ => Err(SmallBoundsError(bounds)),
+        }
+    }
+}
+
+#[cfg(feature = "small-bounds")]
+impl<N: SmallInt> std::fmt::Display for SmallBounds<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        BoundingBox::from(*self).fmt(f)
+    }
+}
+
+#[cfg(feature = "small-bounds")]
+impl<N: SmallInt> IntoIterator for SmallBounds<N> {
+    type Item = [i64; 3];
+    type IntoIter = BoundingBoxIterator;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        BoundingBox::from(self).into_iter()
+    }
+}
+
 impl IntoIterator for BoundingBox {
     type Item = [i64; 3];
     type IntoIter = BoundingBoxIterator;
 
     fn into_iter(self) -> Self::IntoIter {
         BoundingBoxIterator {
-            current: self.min,
+            front: 0,
+            back: self.capacity().max(0) as u128,
             bounding_box: self,
         }
     }
 }
 
+/// Iterator over the positions in a [`BoundingBox`], in X-major order (X changes fastest, then Y, then Z).
+///
+/// Positions are computed from a linear offset into the box, so skipping ahead with [`Iterator::nth`], iterating
+/// from the back, and getting the remaining length are all O(1).
+///
+/// # Panics
+/// [`ExactSizeIterator::len`] panics if more than [`usize::MAX`] positions remain.
+#[derive(Debug, Clone)]
 pub struct BoundingBoxIterator {
-    current: [i64; 3],
+    front: u128,
+    back: u128,
     bounding_box: BoundingBox,
 }
 
+impl BoundingBoxIterator {
+    /// Get the position at the given linear offset into the bounding box.
+    #[inline(always)]
+    fn delinearize(&self, offset: u128) -> [i64; 3] {
+        let [dx, dy, _] = self.bounding_box.dimensions().map(|d| d as u128);
+        let min = self.bounding_box.min();
+
+        let x = offset % dx;
+        let y = (offset / dx) % dy;
+        let z = offset / (dx * dy);
+
+        [min[0] + x as i64, min[1] + y as i64, min[2] + z as i64]
+    }
+
+    /// How many positions are left in this iterator.
+    #[inline(always)]
+    pub fn remaining(&self) -> u128 {
+        self.back - self.front
+    }
+}
+
 impl Iterator for BoundingBoxIterator {
     type Item = [i64; 3];
 
     #[inline(always)]
     fn next(&mut self) -> Option<<Self as Iterator>::Item> {
-        let bb = self.bounding_box;
+        if self.front >= self.back {
+            return None;
+        }
+
+        let out = self.delinearize(self.front);
+        self.front += 1;
+        Some(out)
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.front = std::cmp::min(self.front.saturating_add(n as u128), self.back);
+        self.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match usize::try_from(self.remaining()) {
+            Ok(len) => (len, Some(len)),
+            Err(_) => (usize::MAX, None),
+        }
+    }
+
+    #[inline]
+    fn count(self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn last(mut self) -> Option<Self::Item> {
+        self.next_back()
+    }
+}
+
+impl DoubleEndedIterator for BoundingBoxIterator {
+    #[inline(always)]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+
+        self.back -= 1;
+        Some(self.delinearize(self.back))
+    }
 
-        let out = if self.current[2] >= bb.max()[2] {
-            None
-        } else {
-            Some(self.current)
+    #[inline]
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        self.back = std::cmp::max(self.back.saturating_sub(n as u128), self.front);
+        self.next_back()
+    }
+}
+
+impl ExactSizeIterator for BoundingBoxIterator {}
+impl std::iter::FusedIterator for BoundingBoxIterator {}
+
+/// Iterator over the sub-boxes of a [`BoundingBox`] returned by [`BoundingBox::iter_chunked`].
+#[derive(Debug, Clone)]
+pub struct BoundingBoxChunks {
+    chunk_size: i64,
+    bounding_box: BoundingBox,
+    chunks: BoundingBoxIterator,
+}
+
+impl BoundingBoxChunks {
+    #[inline(always)]
+    fn chunk(&self, [cx, cy, cz]: [i64; 3]) -> BoundingBox {
+        let min = util::sum_ivec3(
+            self.bounding_box.min(),
+            [
+                cx * self.chunk_size,
+                cy * self.chunk_size,
+                cz * self.chunk_size,
+            ],
+        );
+        let max = min.map(|c| c.saturating_add(self.chunk_size));
+        let bb_max = self.bounding_box.max();
+
+        BoundingBox::new(
+            min,
+            [
+                std::cmp::min(max[0], bb_max[0]),
+                std::cmp::min(max[1], bb_max[1]),
+                std::cmp::min(max[2], bb_max[2]),
+            ],
+        )
+    }
+}
+
+impl Iterator for BoundingBoxChunks {
+    type Item = BoundingBox;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let chunk = self.chunks.next()?;
+        Some(self.chunk(chunk))
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        let chunk = self.chunks.nth(n)?;
+        Some(self.chunk(chunk))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for BoundingBoxChunks {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let chunk = self.chunks.next_back()?;
+        Some(self.chunk(chunk))
+    }
+}
+
+impl ExactSizeIterator for BoundingBoxChunks {}
+impl std::iter::FusedIterator for BoundingBoxChunks {}
+
+/// Iterator over every Nth position along each axis of a [`BoundingBox`]. Returned by [`BoundingBox::iter_strided`].
+#[derive(Debug, Clone)]
+pub struct StridedIterator {
+    min: [i64; 3],
+    step: [i64; 3],
+    positions: BoundingBoxIterator,
+}
+
+impl StridedIterator {
+    #[inline(always)]
+    fn strided(&self, [x, y, z]: [i64; 3]) -> [i64; 3] {
+        [
+            self.min[0] + x * self.step[0],
+            self.min[1] + y * self.step[1],
+            self.min[2] + z * self.step[2],
+        ]
+    }
+}
+
+impl Iterator for StridedIterator {
+    type Item = [i64; 3];
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let pos = self.positions.next()?;
+        Some(self.strided(pos))
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        let pos = self.positions.nth(n)?;
+        Some(self.strided(pos))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.positions.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for StridedIterator {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let pos = self.positions.next_back()?;
+        Some(self.strided(pos))
+    }
+}
+
+impl ExactSizeIterator for StridedIterator {}
+impl std::iter::FusedIterator for StridedIterator {}
+
+/// Traversal order for iterating over a [`BoundingBox`] or a volume.
+///
+/// For the axis orders the axes are listed from fastest to slowest changing, so [`Order::Xyz`] (the default order used by
+/// [`BoundingBox::into_iter`]) visits every position along X before moving one step along Y, and every XY plane before moving
+/// one step along Z.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum Order {
+    #[default]
+    Xyz,
+    Xzy,
+    Yxz,
+    Yzx,
+    Zxy,
+    Zyx,
+    /// Z-order curve. Positions that are close in the traversal are close in space, which keeps cache locality good
+    /// for algorithms that access the neighbors of each position.
+    Morton,
+}
+
+impl Order {
+    /// The axes (0 = X, 1 = Y, 2 = Z) of this order from fastest to slowest changing, or [`None`] for [`Order::Morton`].
+    #[inline]
+    pub fn axes(self) -> Option<[usize; 3]> {
+        match self {
+            Self::Xyz => Some([0, 1, 2]),
+            Self::Xzy => Some([0, 2, 1]),
+            Self::Yxz => Some([1, 0, 2]),
+            Self::Yzx => Some([1, 2, 0]),
+            Self::Zxy => Some([2, 0, 1]),
+            Self::Zyx => Some([2, 1, 0]),
+            Self::Morton => None,
+        }
+    }
+}
+
+/// Iterator over the positions in a [`BoundingBox`] in a chosen [`Order`]. Returned by [`BoundingBox::iter_order`].
+#[derive(Debug, Clone)]
+pub struct OrderedIterator {
+    kind: OrderedIteratorKind,
+}
+
+#[derive(Debug, Clone)]
+enum OrderedIteratorKind {
+    /// Iterates a box with permuted dimensions in the default order, and maps the positions back to the real axes.
+    Axes {
+        inner: BoundingBoxIterator,
+        axes: [usize; 3],
+        min: [i64; 3],
+    },
+    /// Iterates over all Morton codes with enough bits to cover the box, skipping the ones that decode to positions outside of it.
+    /// Axes only get bits interleaved for as long as they need them, so elongated boxes don't waste codes on a cube around them.
+    Morton {
+        code: u128,
+        end: u128,
+        bits: [u32; 3],
+        dims: [i64; 3],
+        min: [i64; 3],
+    },
+}
+
+impl OrderedIterator {
+    #[inline]
+    pub(crate) fn new(bounding_box: BoundingBox, order: Order) -> Self {
+        let dims = bounding_box.dimensions();
+        let min = bounding_box.min();
+
+        let kind = match order.axes() {
+            Some(axes) => OrderedIteratorKind::Axes {
+                inner: BoundingBox::new_origin(axes.map(|axis| dims[axis])).into_iter(),
+                axes,
+                min,
+            },
+            None => {
+                let bits = dims.map(|d| 64 - (d.max(1) as u64 - 1).leading_zeros());
+                let end = if dims.contains(&0) {
+                    0
+                } else {
+                    1u128.checked_shl(bits.iter().sum()).unwrap_or(u128::MAX)
+                };
+
+                OrderedIteratorKind::Morton {
+                    code: 0,
+                    end,
+                    bits,
+                    dims,
+                    min,
+                }
+            }
         };
 
-        self.current[0] += 1;
-        if self.current[0] >= bb.max()[0] {
-            self.current[0] = bb.min()[0];
-            self.current[1] += 1;
+        Self { kind }
+    }
+}
+
+#[inline]
+fn decode_morton(mut code: u128, bits: [u32; 3]) -> [i64; 3] {
+    let mut out = [0i64; 3];
 
-            if self.current[1] >= bb.max()[1] {
-                self.current[1] = bb.min()[1];
-                self.current[2] += 1;
+    for level in 0..bits.iter().copied().max().unwrap_or(0) {
+        for axis in 0..3 {
+            if level < bits[axis] {
+                out[axis] |= ((code & 1) as i64) << level;
+                code >>= 1;
             }
         }
+    }
 
-        out
+    out
+}
+
+impl Iterator for OrderedIterator {
+    type Item = [i64; 3];
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.kind {
+            OrderedIteratorKind::Axes { inner, axes, min } => {
+                let permuted = inner.next()?;
+
+                let mut out = *min;
+                for i in 0..3 {
+                    out[axes[i]] += permuted[i];
+                }
+
+                Some(out)
+            }
+            OrderedIteratorKind::Morton {
+                code,
+                end,
+                bits,
+                dims,
+                min,
+            } => {
+                while *code < *end {
+                    let pos = decode_morton(*code, *bits);
+                    *code += 1;
+
+                    if pos[0] < dims[0] && pos[1] < dims[1] && pos[2] < dims[2] {
+                        return Some(util::sum_ivec3(pos, *min));
+                    }
+                }
+
+                None
+            }
+        }
     }
 }
 
+impl std::iter::FusedIterator for OrderedIterator {}
+
 pub struct VolumeIterator<'a, Vol: Volume> {
     pub(crate) volume: &'a Vol,
     pub(crate) bb_iterator: BoundingBoxIterator,
@@ -240,4 +1635,152 @@ impl<'a, Vol: Volume> Iterator for VolumeIterator<'a, Vol> {
         let idx = self.bb_iterator.next()?;
         self.volume.get(idx)
     }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.bb_iterator.size_hint()
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        let idx = self.bb_iterator.nth(n)?;
+        self.volume.get(idx)
+    }
+}
+
+impl<'a, Vol: Volume> DoubleEndedIterator for VolumeIterator<'a, Vol> {
+    #[inline(always)]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let idx = self.bb_iterator.next_back()?;
+        self.volume.get(idx)
+    }
+}
+
+impl<'a, Vol: Volume> ExactSizeIterator for VolumeIterator<'a, Vol> {}
+impl<'a, Vol: Volume> std::iter::FusedIterator for VolumeIterator<'a, Vol> {}
+
+/// Iterator over the worldspace indices and items of a volume. Returned by [`Volume::iter_indexed`].
+pub struct IndexedVolumeIterator<'a, Vol: Volume> {
+    pub(crate) volume: &'a Vol,
+    pub(crate) bb_iterator: BoundingBoxIterator,
+}
+
+impl<'a, Vol: Volume> Iterator for IndexedVolumeIterator<'a, Vol> {
+    type Item = ([i64; 3], &'a <Vol as Volume>::Item);
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        let idx = self.bb_iterator.next()?;
+        Some((idx, self.volume.get(idx)?))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.bb_iterator.size_hint()
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        let idx = self.bb_iterator.nth(n)?;
+        Some((idx, self.volume.get(idx)?))
+    }
+}
+
+impl<'a, Vol: Volume> DoubleEndedIterator for IndexedVolumeIterator<'a, Vol> {
+    #[inline(always)]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let idx = self.bb_iterator.next_back()?;
+        Some((idx, self.volume.get(idx)?))
+    }
 }
+
+impl<'a, Vol: Volume> ExactSizeIterator for IndexedVolumeIterator<'a, Vol> {}
+impl<'a, Vol: Volume> std::iter::FusedIterator for IndexedVolumeIterator<'a, Vol> {}
+
+/// Iterator over every Nth worldspace index and item along each axis of a volume. Returned by [`Volume::iter_strided`].
+pub struct StridedVolumeIterator<'a, Vol: Volume> {
+    pub(crate) volume: &'a Vol,
+    pub(crate) indices: StridedIterator,
+}
+
+impl<'a, Vol: Volume> Iterator for StridedVolumeIterator<'a, Vol> {
+    type Item = ([i64; 3], &'a <Vol as Volume>::Item);
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        let idx = self.indices.next()?;
+        Some((idx, self.volume.get(idx)?))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indices.size_hint()
+    }
+}
+
+impl<'a, Vol: Volume> DoubleEndedIterator for StridedVolumeIterator<'a, Vol> {
+    #[inline(always)]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let idx = self.indices.next_back()?;
+        Some((idx, self.volume.get(idx)?))
+    }
+}
+
+impl<'a, Vol: Volume> ExactSizeIterator for StridedVolumeIterator<'a, Vol> {}
+impl<'a, Vol: Volume> std::iter::FusedIterator for StridedVolumeIterator<'a, Vol> {}
+
+/// Iterator over the items of a volume in a chosen [`Order`]. Returned by [`Volume::iter_ordered`].
+pub struct OrderedVolumeIterator<'a, Vol: Volume> {
+    pub(crate) volume: &'a Vol,
+    pub(crate) indices: OrderedIterator,
+}
+
+impl<'a, Vol: Volume> Iterator for OrderedVolumeIterator<'a, Vol> {
+    type Item = &'a <Vol as Volume>::Item;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        let idx = self.indices.next()?;
+        self.volume.get(idx)
+    }
+}
+
+/// Iterator over the worldspace indices in the intersection of two volumes' bounds, and the items of both volumes at them.
+/// Returned by [`Volume::zip_iter`].
+pub struct ZipVolumeIterator<'a, A: Volume, B: Volume> {
+    pub(crate) lhs: &'a A,
+    pub(crate) rhs: &'a B,
+    pub(crate) bb_iterator: BoundingBoxIterator,
+}
+
+impl<'a, A: Volume, B: Volume> Iterator for ZipVolumeIterator<'a, A, B> {
+    type Item = ([i64; 3], &'a A::Item, &'a B::Item);
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        let idx = self.bb_iterator.next()?;
+        Some((idx, self.lhs.get(idx)?, self.rhs.get(idx)?))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.bb_iterator.size_hint()
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        let idx = self.bb_iterator.nth(n)?;
+        Some((idx, self.lhs.get(idx)?, self.rhs.get(idx)?))
+    }
+}
+
+impl<'a, A: Volume, B: Volume> DoubleEndedIterator for ZipVolumeIterator<'a, A, B> {
+    #[inline(always)]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let idx = self.bb_iterator.next_back()?;
+        Some((idx, self.lhs.get(idx)?, self.rhs.get(idx)?))
+    }
+}
+
+impl<'a, A: Volume, B: Volume> ExactSizeIterator for ZipVolumeIterator<'a, A, B> {}
+impl<'a, A: Volume, B: Volume> std::iter::FusedIterator for ZipVolumeIterator<'a, A, B> {}
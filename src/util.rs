@@ -1,26 +1,256 @@
-use num_traits::{NumCast, PrimInt};
+use crate::traits::VolumeIdx;
+use crate::types::{Axis, BoundingBox, Cancelled, InsertError, InsertErrorDetails, VIdx};
+use std::ops::ControlFlow;
+
+pub(crate) use crate::math::{cast_ivec3, sub_ivec3, sum_ivec3};
+
+/// Panic because `idx` doesn't refer to an item in a volume with the given bounds. Used by the [`Index`](std::ops::Index)
+/// and [`IndexMut`](std::ops::IndexMut) implementations of the volumes.
+#[cold]
+#[inline(never)]
+#[track_caller]
+pub(crate) fn index_out_of_bounds<Idx: VolumeIdx>(idx: Idx, bounds: BoundingBox) -> ! {
+    match idx.array::<i64>() {
+        Some(pos) => panic!("index {} is out of bounds for a volume with bounds {bounds}", VIdx(pos)),
+        None => panic!("index cannot be converted to [i64; 3], so it's out of bounds for a volume with bounds {bounds}"),
+    }
+}
+
+/// The localspace ranges along each axis covered by the part of `region` inside of `bounds`, for walking the rows of
+/// `[x][y][z]` storage directly. Returns [`None`] if they don't overlap.
+#[inline]
+pub(crate) fn local_ranges(
+    bounds: BoundingBox,
+    region: BoundingBox,
+) -> Option<[std::ops::Range<usize>; 3]> {
+    let region = region.intersection(&bounds)?;
+    let min = cast_ivec3::<usize, _>(sub_ivec3(region.min(), bounds.min()))?;
+    let max = cast_ivec3::<usize, _>(sub_ivec3(region.max(), bounds.min()))?;
+
+    Some([0, 1, 2].map(|axis| min[axis]..max[axis]))
+}
+
+/// Hint to the CPU that the memory at `ptr` is about to be read, so it can start loading it into the cache. Never faults,
+/// even for dangling pointers, and does nothing on architectures without a stable prefetch instruction.
+#[inline(always)]
+pub(crate) fn prefetch<T>(ptr: *const T) {
+    #[cfg(target_arch = "x86_64")]
+    #[allow(unused_unsafe)]
+    // SAFETY: prefetching is only a hint and doesn't access the memory, so any address is fine.
+    unsafe {
+        use std::arch::x86_64::{_mm_prefetch, _MM_HINT_T0};
+        _mm_prefetch::<_MM_HINT_T0>(ptr.cast());
+    }
+
+    #[cfg(not(target_arch = "x86_64"))]
+    let _ = ptr;
+}
+
+/// Fold the items of `[x][y][z]` storage (`planes`, covering `bounds`) that are inside `region`, walking whole rows at a
+/// time. Shared by the [`try_fold_region`](crate::traits::Volume::try_fold_region) implementations of the array-backed volumes.
+#[inline]
+pub(crate) fn try_fold_rows<P, R, T, B, C, F>(
+    planes: &[P],
+    bounds: BoundingBox,
+    region: BoundingBox,
+    init: B,
+    mut f: F,
+) -> ControlFlow<C, B>
+where
+    P: AsRef<[R]>,
+    R: AsRef<[T]>,
+    F: FnMut(B, &T) -> ControlFlow<C, B>,
+{
+    let Some([xs, ys, zs]) = local_ranges(bounds, region) else {
+        return ControlFlow::Continue(init);
+    };
+
+    if zs.is_empty() {
+        return ControlFlow::Continue(init);
+    }
+
+    let mut rows = planes[xs]
+        .iter()
+        .flat_map(|plane| &plane.as_ref()[ys.clone()])
+        .map(AsRef::as_ref)
+        .peekable();
+
+    let mut acc = init;
+    while let Some(row) = rows.next() {
+        // Only prefetch one row ahead, so stopping early doesn't pay for the rest of the region.
+        if let Some(next) = rows.peek() {
+            prefetch(&next[zs.start]);
+        }
+
+        for item in &row[zs.clone()] {
+            acc = f(acc, item)?;
+        }
+    }
+
+    ControlFlow::Continue(acc)
+}
 
 pub(crate) fn boxed_slice<T: Clone>(item: T, len: usize) -> Box<[T]> {
     vec![item; len].into_boxed_slice()
 }
 
-#[inline(always)]
-pub(crate) fn cast_ivec3<T: NumCast, N: PrimInt>(arr: [N; 3]) -> Option<[T; 3]> {
-    let [x, y, z] = arr;
+/// Fallible version of [`boxed_slice`], returning [`None`] instead of aborting if the allocation fails.
+#[inline]
+pub(crate) fn try_boxed_slice<T>(
+    len: usize,
+    mut item: impl FnMut() -> Option<T>,
+) -> Option<Box<[T]>> {
+    let mut items = Vec::new();
+    items.try_reserve_exact(len).ok()?;
+    for _ in 0..len {
+        items.push(item()?);
+    }
 
-    Some([
-        <T as NumCast>::from(x)?,
-        <T as NumCast>::from(y)?,
-        <T as NumCast>::from(z)?,
-    ])
+    Some(items.into_boxed_slice())
 }
 
-#[inline(always)]
-pub(crate) fn sum_ivec3<N: std::ops::Add<Output = N> + Copy>(lhs: [N; 3], rhs: [N; 3]) -> [N; 3] {
-    [lhs[0] + rhs[0], lhs[1] + rhs[1], lhs[2] + rhs[2]]
+/// The bounds `inserted` covers when moved by `offset`, along with the offset itself. Errors if moving it overflows.
+/// A `None` offset means the offset couldn't be converted to `[i64; 3]`.
+pub(crate) fn moved_bounds(
+    inserted: BoundingBox,
+    offset: Option<[i64; 3]>,
+) -> Result<([i64; 3], BoundingBox), InsertError> {
+    let overflow = || InsertError::OffsetOverflow { inserted };
+    let offset = offset.ok_or_else(overflow)?;
+    let min = inserted.min().checked_add(offset).ok_or_else(overflow)?;
+    let max = inserted.max().checked_add(offset).ok_or_else(overflow)?;
+
+    Ok((offset, BoundingBox::new(min, max)))
 }
 
+/// The region `inserted` covers in `destination` when moved by `offset`, erroring if it doesn't fit.
+/// A `None` offset means the offset couldn't be converted to `[i64; 3]`.
+pub(crate) fn insert_target(
+    destination: BoundingBox,
+    inserted: BoundingBox,
+    offset: Option<[i64; 3]>,
+) -> Result<BoundingBox, InsertError> {
+    let (offset, target) = moved_bounds(inserted, offset)?;
+    if target.is_empty() {
+        return Ok(target);
+    }
+    if usize::try_from(inserted.capacity()).is_err() {
+        return Err(InsertError::CapacityOverflow { inserted });
+    }
+
+    let escaping = Axis::ALL.into_iter().find(|&axis| {
+        let axis = axis.index();
+        target.min()[axis] < destination.min()[axis] || target.max()[axis] > destination.max()[axis]
+    });
+
+    match escaping {
+        Some(axis) => Err(InsertError::VolumeEscapesBounds(Box::new(
+            InsertErrorDetails {
+                destination,
+                inserted,
+                offset,
+                axis,
+            },
+        ))),
+        None => Ok(target),
+    }
+}
+
+/// Tracks the progress of a long-running operation and reports it to a user provided callback.
+/// The callback is only invoked when the progress has advanced by at least 0.1%,
+/// so that reporting doesn't dominate the cost of the operation.
+pub(crate) struct ProgressTracker<F: FnMut(f32) -> ControlFlow<()>> {
+    callback: F,
+    total: u64,
+    done: u64,
+    last_reported: u64,
+}
+
+impl<F: FnMut(f32) -> ControlFlow<()>> ProgressTracker<F> {
+    #[inline]
+    pub(crate) fn new(total: u64, callback: F) -> Self {
+        Self {
+            callback,
+            total: total.max(1),
+            done: 0,
+            last_reported: 0,
+        }
+    }
+
+    /// Advance the progress by `steps` and report it if needed.
+    /// Returns [`Err(Cancelled)`] if the callback requested cancellation.
+    #[inline]
+    pub(crate) fn advance(&mut self, steps: u64) -> Result<(), Cancelled> {
+        self.done = (self.done + steps).min(self.total);
+
+        let permille = self.done * 1000 / self.total;
+        if permille > self.last_reported {
+            self.last_reported = permille;
+            if let ControlFlow::Break(()) = (self.callback)(self.done as f32 / self.total as f32) {
+                return Err(Cancelled);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Progress callback that never cancels, used by the non-reporting variants of long-running operations.
 #[inline(always)]
-pub(crate) fn sub_ivec3<N: std::ops::Sub<Output = N> + Copy>(lhs: [N; 3], rhs: [N; 3]) -> [N; 3] {
-    [lhs[0] - rhs[0], lhs[1] - rhs[1], lhs[2] - rhs[2]]
+pub(crate) fn no_progress(_: f32) -> ControlFlow<()> {
+    ControlFlow::Continue(())
+}
+
+/// 64-bit FNV-1a hasher. Unlike [`std::collections::hash_map::DefaultHasher`] its output is specified and never changes,
+/// and integers are always hashed as little endian (with `usize`/`isize` widened to 64 bits), so hashes are stable across
+/// runs, Rust versions, and platforms.
+pub(crate) struct StableHasher(u64);
+
+impl StableHasher {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    #[inline]
+    pub(crate) fn new() -> Self {
+        Self(Self::OFFSET_BASIS)
+    }
+}
+
+macro_rules! stable_hasher_write_int {
+    ($($method:ident: $t:ty => $as:ty),+) => {
+        $(
+            #[inline]
+            fn $method(&mut self, i: $t) {
+                self.write(&(i as $as).to_le_bytes());
+            }
+        )+
+    };
+}
+
+impl std::hash::Hasher for StableHasher {
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(Self::PRIME);
+        }
+    }
+
+    stable_hasher_write_int!(
+        write_u16: u16 => u16,
+        write_u32: u32 => u32,
+        write_u64: u64 => u64,
+        write_u128: u128 => u128,
+        write_usize: usize => u64,
+        write_i16: i16 => i16,
+        write_i32: i32 => i32,
+        write_i64: i64 => i64,
+        write_i128: i128 => i128,
+        write_isize: isize => i64
+    );
 }
@@ -0,0 +1,911 @@
+//! Views presenting an existing volume differently without copying its items.
+
+use crate::prelude::*;
+use crate::traits::IntoBoundingBox;
+
+/// Read-only view of one part of every item of a volume, e.g. one field of a struct item. Created with [`Volume::project`].
+///
+/// The view is itself a volume (with the same bounds as the viewed volume), so algorithms written for volumes of scalars
+/// can run directly on one field of a volume of composite items.
+pub struct Projection<'a, V, F> {
+    pub(crate) volume: &'a V,
+    pub(crate) f: F,
+}
+
+impl<'a, V, F> Clone for Projection<'a, V, F>
+where
+    F: Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            volume: self.volume,
+            f: self.f.clone(),
+        }
+    }
+}
+
+impl<'a, V, F, U> Volume for Projection<'a, V, F>
+where
+    V: Volume,
+    F: Fn(&V::Item) -> &U,
+{
+    type Item = U;
+
+    #[inline(always)]
+    fn ls_get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        self.volume.ls_get(idx).map(&self.f)
+    }
+
+    #[inline(always)]
+    fn bounding_box(&self) -> BoundingBox {
+        self.volume.bounding_box()
+    }
+
+    #[inline(always)]
+    fn get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        self.volume.get(idx).map(&self.f)
+    }
+}
+
+impl_indexing!(['a, V, F], Projection<'a, V, F>);
+
+/// Wrapper around a volume where the chosen axes wrap around, so the volume repeats forever along them.
+/// Along a wrapping axis with a span of `s`, `get([x + s, y, z])` is the same item as `get([x, y, z])`.
+///
+/// The [bounding box](Volume::bounding_box) is the inner volume's, so iterating over this volume visits every item once.
+/// Indices outside of it are resolved by wrapping them into it, and along axes that don't wrap they're out of bounds as usual.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TorusVolume<V> {
+    inner: V,
+    wrap: [bool; 3],
+}
+
+impl_indexing!(V, TorusVolume<V>);
+
+impl<V: Volume> TorusVolume<V> {
+    /// Wrap `inner` along every axis where `wrap` is `true`.
+    #[inline]
+    pub fn new(inner: V, wrap: [bool; 3]) -> Self {
+        Self { inner, wrap }
+    }
+
+    /// The axes this volume wraps around along.
+    #[inline]
+    pub fn wrapping(&self) -> [bool; 3] {
+        self.wrap
+    }
+
+    #[inline]
+    pub fn inner(&self) -> &V {
+        &self.inner
+    }
+
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut V {
+        &mut self.inner
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> V {
+        self.inner
+    }
+
+    /// Resolve a worldspace index to the position inside of the inner volume's bounds it refers to.
+    /// Axes that don't wrap are left as-is. Returns [`None`] if a wrapping axis has a span of 0.
+    #[inline]
+    pub fn wrap_index<Idx: VolumeIdx>(&self, idx: Idx) -> Option<[i64; 3]> {
+        let pos = idx.array::<i64>()?;
+        let bounds = self.inner.bounding_box();
+        let (min, dims) = (bounds.min(), bounds.dimensions());
+
+        let mut out = pos;
+        for axis in (0..3).filter(|&axis| self.wrap[axis]) {
+            if dims[axis] <= 0 {
+                return None;
+            }
+
+            let offset = (pos[axis] as i128 - min[axis] as i128).rem_euclid(dims[axis] as i128);
+            out[axis] = min[axis] + offset as i64;
+        }
+
+        Some(out)
+    }
+
+    /// Resolve a localspace index by wrapping it into the inner volume's dimensions along the wrapping axes.
+    #[inline]
+    fn wrap_local<Idx: VolumeIdx>(&self, idx: Idx) -> Option<[u64; 3]> {
+        let pos = idx.array::<i128>()?;
+        let dims = self.inner.bounding_box().dimensions();
+
+        let mut out = [0; 3];
+        for axis in 0..3 {
+            let p = match self.wrap[axis] {
+                true if dims[axis] <= 0 => return None,
+                true => pos[axis].rem_euclid(dims[axis] as i128),
+                false => pos[axis],
+            };
+            out[axis] = u64::try_from(p).ok()?;
+        }
+
+        Some(out)
+    }
+}
+
+impl<V: Volume> Volume for TorusVolume<V> {
+    type Item = V::Item;
+
+    #[inline]
+    fn ls_get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        self.inner.ls_get(self.wrap_local(idx)?)
+    }
+
+    #[inline(always)]
+    fn bounding_box(&self) -> BoundingBox {
+        self.inner.bounding_box()
+    }
+
+    #[inline]
+    fn get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        self.inner.get(self.wrap_index(idx)?)
+    }
+
+    /// Checks if the worldspace index refers to an item, i.e. if it's inside of the bounding box along every axis that doesn't wrap.
+    #[inline]
+    fn contains<Idx: VolumeIdx>(&self, idx: Idx) -> bool {
+        self.wrap_index(idx)
+            .is_some_and(|pos| self.inner.bounding_box().contains(pos))
+    }
+
+    #[inline]
+    fn validate(&self) -> Result<(), crate::types::ValidationError> {
+        self.inner.validate()
+    }
+
+    #[inline]
+    fn heap_size(&self) -> usize {
+        self.inner.heap_size()
+    }
+}
+
+impl<V: VolumeMut> VolumeMut for TorusVolume<V> {
+    #[inline]
+    fn ls_get_mut<Idx: VolumeIdx>(&mut self, idx: Idx) -> Option<&mut Self::Item> {
+        let idx = self.wrap_local(idx)?;
+        self.inner.ls_get_mut(idx)
+    }
+
+    #[inline]
+    fn get_mut<Idx: VolumeIdx>(&mut self, idx: Idx) -> Option<&mut Self::Item> {
+        let idx = self.wrap_index(idx)?;
+        self.inner.get_mut(idx)
+    }
+}
+
+impl<V: Volume + Versioned> Versioned for TorusVolume<V> {
+    #[inline]
+    fn version(&self) -> u64 {
+        self.inner.version()
+    }
+}
+
+/// Wrapper around a volume that moves the origin of its worldspace to `origin`, so `get(idx)` reads the inner volume's item
+/// at `idx + origin`. For engines that periodically shift the origin of the world to keep `f32` coordinates precise, without
+/// touching the bounds stored in every volume (see [`HeapVolume::rebase`] for that).
+///
+/// The [bounding box](Volume::bounding_box) is the inner volume's with `origin` subtracted from it, and localspace indices
+/// are the same as the inner volume's. The [version](Versioned::version) changes whenever the inner volume's does and
+/// whenever the view is [rebased](RebasedView::rebase), since every index refers to a different item after that.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RebasedView<V> {
+    inner: V,
+    origin: [i64; 3],
+    /// How many times the view was rebased, added to the inner volume's version.
+    rebases: u64,
+}
+
+impl_indexing!(V, RebasedView<V>);
+
+impl<V: Volume> RebasedView<V> {
+    /// View `inner` with the origin of its worldspace moved to `origin`.
+    ///
+    /// # Panics
+    /// Panics if the rebased bounding box overflows [`i64`]. See [`RebasedView::try_new`] for a non-panicking version.
+    #[inline]
+    pub fn new(inner: V, origin: [i64; 3]) -> Self {
+        Self::try_new(inner, origin).expect("rebased bounding box overflows i64")
+    }
+
+    /// View `inner` with the origin of its worldspace moved to `origin`, or return [`None`] if the rebased bounding box
+    /// overflows [`i64`].
+    #[inline]
+    pub fn try_new(inner: V, origin: [i64; 3]) -> Option<Self> {
+        inner.bounding_box().checked_rebased(origin)?;
+
+        Some(Self {
+            inner,
+            origin,
+            rebases: 0,
+        })
+    }
+
+    /// The position in the inner volume's worldspace that's at `[0, 0, 0]` in this view.
+    #[inline]
+    pub fn origin(&self) -> [i64; 3] {
+        self.origin
+    }
+
+    /// Move the origin of this view's worldspace to `new_origin` (in this view's current worldspace), so the item at
+    /// `new_origin` ends up at `[0, 0, 0]`. Changes the version of the view.
+    ///
+    /// Returns `false` (and changes nothing) if the new origin or the rebased bounding box overflows [`i64`].
+    #[inline]
+    pub fn rebase(&mut self, new_origin: [i64; 3]) -> bool {
+        let Some(origin) = self.origin.checked_add(new_origin) else {
+            return false;
+        };
+        if self.inner.bounding_box().checked_rebased(origin).is_none() {
+            return false;
+        }
+
+        self.origin = origin;
+        self.rebases = self.rebases.wrapping_add(1);
+        true
+    }
+
+    #[inline]
+    pub fn inner(&self) -> &V {
+        &self.inner
+    }
+
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut V {
+        &mut self.inner
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> V {
+        self.inner
+    }
+
+    /// The inner volume's worldspace index for an index in this view, or [`None`] if it overflows.
+    #[inline]
+    pub fn to_inner<Idx: VolumeIdx>(&self, idx: Idx) -> Option<[i64; 3]> {
+        let pos = idx.array::<i64>()?;
+        Some([
+            pos[0].checked_add(self.origin[0])?,
+            pos[1].checked_add(self.origin[1])?,
+            pos[2].checked_add(self.origin[2])?,
+        ])
+    }
+}
+
+impl<V: Volume> Volume for RebasedView<V> {
+    type Item = V::Item;
+
+    #[inline(always)]
+    fn ls_get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        self.inner.ls_get(idx)
+    }
+
+    /// The corners are clamped to [`i64`] if they overflow, which can only happen if the inner volume's bounds were moved
+    /// (through [`RebasedView::inner_mut`]) after the origin was checked.
+    #[inline]
+    fn bounding_box(&self) -> BoundingBox {
+        let bounds = self.inner.bounding_box();
+        let shift =
+            |corner: [i64; 3]| [0, 1, 2].map(|axis| corner[axis].saturating_sub(self.origin[axis]));
+
+        BoundingBox::new(shift(bounds.min()), shift(bounds.max()))
+    }
+
+    #[inline]
+    fn get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        self.inner.get(self.to_inner(idx)?)
+    }
+
+    #[inline]
+    fn validate(&self) -> Result<(), crate::types::ValidationError> {
+        self.inner.validate()
+    }
+
+    #[inline]
+    fn heap_size(&self) -> usize {
+        self.inner.heap_size()
+    }
+}
+
+impl<V: VolumeMut> VolumeMut for RebasedView<V> {
+    #[inline(always)]
+    fn ls_get_mut<Idx: VolumeIdx>(&mut self, idx: Idx) -> Option<&mut Self::Item> {
+        self.inner.ls_get_mut(idx)
+    }
+
+    #[inline]
+    fn get_mut<Idx: VolumeIdx>(&mut self, idx: Idx) -> Option<&mut Self::Item> {
+        let idx = self.to_inner(idx)?;
+        self.inner.get_mut(idx)
+    }
+}
+
+impl<V: Volume + Versioned> Versioned for RebasedView<V> {
+    #[inline]
+    fn version(&self) -> u64 {
+        self.inner.version().wrapping_add(self.rebases)
+    }
+}
+
+/// View of a volume with its axes permuted and optionally flipped, e.g. to present a Z-up volume as Y-up without copying it.
+/// Created with [`Volume::permute_axes`].
+///
+/// `axes[i]` is the axis of the inner volume that axis `i` of the view reads along, so with `[Axis::X, Axis::Z, Axis::Y]`
+/// index `[x, y, z]` of the view is index `[x, z, y]` of the inner volume. This applies to worldspace and localspace indices
+/// as well as the bounds.
+///
+/// Swapping two axes mirrors the volume, which flipping one of them (with a negative [`SignedAxis`]) undoes. E.g.,
+/// `[Axis::X.into(), Axis::Z.into(), -Axis::Y]` turns right-handed Z-up data into right-handed Y-up, mapping `(x, y, z)`
+/// to `(x, z, -y)`. Along a flipped axis, cell `c` of the view is cell `-c - 1` of the inner volume, so that the cells are
+/// mirrored about the plane between cells `-1` and `0` and keep covering the same space.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PermutedView<V> {
+    inner: V,
+    axes: [SignedAxis; 3],
+}
+
+impl_indexing!(V, PermutedView<V>);
+
+impl<V: Volume> PermutedView<V> {
+    /// View `inner` with its axes permuted (and flipped) by `axes`.
+    ///
+    /// # Panics
+    /// Panics if `axes` isn't a permutation of X, Y, and Z (i.e., if any axis appears twice, in either direction).
+    #[inline]
+    pub fn new<A: Into<SignedAxis>>(inner: V, axes: [A; 3]) -> Self {
+        let axes = axes.map(Into::into);
+        let [a, b, c] = axes.map(SignedAxis::axis);
+        assert!(
+            a != b && a != c && b != c,
+            "{axes:?} is not a permutation of the axes"
+        );
+
+        Self { inner, axes }
+    }
+
+    /// The axes of the inner volume that the X, Y, and Z axes of this view read along.
+    #[inline]
+    pub fn axes(&self) -> [SignedAxis; 3] {
+        self.axes
+    }
+
+    #[inline]
+    pub fn inner(&self) -> &V {
+        &self.inner
+    }
+
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut V {
+        &mut self.inner
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> V {
+        self.inner
+    }
+
+    /// The inner volume's worldspace index for a worldspace index in this view.
+    #[inline]
+    pub fn to_inner(&self, pos: [i64; 3]) -> [i64; 3] {
+        let mut inner = [0; 3];
+        for (component, axis) in pos.into_iter().zip(self.axes) {
+            inner[axis.axis().index()] = if axis.is_negative() {
+                !component
+            } else {
+                component
+            };
+        }
+        inner
+    }
+
+    /// This view's worldspace index for a worldspace index in the inner volume. The inverse of [`PermutedView::to_inner`].
+    #[inline]
+    pub fn from_inner(&self, pos: [i64; 3]) -> [i64; 3] {
+        self.axes.map(|axis| {
+            let component = pos[axis.axis().index()];
+            if axis.is_negative() {
+                !component
+            } else {
+                component
+            }
+        })
+    }
+
+    /// The inner volume's localspace index for a localspace index in this view, or [`None`] if it overflows. Localspace
+    /// indices count from the minimum corner, so along a flipped axis they count from the other end of the inner volume.
+    #[inline]
+    fn local_to_inner(&self, pos: [i64; 3]) -> Option<[i64; 3]> {
+        let dims = self.inner.bounding_box().dimensions();
+        let mut inner = [0; 3];
+        for (component, axis) in pos.into_iter().zip(self.axes) {
+            let a = axis.axis().index();
+            inner[a] = match axis.is_negative() {
+                true => dims[a].checked_sub(1)?.checked_sub(component)?,
+                false => component,
+            };
+        }
+        Some(inner)
+    }
+}
+
+impl<V: Volume> Volume for PermutedView<V> {
+    type Item = V::Item;
+
+    #[inline]
+    fn ls_get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        self.inner.ls_get(self.local_to_inner(idx.array()?)?)
+    }
+
+    /// Along a flipped axis, an inner volume reaching down to [`i64::MIN`] would reach up to (exclusive) `i64::MAX + 1` in
+    /// this view, so the last cell is left out of the bounding box. It can still be accessed with [`Volume::get`].
+    #[inline]
+    fn bounding_box(&self) -> BoundingBox {
+        let bounds = self.inner.bounding_box();
+        let (mut min, mut max) = ([0; 3], [0; 3]);
+        for (i, axis) in self.axes.into_iter().enumerate() {
+            let a = axis.axis().index();
+            (min[i], max[i]) = match axis.is_negative() {
+                true => (
+                    bounds.max()[a].saturating_neg(),
+                    bounds.min()[a].saturating_neg(),
+                ),
+                false => (bounds.min()[a], bounds.max()[a]),
+            };
+        }
+
+        BoundingBox::new(min, max)
+    }
+
+    #[inline]
+    fn get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        self.inner.get(self.to_inner(idx.array()?))
+    }
+
+    #[inline]
+    fn validate(&self) -> Result<(), crate::types::ValidationError> {
+        self.inner.validate()
+    }
+
+    #[inline]
+    fn heap_size(&self) -> usize {
+        self.inner.heap_size()
+    }
+}
+
+impl<V: VolumeMut> VolumeMut for PermutedView<V> {
+    #[inline]
+    fn ls_get_mut<Idx: VolumeIdx>(&mut self, idx: Idx) -> Option<&mut Self::Item> {
+        let idx = self.local_to_inner(idx.array()?)?;
+        self.inner.ls_get_mut(idx)
+    }
+
+    #[inline]
+    fn get_mut<Idx: VolumeIdx>(&mut self, idx: Idx) -> Option<&mut Self::Item> {
+        let idx = self.to_inner(idx.array()?);
+        self.inner.get_mut(idx)
+    }
+}
+
+impl<V: Volume + Versioned> Versioned for PermutedView<V> {
+    #[inline]
+    fn version(&self) -> u64 {
+        self.inner.version()
+    }
+}
+
+/// How a [`ScaledView`] picks the item it reads for a block of items.
+pub enum ScalePolicy<T> {
+    /// The first item of the block (in X-major order).
+    First,
+    /// The item that occurs the most in the block. Ties go to the item that occurs first.
+    Majority,
+    /// The first item of the block for which the function returns `true`, or the first item of the block if there's none.
+    /// Useful for conservative queries, e.g. treating a block as solid if any of its items are solid.
+    Any(fn(&T) -> bool),
+}
+
+// Implemented manually since deriving would require `T: Clone`, `T: Debug`, etc.
+impl<T> Clone for ScalePolicy<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ScalePolicy<T> {}
+
+impl<T> std::fmt::Debug for ScalePolicy<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::First => write!(f, "First"),
+            Self::Majority => write!(f, "Majority"),
+            Self::Any(_) => write!(f, "Any(..)"),
+        }
+    }
+}
+
+/// Wrapper around a volume presenting it at a coarser scale, where every index refers to an `N`×`N`×`N` block of the inner volume.
+/// Coarse index `[x, y, z]` covers the inner positions from `[x, y, z] * N` (inclusive) to `([x, y, z] + 1) * N` (exclusive).
+///
+/// Reading an index picks one item of its block according to the [`ScalePolicy`], without copying or downsampling anything.
+/// Blocks on the edges of the inner volume may be partially outside of it, in which case only the items inside of it are considered.
+/// Writing is done with [`ScaledView::fill`], which sets every item of a block.
+#[derive(Debug, Clone)]
+pub struct ScaledView<V: Volume, const N: usize> {
+    inner: V,
+    policy: ScalePolicy<V::Item>,
+}
+
+impl<V, Idx, const N: usize> std::ops::Index<Idx> for ScaledView<V, N>
+where
+    V: Volume,
+    V::Item: PartialEq,
+    Idx: VolumeIdx,
+{
+    type Output = V::Item;
+
+    #[inline(always)]
+    #[track_caller]
+    fn index(&self, idx: Idx) -> &Self::Output {
+        match self.get(idx) {
+            Some(item) => item,
+            None => crate::util::index_out_of_bounds(idx, self.bounding_box()),
+        }
+    }
+}
+
+impl<V: Volume, const N: usize> ScaledView<V, N> {
+    /// The block size as a worldspace distance.
+    const SCALE: i64 = {
+        assert!(N > 0, "scaled views need a block size of at least 1");
+        N as i64
+    };
+
+    #[inline]
+    pub fn new(inner: V, policy: ScalePolicy<V::Item>) -> Self {
+        let _ = Self::SCALE;
+        Self { inner, policy }
+    }
+
+    #[inline]
+    pub fn policy(&self) -> ScalePolicy<V::Item> {
+        self.policy
+    }
+
+    #[inline]
+    pub fn inner(&self) -> &V {
+        &self.inner
+    }
+
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut V {
+        &mut self.inner
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> V {
+        self.inner
+    }
+
+    /// The part of the inner volume covered by the coarse index, or [`None`] if it doesn't cover any of it.
+    #[inline]
+    pub fn block<Idx: VolumeIdx>(&self, idx: Idx) -> Option<BoundingBox> {
+        let min = idx.array::<i64>()?.map(|c| c.checked_mul(Self::SCALE));
+        let [Some(x), Some(y), Some(z)] = min else {
+            return None;
+        };
+        let max = [x, y, z].map(|c| c.saturating_add(Self::SCALE));
+
+        BoundingBox::new([x, y, z], max).intersection(&self.inner.bounding_box())
+    }
+
+    /// Set every item in the block of the coarse index to `item`. Returns how many items were set.
+    #[inline]
+    pub fn fill<Idx: VolumeIdx>(&mut self, idx: Idx, item: V::Item) -> usize
+    where
+        V: VolumeMut,
+        V::Item: Clone,
+    {
+        match self.block(idx) {
+            Some(block) => self.inner.fill_region(&block, item),
+            None => 0,
+        }
+    }
+}
+
+impl<V, const N: usize> Volume for ScaledView<V, N>
+where
+    V: Volume,
+    V::Item: PartialEq,
+{
+    type Item = V::Item;
+
+    #[inline]
+    fn ls_get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        let min = self.bounding_box().min();
+        self.get(idx.array::<i64>()?.checked_add(min)?)
+    }
+
+    /// The smallest box of coarse indices covering the entire inner volume.
+    #[inline]
+    fn bounding_box(&self) -> BoundingBox {
+        let inner = self.inner.bounding_box();
+        if inner.is_empty() {
+            return BoundingBox::new_origin([0, 0, 0]);
+        }
+
+        BoundingBox::new(
+            inner.min().map(|c| c.div_euclid(Self::SCALE)),
+            inner.max().map(|c| (c - 1).div_euclid(Self::SCALE) + 1),
+        )
+    }
+
+    #[inline]
+    fn get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        let mut items = self
+            .block(idx)?
+            .into_iter()
+            .map(|pos| self.inner.get(pos).unwrap());
+
+        match self.policy {
+            ScalePolicy::First => items.next(),
+            ScalePolicy::Any(pred) => {
+                let first = items.next()?;
+                match pred(first) {
+                    true => Some(first),
+                    false => Some(items.find(|item| pred(item)).unwrap_or(first)),
+                }
+            }
+            ScalePolicy::Majority => {
+                let mut counts: Vec<(&V::Item, usize)> = Vec::new();
+                for item in items {
+                    match counts.iter_mut().find(|(other, _)| *other == item) {
+                        Some((_, count)) => *count += 1,
+                        None => counts.push((item, 1)),
+                    }
+                }
+
+                // `max_by_key` picks the last of equal elements, so search in reverse to let ties go to the first item.
+                counts
+                    .into_iter()
+                    .rev()
+                    .max_by_key(|&(_, count)| count)
+                    .map(|(item, _)| item)
+            }
+        }
+    }
+
+    #[inline]
+    fn validate(&self) -> Result<(), crate::types::ValidationError> {
+        self.inner.validate()
+    }
+
+    #[inline]
+    fn heap_size(&self) -> usize {
+        self.inner.heap_size()
+    }
+}
+
+/// The part of `range` inside of `bounds`, or an empty box at the start of the range if they don't overlap.
+#[inline]
+pub(crate) fn clip_range<R: IntoBoundingBox>(bounds: BoundingBox, range: R) -> BoundingBox {
+    match range.into_bounding_box() {
+        Some(range) => bounds
+            .intersection(&range)
+            .unwrap_or(BoundingBox::new(range.min(), range.min())),
+        None => BoundingBox::new(bounds.min(), bounds.min()),
+    }
+}
+
+/// Read-only view of a box of items in a volume. Created with [`Volume::slice`].
+///
+/// Uses the same worldspace indices as the viewed volume, but only indices inside of the view's bounds are accessible.
+pub struct SubVolume<'a, V> {
+    pub(crate) volume: &'a V,
+    pub(crate) bounds: BoundingBox,
+}
+
+impl<V> Clone for SubVolume<'_, V> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<V> Copy for SubVolume<'_, V> {}
+
+impl_indexing!(['a, V], SubVolume<'a, V>);
+impl_debug!(['a, V], SubVolume<'a, V>);
+
+impl<V: Volume> Volume for SubVolume<'_, V> {
+    type Item = V::Item;
+
+    #[inline]
+    fn ls_get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        self.get(idx.array::<i64>()?.checked_add(self.bounds.min())?)
+    }
+
+    #[inline(always)]
+    fn bounding_box(&self) -> BoundingBox {
+        self.bounds
+    }
+
+    #[inline]
+    fn get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        match self.bounds.contains(idx) {
+            true => self.volume.get(idx),
+            false => None,
+        }
+    }
+}
+
+/// Mutable view of a box of items in a volume. Created with [`VolumeMut::slice_mut`].
+///
+/// Uses the same worldspace indices as the viewed volume, but only indices inside of the view's bounds are accessible.
+pub struct SubVolumeMut<'a, V> {
+    pub(crate) volume: &'a mut V,
+    pub(crate) bounds: BoundingBox,
+}
+
+impl_indexing!(['a, V], SubVolumeMut<'a, V>);
+impl_debug!(['a, V], SubVolumeMut<'a, V>);
+
+impl<V: Volume> Volume for SubVolumeMut<'_, V> {
+    type Item = V::Item;
+
+    #[inline]
+    fn ls_get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        self.get(idx.array::<i64>()?.checked_add(self.bounds.min())?)
+    }
+
+    #[inline(always)]
+    fn bounding_box(&self) -> BoundingBox {
+        self.bounds
+    }
+
+    #[inline]
+    fn get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        match self.bounds.contains(idx) {
+            true => self.volume.get(idx),
+            false => None,
+        }
+    }
+}
+
+impl<V: VolumeMut> VolumeMut for SubVolumeMut<'_, V> {
+    #[inline]
+    fn ls_get_mut<Idx: VolumeIdx>(&mut self, idx: Idx) -> Option<&mut Self::Item> {
+        self.get_mut(idx.array::<i64>()?.checked_add(self.bounds.min())?)
+    }
+
+    #[inline]
+    fn get_mut<Idx: VolumeIdx>(&mut self, idx: Idx) -> Option<&mut Self::Item> {
+        match self.bounds.contains(idx) {
+            true => self.volume.get_mut(idx),
+            false => None,
+        }
+    }
+}
+
+/// Read-only view of a box of items in a volume, with indices re-based so the view starts at `[0, 0, 0]`. Created with
+/// [`Volume::view_local`].
+///
+/// Index `[0, 0, 0]` of the view is the minimum corner of the box in the viewed volume, so algorithms written for origin-based
+/// volumes (like [`StackVolume`]) can run on any window of a larger volume without translating indices themselves.
+pub struct LocalView<'a, V> {
+    pub(crate) volume: &'a V,
+    /// The viewed box, in the worldspace of the viewed volume.
+    pub(crate) window: BoundingBox,
+}
+
+impl<V> Clone for LocalView<'_, V> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<V> Copy for LocalView<'_, V> {}
+
+impl_indexing!(['a, V], LocalView<'a, V>);
+impl_debug!(['a, V], LocalView<'a, V>);
+
+impl<V> LocalView<'_, V> {
+    /// The viewed box, in the worldspace of the viewed volume.
+    #[inline]
+    pub fn window(&self) -> BoundingBox {
+        self.window
+    }
+}
+
+impl<V: Volume> Volume for LocalView<'_, V> {
+    type Item = V::Item;
+
+    #[inline]
+    fn ls_get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        self.get(idx)
+    }
+
+    #[inline(always)]
+    fn bounding_box(&self) -> BoundingBox {
+        BoundingBox::new_origin(self.window.dimensions())
+    }
+
+    #[inline]
+    fn get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        match self.bounding_box().contains(idx) {
+            true => self
+                .volume
+                .get(idx.array::<i64>()?.checked_add(self.window.min())?),
+            false => None,
+        }
+    }
+}
+
+/// Mutable view of a box of items in a volume, with indices re-based so the view starts at `[0, 0, 0]`, like [`LocalView`].
+/// Created with [`VolumeMut::view_local_mut`].
+pub struct LocalViewMut<'a, V> {
+    pub(crate) volume: &'a mut V,
+    /// The viewed box, in the worldspace of the viewed volume.
+    pub(crate) window: BoundingBox,
+}
+
+impl_indexing!(['a, V], LocalViewMut<'a, V>);
+impl_debug!(['a, V], LocalViewMut<'a, V>);
+
+impl<V> LocalViewMut<'_, V> {
+    /// The viewed box, in the worldspace of the viewed volume.
+    #[inline]
+    pub fn window(&self) -> BoundingBox {
+        self.window
+    }
+}
+
+impl<V: Volume> Volume for LocalViewMut<'_, V> {
+    type Item = V::Item;
+
+    #[inline]
+    fn ls_get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        self.get(idx)
+    }
+
+    #[inline(always)]
+    fn bounding_box(&self) -> BoundingBox {
+        BoundingBox::new_origin(self.window.dimensions())
+    }
+
+    #[inline]
+    fn get<Idx: VolumeIdx>(&self, idx: Idx) -> Option<&Self::Item> {
+        match self.bounding_box().contains(idx) {
+            true => self
+                .volume
+                .get(idx.array::<i64>()?.checked_add(self.window.min())?),
+            false => None,
+        }
+    }
+}
+
+impl<V: VolumeMut> VolumeMut for LocalViewMut<'_, V> {
+    #[inline]
+    fn ls_get_mut<Idx: VolumeIdx>(&mut self, idx: Idx) -> Option<&mut Self::Item> {
+        self.get_mut(idx)
+    }
+
+    #[inline]
+    fn get_mut<Idx: VolumeIdx>(&mut self, idx: Idx) -> Option<&mut Self::Item> {
+        match self.bounding_box().contains(idx) {
+            true => self
+                .volume
+                .get_mut(idx.array::<i64>()?.checked_add(self.window.min())?),
+            false => None,
+        }
+    }
+}
@@ -0,0 +1,81 @@
+//! Line of sight queries between cells of a volume.
+
+use crate::prelude::*;
+
+/// Visit every cell a line from the center of `from` to the center of `to` passes through, in order,
+/// excluding `from` and `to` themselves. Stops early if `visit` returns `false`. Returns `false` if it was stopped early.
+///
+/// Where the line passes exactly through an edge or corner between cells, it moves diagonally without visiting the cells
+/// that only touch the line there. This makes the traversal symmetric: the line from `a` to `b` visits the same cells as the line from `b` to `a`.
+pub fn traverse_line<F>(from: [i64; 3], to: [i64; 3], mut visit: F) -> bool
+where
+    F: FnMut([i64; 3]) -> bool,
+{
+    let delta = [0, 1, 2].map(|a| to[a] as i128 - from[a] as i128);
+    let len = delta.map(|d| d.abs());
+    let step = delta.map(|d| d.signum() as i64);
+
+    // How many cell boundaries have been crossed along each axis. The line crosses the k-th boundary along an axis
+    // at t = (2k + 1) / (2 * len), so comparing crossings only needs integer arithmetic.
+    let mut crossed = [0i128; 3];
+    let mut pos = from;
+
+    loop {
+        let next = (0..3)
+            .filter(|&a| crossed[a] < len[a])
+            .min_by(|&a, &b| ((2 * crossed[a] + 1) * len[b]).cmp(&((2 * crossed[b] + 1) * len[a])));
+        let Some(first) = next else {
+            return true;
+        };
+
+        let crossing =
+            |a: usize| (2 * crossed[a] + 1) * len[first] == (2 * crossed[first] + 1) * len[a];
+        let tied = [0, 1, 2].map(|a| crossed[a] < len[a] && crossing(a));
+        for a in (0..3).filter(|&a| tied[a]) {
+            crossed[a] += 1;
+            pos[a] += step[a];
+        }
+
+        if pos == to {
+            return true;
+        }
+        if !visit(pos) {
+            return false;
+        }
+    }
+}
+
+/// Check if the center of cell `b` can be seen from the center of cell `a`, i.e. no cell between them is opaque (according to `is_opaque`).
+/// The cells `a` and `b` themselves may be opaque, so this can be used to check if an opaque cell is visible.
+/// Cells outside of the volume are never opaque.
+#[inline]
+pub fn line_of_sight<V, F>(vol: &V, a: [i64; 3], b: [i64; 3], is_opaque: F) -> bool
+where
+    V: Volume,
+    F: Fn(&V::Item) -> bool,
+{
+    traverse_line(a, b, |pos| !vol.get(pos).is_some_and(&is_opaque))
+}
+
+/// Find every cell within `radius` of `origin` (by the distance between cell centers) that has [`line_of_sight`] from `origin`.
+/// Only cells inside the volume are returned. The cells are returned in X-major order.
+///
+/// This casts a line to every cell in range, so it's meant for moderate radii (e.g. AI perception or lighting previews).
+pub fn visible_cells_from<V, F>(
+    vol: &V,
+    origin: [i64; 3],
+    radius: f64,
+    is_opaque: F,
+) -> Vec<[i64; 3]>
+where
+    V: Volume,
+    F: Fn(&V::Item) -> bool,
+{
+    debug_span!("visible_cells_from", ?origin, radius, bounds = %vol.bounding_box());
+
+    let center = origin.map(|c| c as f64 + 0.5);
+    vol.bounding_box()
+        .iter_within_sphere(center, radius)
+        .filter(|&pos| line_of_sight(vol, origin, pos, &is_opaque))
+        .collect()
+}